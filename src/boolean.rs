@@ -0,0 +1,495 @@
+//! Boolean operations, splitting and repair for the polygons of terrain-like objects.
+//!
+//! `TerrainCorners` are filled with the even-odd rule. Every result is written as outer rings going
+//! counter-clockwise and holes going clockwise, without rings that intersect themselves or each other. Operations
+//! between objects work in world space, so the transform of the object is baked into its corners.
+
+use i_overlay::core::{fill_rule::FillRule, overlay_rule::OverlayRule};
+use i_overlay::float::{simplify::SimplifyShape, single::SingleFloatOverlay};
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+
+use crate::error::Error;
+use crate::geometry::{contains_point, signed_area, simplify};
+use crate::references::{DeletePolicy, EntityRef, References};
+use crate::shapes::ShapeKind;
+use crate::types::{level_data::LevelData, object::Object, object_property::ObjectProperty, vec2::Vec2};
+
+/// A boolean operation between the shapes of two objects.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Everything that's in either shape.
+    Union,
+    /// The first shape without the second one.
+    Difference,
+    /// Only what's in both shapes.
+    Intersection,
+    /// What's in exactly one of the shapes.
+    Xor,
+}
+
+impl BooleanOp {
+    const fn rule(self) -> OverlayRule {
+        match self {
+            Self::Union => OverlayRule::Union,
+            Self::Difference => OverlayRule::Difference,
+            Self::Intersection => OverlayRule::Intersect,
+            Self::Xor => OverlayRule::Xor,
+        }
+    }
+}
+
+impl FloatPointCompatible<f32> for Vec2 {
+    fn from_xy(x: f32, y: f32) -> Self {
+        Self::new(x, y)
+    }
+
+    fn x(&self) -> f32 {
+        self.x.0
+    }
+
+    fn y(&self) -> f32 {
+        self.y.0
+    }
+}
+
+impl Object {
+    /// Returns this object with its shape combined with the shape of `other`, or `None` if nothing is left.
+    ///
+    /// The result is drawn from its `TerrainCorners`, so this should be a terrain-like object, but `other` can be
+    /// any object, like a `SpriteCircle` to cut a round hole with. The result is centered on its corners, without
+    /// rotation, scale or flip.
+    #[must_use]
+    pub fn boolean(&self, operation: BooleanOp, other: &Self) -> Option<Self> {
+        let shapes = self.world_rings().overlay(&other.world_rings(), operation.rule(), FillRule::EvenOdd);
+        let rings: Vec<Vec<Vec2>> = shapes.into_iter().flatten().collect();
+
+        (!rings.is_empty()).then(|| self.with_world_rings(rings))
+    }
+
+    /// Splits this object along the line through `from` and `to` in world space and returns every piece as an
+    /// object centered on its corners, like [`Object::boolean`] does.
+    ///
+    /// Pieces on the left of the line come first. If `from` and `to` are the same point, the object is returned
+    /// as it is.
+    pub fn split(&self, from: Vec2, to: Vec2) -> Vec<Self> {
+        let direction = (to - from).normalize_or_zero();
+
+        if direction == Vec2::ZERO {
+            return vec![self.clone()];
+        }
+
+        let rings = self.world_rings();
+        let reach = rings.iter().flatten().map(|point| point.distance(from)).fold(1.0, f32::max) * 2.0;
+        let (along, across) = (direction * reach, direction.perp() * reach);
+
+        // Two rectangles that cover the object on either side of the line.
+        let left = vec![from - along, from + along, from + along + across, from - along + across];
+        let right = vec![from - along - across, from + along - across, from + along, from - along];
+
+        [left, right]
+            .iter()
+            .flat_map(|side| rings.overlay(side, OverlayRule::Intersect, FillRule::EvenOdd))
+            .map(|shape| self.with_world_rings(shape))
+            .collect()
+    }
+
+    /// Reverses the rings of the `TerrainCorners` where needed, so that outer rings go counter-clockwise and holes
+    /// go clockwise.
+    ///
+    /// Whether a ring is a hole depends on how many other rings it lies in, which the even-odd rule goes by.
+    pub fn normalize_winding(&mut self) {
+        let Some(rings) = self.terrain_corners_mut() else { return };
+
+        let holes: Vec<bool> = (0..rings.len())
+            .map(|index| {
+                let ring = &rings[index];
+                let Some(&first) = ring.first() else { return false };
+                let probe = first.midpoint(ring.get(1).copied().unwrap_or(first));
+
+                let depth = rings
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, outer)| other != index && contains_point(outer, probe))
+                    .count();
+
+                depth % 2 == 1
+            })
+            .collect();
+
+        for (ring, hole) in rings.iter_mut().zip(holes) {
+            if (signed_area(ring) < 0.0) != hole {
+                ring.reverse();
+            }
+        }
+    }
+
+    /// Rebuilds the `TerrainCorners` from the area that the even-odd rule fills, which removes self-intersections,
+    /// overlapping rings and rings without area, and normalizes the winding.
+    ///
+    /// An object without any area is left without rings.
+    pub fn repair_terrain(&mut self) {
+        if let Some(rings) = self.terrain_corners_mut() {
+            *rings = rings.simplify_shape(FillRule::EvenOdd).into_iter().flatten().collect();
+        }
+    }
+
+    /// Simplifies the rings of the `TerrainCorners` with [`simplify`], leaving out corners that are at most
+    /// `tolerance` away from the simplified outline in local units, and then repairs them with
+    /// [`Object::repair_terrain`].
+    pub fn simplify_terrain(&mut self, tolerance: f32) {
+        if let Some(rings) = self.terrain_corners_mut() {
+            for ring in rings.iter_mut() {
+                *ring = simplify(ring, tolerance);
+            }
+        }
+
+        self.repair_terrain();
+    }
+
+    fn terrain_corners_mut(&mut self) -> Option<&mut Vec<Vec<Vec2>>> {
+        self.properties.iter_mut().find_map(|property| match property {
+            ObjectProperty::TerrainCorners(rings) => Some(rings),
+            _ => None,
+        })
+    }
+
+    /// Returns a copy of this object with the given rings in world space as its `TerrainCorners`, moved to their
+    /// center and without rotation, scale or flip.
+    fn with_world_rings(&self, rings: Vec<Vec<Vec2>>) -> Self {
+        let (min, max) = bounds(&rings);
+        let position = min.midpoint(max);
+        let corners = rings.into_iter().map(|ring| ring.into_iter().map(|point| point - position).collect()).collect();
+
+        let mut object = self.clone();
+        object.position = position;
+        object.rotation = 0.0.into();
+        object.scale = Vec2::ONE;
+        object.properties.retain(|property| {
+            !matches!(property, ObjectProperty::FlipX(_) | ObjectProperty::FlipY(_) | ObjectProperty::TerrainCorners(_))
+        });
+        object.properties.push(ObjectProperty::TerrainCorners(corners));
+
+        object
+    }
+}
+
+impl LevelData {
+    /// Combines the shape of the object `other` into the terrain-like object `entity_id` with
+    /// [`Object::boolean`].
+    ///
+    /// `other` stays as it is, delete it with [`LevelData::delete_object`] if it was only used as a tool. Returns
+    /// `false` without changing anything if nothing would be left of the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingObject`] if either object doesn't exist, and [`Error::NotTerrainLike`] if the
+    /// object `entity_id` isn't drawn from `TerrainCorners`.
+    pub fn combine_terrain(&mut self, entity_id: i32, other: i32, operation: BooleanOp) -> Result<bool, Error> {
+        let find = |entity_id: i32| {
+            self.objects
+                .iter()
+                .position(|object| object.entity_id == entity_id)
+                .ok_or(Error::MissingObject(entity_id))
+        };
+        let (index, other) = (find(entity_id)?, find(other)?);
+
+        let object = &self.objects[index];
+
+        if !ShapeKind::of(object.object_id).is_terrain_like() {
+            return Err(Error::NotTerrainLike(object.object_id));
+        }
+
+        match object.boolean(operation, &self.objects[other]) {
+            Some(combined) => {
+                self.objects[index] = combined;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Merges terrain-like objects that overlap or share an edge into a single object each, and returns the
+    /// entity ids of the objects that were merged into another one and deleted.
+    ///
+    /// Objects are only merged with objects in the same layer that have the same object id and properties apart
+    /// from their shape, so the level looks the same afterwards. Each merged object is the one that comes first
+    /// in the level, centered on its corners like [`Object::boolean`] does. Prefab instances and objects that
+    /// anything else uses, like scripts, groups or linked objects, are left alone.
+    pub fn merge_terrain(&mut self) -> Vec<i32> {
+        let references = References::new(self);
+        let candidates: Vec<(usize, Vec<Vec<Vec2>>)> = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, object)| {
+                ShapeKind::of(object.object_id).is_terrain_like()
+                    && object.prefab_id == 0
+                    && !references.is_used(EntityRef::Object(object.entity_id))
+            })
+            .map(|(index, object)| (index, object.world_rings()))
+            .filter(|(_, rings)| !rings.is_empty())
+            .collect();
+
+        let bounds: Vec<(Vec2, Vec2)> = candidates.iter().map(|(_, rings)| bounds(rings)).collect();
+        let pieces: Vec<usize> =
+            candidates.iter().map(|(_, rings)| rings.simplify_shape(FillRule::EvenOdd).len()).collect();
+
+        // Every candidate points at a candidate it's merged with, or at itself.
+        let mut parents: Vec<usize> = (0..candidates.len()).collect();
+        let root = |parents: &[usize], mut index: usize| {
+            while parents[index] != index {
+                index = parents[index];
+            }
+
+            index
+        };
+
+        for first in 0..candidates.len() {
+            for second in first + 1..candidates.len() {
+                let ((a, a_rings), (b, b_rings)) = (&candidates[first], &candidates[second]);
+                let (a_bounds, b_bounds) = (bounds[first], bounds[second]);
+                let apart = a_bounds.1.x < b_bounds.0.x
+                    || b_bounds.1.x < a_bounds.0.x
+                    || a_bounds.1.y < b_bounds.0.y
+                    || b_bounds.1.y < a_bounds.0.y;
+
+                if apart || !same_look(&self.objects[*a], &self.objects[*b]) {
+                    continue;
+                }
+
+                // Shapes that overlap or share an edge merge into fewer pieces than they have together.
+                let union = a_rings.overlay(b_rings, OverlayRule::Union, FillRule::EvenOdd);
+
+                if union.len() < pieces[first] + pieces[second] {
+                    let (a_root, b_root) = (root(&parents, first), root(&parents, second));
+                    parents[a_root.max(b_root)] = a_root.min(b_root);
+                }
+            }
+        }
+
+        let mut deleted = vec![];
+
+        for kept in 0..candidates.len() {
+            let members: Vec<usize> =
+                (kept + 1..candidates.len()).filter(|&member| root(&parents, member) == kept).collect();
+
+            if members.is_empty() {
+                continue;
+            }
+
+            let rings = members.iter().fold(candidates[kept].1.clone(), |rings, &member| {
+                let union = rings.overlay(&candidates[member].1, OverlayRule::Union, FillRule::EvenOdd);
+                union.into_iter().flatten().collect()
+            });
+
+            let index = candidates[kept].0;
+            self.objects[index] = self.objects[index].with_world_rings(rings);
+            deleted.extend(members.iter().map(|&member| self.objects[candidates[member].0].entity_id));
+        }
+
+        for &entity_id in &deleted {
+            // Nothing uses these objects, so this only removes them from their layer.
+            let _ = self.delete_object(entity_id, DeletePolicy::Cascade);
+        }
+
+        deleted
+    }
+}
+
+/// Whether the objects look the same apart from their shape and transform.
+fn same_look(a: &Object, b: &Object) -> bool {
+    let look = |object: &Object| {
+        object
+            .properties
+            .iter()
+            .filter(|property| {
+                !matches!(
+                    property,
+                    ObjectProperty::FlipX(_) | ObjectProperty::FlipY(_) | ObjectProperty::TerrainCorners(_)
+                )
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    a.object_id == b.object_id && a.in_layer == b.in_layer && a.in_group == b.in_group && look(a) == look(b)
+}
+
+/// Returns the smallest and largest coordinates of the rings.
+fn bounds(rings: &[Vec<Vec2>]) -> (Vec2, Vec2) {
+    let mut points = rings.iter().flatten().copied();
+    let first = points.next().unwrap_or(Vec2::ZERO);
+
+    points.fold((first, first), |(min, max), point| (min.min(point), max.max(point)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::is_self_intersecting;
+    use crate::types::{color::Color, layer::Layer, object_id::ObjectId};
+
+    /// A terrain square of the given size centered on `center`.
+    fn square(entity_id: i32, center: (f32, f32), size: f32) -> Object {
+        let mut object = Object::get_default_with_id(ObjectId::Terrain, 1);
+        object.entity_id = entity_id;
+        object.position = Vec2::new(center.0, center.1);
+        object.scale = Vec2::splat(size);
+        object
+    }
+
+    fn corners(object: &Object) -> Vec<Vec<Vec2>> {
+        object.clone().terrain_corners_mut().cloned().unwrap_or_default()
+    }
+
+    /// The signed area of every ring.
+    fn areas(object: &Object) -> Vec<f32> {
+        corners(object).iter().map(|ring| signed_area(ring)).collect()
+    }
+
+    #[test]
+    fn union() {
+        let combined = square(1, (0.0, 0.0), 2.0).boolean(BooleanOp::Union, &square(2, (1.0, 0.0), 2.0)).unwrap();
+
+        assert_eq!(areas(&combined), [6.0]);
+        assert_eq!(combined.entity_id, 1);
+        assert_eq!((combined.position, combined.scale), (Vec2::new(0.5, 0.0), Vec2::ONE));
+    }
+
+    #[test]
+    fn difference() {
+        let frame = square(1, (0.0, 0.0), 4.0).boolean(BooleanOp::Difference, &square(2, (0.0, 0.0), 2.0)).unwrap();
+
+        // The hole goes clockwise, inside the counter-clockwise outer ring.
+        assert_eq!(areas(&frame), [16.0, -4.0]);
+        assert!(square(1, (0.0, 0.0), 2.0).boolean(BooleanOp::Difference, &square(2, (0.0, 0.0), 4.0)).is_none());
+    }
+
+    #[test]
+    fn intersection_and_xor() {
+        let (a, b) = (square(1, (0.0, 0.0), 2.0), square(2, (1.0, 1.0), 2.0));
+
+        assert_eq!(areas(&a.boolean(BooleanOp::Intersection, &b).unwrap()), [1.0]);
+
+        // What's left of each square is an L of three quarters, one for each of them.
+        let xor = a.boolean(BooleanOp::Xor, &b).unwrap();
+        assert_eq!(areas(&xor), [3.0, 3.0]);
+        assert!(corners(&xor).iter().all(|ring| ring.len() == 6 && !is_self_intersecting(ring)));
+
+        assert!(a.boolean(BooleanOp::Intersection, &square(3, (5.0, 0.0), 2.0)).is_none());
+    }
+
+    #[test]
+    fn transforms_are_baked_in() {
+        let mut rotated = square(1, (2.0, 0.0), 2.0);
+        rotated.rotation = 45.0.into();
+        rotated.properties.push(ObjectProperty::FlipX(true));
+
+        let circle = Object {
+            entity_id: 2,
+            object_id: ObjectId::SpriteCircle,
+            position: Vec2::new(2.0, 0.0),
+            scale: Vec2::ONE,
+            ..Default::default()
+        };
+
+        let cut = rotated.boolean(BooleanOp::Difference, &circle).unwrap();
+        let areas = areas(&cut);
+
+        assert_eq!((cut.rotation.0, cut.scale), (0.0, Vec2::ONE));
+        assert!(!cut.properties.contains(&ObjectProperty::FlipX(true)));
+        assert!(cut.position.abs_diff_eq(Vec2::new(2.0, 0.0), 1e-4));
+        assert!(corners(&cut)[0].iter().any(|point| point.abs_diff_eq(Vec2::new(std::f32::consts::SQRT_2, 0.0), 1e-4)));
+        assert_eq!(areas.len(), 2);
+        assert!((areas[0] - 4.0).abs() < 1e-3 && areas[1] < 0.0 && areas[1] > -std::f32::consts::FRAC_PI_4);
+    }
+
+    #[test]
+    fn split() {
+        let object = square(1, (0.0, 0.0), 2.0);
+        let pieces = object.split(Vec2::new(0.5, -5.0), Vec2::new(0.5, 5.0));
+
+        // Going up, the left of the line is towards negative x.
+        assert_eq!(pieces.len(), 2);
+        let positions: Vec<Vec2> = pieces.iter().map(|piece| piece.position).collect();
+        assert_eq!(positions, [Vec2::new(-0.25, 0.0), Vec2::new(0.75, 0.0)]);
+        assert_eq!(pieces.iter().map(areas).collect::<Vec<_>>(), [[3.0], [1.0]]);
+
+        assert_eq!(object.split(Vec2::new(5.0, -5.0), Vec2::new(5.0, 5.0)).len(), 1);
+        assert_eq!(object.split(Vec2::ONE, Vec2::ONE), [object]);
+    }
+
+    #[test]
+    fn winding_and_repair() {
+        let ring = |points: &[(f32, f32)]| points.iter().map(|&(x, y)| Vec2::new(x, y)).collect::<Vec<_>>();
+        let mut object = square(1, (0.0, 0.0), 1.0);
+        object.properties = vec![ObjectProperty::TerrainCorners(vec![
+            ring(&[(-2.0, -2.0), (-2.0, 2.0), (2.0, 2.0), (2.0, -2.0)]),
+            ring(&[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]),
+        ])];
+
+        object.normalize_winding();
+        assert_eq!(areas(&object), [16.0, -4.0]);
+
+        // A bow tie is two triangles touching at their tips.
+        let bow_tie = ring(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)]);
+        object.properties = vec![ObjectProperty::TerrainCorners(vec![bow_tie])];
+        object.repair_terrain();
+        assert_eq!(areas(&object), [1.0, 1.0]);
+
+        object.properties = vec![ObjectProperty::TerrainCorners(vec![ring(&[(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)])])];
+        object.repair_terrain();
+        assert!(corners(&object).is_empty());
+    }
+
+    #[test]
+    fn combine() {
+        let mut level = LevelData {
+            objects: vec![
+                square(1, (0.0, 0.0), 2.0),
+                square(2, (1.0, 0.0), 2.0),
+                Object { entity_id: 3, object_id: ObjectId::SpriteSquare, ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        assert!(level.combine_terrain(1, 2, BooleanOp::Union).unwrap());
+        assert_eq!(areas(&level.objects[0]), [6.0]);
+        assert_eq!(level.objects.len(), 3);
+
+        assert!(matches!(level.combine_terrain(1, 9, BooleanOp::Union), Err(Error::MissingObject(9))));
+        assert!(matches!(
+            level.combine_terrain(3, 1, BooleanOp::Union),
+            Err(Error::NotTerrainLike(ObjectId::SpriteSquare))
+        ));
+
+        let before = level.objects[0].clone();
+        assert!(!level.combine_terrain(1, 1, BooleanOp::Difference).unwrap());
+        assert_eq!(level.objects[0], before);
+    }
+
+    #[test]
+    fn merge() {
+        let mut red = square(4, (5.0, 0.0), 2.0);
+        red.properties.push(ObjectProperty::Color(Color::new(1.0, 0.0, 0.0, 1.0)));
+
+        let mut level = LevelData {
+            layers: vec![Layer { layer_id: 1, children: vec![1, 2, 3, 4, 5], ..Default::default() }],
+            objects: vec![
+                square(1, (0.0, 0.0), 2.0),
+                square(2, (2.0, 0.0), 2.0),
+                square(3, (10.0, 0.0), 2.0),
+                red,
+                square(5, (4.0, 0.0), 2.0),
+            ],
+            ..Default::default()
+        };
+
+        // The squares sharing edges become one, and the red one in between stays on its own.
+        assert_eq!(level.merge_terrain(), [2, 5]);
+        assert_eq!(level.objects.iter().map(|object| object.entity_id).collect::<Vec<_>>(), [1, 3, 4]);
+        assert_eq!(level.layers[0].children, [1, 3, 4]);
+        assert_eq!(areas(&level.objects[0]), [12.0]);
+        assert_eq!(level.objects[0].position, Vec2::new(2.0, 0.0));
+    }
+}
@@ -0,0 +1,152 @@
+//! Which nova scripts call which functions through `RunFunction` actions.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::types::level_data::LevelData;
+
+/// The calls between the nova scripts of a level.
+///
+/// The graph is a snapshot, rebuild it after modifying the level.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    functions: BTreeSet<i32>,
+    entry_points: BTreeSet<i32>,
+    callees: BTreeMap<i32, BTreeSet<i32>>,
+    callers: BTreeMap<i32, BTreeSet<i32>>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of the given level. Calls of scripts that don't exist or aren't functions are left out.
+    #[must_use]
+    pub fn new(level: &LevelData) -> Self {
+        let mut graph = Self::default();
+
+        for script in &level.nova_scripts {
+            if script.is_function {
+                graph.functions.insert(script.script_id);
+            } else {
+                graph.entry_points.insert(script.script_id);
+            }
+        }
+
+        for script in &level.nova_scripts {
+            script.for_each_action(&mut |action| {
+                if let Some(call) = action.action_type.function_call() {
+                    if graph.functions.contains(&call.id) {
+                        graph.callees.entry(script.script_id).or_default().insert(call.id);
+                        graph.callers.entry(call.id).or_default().insert(script.script_id);
+                    }
+                }
+            });
+        }
+
+        graph
+    }
+
+    /// The functions that the script calls directly.
+    pub fn callees(&self, script_id: i32) -> impl Iterator<Item = i32> + '_ {
+        self.callees.get(&script_id).into_iter().flatten().copied()
+    }
+
+    /// The scripts that call the function directly.
+    pub fn callers(&self, function_id: i32) -> impl Iterator<Item = i32> + '_ {
+        self.callers.get(&function_id).into_iter().flatten().copied()
+    }
+
+    /// Every function that the script calls, directly or through other functions.
+    #[must_use]
+    pub fn reachable(&self, script_id: i32) -> BTreeSet<i32> {
+        let mut reached = BTreeSet::new();
+        let mut stack: Vec<i32> = self.callees(script_id).collect();
+
+        while let Some(function) = stack.pop() {
+            if reached.insert(function) {
+                stack.extend(self.callees(function));
+            }
+        }
+
+        reached
+    }
+
+    /// Whether the function can end up calling itself.
+    #[must_use]
+    pub fn is_recursive(&self, function_id: i32) -> bool {
+        self.reachable(function_id).contains(&function_id)
+    }
+
+    /// The groups of functions that call each other in a cycle, each sorted by id. A function that calls itself
+    /// directly is a group of one.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<i32>> {
+        let mut cycles: Vec<Vec<i32>> = Vec::new();
+        let mut seen = BTreeSet::new();
+
+        for &function in &self.functions {
+            if seen.contains(&function) || !self.is_recursive(function) {
+                continue;
+            }
+
+            // The functions reachable from this one that can also reach it back.
+            let cycle: Vec<i32> = self
+                .reachable(function)
+                .into_iter()
+                .filter(|&other| self.reachable(other).contains(&function))
+                .collect();
+
+            seen.extend(cycle.iter().copied());
+            cycles.push(cycle);
+        }
+
+        cycles
+    }
+
+    /// The functions that no script that isn't a function can reach, so they never run.
+    #[must_use]
+    pub fn unused_functions(&self) -> Vec<i32> {
+        let used: BTreeSet<i32> = self.entry_points.iter().flat_map(|&script| self.reachable(script)).collect();
+
+        self.functions.iter().copied().filter(|function| !used.contains(function)).collect()
+    }
+}
+
+impl LevelData {
+    /// Builds the [`CallGraph`] of this level.
+    #[must_use]
+    pub fn call_graph(&self) -> CallGraph {
+        CallGraph::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+
+    #[test]
+    fn cycles() {
+        let source = r#"
+            script 1 "Start" {
+                if true {
+                    repeat 2 {
+                        call 13();
+                    }
+                }
+            }
+            function 10 "ping" { call 11(); }
+            function 11 "pong" { if true { call 10(); } }
+            function 12 "self" { call 12(); call 10(); }
+            function 13 "once" { call 10(); call 99(); }
+            function 14 "unused" {}
+        "#;
+        let level = LevelData { nova_scripts: compile_scripts(source, &[]).unwrap(), ..Default::default() };
+        let graph = level.call_graph();
+
+        assert_eq!(graph.cycles(), vec![vec![10, 11], vec![12]]);
+        assert!(graph.is_recursive(11));
+        assert!(!graph.is_recursive(13));
+        assert_eq!(graph.callees(1).collect::<Vec<_>>(), vec![13]);
+        assert_eq!(graph.callers(10).collect::<Vec<_>>(), vec![11, 12, 13]);
+        assert_eq!(graph.reachable(1), BTreeSet::from([10, 11, 13]));
+        assert_eq!(graph.unused_functions(), vec![12, 14]);
+    }
+}
@@ -0,0 +1,911 @@
+//! Conversion of legacy-editor levels into nova levels.
+//!
+//! See [`Exolvl::convert_to_nova`].
+//!
+//! Legacy actions are read through [`OldActionKind`]. Missing arguments fall back to the defaults of the nova actions,
+//! and actions without a `target` target the object that owns the script.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use glam::IVec2;
+use ordered_float::OrderedFloat;
+use strum::IntoEnumIterator;
+
+use crate::geometry::grid_outlines;
+use crate::types::{
+    exolvl::Exolvl,
+    layer::Layer,
+    level_data::LevelData,
+    nova_script::{
+        action::Action,
+        action_type::ActionType,
+        activator::ActivatorKind,
+        expr::{
+            BoolExpr, ColorExpr, EasingExpr, FloatExpr, IntExpr, MusicExpr, ObjectSetExpr, SoundExpr, StringExpr,
+            VectorExpr,
+        },
+        function_call::FunctionCall,
+        nova_value::NovaValue,
+        NovaScript,
+    },
+    object::Object,
+    object_id::ObjectId,
+    object_property::ObjectProperty,
+    old_editor_types::{object_tile::ObjectTile, object_tile_property::ObjectTileProperty, simple_tile::SimpleTile},
+    old_script::{
+        old_action_kind::OldActionKind, old_action_property::OldActionProperty, old_action_type::OldActionType, Script,
+    },
+    sprite::Sprite,
+    vec2::Vec2,
+};
+use crate::Uuid;
+
+/// The legacy tile list a tile comes from. Each list becomes a nova layer of its own.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TileLayer {
+    UnderDecoration,
+    BackgroundDecoration,
+    Terrain,
+    FloatingZone,
+    Object,
+    ForegroundDecoration,
+}
+
+impl TileLayer {
+    const fn layer_name(self) -> &'static str {
+        match self {
+            Self::UnderDecoration => "Under decoration",
+            Self::BackgroundDecoration => "Background decoration",
+            Self::Terrain => "Terrain",
+            Self::FloatingZone => "Floating zones",
+            Self::Object => "Objects",
+            Self::ForegroundDecoration => "Foreground decoration",
+        }
+    }
+}
+
+/// Something [`Exolvl::convert_to_nova`] couldn't carry over into the nova level.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ConversionIssue {
+    /// The tile id doesn't name an object. The tile was dropped.
+    UnknownTile { layer: TileLayer, position: IVec2, tile_id: String },
+    /// Nova objects have no equivalent of this property. It was dropped.
+    UnsupportedProperty { entity_id: i32, property: ObjectTileProperty },
+    /// The object refers to a script that wasn't passed to the converter. The trigger or action was dropped.
+    MissingScript { entity_id: i32, script_id: Uuid },
+    /// Nova scripts have no equivalent of this action. It was dropped.
+    UnsupportedAction { script_id: Uuid, index: usize, action_type: OldActionType },
+    /// An argument of the action is missing or couldn't be parsed. The action was dropped.
+    InvalidActionProperty { script_id: Uuid, index: usize, property: OldActionProperty },
+    /// The action type doesn't know this property. The property was dropped.
+    UnknownActionProperty { script_id: Uuid, index: usize, property: OldActionProperty },
+}
+
+impl fmt::Display for ConversionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTile { layer, position, tile_id } => {
+                write!(f, "unknown tile {tile_id:?} at {position} in {layer:?}")
+            }
+            Self::UnsupportedProperty { entity_id, property } => {
+                write!(f, "object {entity_id}: property {property:?} has no nova equivalent")
+            }
+            Self::MissingScript { entity_id, script_id } => {
+                write!(f, "object {entity_id}: script {script_id} is missing")
+            }
+            Self::UnsupportedAction { script_id, index, action_type } => {
+                write!(f, "script {script_id}, action {index}: {action_type:?} has no nova equivalent")
+            }
+            Self::InvalidActionProperty { script_id, index, property } => {
+                write!(f, "script {script_id}, action {index}: invalid {} {:?}", property.name, property.value)
+            }
+            Self::UnknownActionProperty { script_id, index, property } => {
+                write!(f, "script {script_id}, action {index}: unknown property {}", property.name)
+            }
+        }
+    }
+}
+
+/// What [`Exolvl::convert_to_nova`] did.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ConversionReport {
+    /// The number of objects created from tiles.
+    pub objects: usize,
+    /// The ids of the nova scripts created from legacy scripts.
+    pub scripts: Vec<i32>,
+    /// Everything that was dropped on the way.
+    pub issues: Vec<ConversionIssue>,
+}
+
+impl ConversionReport {
+    /// Whether everything was converted.
+    #[must_use]
+    pub const fn is_lossless(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Exolvl {
+    /// Converts a legacy-editor level into a nova level, using `scripts` to look up the scripts its objects refer to.
+    ///
+    /// Legacy scripts aren't stored in the level file, so they have to be loaded separately. Scripts that objects
+    /// refer to but that aren't in `scripts` are reported as [`ConversionIssue::MissingScript`].
+    ///
+    /// Every tile becomes an object with the matching [`ObjectId`], and every tile list becomes a layer. Square
+    /// terrain tiles are merged into one `Terrain` object per connected region, outlined by its `TerrainCorners`.
+    /// Object tiles keep their entity ids, so the legacy scripts that target them stay valid.
+    ///
+    /// The start, activation and death scripts of an object become nova scripts triggered at level start, when the
+    /// object is activated and when it's killed. Scripts run through `RunScript` become functions.
+    ///
+    /// Levels that already are nova levels are left untouched.
+    pub fn convert_to_nova(&mut self, scripts: &[Script]) -> ConversionReport {
+        if self.level_data.nova_level {
+            return ConversionReport::default();
+        }
+
+        let report = Converter::new(&mut self.level_data, scripts).run();
+        self.local_level.nova_level = true;
+
+        report
+    }
+}
+
+/// When a legacy script of an object runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Trigger {
+    Start,
+    Activation,
+    Death,
+}
+
+/// Why a legacy action was dropped.
+enum Dropped {
+    /// Nova scripts have no equivalent of the action.
+    Unsupported,
+    /// The property with this name is missing or invalid.
+    Invalid(String),
+    /// The action runs a script that wasn't passed to the converter.
+    MissingScript(Uuid),
+}
+
+struct Converter<'a> {
+    level: &'a mut LevelData,
+    scripts: BTreeMap<Uuid, &'a Script>,
+    layers: BTreeMap<TileLayer, i32>,
+    next_entity_id: i32,
+    next_script_id: i32,
+    /// The functions made from scripts run through `RunScript`, by legacy script and owning object.
+    functions: BTreeMap<(Uuid, i32), i32>,
+    report: ConversionReport,
+}
+
+impl<'a> Converter<'a> {
+    fn new(level: &'a mut LevelData, scripts: &'a [Script]) -> Self {
+        let next_entity_id = level
+            .objects
+            .iter()
+            .map(|object| object.entity_id)
+            .chain(level.object_tiles.iter().map(|tile| tile.entity_id))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let next_script_id = level.nova_scripts.iter().map(|script| script.script_id).max().unwrap_or(0) + 1;
+
+        Self {
+            level,
+            scripts: scripts.iter().map(|script| (script.script_id, script)).collect(),
+            layers: BTreeMap::new(),
+            next_entity_id,
+            next_script_id,
+            functions: BTreeMap::new(),
+            report: ConversionReport::default(),
+        }
+    }
+
+    fn run(mut self) -> ConversionReport {
+        let level = &mut *self.level;
+        let simple_tiles = [
+            (TileLayer::UnderDecoration, std::mem::take(&mut level.under_decoration_tiles)),
+            (TileLayer::BackgroundDecoration, std::mem::take(&mut level.background_decoration_tiles)),
+            (TileLayer::Terrain, std::mem::take(&mut level.terrain_tiles)),
+            (TileLayer::FloatingZone, std::mem::take(&mut level.floating_zone_tiles)),
+        ];
+        let object_tiles = std::mem::take(&mut level.object_tiles);
+        let foreground_tiles = std::mem::take(&mut level.foreground_decoration_tiles);
+
+        for (layer, tiles) in simple_tiles {
+            self.convert_simple_tiles(layer, tiles);
+        }
+
+        // Scripts are converted once every object exists, so camera pans can look up their targets.
+        let mut triggers = Vec::new();
+
+        for tile in object_tiles {
+            self.convert_object_tile(tile, &mut triggers);
+        }
+
+        self.convert_simple_tiles(TileLayer::ForegroundDecoration, foreground_tiles);
+
+        for (entity_id, trigger, script_id, activation_count) in triggers {
+            self.convert_trigger(entity_id, trigger, script_id, activation_count);
+        }
+
+        self.level.nova_level = true;
+        self.report
+    }
+
+    fn convert_simple_tiles(&mut self, layer: TileLayer, tiles: Vec<SimpleTile>) {
+        let mut terrain = Vec::new();
+
+        for tile in tiles {
+            match object_id(&tile.tile_id) {
+                Some(ObjectId::Terrain) => terrain.push(tile.position),
+                Some(object_id) => {
+                    let mut object = Object::get_default_with_id(object_id, 0);
+                    object.entity_id = self.next_entity_id();
+                    object.position = tile_position(tile.position);
+                    self.add_object(layer, object);
+                }
+                None => self.report.issues.push(ConversionIssue::UnknownTile {
+                    layer,
+                    position: tile.position,
+                    tile_id: tile.tile_id,
+                }),
+            }
+        }
+
+        for rings in grid_outlines(terrain) {
+            let mut object = Object::get_default_with_id(ObjectId::Terrain, 0);
+            object.entity_id = self.next_entity_id();
+            object.properties = vec![ObjectProperty::TerrainCorners(rings)];
+            self.add_object(layer, object);
+        }
+    }
+
+    fn convert_object_tile(&mut self, tile: ObjectTile, triggers: &mut Vec<(i32, Trigger, Uuid, Option<i32>)>) {
+        let Some(object_id) = object_id(&tile.tile_id) else {
+            self.report.issues.push(ConversionIssue::UnknownTile {
+                layer: TileLayer::Object,
+                position: tile.position,
+                tile_id: tile.tile_id,
+            });
+            return;
+        };
+
+        let mut object = Object::get_default_with_id(object_id, 0);
+        object.entity_id = tile.entity_id;
+        object.position = tile_position(tile.position) + tile.offset;
+
+        let first_trigger = triggers.len();
+        let mut activation_count = None;
+
+        for property in tile.properties {
+            match property {
+                ObjectTileProperty::StartScript(script_id) => {
+                    triggers.push((tile.entity_id, Trigger::Start, script_id, None));
+                }
+                ObjectTileProperty::ActivationScript(script_id) => {
+                    triggers.push((tile.entity_id, Trigger::Activation, script_id, None));
+                }
+                ObjectTileProperty::DeathScript(script_id) => {
+                    triggers.push((tile.entity_id, Trigger::Death, script_id, None));
+                }
+                ObjectTileProperty::ActivationCount(count) => activation_count = Some(count),
+                property => {
+                    if !convert_property(&mut object, &property) {
+                        self.report.issues.push(ConversionIssue::UnsupportedProperty {
+                            entity_id: tile.entity_id,
+                            property,
+                        });
+                    }
+                }
+            }
+        }
+
+        // The activation count limits how often the activation script runs.
+        if let Some(count) = activation_count {
+            let activation = triggers[first_trigger..].iter_mut().find(|trigger| trigger.1 == Trigger::Activation);
+
+            match activation {
+                Some(trigger) => trigger.3 = Some(count),
+                None => self.report.issues.push(ConversionIssue::UnsupportedProperty {
+                    entity_id: tile.entity_id,
+                    property: ObjectTileProperty::ActivationCount(count),
+                }),
+            }
+        }
+
+        self.add_object(TileLayer::Object, object);
+    }
+
+    fn convert_trigger(&mut self, entity_id: i32, trigger: Trigger, script_id: Uuid, activation_count: Option<i32>) {
+        let Some(script) = self.scripts.get(&script_id).copied() else {
+            self.report.issues.push(ConversionIssue::MissingScript { entity_id, script_id });
+            return;
+        };
+
+        let target_objects: NovaValue = ObjectSetExpr::constant([entity_id]).into();
+        let activator = match trigger {
+            Trigger::Start => ActivatorKind::LevelStart,
+            Trigger::Activation => ActivatorKind::ObjectActivated { target_objects },
+            Trigger::Death => ActivatorKind::ObjectKilled { target_objects },
+        };
+
+        let id = self.next_script_id();
+        let actions = self.convert_actions(script, entity_id);
+
+        self.add_script(NovaScript {
+            script_id: id,
+            script_name: script.name.clone(),
+            is_function: false,
+            activation_count: activation_count.unwrap_or_default(),
+            condition: BoolExpr::constant(true).into(),
+            activation_list: vec![activator.into()],
+            parameters: Vec::new(),
+            variables: Vec::new(),
+            actions,
+        });
+    }
+
+    /// Returns the function that runs the legacy script for the owning object, converting it the first time.
+    fn function(&mut self, script_id: Uuid, owner: i32) -> Option<i32> {
+        if let Some(&id) = self.functions.get(&(script_id, owner)) {
+            return Some(id);
+        }
+
+        let script = self.scripts.get(&script_id).copied()?;
+        let id = self.next_script_id();
+
+        // Registered before converting the actions, so scripts that run themselves call the same function.
+        self.functions.insert((script_id, owner), id);
+        let actions = self.convert_actions(script, owner);
+
+        self.add_script(NovaScript {
+            script_id: id,
+            script_name: format!("{} ({owner})", script.name),
+            is_function: true,
+            activation_count: 0,
+            condition: BoolExpr::constant(true).into(),
+            activation_list: Vec::new(),
+            parameters: Vec::new(),
+            variables: Vec::new(),
+            actions,
+        });
+
+        Some(id)
+    }
+
+    fn convert_actions(&mut self, script: &Script, owner: i32) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        for (index, action) in script.actions.iter().enumerate() {
+            let typed = action.typed();
+
+            if let Some(property) = typed.invalid_properties().next() {
+                self.report.issues.push(ConversionIssue::InvalidActionProperty {
+                    script_id: script.script_id,
+                    index,
+                    property: property.clone(),
+                });
+                continue;
+            }
+
+            for property in typed.unknown_properties {
+                self.report.issues.push(ConversionIssue::UnknownActionProperty {
+                    script_id: script.script_id,
+                    index,
+                    property,
+                });
+            }
+
+            match self.convert_action(typed.kind, owner) {
+                Ok(action_type) => actions.push(Action { closed: false, wait: typed.wait, action_type }),
+                Err(dropped) => self.report.issues.push(dropped_issue(script, index, dropped, owner)),
+            }
+        }
+
+        actions
+    }
+
+    fn convert_action(&mut self, kind: OldActionKind, owner: i32) -> Result<ActionType, Dropped> {
+        let targets = |target: Option<Vec<i32>>| -> NovaValue {
+            let ids = target.filter(|ids| !ids.is_empty()).unwrap_or_else(|| vec![owner]);
+            ObjectSetExpr::constant(ids).into()
+        };
+
+        Ok(match kind {
+            OldActionKind::RunScript { script } => {
+                let script_id = script.ok_or_else(|| Dropped::Invalid("script".to_string()))?;
+                let id = self.function(script_id, owner).ok_or(Dropped::MissingScript(script_id))?;
+
+                ActionType::RunFunction { function: FunctionCall { id, parameters: Vec::new() } }
+            }
+            OldActionKind::Wait { duration } => ActionType::Wait { duration: float(duration, 0.0) },
+            OldActionKind::WaitFrames { frames } => ActionType::WaitFrames { frames: int(frames, 0) },
+            OldActionKind::Move { target, position, duration, easing } => ActionType::Move {
+                target_objects: targets(target),
+                position: vector(position, Vec2::ZERO),
+                global: bool(None, true),
+                duration: float(duration, 0.0),
+                easing: easing_value(easing),
+            },
+            OldActionKind::Scale { target, scale, duration, easing } => ActionType::Scale {
+                target_objects: targets(target),
+                scale: vector(scale, Vec2::ONE),
+                duration: float(duration, 0.0),
+                easing: easing_value(easing),
+            },
+            OldActionKind::Rotate { target, rotation, duration, easing } => ActionType::Rotate {
+                target_objects: targets(target),
+                rotation: float(rotation, 0.0),
+                shortest_path: bool(None, false),
+                global: bool(None, true),
+                duration: float(duration, 0.0),
+                easing: easing_value(easing),
+            },
+            OldActionKind::RotateAround { target, pivot, rotation, duration, easing } => ActionType::RotateAround {
+                target_objects: targets(target),
+                pivot: vector(pivot, Vec2::ZERO),
+                rotation: float(rotation, 0.0),
+                is_global: bool(None, true),
+                rotate_target: bool(None, true),
+                duration: float(duration, 0.0),
+                easing: easing_value(easing),
+            },
+            OldActionKind::SetDirection { target, direction } => ActionType::SetDirection {
+                target_objects: targets(target),
+                direction: int(direction, 1),
+            },
+            OldActionKind::Activate { target } => ActionType::Activate { target_objects: targets(target) },
+            OldActionKind::Deactivate { target } => ActionType::Deactivate { target_objects: targets(target) },
+            OldActionKind::PlaySound { sound, volume, pitch } => ActionType::PlaySound {
+                sound: SoundExpr::constant(sound.unwrap_or_default()).into(),
+                volume: float(volume, 1.0),
+                pitch: float(pitch, 1.0),
+                do_loop: bool(None, false),
+                fade_in: float(None, 0.0),
+            },
+            OldActionKind::PlayMusic { music, volume, pitch } => ActionType::PlayMusic {
+                music: MusicExpr::constant(music.unwrap_or_default()).into(),
+                volume: float(volume, 1.0),
+                pitch: float(pitch, 1.0),
+            },
+            OldActionKind::SetCinematic { enabled } => ActionType::SetCinematic { enabled: bool(enabled, true) },
+            OldActionKind::SetInputEnabled { enabled } => ActionType::SetInputEnabled { enabled: bool(enabled, true) },
+            OldActionKind::PanCameraToObject { target, duration, easing } => ActionType::CameraPan {
+                position: VectorExpr::constant(self.target_position(target.as_deref(), owner)?).into(),
+                duration: float(duration, 0.0),
+                easing: easing_value(easing),
+            },
+            OldActionKind::CameraFollowPlayer {} => ActionType::CameraFollowPlayer,
+            OldActionKind::ShowGameText { text, duration } => ActionType::GameTextShow {
+                text: StringExpr::constant(text.unwrap_or_default()).into(),
+                duration: float(duration, 0.0),
+            },
+            OldActionKind::Color { target, color, duration, easing } => ActionType::SetColor {
+                target_objects: targets(target),
+                color: ColorExpr::constant(color.unwrap_or_default()).into(),
+                channel: int(None, 0),
+                duration: float(duration, 0.0),
+                easing: easing_value(easing),
+            },
+            OldActionKind::Damage { target, damage } => ActionType::Damage {
+                target_objects: targets(target),
+                damage: float(damage, 1.0),
+            },
+            OldActionKind::Kill { target } => ActionType::Kill { target_objects: targets(target) },
+            OldActionKind::Finish {} => ActionType::GameFinish,
+            OldActionKind::SetGravity { target, gravity } => ActionType::SetGravity {
+                target_objects: targets(target),
+                gravity: vector(gravity, Vec2::ZERO),
+            },
+            OldActionKind::SetVelocity { target, velocity } => ActionType::SetVelocity {
+                target_objects: targets(target),
+                velocity: vector(velocity, Vec2::ZERO),
+            },
+            OldActionKind::StopScripts {}
+            | OldActionKind::Jump { .. }
+            | OldActionKind::Slam { .. }
+            | OldActionKind::Charge { .. }
+            | OldActionKind::SetVulnerable { .. } => return Err(Dropped::Unsupported),
+        })
+    }
+
+    /// The position of the first target of a camera pan, which nova only accepts as a vector.
+    fn target_position(&self, target: Option<&[i32]>, owner: i32) -> Result<Vec2, Dropped> {
+        let entity_id = target.and_then(<[i32]>::first).copied().unwrap_or(owner);
+
+        self.level
+            .objects
+            .iter()
+            .find(|object| object.entity_id == entity_id)
+            .map(|object| object.position)
+            .ok_or_else(|| Dropped::Invalid("target".to_string()))
+    }
+
+    fn add_object(&mut self, layer: TileLayer, mut object: Object) {
+        let layer_id = self.layer(layer);
+
+        if let Some(layer) = self.level.layers.iter_mut().find(|candidate| candidate.layer_id == layer_id) {
+            layer.children.push(object.entity_id);
+        }
+
+        object.in_layer = layer_id;
+        self.level.objects.push(object);
+        self.report.objects += 1;
+    }
+
+    /// Returns the id of the nova layer for the tile list, creating it on first use.
+    fn layer(&mut self, layer: TileLayer) -> i32 {
+        if let Some(&layer_id) = self.layers.get(&layer) {
+            return layer_id;
+        }
+
+        let layer_id = self.level.layers.iter().map(|layer| layer.layer_id).max().unwrap_or(0) + 1;
+
+        self.level.layers.push(Layer {
+            layer_id,
+            layer_name: layer.layer_name().to_string(),
+            selected: false,
+            ..Layer::default()
+        });
+        self.layers.insert(layer, layer_id);
+
+        layer_id
+    }
+
+    fn add_script(&mut self, script: NovaScript) {
+        self.report.scripts.push(script.script_id);
+        self.level.nova_scripts.push(script);
+    }
+
+    const fn next_entity_id(&mut self) -> i32 {
+        let id = self.next_entity_id;
+        self.next_entity_id += 1;
+        id
+    }
+
+    const fn next_script_id(&mut self) -> i32 {
+        let id = self.next_script_id;
+        self.next_script_id += 1;
+        id
+    }
+}
+
+/// Describes why an action of the script was dropped.
+fn dropped_issue(script: &Script, index: usize, dropped: Dropped, owner: i32) -> ConversionIssue {
+    let action = &script.actions[index];
+
+    match dropped {
+        Dropped::Unsupported => ConversionIssue::UnsupportedAction {
+            script_id: script.script_id,
+            index,
+            action_type: action.action_type,
+        },
+        Dropped::Invalid(name) => ConversionIssue::InvalidActionProperty {
+            script_id: script.script_id,
+            index,
+            property: action.properties.iter().find(|property| property.name == name).cloned().unwrap_or(
+                OldActionProperty { name, value: String::new() },
+            ),
+        },
+        Dropped::MissingScript(script_id) => ConversionIssue::MissingScript { entity_id: owner, script_id },
+    }
+}
+
+fn float(value: Option<OrderedFloat<f32>>, default: f32) -> NovaValue {
+    FloatExpr::constant(value.map_or(default, |value| value.0)).into()
+}
+
+fn int(value: Option<i32>, default: i32) -> NovaValue {
+    IntExpr::constant(value.unwrap_or(default)).into()
+}
+
+fn bool(value: Option<bool>, default: bool) -> NovaValue {
+    BoolExpr::constant(value.unwrap_or(default)).into()
+}
+
+fn vector(value: Option<Vec2>, default: Vec2) -> NovaValue {
+    VectorExpr::constant(value.unwrap_or(default)).into()
+}
+
+fn easing_value(value: Option<i32>) -> NovaValue {
+    EasingExpr::constant(value.unwrap_or_default()).into()
+}
+
+/// Finds the object id a legacy tile id names, ignoring case and separators.
+fn object_id(tile_id: &str) -> Option<ObjectId> {
+    let normalize = |name: &str| -> String {
+        name.chars().filter(char::is_ascii_alphanumeric).map(|character| character.to_ascii_lowercase()).collect()
+    };
+    let tile_id = normalize(tile_id);
+
+    ObjectId::iter().find(|object_id| normalize(&object_id.to_string()) == tile_id)
+}
+
+#[allow(clippy::cast_precision_loss)]
+const fn tile_position(position: IVec2) -> Vec2 {
+    Vec2::new(position.x as f32, position.y as f32)
+}
+
+/// Copies a legacy property onto the object. Returns `false` if nova objects have no equivalent of it.
+fn convert_property(object: &mut Object, property: &ObjectTileProperty) -> bool {
+    let converted = match property {
+        ObjectTileProperty::Rotation(rotation) => {
+            object.rotation = *rotation;
+            return true;
+        }
+        ObjectTileProperty::ScaleX(scale) => {
+            object.scale.x = *scale;
+            return true;
+        }
+        ObjectTileProperty::ScaleY(scale) => {
+            object.scale.y = *scale;
+            return true;
+        }
+        ObjectTileProperty::Tag(tag) => {
+            object.tag.clone_from(tag);
+            return true;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        ObjectTileProperty::Impulse(impulse) => ObjectProperty::Impulse(impulse.round() as i32),
+        ObjectTileProperty::Size(size) => ObjectProperty::Size(*size),
+        ObjectTileProperty::Direction(direction) => ObjectProperty::Direction(*direction),
+        ObjectTileProperty::Bounce(bounce) => ObjectProperty::Bounce(*bounce),
+        ObjectTileProperty::LinkedObjects(objects) => ObjectProperty::LinkedObjects(objects.clone()),
+        ObjectTileProperty::RestoreVelocity(restore) => ObjectProperty::RestoreVelocity(*restore),
+        ObjectTileProperty::ReverseDirection(reverse) => ObjectProperty::ReverseDirection(*reverse),
+        ObjectTileProperty::Sprite(sprite) => match sprite.parse::<Sprite>() {
+            Ok(sprite) => ObjectProperty::Sprite(sprite),
+            Err(_) => return false,
+        },
+        ObjectTileProperty::Color(color) => ObjectProperty::Color(*color),
+        ObjectTileProperty::FlipX(flip) => ObjectProperty::FlipX(*flip),
+        ObjectTileProperty::FlipY(flip) => ObjectProperty::FlipY(*flip),
+        ObjectTileProperty::Text(text) => ObjectProperty::Text(text.clone()),
+        ObjectTileProperty::Width(width) => ObjectProperty::Width(*width),
+        ObjectTileProperty::Height(height) => ObjectProperty::Height(*height),
+        ObjectTileProperty::FontSize(size) => ObjectProperty::FontSize(*size),
+        // Named after Unity's rigidbody types, which nova stores by index.
+        ObjectTileProperty::PhysicsType(physics_type) => match physics_type.to_ascii_lowercase().as_str() {
+            "dynamic" => ObjectProperty::PhysicsType(0),
+            "kinematic" => ObjectProperty::PhysicsType(1),
+            "static" => ObjectProperty::PhysicsType(2),
+            _ => return false,
+        },
+        ObjectTileProperty::Health(health) => ObjectProperty::Health(*health),
+        ObjectTileProperty::Killer(killer) => ObjectProperty::Killer(*killer),
+        ObjectTileProperty::DamageFromJump(damage) => ObjectProperty::DamageFromJump(*damage),
+        ObjectTileProperty::DamageFromDash(damage) => ObjectProperty::DamageFromDash(*damage),
+        ObjectTileProperty::ReverseDirOnDmg(reverse) => ObjectProperty::ReverseDirOnDamage(*reverse),
+        ObjectTileProperty::Floating(floating) => ObjectProperty::Floating(*floating),
+        ObjectTileProperty::Radius(radius) => ObjectProperty::Radius(*radius),
+        ObjectTileProperty::StaticPos(_)
+        | ObjectTileProperty::StaticScale(_)
+        | ObjectTileProperty::StaticRotation(_)
+        | ObjectTileProperty::StaticColor(_)
+        | ObjectTileProperty::BlendMode(_)
+        | ObjectTileProperty::Layer(_)
+        | ObjectTileProperty::OrderInLayer(_)
+        | ObjectTileProperty::FeetOffset(_)
+        | ObjectTileProperty::AllPlayers(_)
+        | ObjectTileProperty::ActivationCount(_)
+        | ObjectTileProperty::StartScript(_)
+        | ObjectTileProperty::ActivationScript(_)
+        | ObjectTileProperty::DeathScript(_) => return false,
+    };
+
+    // Replace the defaults of `get_default_with_id` instead of adding a second copy.
+    let discriminant = std::mem::discriminant(&converted);
+    object.properties.retain(|existing| std::mem::discriminant(existing) != discriminant);
+    object.properties.push(converted);
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::old_script::old_action::OldAction;
+
+    fn action(action_type: OldActionType, properties: &[(&str, &str)]) -> OldAction {
+        OldAction {
+            action_type,
+            wait: false,
+            properties: properties
+                .iter()
+                .map(|&(name, value)| OldActionProperty { name: name.to_string(), value: value.to_string() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn dropped_actions_report_their_cause() {
+        let script_id = Uuid::from_u128(1);
+        let missing_id = Uuid::from_u128(2);
+        let script = Script {
+            script_id,
+            name: "Start".to_string(),
+            creation_date: chrono::DateTime::default(),
+            actions: vec![
+                action(OldActionType::Wait, &[("duration", "abc")]),
+                action(OldActionType::Jump, &[]),
+                action(OldActionType::RunScript, &[("script", &missing_id.to_string())]),
+                action(OldActionType::RunScript, &[]),
+                action(OldActionType::Wait, &[("foo", "bar"), ("duration", "1")]),
+            ],
+        };
+        let mut level = LevelData {
+            object_tiles: vec![ObjectTile {
+                position: IVec2::ZERO,
+                tile_id: "Bumper".to_string(),
+                entity_id: 5,
+                offset: Vec2::ZERO,
+                properties: vec![ObjectTileProperty::StartScript(script_id)],
+            }],
+            ..Default::default()
+        };
+
+        let report = Converter::new(&mut level, &[script]).run();
+        let property = |name: &str, value: &str| OldActionProperty { name: name.to_string(), value: value.to_string() };
+
+        assert_eq!(
+            report.issues,
+            vec![
+                ConversionIssue::InvalidActionProperty { script_id, index: 0, property: property("duration", "abc") },
+                ConversionIssue::UnsupportedAction { script_id, index: 1, action_type: OldActionType::Jump },
+                ConversionIssue::MissingScript { entity_id: 5, script_id: missing_id },
+                ConversionIssue::InvalidActionProperty { script_id, index: 3, property: property("script", "") },
+                ConversionIssue::UnknownActionProperty { script_id, index: 4, property: property("foo", "bar") },
+            ]
+        );
+        assert_eq!(level.nova_scripts[0].actions.len(), 1);
+    }
+
+    fn legacy_level(level_data: LevelData) -> Exolvl {
+        let mut level = Exolvl { level_data, ..Default::default() };
+        level.level_data.nova_level = false;
+        level.local_level.nova_level = false;
+        level
+    }
+
+    fn tile(x: i32, y: i32, tile_id: &str) -> SimpleTile {
+        SimpleTile { position: IVec2::new(x, y), tile_id: tile_id.to_string() }
+    }
+
+    #[test]
+    fn terrain_tiles_merge_into_terrain_corners() {
+        let mut level = legacy_level(LevelData {
+            terrain_tiles: vec![tile(0, 0, "Terrain"), tile(1, 0, "Terrain"), tile(5, 5, "Terrain"), tile(9, 9, "?")],
+            ..Default::default()
+        });
+
+        let report = level.convert_to_nova(&[]);
+
+        assert!(level.level_data.nova_level && level.local_level.nova_level);
+        assert_eq!(report.objects, 2);
+        assert_eq!(
+            report.issues,
+            vec![ConversionIssue::UnknownTile {
+                layer: TileLayer::Terrain,
+                position: IVec2::new(9, 9),
+                tile_id: "?".to_string(),
+            }]
+        );
+
+        let corners: Vec<_> = level
+            .level_data
+            .objects
+            .iter()
+            .map(|object| {
+                assert_eq!(object.object_id, ObjectId::Terrain);
+                match object.properties.as_slice() {
+                    [ObjectProperty::TerrainCorners(rings)] => rings.clone(),
+                    properties => panic!("unexpected properties {properties:?}"),
+                }
+            })
+            .collect();
+
+        // The two neighbouring tiles become a single rectangle with four corners.
+        let mut wide = corners[0][0].clone();
+        wide.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        assert_eq!(corners[0].len(), 1);
+        assert_eq!(
+            wide,
+            [Vec2::new(-0.5, -0.5), Vec2::new(-0.5, 0.5), Vec2::new(1.5, -0.5), Vec2::new(1.5, 0.5)]
+        );
+        assert_eq!(corners[1].len(), 1);
+        assert_eq!(corners[1][0].len(), 4);
+
+        let layer = &level.level_data.layers[0];
+        assert_eq!(layer.layer_name, "Terrain");
+        assert_eq!(layer.children.len(), 2);
+        assert!(level.level_data.objects.iter().all(|object| object.in_layer == layer.layer_id));
+    }
+
+    #[test]
+    fn object_tile_properties() {
+        let mut level = legacy_level(LevelData {
+            object_tiles: vec![ObjectTile {
+                position: IVec2::new(2, 3),
+                tile_id: "bumper".to_string(),
+                entity_id: 8,
+                offset: Vec2::new(0.25, 0.0),
+                properties: vec![
+                    ObjectTileProperty::Rotation(OrderedFloat(90.0)),
+                    ObjectTileProperty::ScaleX(OrderedFloat(2.0)),
+                    ObjectTileProperty::Tag("door".to_string()),
+                    ObjectTileProperty::Impulse(OrderedFloat(2.6)),
+                    ObjectTileProperty::PhysicsType("Static".to_string()),
+                    ObjectTileProperty::Sprite("not a sprite".to_string()),
+                    ObjectTileProperty::BlendMode("Add".to_string()),
+                    ObjectTileProperty::ActivationCount(2),
+                ],
+            }],
+            ..Default::default()
+        });
+
+        let report = level.convert_to_nova(&[]);
+        let object = &level.level_data.objects[0];
+
+        assert_eq!((object.entity_id, object.object_id), (8, ObjectId::Bumper));
+        assert_eq!(object.position, Vec2::new(2.25, 3.0));
+        assert_eq!(object.rotation, OrderedFloat(90.0));
+        assert_eq!(object.scale, Vec2::new(2.0, 1.0));
+        assert_eq!(object.tag, "door");
+        assert!(object.properties.contains(&ObjectProperty::Impulse(3)));
+        assert!(object.properties.contains(&ObjectProperty::PhysicsType(2)));
+        let impulses = object.properties.iter().filter(|property| matches!(property, ObjectProperty::Impulse(_)));
+        assert_eq!(impulses.count(), 1);
+
+        // An activation count only applies to an activation script, which this tile doesn't have.
+        let unsupported = |property| ConversionIssue::UnsupportedProperty { entity_id: 8, property };
+        assert_eq!(
+            report.issues,
+            vec![
+                unsupported(ObjectTileProperty::Sprite("not a sprite".to_string())),
+                unsupported(ObjectTileProperty::BlendMode("Add".to_string())),
+                unsupported(ObjectTileProperty::ActivationCount(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn trigger_scripts() {
+        let script_id = Uuid::from_u128(1);
+        let script = Script {
+            script_id,
+            name: "Open".to_string(),
+            creation_date: chrono::DateTime::default(),
+            actions: vec![action(OldActionType::Wait, &[("duration", "1")])],
+        };
+        let mut level = legacy_level(LevelData {
+            object_tiles: vec![ObjectTile {
+                position: IVec2::ZERO,
+                tile_id: "Bumper".to_string(),
+                entity_id: 3,
+                offset: Vec2::ZERO,
+                properties: vec![
+                    ObjectTileProperty::ActivationScript(script_id),
+                    ObjectTileProperty::ActivationCount(2),
+                    ObjectTileProperty::DeathScript(Uuid::from_u128(2)),
+                ],
+            }],
+            ..Default::default()
+        });
+
+        let report = level.convert_to_nova(&[script]);
+        let converted = &level.level_data.nova_scripts[0];
+
+        assert_eq!(report.issues, vec![ConversionIssue::MissingScript { entity_id: 3, script_id: Uuid::from_u128(2) }]);
+        assert_eq!(level.level_data.nova_scripts.len(), 1);
+        assert_eq!(converted.activation_count, 2);
+        assert_eq!(
+            converted.activation_list[0].kind().unwrap(),
+            ActivatorKind::ObjectActivated { target_objects: ObjectSetExpr::constant([3]).into() }
+        );
+    }
+
+}
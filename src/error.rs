@@ -1,5 +1,8 @@
 //! Errors that the library can return.
 
+use crate::references::Reference;
+use crate::types::object_id::ObjectId;
+
 /// Errors that the library can return while reading/writing the binary format.
 #[derive(Debug)]
 pub enum Error {
@@ -15,6 +18,12 @@ pub enum Error {
     InvalidOldActionType(i32),
     /// The value of an `ActionType` is invalid.
     InvalidActionType(i32),
+    /// The value of an `ActivatorKind` is invalid.
+    InvalidActivatorType(i32),
+    /// An activator has a different number of parameters than its type takes.
+    InvalidActivatorParameterCount { activator_type: i32, expected: usize, found: usize },
+    /// A parameter of an activator doesn't have the type its activator type requires.
+    InvalidActivatorParameter { activator_type: i32, index: usize },
     /// An error occurred while reading a LEB128 value.
     LebRead(leb128::read::Error),
     /// An I/O error occurred while reading/writing to a file.
@@ -22,6 +31,19 @@ pub enum Error {
     #[cfg(feature = "image")]
     /// An error occured while loading an image.
     Image(image::ImageError),
+    /// An animation has more frames than the limit it was imported with.
+    TooManyFrames { limit: usize, found: usize },
+    /// A geometrize shape has an unknown type or too few coordinates for its type.
+    InvalidGeometrizeShape { index: usize, shape_type: u32 },
+    /// An object that should be drawn from its `TerrainCorners` has an `ObjectId` that isn't terrain-like.
+    NotTerrainLike(ObjectId),
+    /// There's no object with the given entity id.
+    MissingObject(i32),
+    #[cfg(feature = "svg")]
+    /// An SVG document isn't well-formed XML.
+    Xml(roxmltree::Error),
+    /// A string that should be base64 isn't.
+    Base64(base64::DecodeError),
     /// An error occurred while parsing a String into an enum value.
     StrumParse(strum::ParseError),
     /// An error occurred while reading a file.
@@ -32,6 +54,8 @@ pub enum Error {
     /// An error occurred while requesting with reqwest.
     #[cfg(feature = "request")]
     InvalidRequest(reqwest::Error),
+    /// An entity couldn't be deleted because other entities still refer to it.
+    DanglingReferences(Vec<Reference>),
 }
 
 impl std::fmt::Display for Error {
@@ -45,16 +69,37 @@ impl std::fmt::Display for Error {
             }
             Self::InvalidOldActionType(value) => write!(f, "invalid old action type: {value}"),
             Self::InvalidActionType(value) => write!(f, "invalid action type: {value}"),
+            Self::InvalidActivatorType(value) => write!(f, "invalid activator type: {value}"),
+            Self::InvalidActivatorParameterCount { activator_type, expected, found } => {
+                write!(f, "activator type {activator_type} takes {expected} parameter(s), found {found}")
+            }
+            Self::InvalidActivatorParameter { activator_type, index } => {
+                write!(f, "parameter {index} of activator type {activator_type} has the wrong type")
+            }
             Self::LebRead(err) => write!(f, "{err}"),
             Self::Io(err) => write!(f, "{err}"),
             #[cfg(feature = "image")]
             Self::Image(err) => write!(f, "{err}"),
+            Self::TooManyFrames { limit, found } => {
+                write!(f, "the animation has {found} frames, more than the limit of {limit}")
+            }
+            Self::InvalidGeometrizeShape { index, shape_type } => {
+                write!(f, "geometrize shape {index} of type {shape_type} is unknown or lacks coordinates")
+            }
+            Self::NotTerrainLike(object_id) => write!(f, "{object_id} objects have no terrain corners"),
+            Self::MissingObject(entity_id) => write!(f, "there is no object with entity id {entity_id}"),
+            #[cfg(feature = "svg")]
+            Self::Xml(err) => write!(f, "{err}"),
+            Self::Base64(err) => write!(f, "{err}"),
             Self::StrumParse(err) => write!(f, "{err}"),
             Self::FileRead(err) => write!(f, "{err}"),
             #[cfg(feature = "serde")]
             Self::SerdeParse(err) => write!(f, "{err}"),
             #[cfg(feature = "request")]
             Self::InvalidRequest(err) => write!(f, "{err}"),
+            Self::DanglingReferences(references) => {
+                write!(f, "entity is still used by {} reference(s)", references.len())
+            }
         }
     }
 }
@@ -79,3 +124,16 @@ impl From<image::ImageError> for Error {
         Self::Image(err)
     }
 }
+
+#[cfg(feature = "svg")]
+impl From<roxmltree::Error> for Error {
+    fn from(err: roxmltree::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(err: base64::DecodeError) -> Self {
+        Self::Base64(err)
+    }
+}
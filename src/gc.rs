@@ -0,0 +1,391 @@
+//! Garbage collection and deduplication of level assets.
+//!
+//! See [`LevelData::gc`].
+
+use crate::references::{DeletePolicy, EntityRef};
+use crate::types::{
+    level_data::LevelData,
+    nova_script::{dynamic_type::DynamicType, variable::VariableRef},
+    object_property::ObjectProperty,
+};
+use crate::Write;
+
+/// Controls what [`LevelData::gc`] is allowed to touch.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GcOptions {
+    /// Remove patterns that no object, prefab or terrain setting uses.
+    pub remove_patterns: bool,
+    /// Remove prefabs that have no instances and aren't used by any script.
+    pub remove_prefabs: bool,
+    /// Remove brushes that don't contain any brush objects.
+    pub remove_brushes: bool,
+    /// Remove global variables that no script reads or writes.
+    pub remove_variables: bool,
+    /// Remove functions (scripts with `is_function` set) that are never called or referenced.
+    pub remove_functions: bool,
+    /// Merge patterns with byte-identical frames into one.
+    pub merge_patterns: bool,
+    /// Merge prefabs with byte-identical items into one.
+    pub merge_prefabs: bool,
+    /// Only compute the report, don't modify the level.
+    pub dry_run: bool,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        Self {
+            remove_patterns: true,
+            remove_prefabs: true,
+            remove_brushes: true,
+            remove_variables: true,
+            remove_functions: true,
+            merge_patterns: true,
+            merge_prefabs: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// What [`LevelData::gc`] removed or merged.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct GcReport {
+    /// The ids of the removed patterns.
+    pub removed_patterns: Vec<i32>,
+    /// The ids of the removed prefabs.
+    pub removed_prefabs: Vec<i32>,
+    /// The ids of the removed brushes.
+    pub removed_brushes: Vec<i32>,
+    /// The ids of the removed global variables.
+    pub removed_variables: Vec<i32>,
+    /// The ids of the removed functions.
+    pub removed_functions: Vec<i32>,
+    /// Merged patterns as `(duplicate, kept)` id pairs. References to the duplicate now point at the kept pattern.
+    pub merged_patterns: Vec<(i32, i32)>,
+    /// Merged prefabs as `(duplicate, kept)` id pairs. References to the duplicate now point at the kept prefab.
+    pub merged_prefabs: Vec<(i32, i32)>,
+    /// How many bytes smaller the serialized level data got.
+    pub bytes_saved: usize,
+}
+
+impl GcReport {
+    /// Whether the garbage collection didn't find anything to do.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.removed_patterns.is_empty()
+            && self.removed_prefabs.is_empty()
+            && self.removed_brushes.is_empty()
+            && self.removed_variables.is_empty()
+            && self.removed_functions.is_empty()
+            && self.merged_patterns.is_empty()
+            && self.merged_prefabs.is_empty()
+    }
+}
+
+impl LevelData {
+    /// Removes unused assets and merges duplicated ones.
+    ///
+    /// Duplicates are merged first, keeping the first occurence and rewriting every reference to the others.
+    /// Then unused assets are removed until nothing else becomes unused, so that e.g. a variable that was only
+    /// used by a removed function gets removed as well.
+    ///
+    /// With [`GcOptions::dry_run`] the level is left untouched and the report describes what would have happened.
+    pub fn gc(&mut self, options: GcOptions) -> GcReport {
+        if options.dry_run {
+            let mut copy = self.clone();
+            return copy.gc(GcOptions { dry_run: false, ..options });
+        }
+
+        let size_before = serialized_len(self);
+        let mut report = GcReport::default();
+
+        if options.merge_patterns {
+            self.merge_duplicate_patterns(&mut report);
+        }
+
+        if options.merge_prefabs {
+            self.merge_duplicate_prefabs(&mut report);
+        }
+
+        if options.remove_brushes {
+            report.removed_brushes.extend(self.brushes.iter().filter(|brush| brush.objects.is_empty()).map(|brush| brush.brush_id));
+            self.brushes.retain(|brush| !brush.objects.is_empty());
+        }
+
+        loop {
+            let references = self.references();
+            let mut removed_any = false;
+
+            if options.remove_patterns {
+                let unused: Vec<i32> = self
+                    .patterns
+                    .iter()
+                    .map(|pattern| pattern.pattern_id)
+                    .filter(|&id| !references.is_used(EntityRef::Pattern(id)))
+                    .collect();
+
+                for &id in &unused {
+                    let _ = self.delete_pattern(id, DeletePolicy::Cascade);
+                }
+
+                removed_any |= !unused.is_empty();
+                report.removed_patterns.extend(unused);
+            }
+
+            if options.remove_prefabs {
+                let unused: Vec<i32> = self
+                    .prefabs
+                    .iter()
+                    .map(|prefab| prefab.prefab_id)
+                    .filter(|&id| !references.is_used(EntityRef::Prefab(id)))
+                    .collect();
+
+                self.prefabs.retain(|prefab| !unused.contains(&prefab.prefab_id));
+
+                removed_any |= !unused.is_empty();
+                report.removed_prefabs.extend(unused);
+            }
+
+            if options.remove_variables {
+                let unused: Vec<i32> = self
+                    .global_variables
+                    .iter()
+                    .map(|variable| variable.variable_id)
+                    .filter(|&id| !references.is_used(EntityRef::Variable(VariableRef::Global(id))))
+                    .collect();
+
+                for &id in &unused {
+                    let _ = self.delete_variable(VariableRef::Global(id), DeletePolicy::Cascade);
+                }
+
+                removed_any |= !unused.is_empty();
+                report.removed_variables.extend(unused);
+            }
+
+            if options.remove_functions {
+                let unused: Vec<i32> = self
+                    .nova_scripts
+                    .iter()
+                    .filter(|script| script.is_function)
+                    .map(|script| script.script_id)
+                    .filter(|&id| !references.is_used(EntityRef::Script(id)))
+                    .collect();
+
+                for &id in &unused {
+                    let _ = self.delete_script(id, DeletePolicy::Cascade);
+                }
+
+                removed_any |= !unused.is_empty();
+                report.removed_functions.extend(unused);
+            }
+
+            if !removed_any {
+                break;
+            }
+        }
+
+        report.bytes_saved = size_before.saturating_sub(serialized_len(self));
+
+        report
+    }
+
+    fn merge_duplicate_patterns(&mut self, report: &mut GcReport) {
+        let mut index = 0;
+
+        while index < self.patterns.len() {
+            let kept = self.patterns[index].pattern_id;
+            let mut duplicate_index = index + 1;
+
+            while duplicate_index < self.patterns.len() {
+                if self.patterns[duplicate_index].pattern_frames == self.patterns[index].pattern_frames {
+                    let duplicate = self.patterns.remove(duplicate_index).pattern_id;
+                    self.replace_pattern_id(duplicate, kept);
+                    report.merged_patterns.push((duplicate, kept));
+                } else {
+                    duplicate_index += 1;
+                }
+            }
+
+            index += 1;
+        }
+    }
+
+    fn merge_duplicate_prefabs(&mut self, report: &mut GcReport) {
+        let mut index = 0;
+
+        while index < self.prefabs.len() {
+            let kept = self.prefabs[index].prefab_id;
+            let mut duplicate_index = index + 1;
+
+            while duplicate_index < self.prefabs.len() {
+                if self.prefabs[duplicate_index].items == self.prefabs[index].items {
+                    let duplicate = self.prefabs.remove(duplicate_index).prefab_id;
+                    self.replace_prefab_id(duplicate, kept);
+                    report.merged_prefabs.push((duplicate, kept));
+                } else {
+                    duplicate_index += 1;
+                }
+            }
+
+            index += 1;
+        }
+    }
+
+    /// Points every reference to the pattern `from` at the pattern `to`.
+    pub fn replace_pattern_id(&mut self, from: i32, to: i32) {
+        let objects = self.objects.iter_mut().chain(self.prefabs.iter_mut().flat_map(|prefab| prefab.items.iter_mut()));
+
+        for object in objects {
+            for property in &mut object.properties {
+                if *property == ObjectProperty::Pattern(from) {
+                    *property = ObjectProperty::Pattern(to);
+                }
+            }
+        }
+
+        if self.custom_terrain_pattern_id == from {
+            self.custom_terrain_pattern_id = to;
+        }
+    }
+
+    /// Points every reference to the prefab `from` at the prefab `to`.
+    pub fn replace_prefab_id(&mut self, from: i32, to: i32) {
+        for object in &mut self.objects {
+            if object.prefab_id == from {
+                object.prefab_id = to;
+            }
+        }
+
+        self.for_each_value_mut(&mut |value| match value.dynamic_type {
+            DynamicType::PrefabConstant if value.int_value == from => value.int_value = to,
+            DynamicType::PrefabListConstant => {
+                for prefab in value.int_list_values.iter_mut().flatten() {
+                    if *prefab == from {
+                        *prefab = to;
+                    }
+                }
+            }
+            _ => {}
+        });
+    }
+}
+
+/// Returns how many bytes the value takes up in the binary format.
+fn serialized_len(value: &impl Write) -> usize {
+    let mut buffer = Vec::new();
+
+    value.write(&mut buffer).map_or(0, |()| buffer.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+    use crate::types::{
+        image::Image,
+        nova_script::{nova_value::NovaValue, static_type::StaticType, variable::Variable},
+        object::Object,
+        pattern::Pattern,
+        prefab::Prefab,
+    };
+
+    fn pattern(pattern_id: i32, size: u32) -> Pattern {
+        Pattern { pattern_id, pattern_frames: vec![Image::blank(size)] }
+    }
+
+    fn global(variable_id: i32, name: &str) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type: StaticType::Int,
+            persistance: None,
+            initial_value: NovaValue::default_constant(StaticType::Int),
+        }
+    }
+
+    const SCRIPT: &str = r#"script 1 "A" { on level_start(); $score = $score + 1; }"#;
+
+    /// Pattern 2 duplicates pattern 1, prefab 5 is unused and is the only user of pattern 3, and function 10 is
+    /// never called and is the only user of the variable `unused`.
+    fn level() -> LevelData {
+        let globals = vec![global(6, "score"), global(7, "unused")];
+        let scripts = format!(r#"{SCRIPT} function 10 "f" {{ $unused = 1; }}"#);
+
+        LevelData {
+            patterns: vec![pattern(1, 1), pattern(2, 1), pattern(3, 2)],
+            objects: vec![
+                Object { entity_id: 1, properties: vec![ObjectProperty::Pattern(2)], ..Default::default() },
+                Object { entity_id: 2, prefab_id: 4, ..Default::default() },
+            ],
+            prefabs: vec![
+                Prefab { prefab_id: 4, prefab_image_data: Image::blank(1), items: vec![Object::default()] },
+                Prefab {
+                    prefab_id: 5,
+                    prefab_image_data: Image::blank(1),
+                    items: vec![Object { properties: vec![ObjectProperty::Pattern(3)], ..Default::default() }],
+                },
+            ],
+            nova_scripts: compile_scripts(&scripts, &globals).unwrap(),
+            global_variables: globals,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn removes_chains_of_unused_assets() {
+        let mut level = level();
+        let size_before = serialized_len(&level);
+        let report = level.gc(GcOptions::default());
+
+        assert_eq!(
+            report,
+            GcReport {
+                removed_patterns: vec![3],
+                removed_prefabs: vec![5],
+                removed_brushes: Vec::new(),
+                removed_variables: vec![7],
+                removed_functions: vec![10],
+                merged_patterns: vec![(2, 1)],
+                merged_prefabs: Vec::new(),
+                bytes_saved: size_before - serialized_len(&level),
+            }
+        );
+        assert!(report.bytes_saved > 0);
+
+        let mut expected = self::level();
+        let globals = vec![global(6, "score")];
+        expected.patterns.truncate(1);
+        expected.objects[0].properties = vec![ObjectProperty::Pattern(1)];
+        expected.prefabs.truncate(1);
+        expected.nova_scripts = compile_scripts(SCRIPT, &globals).unwrap();
+        expected.global_variables = globals;
+
+        assert_eq!(level, expected);
+        assert!(level.gc(GcOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn dry_run() {
+        let mut level = level();
+        let report = level.gc(GcOptions { dry_run: true, ..GcOptions::default() });
+
+        assert_eq!(report, self::level().gc(GcOptions::default()));
+        assert_eq!(level, self::level());
+    }
+
+    #[test]
+    fn options_limit_what_is_touched() {
+        let mut level = level();
+        let options = GcOptions {
+            remove_patterns: false,
+            remove_prefabs: false,
+            remove_variables: false,
+            merge_patterns: false,
+            ..GcOptions::default()
+        };
+
+        assert_eq!(level.gc(options).removed_functions, vec![10]);
+        assert_eq!(level.patterns.len(), 3);
+        assert_eq!(level.prefabs.len(), 2);
+        assert_eq!(level.global_variables.len(), 2);
+    }
+}
@@ -0,0 +1,461 @@
+//! Turning the shapes that [geometrize](https://www.geometrize.co.uk/) approximates an image with into sprites.
+//!
+//! Geometrize exports its shapes as JSON, a list of `{"type", "data", "color"}` objects in pixel coordinates with
+//! the y axis pointing down. [`shapes_to_objects`] turns every shape into one or more sprite objects, and
+//! [`apply_to_level`] adds them to a level. With the `image` feature, [`vectorize`] makes the shapes itself.
+
+#[cfg(feature = "image")]
+mod vectorize;
+
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+use crate::error::Error;
+use crate::types::{
+    color::Color, exolvl::Exolvl, object::Object, object_id::ObjectId, object_property::ObjectProperty, vec2::Vec2,
+};
+
+#[cfg(feature = "image")]
+pub use vectorize::{vectorize, vectorize_with_progress, Progress, VectorizeOptions};
+
+/// The shapes of a geometrize JSON export.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Shapes {
+    /// The shapes from back to front.
+    pub shapes: Vec<Shape>,
+}
+
+/// A single shape as geometrize exports it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shape {
+    /// One of the [`ShapeType`] flags.
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub shape_type: u32,
+    /// The coordinates of the shape in pixels, laid out as described on the [`ShapeType`] variants.
+    pub data: Vec<f32>,
+    /// The red, green, blue and alpha channels.
+    pub color: [u8; 4],
+}
+
+/// The kinds of shapes geometrize makes, with the values of their `type` flag.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShapeType {
+    /// `[x1, y1, x2, y2]`, two opposite corners.
+    Rectangle = 1,
+    /// `[x1, y1, x2, y2, angle]`, a rectangle rotated clockwise by `angle` degrees around its center.
+    RotatedRectangle = 2,
+    /// `[x1, y1, x2, y2, x3, y3]`
+    Triangle = 4,
+    /// `[x, y, rx, ry]`, the center and the radii.
+    Ellipse = 8,
+    /// `[x, y, rx, ry, angle]`, an ellipse rotated clockwise by `angle` degrees.
+    RotatedEllipse = 16,
+    /// `[x, y, r]`
+    Circle = 32,
+    /// `[x1, y1, x2, y2]`
+    Line = 64,
+    /// `[cx, cy, x1, y1, x2, y2]`, the control point followed by the end points.
+    QuadraticBezier = 128,
+    /// `[x1, y1, x2, y2, ...]`
+    Polyline = 256,
+}
+
+impl TryFrom<u32> for ShapeType {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => Self::Rectangle,
+            2 => Self::RotatedRectangle,
+            4 => Self::Triangle,
+            8 => Self::Ellipse,
+            16 => Self::RotatedEllipse,
+            32 => Self::Circle,
+            64 => Self::Line,
+            128 => Self::QuadraticBezier,
+            256 => Self::Polyline,
+            _ => return Err(value),
+        })
+    }
+}
+
+/// Where [`apply_to_level`] puts the objects among the ones already in the layer.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZOrder {
+    /// In front of the objects already in the layer.
+    #[default]
+    Front,
+    /// Behind the objects already in the layer.
+    Back,
+}
+
+/// How the shapes are placed in the world.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeometrizeOptions {
+    /// The layer the objects go in. `None` is the first layer of the level, or a new one if it has none.
+    pub layer_id: Option<i32>,
+    /// Where the top left corner of the image ends up in the world.
+    pub origin: Vec2,
+    /// The size of a pixel in world units.
+    pub scale: f32,
+    /// The width of lines, Béziers and polylines in pixels. Geometrize draws them one pixel wide.
+    pub line_width: f32,
+    /// Where the objects go among the ones already in the layer. The shapes keep their order among themselves.
+    pub z_order: ZOrder,
+}
+
+impl Default for GeometrizeOptions {
+    fn default() -> Self {
+        Self { layer_id: None, origin: Vec2::ZERO, scale: 0.1, line_width: 1.0, z_order: ZOrder::Front }
+    }
+}
+
+impl Shapes {
+    /// Reads the shapes from a geometrize JSON export.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file can't be opened, or [`Error::SerdeParse`] if it isn't a geometrize JSON
+    /// export.
+    #[cfg(feature = "serde")]
+    pub fn read_from_json(path: &Path) -> Result<Self, Error> {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+
+        serde_json::from_reader(reader).map_err(Error::SerdeParse)
+    }
+}
+
+/// The number of straight pieces a quadratic Bézier is drawn with.
+const BEZIER_SEGMENTS: usize = 8;
+
+/// Turns the shapes into sprites, from back to front, with entity ids counting up from 1.
+///
+/// Rectangles become `SpriteSquare`s, ellipses and circles `SpriteCircle`s, and lines, Béziers and polylines a
+/// `SpriteSquare` for every straight piece. Triangles are split along their longest side's altitude into two
+/// `SpriteRightTriangle`s, since sprites can only be scaled along their own axes.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidGeometrizeShape`] if a shape has an unknown type or too few coordinates for its type.
+pub fn shapes_to_objects(shapes: &Shapes, options: &GeometrizeOptions) -> Result<Vec<Object>, Error> {
+    let mut objects = vec![];
+
+    for (index, shape) in shapes.shapes.iter().enumerate() {
+        let invalid = || Error::InvalidGeometrizeShape { index, shape_type: shape.shape_type };
+        let shape_type = ShapeType::try_from(shape.shape_type).map_err(|_| invalid())?;
+        let data = &shape.data;
+
+        let expected = match shape_type {
+            ShapeType::Rectangle | ShapeType::Ellipse | ShapeType::Line => 4,
+            ShapeType::RotatedRectangle | ShapeType::RotatedEllipse => 5,
+            ShapeType::Triangle | ShapeType::QuadraticBezier => 6,
+            ShapeType::Circle => 3,
+            ShapeType::Polyline => 4.max(data.len() & !1),
+        };
+
+        if data.len() < expected {
+            return Err(invalid());
+        }
+
+        let point = |index: usize| options.origin + Vec2::new(data[index], -data[index + 1]) * options.scale;
+        let rectangle = |rotation: f32| {
+            Sprite::new(ObjectId::SpriteSquare, point(0).midpoint(point(2)), (point(2) - point(0)).abs(), rotation)
+        };
+        let ellipse = |radii: Vec2, rotation: f32| {
+            Sprite::new(ObjectId::SpriteCircle, point(0), radii.abs() * options.scale * 2.0, rotation)
+        };
+        let width = options.line_width * options.scale;
+
+        // Geometrize rotates clockwise with the y axis pointing down, which is counter-clockwise in the world.
+        let sprites = match shape_type {
+            ShapeType::Rectangle => vec![rectangle(0.0)],
+            ShapeType::RotatedRectangle => vec![rectangle(-data[4])],
+            ShapeType::Triangle => triangle(point(0), point(2), point(4)),
+            ShapeType::Ellipse => vec![ellipse(Vec2::new(data[2], data[3]), 0.0)],
+            ShapeType::RotatedEllipse => vec![ellipse(Vec2::new(data[2], data[3]), -data[4])],
+            ShapeType::Circle => vec![ellipse(Vec2::splat(data[2]), 0.0)],
+            ShapeType::Line => vec![line(point(0), point(2), width)],
+            ShapeType::QuadraticBezier => {
+                let (control, start, end) = (point(0), point(2), point(4));
+                #[allow(clippy::cast_precision_loss)]
+                let at = |step: usize| {
+                    let t = step as f32 / BEZIER_SEGMENTS as f32;
+
+                    start * (1.0 - t) * (1.0 - t) + control * 2.0 * (1.0 - t) * t + end * t * t
+                };
+
+                (0..BEZIER_SEGMENTS).map(|step| line(at(step), at(step + 1), width)).collect()
+            }
+            ShapeType::Polyline => {
+                (0..expected / 2 - 1).map(|pair| line(point(pair * 2), point(pair * 2 + 2), width)).collect()
+            }
+        };
+
+        let [red, green, blue, alpha] = shape.color.map(|channel| f32::from(channel) / 255.0);
+
+        for sprite in sprites {
+            let mut object = Object::get_default_with_id(sprite.object_id, options.layer_id.unwrap_or_default());
+            object.entity_id = i32::try_from(objects.len()).map_or(i32::MAX, |count| count + 1);
+            object.position = sprite.position;
+            object.scale = sprite.scale;
+            object.rotation = sprite.rotation.rem_euclid(360.0).into();
+            object.properties.retain(|property| !matches!(property, ObjectProperty::Color(_)));
+            object.properties.push(ObjectProperty::Color(Color::new(red, green, blue, alpha)));
+
+            if sprite.flip_y {
+                object.properties.push(ObjectProperty::FlipY(true));
+            }
+
+            objects.push(object);
+        }
+    }
+
+    Ok(objects)
+}
+
+/// Adds the sprites of the shapes to a layer of the level, with new entity ids.
+///
+/// The layer is created if the level doesn't have it. Nothing else about the level changes, so the theme may need
+/// a background that suits the image.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidGeometrizeShape`] if a shape has an unknown type or too few coordinates for its type. The
+/// level is left unchanged then.
+pub fn apply_to_level(exolvl: &mut Exolvl, shapes: &Shapes, options: &GeometrizeOptions) -> Result<(), Error> {
+    let mut objects = shapes_to_objects(shapes, options)?;
+    let level = &mut exolvl.level_data;
+
+    let layer_id = match options.layer_id.or_else(|| level.layers.first().map(|layer| layer.layer_id)) {
+        Some(layer_id) => layer_id,
+        None => level.layers.iter().map(|layer| layer.layer_id).max().unwrap_or(0) + 1,
+    };
+
+    let first_entity_id = level.next_entity_id();
+
+    for (object, entity_id) in objects.iter_mut().zip(first_entity_id..) {
+        object.entity_id = entity_id;
+        object.in_layer = layer_id;
+    }
+
+    let entity_ids = objects.iter().map(|object| object.entity_id);
+    let layer = level.layer_or_insert(layer_id, "Geometrize");
+
+    match options.z_order {
+        ZOrder::Front => layer.children.extend(entity_ids),
+        ZOrder::Back => drop(layer.children.splice(0..0, entity_ids)),
+    }
+
+    level.objects.append(&mut objects);
+
+    Ok(())
+}
+
+/// A sprite in world space, before it becomes an object.
+#[derive(Clone, Copy, Debug)]
+struct Sprite {
+    object_id: ObjectId,
+    position: Vec2,
+    scale: Vec2,
+    /// Counter-clockwise, in degrees.
+    rotation: f32,
+    flip_y: bool,
+}
+
+impl Sprite {
+    const fn new(object_id: ObjectId, position: Vec2, scale: Vec2, rotation: f32) -> Self {
+        Self { object_id, position, scale, rotation, flip_y: false }
+    }
+}
+
+/// A square sprite along the line, `width` thick.
+fn line(start: Vec2, end: Vec2, width: f32) -> Sprite {
+    let direction = end - start;
+    let rotation = direction.to_angle().to_degrees();
+
+    Sprite::new(ObjectId::SpriteSquare, start.midpoint(end), Vec2::new(direction.length() + width, width), rotation)
+}
+
+/// Splits the triangle into two right triangles, meeting at the foot of the altitude onto its longest side.
+fn triangle(a: Vec2, b: Vec2, c: Vec2) -> Vec<Sprite> {
+    let sides = [(a, b, c), (b, c, a), (c, a, b)];
+    let Some((start, end, apex)) =
+        sides.into_iter().max_by(|(a1, b1, _), (a2, b2, _)| a1.distance(*b1).total_cmp(&a2.distance(*b2)))
+    else {
+        return vec![];
+    };
+
+    let base = end - start;
+    let length_squared = base.length_squared();
+
+    if length_squared <= f32::EPSILON {
+        return vec![];
+    }
+
+    let foot = start + base * ((apex - start).dot(base) / length_squared).clamp(0.0, 1.0);
+
+    [start, end].into_iter().filter_map(|corner| right_triangle(foot, corner, apex)).collect()
+}
+
+/// The `SpriteRightTriangle` with its right angle at `corner`, and its legs towards `along` and `up`.
+fn right_triangle(corner: Vec2, along: Vec2, up: Vec2) -> Option<Sprite> {
+    let (leg, height) = (along - corner, up - corner);
+
+    if leg.length() <= f32::EPSILON || height.length() <= f32::EPSILON {
+        return None;
+    }
+
+    // The sprite's right angle is in its bottom left corner, its legs along +x and +y.
+    let rotation = leg.to_angle().to_degrees();
+    let flip_y = leg.perp_dot(height) < 0.0;
+    let position = corner + (leg + height) / 2.0;
+    let scale = Vec2::new(leg.length(), height.length());
+
+    Some(Sprite { flip_y, ..Sprite::new(ObjectId::SpriteRightTriangle, position, scale, rotation) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::signed_area;
+    use crate::types::layer::Layer;
+
+    fn shapes(shapes: impl IntoIterator<Item = (u32, Vec<f32>)>) -> Shapes {
+        Shapes {
+            shapes: shapes.into_iter().map(|(shape_type, data)| Shape { shape_type, data, color: [255; 4] }).collect(),
+        }
+    }
+
+    fn placement(object: &Object) -> (ObjectId, Vec2, Vec2, f32) {
+        (object.object_id, object.position, object.scale, object.rotation.0)
+    }
+
+    fn close(a: Vec2, b: Vec2) -> bool {
+        a.abs_diff_eq(b, 1e-4)
+    }
+
+    #[test]
+    fn rectangles_and_ellipses() {
+        let shapes = Shapes {
+            shapes: vec![
+                Shape { shape_type: 1, data: vec![0.0, 0.0, 20.0, 10.0], color: [255, 0, 51, 128] },
+                Shape { shape_type: 2, data: vec![0.0, 0.0, 20.0, 10.0, 30.0], color: [255; 4] },
+                Shape { shape_type: 8, data: vec![10.0, 10.0, 5.0, 2.0], color: [255; 4] },
+                Shape { shape_type: 32, data: vec![10.0, 10.0, 3.0], color: [255; 4] },
+            ],
+        };
+        let options = GeometrizeOptions { origin: Vec2::new(1.0, 1.0), ..GeometrizeOptions::default() };
+        let objects = shapes_to_objects(&shapes, &options).unwrap();
+
+        assert_eq!(
+            objects.iter().map(placement).collect::<Vec<_>>(),
+            [
+                (ObjectId::SpriteSquare, Vec2::new(2.0, 0.5), Vec2::new(2.0, 1.0), 0.0),
+                (ObjectId::SpriteSquare, Vec2::new(2.0, 0.5), Vec2::new(2.0, 1.0), 330.0),
+                (ObjectId::SpriteCircle, Vec2::new(2.0, 0.0), Vec2::new(1.0, 0.4), 0.0),
+                (ObjectId::SpriteCircle, Vec2::new(2.0, 0.0), Vec2::splat(0.6), 0.0),
+            ]
+        );
+        assert_eq!(objects.iter().map(|object| object.entity_id).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert!(objects[0].properties.contains(&ObjectProperty::Color(Color::new(1.0, 0.0, 0.2, 128.0 / 255.0))));
+    }
+
+    #[test]
+    fn triangles_split_into_right_triangles() {
+        let shapes = shapes([(4, vec![0.0, 0.0, 40.0, 0.0, 10.0, 30.0])]);
+        let objects = shapes_to_objects(&shapes, &GeometrizeOptions::default()).unwrap();
+        let corners = [Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(1.0, -3.0)];
+
+        assert_eq!(objects.len(), 2);
+
+        let mut area = 0.0;
+        for object in &objects {
+            assert_eq!(object.object_id, ObjectId::SpriteRightTriangle);
+
+            let rings = object.world_rings();
+            area += signed_area(&rings[0]).abs();
+
+            // Every triangle has two corners of the shape, and the foot of the altitude onto the longest side.
+            let shared = rings[0].iter().filter(|point| corners.iter().any(|corner| close(**point, *corner))).count();
+            assert_eq!(shared, 2, "{rings:?}");
+        }
+
+        assert!((area - 6.0).abs() < 1e-4, "{area}");
+    }
+
+    #[test]
+    fn lines() {
+        let shapes = shapes([
+            (64, vec![0.0, 0.0, 30.0, 40.0]),
+            (256, vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 99.0]),
+            (128, vec![5.0, 5.0, 0.0, 0.0, 10.0, 0.0]),
+        ]);
+        let options = GeometrizeOptions { line_width: 2.0, ..GeometrizeOptions::default() };
+        let objects = shapes_to_objects(&shapes, &options).unwrap();
+
+        assert_eq!(objects.len(), 1 + 2 + BEZIER_SEGMENTS);
+        assert!(objects.iter().all(|object| object.object_id == ObjectId::SpriteSquare));
+
+        // The line is 5 units long, and reaches half of its width past either end.
+        let (_, position, scale, rotation) = placement(&objects[0]);
+        assert!(close(position, Vec2::new(1.5, -2.0)));
+        assert!(close(scale, Vec2::new(5.2, 0.2)));
+        assert!((rotation - (-4.0f32).atan2(3.0).to_degrees().rem_euclid(360.0)).abs() < 1e-3);
+
+        // The odd coordinate at the end of the polyline is ignored.
+        assert!(close(objects[2].position, Vec2::new(1.0, -0.5)));
+        assert!((objects[2].rotation.0 - 270.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn invalid_shapes() {
+        // An unknown type, a rectangle with three coordinates and a polyline with a single point.
+        for (shape_type, data) in [(3, vec![0.0; 6]), (1, vec![0.0; 3]), (256, vec![0.0; 3])] {
+            let shapes = shapes([(1, vec![0.0; 4]), (shape_type, data)]);
+
+            assert!(matches!(
+                shapes_to_objects(&shapes, &GeometrizeOptions::default()),
+                Err(Error::InvalidGeometrizeShape { index: 1, shape_type: found }) if found == shape_type
+            ));
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let mut exolvl = Exolvl::default();
+        let level = &mut exolvl.level_data;
+        level.objects.push(Object { entity_id: 5, in_layer: 2, ..Default::default() });
+        level.layers = vec![Layer { layer_id: 2, children: vec![5], ..Default::default() }];
+
+        let shapes = shapes([(1, vec![0.0; 4]), (32, vec![0.0; 3])]);
+        let back = GeometrizeOptions { z_order: ZOrder::Back, ..Default::default() };
+        apply_to_level(&mut exolvl, &shapes, &back).unwrap();
+        apply_to_level(&mut exolvl, &shapes, &GeometrizeOptions { layer_id: Some(9), ..Default::default() }).unwrap();
+
+        let level = &exolvl.level_data;
+        assert_eq!(level.layers[0].children, [6, 7, 5]);
+        assert_eq!(level.layers[1].layer_id, 9);
+        assert_eq!(level.layers[1].layer_name, "Geometrize");
+        assert_eq!(level.layers[1].children, [8, 9]);
+        assert_eq!(
+            level.objects.iter().map(|object| (object.entity_id, object.in_layer)).collect::<Vec<_>>(),
+            [(5, 2), (6, 2), (7, 2), (8, 9), (9, 9)]
+        );
+
+        let before = exolvl.clone();
+        let invalid = Shapes { shapes: vec![Shape { shape_type: 0, data: vec![], color: [0; 4] }] };
+        assert!(apply_to_level(&mut exolvl, &invalid, &GeometrizeOptions::default()).is_err());
+        assert_eq!(exolvl, before);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json() {
+        let json = r#"{"shapes": [{"type": 32, "data": [1, 2, 3], "color": [4, 5, 6, 7]}]}"#;
+        let shapes: Shapes = serde_json::from_str(json).unwrap();
+
+        assert_eq!(shapes.shapes, [Shape { shape_type: 32, data: vec![1.0, 2.0, 3.0], color: [4, 5, 6, 7] }]);
+    }
+}
@@ -0,0 +1,583 @@
+//! Approximating an image with shapes like geometrize does, without the external tool.
+//!
+//! Every step tries a number of random shapes, improves each of them by hill climbing with random mutations, and
+//! adds the one that brings the image closest to the target, in the color that suits it best.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use image::{imageops::FilterType, DynamicImage, Rgba, RgbaImage};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{Shape, ShapeType, Shapes};
+
+/// How [`vectorize`] approximates an image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VectorizeOptions {
+    /// How many shapes are added on top of the background.
+    pub shape_count: usize,
+    /// The kinds of shapes to pick from. Lines, Béziers and polylines are left out.
+    pub shape_types: Vec<ShapeType>,
+    /// The opacity of every shape.
+    pub alpha: u8,
+    /// How many random shapes are tried for every shape that's added.
+    pub candidates: usize,
+    /// How many mutations in a row have to fail to improve a shape before it's done climbing.
+    pub max_age: usize,
+    /// Images larger than this in either direction are scaled down to fit while working. The shapes use the
+    /// coordinates of the original image either way.
+    pub max_size: Option<u32>,
+    /// The same seed and options always give the same shapes, however many threads there are.
+    pub seed: u64,
+    /// How many threads try shapes at once. `None` uses every core.
+    pub threads: Option<NonZeroUsize>,
+}
+
+impl Default for VectorizeOptions {
+    fn default() -> Self {
+        Self {
+            shape_count: 300,
+            shape_types: vec![
+                ShapeType::Rectangle,
+                ShapeType::RotatedRectangle,
+                ShapeType::Triangle,
+                ShapeType::Ellipse,
+                ShapeType::RotatedEllipse,
+            ],
+            alpha: 128,
+            candidates: 50,
+            max_age: 100,
+            max_size: Some(256),
+            seed: 0,
+            threads: None,
+        }
+    }
+}
+
+/// How far [`vectorize_with_progress`] got, reported after every shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    /// The number of shapes added so far, not counting the background.
+    pub shapes: usize,
+    /// The number of shapes that will be added.
+    pub total: usize,
+    /// The root mean square difference between the shapes and the image, from `0` for identical to `1`.
+    pub score: f32,
+}
+
+/// Approximates the image with shapes, see [`vectorize_with_progress`].
+#[must_use]
+pub fn vectorize(image: &RgbaImage, options: &VectorizeOptions) -> Shapes {
+    vectorize_with_progress(image, options, |_| {})
+}
+
+/// Approximates the image with shapes, calling `progress` after every shape.
+///
+/// The first shape is a rectangle covering the image in its average color, followed by the shapes from back to
+/// front, like a geometrize JSON export. Transparent parts of the image are treated as the average color.
+pub fn vectorize_with_progress(
+    image: &RgbaImage,
+    options: &VectorizeOptions,
+    mut progress: impl FnMut(Progress),
+) -> Shapes {
+    let (original_width, original_height) = image.dimensions();
+
+    if original_width == 0 || original_height == 0 {
+        return Shapes::default();
+    }
+
+    let target = match options.max_size {
+        Some(max_size) if original_width > max_size || original_height > max_size => {
+            let max_size = max_size.max(1);
+
+            DynamicImage::ImageRgba8(image.clone()).resize(max_size, max_size, FilterType::Triangle).to_rgba8()
+        }
+        _ => image.clone(),
+    };
+
+    let background = average_color(&target);
+    let target = flatten(target, background);
+    let (width, height) = target.dimensions();
+    #[allow(clippy::cast_precision_loss)]
+    let factor = (original_width as f32 / width as f32, original_height as f32 / height as f32);
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut shapes = vec![Shape {
+        shape_type: ShapeType::Rectangle as u32,
+        data: vec![0.0, 0.0, original_width as f32, original_height as f32],
+        color: background.0,
+    }];
+
+    let shape_types: Vec<ShapeType> =
+        options.shape_types.iter().copied().filter(|&shape_type| Candidate::supports(shape_type)).collect();
+
+    if shape_types.is_empty() || options.candidates == 0 {
+        return Shapes { shapes };
+    }
+
+    let threads = options
+        .threads
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+        .min(options.candidates);
+    let mut current = RgbaImage::from_pixel(width, height, background);
+    let mut score = difference_full(&target, &current);
+
+    for step in 0..options.shape_count {
+        let workspace =
+            Workspace { target: &target, current: &current, score, alpha: options.alpha, shape_types: &shape_types };
+
+        let best = thread::scope(|scope| {
+            let workspace = &workspace;
+            // Every thread has to be started before the first one is joined.
+            #[allow(clippy::needless_collect)]
+            let handles: Vec<_> = (0..threads)
+                .map(|first| {
+                    scope.spawn(move || {
+                        (first..options.candidates)
+                            .step_by(threads)
+                            .map(|index| {
+                                let mut rng = StdRng::seed_from_u64(candidate_seed(options.seed, step, index));
+
+                                (workspace.climb(&mut rng, options.max_age), index)
+                            })
+                            .min_by(by_score)
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().ok().flatten())
+                .min_by(by_score)
+                .map(|(best, _)| best)
+        });
+
+        if let Some(best) = best.filter(|best| best.score < score) {
+            let lines = best.candidate.scanlines(width, height);
+            let color = workspace.color(&lines);
+
+            draw(&mut current, &lines, color);
+            score = best.score;
+            shapes.push(best.candidate.to_shape(color, factor));
+        }
+
+        progress(Progress { shapes: step + 1, total: options.shape_count, score });
+    }
+
+    Shapes { shapes }
+}
+
+/// A shape being climbed, in the pixel coordinates of the scaled image.
+#[derive(Clone, Debug, PartialEq)]
+struct Candidate {
+    shape_type: ShapeType,
+    data: Vec<f32>,
+}
+
+/// What a coordinate of a [`Candidate`] means, which decides how it's mutated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Parameter {
+    X,
+    Y,
+    Radius,
+    Angle,
+}
+
+impl Candidate {
+    const fn supports(shape_type: ShapeType) -> bool {
+        !matches!(shape_type, ShapeType::Line | ShapeType::QuadraticBezier | ShapeType::Polyline)
+    }
+
+    fn random(rng: &mut StdRng, shape_type: ShapeType, width: u32, height: u32) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let (width, height) = (width as f32, height as f32);
+        let reach = (width.max(height) / 8.0).max(2.0);
+        let (x, y) = (rng.random_range(0.0..width), rng.random_range(0.0..height));
+        let mut offset = || rng.random_range(-reach..reach);
+
+        let mut data = match shape_type {
+            ShapeType::Rectangle | ShapeType::RotatedRectangle => vec![x, y, x + offset(), y + offset()],
+            ShapeType::Triangle => vec![x, y, x + offset(), y + offset(), x + offset(), y + offset()],
+            ShapeType::Ellipse | ShapeType::RotatedEllipse => vec![x, y, offset().abs() + 1.0, offset().abs() + 1.0],
+            _ => vec![x, y, offset().abs() + 1.0],
+        };
+
+        if matches!(shape_type, ShapeType::RotatedRectangle | ShapeType::RotatedEllipse) {
+            data.push(rng.random_range(0.0..360.0));
+        }
+
+        Self { shape_type, data }
+    }
+
+    const fn parameter(&self, index: usize) -> Parameter {
+        match (self.shape_type, index) {
+            (ShapeType::RotatedRectangle | ShapeType::RotatedEllipse, 4) => Parameter::Angle,
+            (ShapeType::Ellipse | ShapeType::RotatedEllipse | ShapeType::Circle, 2 | 3) => Parameter::Radius,
+            (_, index) if index % 2 == 0 => Parameter::X,
+            _ => Parameter::Y,
+        }
+    }
+
+    /// Moves one of the coordinates by a random amount, keeping points inside the image and radii below half its size.
+    fn mutate(&mut self, rng: &mut StdRng, width: u32, height: u32) {
+        #[allow(clippy::cast_precision_loss)]
+        let (width, height) = (width as f32, height as f32);
+        let reach = (width.max(height) / 16.0).max(1.0);
+        let max_radius = (width.max(height) / 2.0).max(1.0);
+        let index = rng.random_range(0..self.data.len());
+        let value = self.data[index];
+
+        self.data[index] = match self.parameter(index) {
+            Parameter::X => (value + rng.random_range(-reach..reach)).clamp(0.0, width),
+            Parameter::Y => (value + rng.random_range(-reach..reach)).clamp(0.0, height),
+            Parameter::Radius => (value + rng.random_range(-reach..reach)).clamp(1.0, max_radius),
+            Parameter::Angle => (value + rng.random_range(-30.0..30.0)).rem_euclid(360.0),
+        };
+    }
+
+    /// The outline of the shape, with ellipses as polygons.
+    fn polygon(&self) -> Vec<(f32, f32)> {
+        const ELLIPSE_SEGMENTS: usize = 32;
+
+        let data = &self.data;
+        let rotated_type = matches!(self.shape_type, ShapeType::RotatedRectangle | ShapeType::RotatedEllipse);
+        let rotation = if rotated_type { data[4].to_radians() } else { 0.0 };
+        let (sin, cos) = rotation.sin_cos();
+        let rotated =
+            |(cx, cy): (f32, f32), (x, y): (f32, f32)| (x.mul_add(cos, -y * sin) + cx, x.mul_add(sin, y * cos) + cy);
+
+        match self.shape_type {
+            ShapeType::Rectangle | ShapeType::RotatedRectangle => {
+                let center = (f32::midpoint(data[0], data[2]), f32::midpoint(data[1], data[3]));
+                let (half_width, half_height) = ((data[2] - data[0]).abs() / 2.0, (data[3] - data[1]).abs() / 2.0);
+
+                [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                    .map(|(x, y)| rotated(center, (x * half_width, y * half_height)))
+                    .to_vec()
+            }
+            ShapeType::Triangle => vec![(data[0], data[1]), (data[2], data[3]), (data[4], data[5])],
+            _ => {
+                let radii = if self.shape_type == ShapeType::Circle { (data[2], data[2]) } else { (data[2], data[3]) };
+
+                (0..ELLIPSE_SEGMENTS)
+                    .map(|segment| {
+                        #[allow(clippy::cast_precision_loss)]
+                        let angle = segment as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+
+                        rotated((data[0], data[1]), (radii.0 * angle.cos(), radii.1 * angle.sin()))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the rows of pixels whose centers are inside the shape, clipped to the image.
+    fn scanlines(&self, width: u32, height: u32) -> Vec<Scanline> {
+        let polygon = self.polygon();
+        let (top, bottom) =
+            polygon.iter().fold((f32::MAX, f32::MIN), |(top, bottom), &(_, y)| (top.min(y), bottom.max(y)));
+        let mut lines = vec![];
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+        for y in (top.floor().max(0.0) as u32)..(bottom.ceil().max(0.0) as u32).min(height) {
+            let center = y as f32 + 0.5;
+            let (mut left, mut right) = (f32::MAX, f32::MIN);
+
+            for (index, &(x1, y1)) in polygon.iter().enumerate() {
+                let (x2, y2) = polygon[(index + 1) % polygon.len()];
+
+                if (y1 <= center) != (y2 <= center) {
+                    let x = (x2 - x1).mul_add((center - y1) / (y2 - y1), x1);
+                    (left, right) = (left.min(x), right.max(x));
+                }
+            }
+
+            let (start, end) = ((left - 0.5).ceil().max(0.0), (right - 0.5).floor().min(width as f32 - 1.0));
+
+            if start <= end {
+                lines.push(Scanline { y, start: start as u32, end: end as u32 });
+            }
+        }
+
+        lines
+    }
+
+    /// Converts the candidate to a geometrize shape in the coordinates of the original image.
+    fn to_shape(&self, color: Rgba<u8>, (x_factor, y_factor): (f32, f32)) -> Shape {
+        let data = (self.data.iter().enumerate())
+            .map(|(index, &value)| {
+                let factor = match self.parameter(index) {
+                    Parameter::X => x_factor,
+                    Parameter::Y => y_factor,
+                    Parameter::Radius => if index == 3 { y_factor } else { x_factor },
+                    Parameter::Angle => 1.0,
+                };
+
+                value * factor
+            })
+            .collect();
+
+        Shape { shape_type: self.shape_type as u32, data, color: color.0 }
+    }
+}
+
+/// The pixels `start..=end` of row `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Scanline {
+    y: u32,
+    start: u32,
+    end: u32,
+}
+
+/// A candidate with the score the image would have with it added.
+#[derive(Clone, Debug)]
+struct Scored {
+    candidate: Candidate,
+    score: f32,
+}
+
+/// What every thread needs to try shapes during a step.
+struct Workspace<'a> {
+    target: &'a RgbaImage,
+    current: &'a RgbaImage,
+    score: f32,
+    alpha: u8,
+    shape_types: &'a [ShapeType],
+}
+
+impl Workspace<'_> {
+    /// Starts from a random shape and keeps random mutations that improve it until `max_age` fail in a row.
+    fn climb(&self, rng: &mut StdRng, max_age: usize) -> Scored {
+        let (width, height) = self.target.dimensions();
+        let shape_type = self.shape_types[rng.random_range(0..self.shape_types.len())];
+        let candidate = Candidate::random(rng, shape_type, width, height);
+        let mut best = Scored { score: self.score_of(&candidate), candidate };
+        let mut age = 0;
+
+        while age < max_age {
+            let mut candidate = best.candidate.clone();
+            candidate.mutate(rng, width, height);
+            let score = self.score_of(&candidate);
+
+            if score < best.score {
+                best = Scored { candidate, score };
+                age = 0;
+            } else {
+                age += 1;
+            }
+        }
+
+        best
+    }
+
+    fn score_of(&self, candidate: &Candidate) -> f32 {
+        let (width, height) = self.target.dimensions();
+        let lines = candidate.scanlines(width, height);
+
+        if lines.is_empty() {
+            return self.score;
+        }
+
+        let color = self.color(&lines);
+        let mut total = total_from_score(self.score, width, height);
+
+        for line in &lines {
+            for x in line.start..=line.end {
+                let target = self.target.get_pixel(x, line.y);
+                let before = self.current.get_pixel(x, line.y);
+                let after = blend(*before, color);
+
+                total += squared_difference(*target, after) - squared_difference(*target, *before);
+            }
+        }
+
+        score_from_total(total, width, height)
+    }
+
+    /// The color that brings the pixels of the shape closest to the target when drawn with the shape's alpha.
+    fn color(&self, lines: &[Scanline]) -> Rgba<u8> {
+        let alpha = f64::from(self.alpha.max(1)) / 255.0;
+        let mut sum = [0.0_f64; 3];
+        let mut count = 0.0;
+
+        for line in lines {
+            for x in line.start..=line.end {
+                let (target, current) = (self.target.get_pixel(x, line.y), self.current.get_pixel(x, line.y));
+
+                for channel in 0..3 {
+                    let (target, current) = (f64::from(target[channel]), f64::from(current[channel]));
+                    sum[channel] += (target - current) / alpha + current;
+                }
+
+                count += 1.0;
+            }
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let channel = |sum: f64| (sum / f64::max(count, 1.0)).round().clamp(0.0, 255.0) as u8;
+
+        Rgba([channel(sum[0]), channel(sum[1]), channel(sum[2]), self.alpha])
+    }
+}
+
+/// Orders candidates by their score, and by their index when they're equally good so the order of the threads
+/// doesn't matter.
+fn by_score((a, a_index): &(Scored, usize), (b, b_index): &(Scored, usize)) -> std::cmp::Ordering {
+    a.score.total_cmp(&b.score).then(a_index.cmp(b_index))
+}
+
+/// A seed for every candidate of every step, so the shapes don't depend on which thread tries them.
+const fn candidate_seed(seed: u64, step: usize, index: usize) -> u64 {
+    seed ^ ((step as u64) << 32 | index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// The average color of the image, weighted by alpha, and opaque.
+fn average_color(image: &RgbaImage) -> Rgba<u8> {
+    let mut sum = [0_u64; 3];
+    let mut weight = 0_u64;
+
+    for pixel in image.pixels() {
+        let alpha = u64::from(pixel[3]);
+
+        for channel in 0..3 {
+            sum[channel] += u64::from(pixel[channel]) * alpha;
+        }
+
+        weight += alpha;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let channel = |sum: u64| (sum / weight.max(1)) as u8;
+
+    Rgba([channel(sum[0]), channel(sum[1]), channel(sum[2]), 255])
+}
+
+/// Draws the image over the background, making it opaque.
+fn flatten(mut image: RgbaImage, background: Rgba<u8>) -> RgbaImage {
+    for pixel in image.pixels_mut() {
+        *pixel = blend(background, *pixel);
+    }
+
+    image
+}
+
+/// Draws `color` over `base` with the color's alpha, keeping the alpha of `base`.
+fn blend(base: Rgba<u8>, color: Rgba<u8>) -> Rgba<u8> {
+    let alpha = u32::from(color[3]);
+    #[allow(clippy::cast_possible_truncation)]
+    let channel =
+        |base: u8, color: u8| ((u32::from(base) * (255 - alpha) + u32::from(color) * alpha + 127) / 255) as u8;
+
+    Rgba([channel(base[0], color[0]), channel(base[1], color[1]), channel(base[2], color[2]), base[3]])
+}
+
+fn draw(image: &mut RgbaImage, lines: &[Scanline], color: Rgba<u8>) {
+    for line in lines {
+        for x in line.start..=line.end {
+            let pixel = image.get_pixel_mut(x, line.y);
+            *pixel = blend(*pixel, color);
+        }
+    }
+}
+
+fn squared_difference(a: Rgba<u8>, b: Rgba<u8>) -> f64 {
+    (0..4).map(|channel| (f64::from(a[channel]) - f64::from(b[channel])).powi(2)).sum()
+}
+
+fn difference_full(target: &RgbaImage, current: &RgbaImage) -> f32 {
+    let total = target.pixels().zip(current.pixels()).map(|(a, b)| squared_difference(*a, *b)).sum();
+
+    score_from_total(total, target.width(), target.height())
+}
+
+fn total_from_score(score: f32, width: u32, height: u32) -> f64 {
+    (f64::from(score) * 255.0).powi(2) * f64::from(width) * f64::from(height) * 4.0
+}
+
+fn score_from_total(total: f64, width: u32, height: u32) -> f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    let score = ((total.max(0.0) / (f64::from(width) * f64::from(height) * 4.0)).sqrt() / 255.0) as f32;
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A quick run that's still able to find the halves of [`halves`].
+    fn options(shape_types: Vec<ShapeType>) -> VectorizeOptions {
+        VectorizeOptions {
+            shape_count: 4,
+            shape_types,
+            alpha: 255,
+            candidates: 8,
+            max_age: 20,
+            threads: NonZeroUsize::new(1),
+            ..VectorizeOptions::default()
+        }
+    }
+
+    /// A red left half and a blue right half.
+    fn halves(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, _| {
+            if x < width / 2 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 255, 255]) }
+        })
+    }
+
+    #[test]
+    fn background() {
+        let image = RgbaImage::from_pixel(6, 4, Rgba([10, 20, 30, 255]));
+        let shapes = vectorize(&image, &options(vec![ShapeType::Rectangle]));
+
+        // Nothing can improve on the background, so no shapes are added on top of it.
+        assert_eq!(shapes.shapes, [Shape { shape_type: 1, data: vec![0.0, 0.0, 6.0, 4.0], color: [10, 20, 30, 255] }]);
+
+        // Transparent pixels count as the average color.
+        let mut image = RgbaImage::from_pixel(2, 1, Rgba([100, 0, 0, 255]));
+        image.put_pixel(1, 0, Rgba([0, 0, 200, 0]));
+        assert_eq!(vectorize(&image, &options(vec![])).shapes[0].color, [100, 0, 0, 255]);
+
+        assert!(vectorize(&RgbaImage::new(0, 3), &VectorizeOptions::default()).shapes.is_empty());
+    }
+
+    #[test]
+    fn improves() {
+        let image = halves(16, 8);
+        let background = difference_full(&image, &RgbaImage::from_pixel(16, 8, average_color(&image)));
+        let mut scores = vec![];
+        let shapes = vectorize_with_progress(&image, &options(vec![ShapeType::Rectangle]), |progress| {
+            assert_eq!(progress.total, 4);
+            scores.push(progress.score);
+        });
+
+        assert_eq!(scores.len(), 4);
+        assert!(scores.windows(2).all(|pair| pair[1] <= pair[0]), "{scores:?}");
+        assert!(scores[0] < background && scores[3] < scores[0], "{background} {scores:?}");
+        assert!(shapes.shapes.len() > 1);
+        assert!(shapes.shapes.iter().all(|shape| shape.shape_type == 1 && shape.color[3] == 255));
+    }
+
+    #[test]
+    fn scaled_images_keep_their_coordinates() {
+        let options = VectorizeOptions { max_size: Some(8), ..options(vec![ShapeType::Rectangle, ShapeType::Circle]) };
+        let shapes = vectorize(&halves(64, 32), &options);
+
+        assert_eq!(shapes.shapes[0].data, [0.0, 0.0, 64.0, 32.0]);
+        for shape in &shapes.shapes[1..] {
+            let (x, y) = (shape.data[0], shape.data[1]);
+            assert!((-1.0..=65.0).contains(&x) && (-1.0..=33.0).contains(&y), "{shape:?}");
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let image = halves(12, 12);
+        let all = VectorizeOptions::default().shape_types;
+        let one = vectorize(&image, &options(all.clone()));
+
+        assert_eq!(one, vectorize(&image, &VectorizeOptions { threads: NonZeroUsize::new(3), ..options(all.clone()) }));
+        assert_ne!(one, vectorize(&image, &VectorizeOptions { seed: 1, ..options(all) }));
+    }
+}
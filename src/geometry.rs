@@ -0,0 +1,482 @@
+//! Small 2D geometry helpers for the polygons stored in `ObjectProperty::TerrainCorners`.
+//!
+//! A ring is a closed polygon given as a list of corners, the last corner connects back to the first one.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::IVec2;
+
+use crate::types::vec2::Vec2;
+
+/// Polygons closer to zero area than this are considered degenerate.
+pub const EPSILON: f32 = 1e-6;
+
+/// Returns the signed area of a ring. It's positive for counter-clockwise rings.
+#[must_use]
+pub fn signed_area(ring: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+
+    for (index, &current) in ring.iter().enumerate() {
+        let next = ring[(index + 1) % ring.len()];
+        area += current.perp_dot(next);
+    }
+
+    area / 2.0
+}
+
+/// Returns the orientation of the point `c` relative to the line through `a` and `b`.
+///
+/// Positive if `a`, `b`, `c` turn counter-clockwise, negative if clockwise and zero if they are collinear.
+#[must_use]
+pub fn orientation(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+/// Whether the point `p` lies on the segment from `a` to `b`, assuming the three points are collinear.
+fn on_segment(a: Vec2, b: Vec2, p: Vec2) -> bool {
+    p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+}
+
+/// Whether the segment from `a` to `b` intersects or touches the segment from `c` to `d`.
+#[must_use]
+pub fn segments_intersect(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let o1 = orientation(a, b, c);
+    let o2 = orientation(a, b, d);
+    let o3 = orientation(c, d, a);
+    let o4 = orientation(c, d, b);
+
+    if ((o1 > 0.0 && o2 < 0.0) || (o1 < 0.0 && o2 > 0.0)) && ((o3 > 0.0 && o4 < 0.0) || (o3 < 0.0 && o4 > 0.0)) {
+        return true;
+    }
+
+    (o1 == 0.0 && on_segment(a, b, c))
+        || (o2 == 0.0 && on_segment(a, b, d))
+        || (o3 == 0.0 && on_segment(c, d, a))
+        || (o4 == 0.0 && on_segment(c, d, b))
+}
+
+/// Whether the ring has fewer than three distinct corners or (almost) no area.
+#[must_use]
+pub fn is_degenerate(ring: &[Vec2]) -> bool {
+    let mut distinct = ring.to_vec();
+    distinct.dedup();
+
+    if distinct.len() > 1 && distinct.first() == distinct.last() {
+        distinct.pop();
+    }
+
+    distinct.len() < 3 || signed_area(&distinct).abs() < EPSILON
+}
+
+/// Whether any two non-adjacent edges of the ring intersect.
+#[must_use]
+pub fn is_self_intersecting(ring: &[Vec2]) -> bool {
+    let count = ring.len();
+
+    if count < 4 {
+        return false;
+    }
+
+    for first in 0..count {
+        let (a, b) = (ring[first], ring[(first + 1) % count]);
+
+        for second in first + 2..count {
+            // the last edge is adjacent to the first one
+            if first == 0 && second == count - 1 {
+                continue;
+            }
+
+            let (c, d) = (ring[second], ring[(second + 1) % count]);
+
+            if segments_intersect(a, b, c, d) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Whether the point `p` lies inside the ring, using the even-odd rule. Points on the boundary may go either way.
+#[must_use]
+pub fn contains_point(ring: &[Vec2], p: Vec2) -> bool {
+    let mut inside = false;
+
+    for (index, &a) in ring.iter().enumerate() {
+        let b = ring[(index + 1) % ring.len()];
+
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+/// Rounds the corners of a ring with arcs of the given radius, approximating a quarter circle with `segments`
+/// edges.
+///
+/// The radius shrinks at corners whose edges are too short for it. Reflex corners are only rounded when
+/// `round_reflex` is set, like `ObjectProperty::RoundReflexAngles` does.
+#[must_use]
+pub fn round_corners(ring: &[Vec2], radius: f32, round_reflex: bool, segments: usize) -> Vec<Vec2> {
+    let count = ring.len();
+
+    if radius <= 0.0 || count < 3 {
+        return ring.to_vec();
+    }
+
+    let winding = signed_area(ring).signum();
+    let mut rounded = Vec::with_capacity(count * (segments + 1));
+
+    for index in 0..count {
+        let (previous, corner, next) = (ring[(index + count - 1) % count], ring[index], ring[(index + 1) % count]);
+        let (to_previous, to_next) = ((previous - corner).normalize_or_zero(), (next - corner).normalize_or_zero());
+        let convex = orientation(previous, corner, next) * winding > 0.0;
+
+        if to_previous == Vec2::ZERO || to_next == Vec2::ZERO || !(convex || round_reflex) {
+            rounded.push(corner);
+            continue;
+        }
+
+        let half_angle = to_previous.angle_to(to_next).abs() / 2.0;
+
+        // Straight corners and spikes have nothing to round.
+        if half_angle < EPSILON || half_angle.tan().recip() < EPSILON {
+            rounded.push(corner);
+            continue;
+        }
+
+        // How far the arc starts from the corner, at most half of each edge so neighbouring arcs don't overlap.
+        let cut = (radius / half_angle.tan())
+            .min(previous.distance(corner) / 2.0)
+            .min(next.distance(corner) / 2.0);
+        let arc_radius = cut * half_angle.tan();
+        let center = corner + (to_previous + to_next).normalize_or_zero() * (arc_radius / half_angle.sin());
+        let (start, end) = (corner + to_previous * cut - center, corner + to_next * cut - center);
+        let sweep = start.angle_to(end);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+        let steps = ((sweep.abs() / std::f32::consts::FRAC_PI_2 * segments as f32).ceil() as usize).max(1);
+
+        for step in 0..=steps {
+            #[allow(clippy::cast_precision_loss)]
+            let angle = sweep * step as f32 / steps as f32;
+            rounded.push(center + start.rotate(Vec2::from_angle(angle)));
+        }
+    }
+
+    rounded
+}
+
+/// Traces the outline of a set of grid cells, where the cell `(x, y)` is the unit square centered on `(x, y)`.
+///
+/// Returns one polygon per region, each given as its counter-clockwise outer ring followed by the clockwise rings
+/// of its holes. Straight runs of cell edges are merged into a single edge, and cells that only touch at a corner
+/// end up in separate rings.
+pub fn grid_outlines(cells: impl IntoIterator<Item = IVec2>) -> Vec<Vec<Vec<Vec2>>> {
+    let cells: BTreeSet<(i32, i32)> = cells.into_iter().map(|cell| (cell.x, cell.y)).collect();
+
+    // Corner (x, y) is the bottom left corner of cell (x, y). Every cell side that doesn't border another cell
+    // becomes an edge, directed so that the cell is on its left.
+    let mut edges: BTreeMap<Corner, Vec<Corner>> = BTreeMap::new();
+
+    for &(x, y) in &cells {
+        let sides = [
+            ((0, -1), (x, y), (x + 1, y)),
+            ((1, 0), (x + 1, y), (x + 1, y + 1)),
+            ((0, 1), (x + 1, y + 1), (x, y + 1)),
+            ((-1, 0), (x, y + 1), (x, y)),
+        ];
+
+        for ((dx, dy), from, to) in sides {
+            if !cells.contains(&(x + dx, y + dy)) {
+                edges.entry(from).or_default().push(to);
+            }
+        }
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut rings = Vec::new();
+
+    for (&start, targets) in &edges {
+        for &first in targets {
+            if visited.contains(&(start, first)) {
+                continue;
+            }
+
+            let mut ring = Vec::new();
+            let (mut from, mut to) = (start, first);
+
+            // Turning as far left as possible at every corner keeps the cells that only touch at a corner apart.
+            while visited.insert((from, to)) {
+                ring.push(from);
+                let direction = (to.0 - from.0, to.1 - from.1);
+                let next = edges
+                    .get(&to)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .max_by_key(|&next| turn(direction, (next.0 - to.0, next.1 - to.1)));
+
+                // Every corner with an incoming edge has an outgoing one, so the ring always closes.
+                let Some(next) = next else { break };
+                (from, to) = (to, next);
+            }
+
+            rings.push(merge_straight_runs(&ring));
+        }
+    }
+
+    nest_holes(rings)
+}
+
+/// Traces the smoothed outline of a set of grid cells with marching squares, where the cell `(x, y)` is sampled at
+/// the point `(x, y)`.
+///
+/// The outline runs through the middle between cells that are in the set and cells that aren't, so staircases turn
+/// into diagonal edges. Polygons are returned like [`grid_outlines`] does, and cells that only touch at a corner
+/// end up in separate rings as well. Use [`simplify`] to drop the corners that straight runs are made of.
+pub fn contour_outlines(cells: impl IntoIterator<Item = IVec2>) -> Vec<Vec<Vec<Vec2>>> {
+    let cells: BTreeSet<(i32, i32)> = cells.into_iter().map(|cell| (cell.x, cell.y)).collect();
+
+    // Every square between four samples that touches a cell, keyed by its bottom left sample.
+    let squares: BTreeSet<Corner> =
+        cells.iter().flat_map(|&(x, y)| [(x, y), (x - 1, y), (x - 1, y - 1), (x, y - 1)]).collect();
+
+    // Crossings are the middles of the square sides, in doubled coordinates so that they stay integers. Every
+    // crossing starts exactly one segment and ends exactly one, directed so that the cells are on its left.
+    let mut segments: BTreeMap<Corner, Corner> = BTreeMap::new();
+
+    for (x, y) in squares {
+        let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+        let inside = corners.map(|corner| cells.contains(&corner));
+        let crossing = |side: usize| {
+            let (from, to) = (corners[side], corners[(side + 1) % 4]);
+            (from.0 + to.0, from.1 + to.1)
+        };
+
+        // Going counter-clockwise around the square, a segment runs from where the sides leave the cells to where
+        // they last entered them. Pairing with the last entry keeps diagonal cells apart in the saddle cases.
+        for side in (0..4).filter(|&side| inside[side] && !inside[(side + 1) % 4]) {
+            let entry = (1..4)
+                .map(|back| (side + 4 - back) % 4)
+                .find(|&previous| !inside[previous] && inside[(previous + 1) % 4]);
+
+            if let Some(entry) = entry {
+                segments.insert(crossing(side), crossing(entry));
+            }
+        }
+    }
+
+    let mut rings = Vec::new();
+
+    while let Some((&start, _)) = segments.first_key_value() {
+        let mut ring = Vec::new();
+        let mut current = start;
+
+        while let Some(next) = segments.remove(&current) {
+            #[allow(clippy::cast_precision_loss)]
+            let point = Vec2::new(current.0 as f32, current.1 as f32) / 2.0;
+            ring.push(point);
+            current = next;
+        }
+
+        rings.push(ring);
+    }
+
+    nest_holes(rings)
+}
+
+/// Simplifies a ring with the Douglas–Peucker algorithm, leaving out corners that are at most `tolerance` away from
+/// the simplified outline.
+///
+/// The ring is split at the corner farthest from its first corner and both halves are simplified on their own.
+/// Rings that end up with fewer than three corners are returned as they are. Simplifying can make a ring intersect
+/// itself or a neighbouring ring if the tolerance is large compared to the gaps between them.
+#[must_use]
+pub fn simplify(ring: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let Some(&first) = ring.first() else { return vec![] };
+    let (far, _) = ring
+        .iter()
+        .enumerate()
+        .map(|(index, &point)| (index, point.distance_squared(first)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0));
+
+    if far == 0 {
+        return ring.to_vec();
+    }
+
+    let closed: Vec<Vec2> = ring.iter().chain([&first]).copied().collect();
+    let mut keep = vec![false; closed.len()];
+    keep[0] = true;
+    keep[far] = true;
+    douglas_peucker(&closed[..=far], tolerance, &mut keep[..=far]);
+    douglas_peucker(&closed[far..], tolerance, &mut keep[far..]);
+
+    let simplified: Vec<Vec2> = ring.iter().zip(&keep).filter(|(_, &keep)| keep).map(|(&point, _)| point).collect();
+
+    if simplified.len() < 3 { ring.to_vec() } else { simplified }
+}
+
+/// Marks the points of the polyline that stay after simplifying it, assuming its endpoints stay.
+fn douglas_peucker(points: &[Vec2], tolerance: f32, keep: &mut [bool]) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let (start, end) = (points[0], points[points.len() - 1]);
+    let line = end - start;
+    let length = line.length();
+
+    let distance = |point: Vec2| {
+        if length <= EPSILON { point.distance(start) } else { line.perp_dot(point - start).abs() / length }
+    };
+
+    let farthest = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(index, &point)| (index + 1, distance(point)))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    if let Some((index, distance)) = farthest {
+        if distance > tolerance {
+            keep[index] = true;
+            douglas_peucker(&points[..=index], tolerance, &mut keep[..=index]);
+            douglas_peucker(&points[index..], tolerance, &mut keep[index..]);
+        }
+    }
+}
+
+/// Sorts counter-clockwise outer rings and clockwise holes into polygons, each given as its outer ring followed by
+/// the holes inside of it.
+fn nest_holes(rings: Vec<Vec<Vec2>>) -> Vec<Vec<Vec<Vec2>>> {
+    let (outers, holes): (Vec<_>, Vec<_>) = rings.into_iter().partition(|ring| signed_area(ring) > 0.0);
+    let mut polygons: Vec<Vec<Vec<Vec2>>> = outers.into_iter().map(|outer| vec![outer]).collect();
+
+    for hole in holes {
+        // The middle of an edge is never on another ring, unlike the corners.
+        let probe = (hole[0] + hole[1]) / 2.0;
+        let owner = polygons
+            .iter_mut()
+            .filter(|polygon| contains_point(&polygon[0], probe))
+            .min_by(|a, b| signed_area(&a[0]).total_cmp(&signed_area(&b[0])));
+
+        if let Some(polygon) = owner {
+            polygon.push(hole);
+        }
+    }
+
+    polygons
+}
+
+/// A grid corner, or a step between two of them.
+type Corner = (i32, i32);
+
+/// Ranks the turn from one axis-aligned direction to the next: left turns first, going back last.
+const fn turn(from: Corner, to: Corner) -> i32 {
+    let cross = from.0 * to.1 - from.1 * to.0;
+    let dot = from.0 * to.0 + from.1 * to.1;
+
+    match (cross, dot) {
+        (1.., _) => 3,
+        (0, 1..) => 2,
+        (..=-1, _) => 1,
+        _ => 0,
+    }
+}
+
+/// Converts grid corners to points, leaving out the corners in the middle of a straight run.
+fn merge_straight_runs(corners: &[Corner]) -> Vec<Vec2> {
+    let count = corners.len();
+
+    (0..count)
+        .filter(|&index| {
+            let previous = corners[(index + count - 1) % count];
+            let (current, next) = (corners[index], corners[(index + 1) % count]);
+            (current.0 - previous.0) * (next.1 - current.1) != (current.1 - previous.1) * (next.0 - current.0)
+        })
+        .map(|index| {
+            let (x, y) = corners[index];
+            #[allow(clippy::cast_precision_loss)]
+            Vec2::new(x as f32 - 0.5, y as f32 - 0.5)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(cells: &[(i32, i32)]) -> Vec<IVec2> {
+        cells.iter().map(|&(x, y)| IVec2::new(x, y)).collect()
+    }
+
+    fn ring(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    /// The number of corners and the signed area of every ring of every polygon.
+    fn summary(polygons: &[Vec<Vec<Vec2>>]) -> Vec<Vec<(usize, f32)>> {
+        polygons.iter().map(|rings| rings.iter().map(|ring| (ring.len(), signed_area(ring))).collect()).collect()
+    }
+
+    #[test]
+    fn grid_outlines_of_cells() {
+        assert!(grid_outlines([]).is_empty());
+        assert_eq!(grid_outlines(cells(&[(0, 0)])), [[ring(&[(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)])]]);
+
+        // Straight runs are merged, so an L of three cells has six corners.
+        assert_eq!(summary(&grid_outlines(cells(&[(0, 0), (1, 0), (0, 1)]))), [[(6, 3.0)]]);
+
+        // Cells that only touch at a corner stay apart.
+        assert_eq!(summary(&grid_outlines(cells(&[(0, 0), (1, 1)]))), [[(4, 1.0)], [(4, 1.0)]]);
+    }
+
+    #[test]
+    fn grid_outlines_with_holes() {
+        let square_of = |size: i32| (0..size).flat_map(move |x| (0..size).map(move |y| (x, y)));
+        let frame: Vec<(i32, i32)> =
+            square_of(5).filter(|&(x, y)| !(1..4).contains(&x) || !(1..4).contains(&y)).collect();
+        let island = [(2, 2)];
+        let outlines = grid_outlines(cells(&[frame.as_slice(), &island].concat()));
+
+        // The island is a polygon of its own inside the hole of the frame.
+        assert_eq!(summary(&outlines), [vec![(4, 25.0), (4, -9.0)], vec![(4, 1.0)]]);
+
+        // Keeping the cells between two holes that touch at a corner apart joins the holes into one ring.
+        let holes: Vec<(i32, i32)> = square_of(4).filter(|&cell| cell != (1, 1) && cell != (2, 2)).collect();
+        assert_eq!(summary(&grid_outlines(cells(&holes))), [[(4, 16.0), (8, -2.0)]]);
+    }
+
+    #[test]
+    fn contour_outlines_of_cells() {
+        assert!(contour_outlines([]).is_empty());
+        assert_eq!(summary(&contour_outlines(cells(&[(0, 0)]))), [[(4, 0.5)]]);
+        assert_eq!(summary(&contour_outlines(cells(&[(0, 0), (1, 1)]))), [[(4, 0.5)], [(4, 0.5)]]);
+    }
+
+    #[test]
+    fn simplify_edge_cases() {
+        assert!(simplify(&[], 1.0).is_empty());
+
+        let line = ring(&[(0.0, 0.0), (1.0, 0.0)]);
+        assert_eq!(simplify(&line, 0.0), line);
+
+        let same = ring(&[(1.0, 1.0); 4]);
+        assert_eq!(simplify(&same, 0.0), same);
+
+        // Corners in the middle of straight runs go even without a tolerance.
+        let square = ring(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 2.0), (1.0, 2.0), (0.0, 2.0), (0.0, 1.0)]);
+        assert_eq!(simplify(&square, 0.0), ring(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]));
+
+        // A bump stays until the tolerance reaches its height.
+        let bump = ring(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (2.0, 2.5), (0.0, 2.0)]);
+        assert_eq!(simplify(&bump, 0.4), bump);
+        assert_eq!(simplify(&bump, 0.6), ring(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)]));
+
+        // Rings that would be left with fewer than three corners are returned as they are.
+        let thin = ring(&[(0.0, 0.0), (10.0, 0.0), (5.0, 0.1)]);
+        assert_eq!(simplify(&thin, 1.0), thin);
+    }
+}
@@ -0,0 +1,149 @@
+use std::f32::consts::PI;
+
+/// An easing curve, as selected by the id stored in an `Easing` value.
+///
+/// Ids start at 0 for `Linear`, followed by the in, out and in-out variant of each family. Unknown ids fall back to
+/// `Linear`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    In(EasingFamily),
+    Out(EasingFamily),
+    InOut(EasingFamily),
+}
+
+/// The shape of an [`Easing`] curve.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum EasingFamily {
+    Sine,
+    Quad,
+    Cubic,
+    Quart,
+    Quint,
+    Expo,
+    Circ,
+    Back,
+    Elastic,
+    Bounce,
+}
+
+const FAMILIES: [EasingFamily; 10] = [
+    EasingFamily::Sine,
+    EasingFamily::Quad,
+    EasingFamily::Cubic,
+    EasingFamily::Quart,
+    EasingFamily::Quint,
+    EasingFamily::Expo,
+    EasingFamily::Circ,
+    EasingFamily::Back,
+    EasingFamily::Elastic,
+    EasingFamily::Bounce,
+];
+
+impl Easing {
+    /// The easing with this id.
+    #[must_use]
+    pub fn from_id(id: i32) -> Self {
+        let Some(index) = usize::try_from(id).ok().and_then(|id| id.checked_sub(1)) else {
+            return Self::Linear;
+        };
+
+        match (FAMILIES.get(index / 3), index % 3) {
+            (Some(&family), 0) => Self::In(family),
+            (Some(&family), 1) => Self::Out(family),
+            (Some(&family), _) => Self::InOut(family),
+            (None, _) => Self::Linear,
+        }
+    }
+
+    /// The id of this easing, the inverse of [`Easing::from_id`].
+    #[must_use]
+    pub fn id(self) -> i32 {
+        let (family, offset) = match self {
+            Self::Linear => return 0,
+            Self::In(family) => (family, 1),
+            Self::Out(family) => (family, 2),
+            Self::InOut(family) => (family, 3),
+        };
+        let index = FAMILIES.iter().position(|&found| found == family).unwrap_or_default();
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let index = index as i32;
+
+        index * 3 + offset
+    }
+
+    /// The name of this easing, like `Linear` or `InOutSine`.
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Self::Linear => "Linear".to_string(),
+            Self::In(family) => format!("In{family:?}"),
+            Self::Out(family) => format!("Out{family:?}"),
+            Self::InOut(family) => format!("InOut{family:?}"),
+        }
+    }
+
+    /// The easing with this [name](Easing::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..).map(Self::from_id).take(FAMILIES.len() * 3 + 1).find(|easing| easing.name() == name)
+    }
+
+    /// Maps the linear progress `t` in `0..=1` to the eased progress.
+    #[must_use]
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => t,
+            Self::In(family) => family.ease_in(t),
+            Self::Out(family) => 1.0 - family.ease_in(1.0 - t),
+            Self::InOut(family) if t < 0.5 => family.ease_in(t * 2.0) / 2.0,
+            Self::InOut(family) => 1.0 - family.ease_in((1.0 - t) * 2.0) / 2.0,
+        }
+    }
+}
+
+impl EasingFamily {
+    #[allow(clippy::float_cmp)]
+    fn ease_in(self, t: f32) -> f32 {
+        match self {
+            Self::Sine => 1.0 - (t * PI / 2.0).cos(),
+            Self::Quad => t.powi(2),
+            Self::Cubic => t.powi(3),
+            Self::Quart => t.powi(4),
+            Self::Quint => t.powi(5),
+            Self::Expo if t == 0.0 => 0.0,
+            Self::Expo => 10.0f32.mul_add(t, -10.0).exp2(),
+            Self::Circ => 1.0 - t.mul_add(-t, 1.0).sqrt(),
+            Self::Back => {
+                const C1: f32 = 1.701_58;
+                const C3: f32 = C1 + 1.0;
+
+                (C3 * t).mul_add(t * t, -(C1 * t * t))
+            }
+            Self::Elastic if t == 0.0 || t == 1.0 => t,
+            Self::Elastic => -(10.0f32.mul_add(t, -10.0).exp2()) * (t.mul_add(10.0, -10.75) * (2.0 * PI / 3.0)).sin(),
+            Self::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        (N1 * t).mul_add(t, 0.75)
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        (N1 * t).mul_add(t, 0.9375)
+    } else {
+        let t = t - 2.625 / D1;
+        (N1 * t).mul_add(t, 0.984_375)
+    }
+}
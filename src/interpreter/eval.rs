@@ -0,0 +1,618 @@
+use std::collections::BTreeMap;
+
+use rand::{seq::IndexedRandom, seq::SliceRandom, Rng};
+
+use super::{
+    value::Value,
+    world::{self, PLAYER},
+    Activation, Interpreter, RuntimeError,
+};
+use crate::type_check::TypeErrorKind;
+use crate::types::{
+    color::Color,
+    nova_script::{dynamic_type::DynamicType, nova_value::NovaValue, static_type::StaticType},
+    vec2::Vec2,
+};
+
+/// What a value is evaluated against: the running script, its parameters and activation, and the innermost loops.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct Scope<'s> {
+    pub script_id: i32,
+    pub parameters: Option<&'s BTreeMap<i32, Value>>,
+    pub activation: Activation,
+    /// The game time the activation started at.
+    pub start_time: f64,
+    pub repeat_count: i32,
+    pub repeat_object: i32,
+}
+
+/// The evaluated operands of a value. Their types have already been checked against `DynamicType::operand_types`.
+struct Args(Vec<Value>);
+
+impl Args {
+    fn bool(&self, index: usize) -> bool {
+        self.0[index].as_bool().unwrap_or_default()
+    }
+
+    fn int(&self, index: usize) -> i32 {
+        self.0[index].as_int().unwrap_or_default()
+    }
+
+    fn float(&self, index: usize) -> f32 {
+        self.0[index].as_float().unwrap_or_default()
+    }
+
+    fn string(&self, index: usize) -> &str {
+        self.0[index].as_str().unwrap_or_default()
+    }
+
+    fn color(&self, index: usize) -> Color {
+        self.0[index].as_color().unwrap_or_default()
+    }
+
+    fn vector(&self, index: usize) -> Vec2 {
+        self.0[index].as_vector().unwrap_or_default()
+    }
+
+    fn id(&self, index: usize) -> i32 {
+        self.0[index].as_id().unwrap_or_default()
+    }
+
+    fn objects(&self, index: usize) -> Vec<i32> {
+        self.0[index].as_objects().unwrap_or_default()
+    }
+}
+
+/// The operations shared by all list types and object sets. The list is always the first operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ListOperation {
+    Append,
+    Remove,
+    SetAt,
+    RemoveAt,
+    Sublist,
+    Sort,
+    Shuffle,
+    Reverse,
+    First,
+    Random,
+    Element,
+    Contains,
+    Count,
+    IndexOf,
+}
+
+const fn list_operation(dynamic_type: DynamicType) -> Option<ListOperation> {
+    use DynamicType as D;
+
+    Some(match dynamic_type {
+        D::BoolListAppend
+        | D::IntListAppend
+        | D::FloatListAppend
+        | D::StringListAppend
+        | D::ColorListAppend
+        | D::VectorListAppend
+        | D::PrefabListAppend => ListOperation::Append,
+        D::BoolListRemove
+        | D::IntListRemove
+        | D::FloatListRemove
+        | D::StringListRemove
+        | D::ColorListRemove
+        | D::VectorListRemove
+        | D::PrefabListRemove => ListOperation::Remove,
+        D::ObjectSetSetAtIndex
+        | D::BoolListSetAtIndex
+        | D::IntListSetAtIndex
+        | D::FloatListSetAtIndex
+        | D::StringListSetAtIndex
+        | D::ColorListSetAtIndex
+        | D::VectorListSetAtIndex
+        | D::PrefabListSetAtIndex => ListOperation::SetAt,
+        D::ObjectSetRemoveAtIndex
+        | D::BoolListRemoveAtIndex
+        | D::IntListRemoveAtIndex
+        | D::FloatListRemoveAtIndex
+        | D::StringListRemoveAtIndex
+        | D::ColorListRemoveAtIndex
+        | D::VectorListRemoveAtIndex
+        | D::PrefabListRemoveAtIndex => ListOperation::RemoveAt,
+        D::ObjectSetSublist
+        | D::BoolListSublist
+        | D::IntListSublist
+        | D::FloatListSublist
+        | D::StringListSublist
+        | D::ColorListSublist
+        | D::VectorListSublist
+        | D::PrefabListSublist => ListOperation::Sublist,
+        D::BoolListSort | D::IntListSort | D::FloatListSort | D::StringListSort => ListOperation::Sort,
+        D::ObjectSetShuffle
+        | D::BoolListShuffle
+        | D::IntListShuffle
+        | D::FloatListShuffle
+        | D::StringListShuffle
+        | D::ColorListShuffle
+        | D::VectorListShuffle
+        | D::PrefabListShuffle => ListOperation::Shuffle,
+        D::ObjectSetReverse
+        | D::BoolListReverse
+        | D::IntListReverse
+        | D::FloatListReverse
+        | D::StringListReverse
+        | D::ColorListReverse
+        | D::VectorListReverse
+        | D::PrefabListReverse => ListOperation::Reverse,
+        D::ObjectFirstFromSet
+        | D::BoolFirstFromList
+        | D::IntFirstFromList
+        | D::FloatFirstFromList
+        | D::StringFirstFromList
+        | D::ColorFirstFromList
+        | D::VectorFirstFromList
+        | D::PrefabFirstFromList => ListOperation::First,
+        D::ObjectRandomFromSet
+        | D::BoolRandomFromList
+        | D::IntRandomFromList
+        | D::FloatRandomFromList
+        | D::StringRandomFromList
+        | D::ColorRandomFromList
+        | D::VectorRandomFromList
+        | D::PrefabRandomFromList => ListOperation::Random,
+        D::ObjectElementFromSet
+        | D::BoolElementFromList
+        | D::IntElementFromList
+        | D::FloatElementFromList
+        | D::StringElementFromList
+        | D::ColorElementFromList
+        | D::VectorElementFromList
+        | D::PrefabElementFromList => ListOperation::Element,
+        D::BoolObjectSetContains
+        | D::BoolBoolListContains
+        | D::BoolIntListContains
+        | D::BoolFloatListContains
+        | D::BoolStringListContains
+        | D::BoolColorListContains
+        | D::BoolVectorListContains
+        | D::BoolPrefabListContains => ListOperation::Contains,
+        D::IntObjectSetCount
+        | D::IntBoolListCount
+        | D::IntIntListCount
+        | D::IntFloatListCount
+        | D::IntStringListCount
+        | D::IntColorListCount
+        | D::IntVectorListCount
+        | D::IntPrefabListCount => ListOperation::Count,
+        D::IntObjectSetIndexOf
+        | D::IntBoolListIndexOf
+        | D::IntIntListIndexOf
+        | D::IntFloatListIndexOf
+        | D::IntStringListIndexOf
+        | D::IntColorListIndexOf
+        | D::IntVectorListIndexOf
+        | D::IntPrefabIndexOf => ListOperation::IndexOf,
+        _ => return None,
+    })
+}
+
+fn index(value: &Value) -> Option<usize> {
+    value.as_int().and_then(|index| usize::try_from(index).ok())
+}
+
+fn count(len: usize) -> i32 {
+    i32::try_from(len).unwrap_or(i32::MAX)
+}
+
+/// Concatenates two object sets, skipping objects of `b` that are already in `a`.
+fn union(a: Vec<i32>, b: &[i32]) -> Vec<i32> {
+    let mut result = a;
+
+    for id in b {
+        if !result.contains(id) {
+            result.push(*id);
+        }
+    }
+
+    result
+}
+
+impl Interpreter<'_> {
+    /// Evaluates a value.
+    pub(super) fn eval(&mut self, value: &NovaValue, scope: &Scope) -> Result<Value, RuntimeError> {
+        let dynamic_type = value.dynamic_type;
+        let error = |kind| RuntimeError::Type { script_id: scope.script_id, kind };
+
+        if let Some(static_type) = dynamic_type.constant_static_type() {
+            return Ok(Value::constant(value, static_type));
+        }
+
+        if let Some(used) = dynamic_type.variable_static_type() {
+            let variable_id = value.int_value;
+            let found = self
+                .read_variable(scope.script_id, variable_id)
+                .ok_or_else(|| error(TypeErrorKind::UnknownVariable(variable_id)))?;
+
+            if found.static_type() != used {
+                return Err(error(TypeErrorKind::VariableType { variable_id, declared: found.static_type(), used }));
+            }
+
+            return Ok(found.clone());
+        }
+
+        if let Some(used) = dynamic_type.parameter_static_type() {
+            let parameter_id = value.int_value;
+            let found = scope
+                .parameters
+                .and_then(|parameters| parameters.get(&parameter_id))
+                .ok_or_else(|| error(TypeErrorKind::UnknownParameter(parameter_id)))?;
+
+            if found.static_type() != used {
+                return Err(error(TypeErrorKind::ParameterType { parameter_id, declared: found.static_type(), used }));
+            }
+
+            return Ok(found.clone());
+        }
+
+        let operand_types = dynamic_type.operand_types();
+        let operands = value.sub_values.as_deref().unwrap_or_default();
+
+        if operands.len() != operand_types.len() {
+            return Err(error(TypeErrorKind::OperandCount {
+                dynamic_type,
+                expected: operand_types.len(),
+                found: operands.len(),
+            }));
+        }
+
+        let mut args = Vec::with_capacity(operands.len());
+
+        for (operand, &expected) in operands.iter().zip(operand_types) {
+            let arg = self.eval(operand, scope)?;
+            let found = arg.static_type();
+
+            if found != expected {
+                return Err(error(TypeErrorKind::Mismatch { expected, found }));
+            }
+
+            args.push(arg);
+        }
+
+        if let Some(operation) = list_operation(dynamic_type) {
+            return Ok(self.list(operation, args, dynamic_type.result_type()));
+        }
+
+        Ok(self.apply(dynamic_type, &Args(args), scope))
+    }
+
+    pub(super) fn read_variable(&self, script_id: i32, variable_id: i32) -> Option<&Value> {
+        self.locals
+            .get(&script_id)
+            .and_then(|locals| locals.get(&variable_id))
+            .or_else(|| self.globals.get(&variable_id))
+    }
+
+    fn list(&mut self, operation: ListOperation, args: Vec<Value>, result_type: StaticType) -> Value {
+        let mut args = args.into_iter();
+        let Some(list) = args.next() else {
+            return Value::default_for(result_type);
+        };
+
+        let list_type = list.static_type();
+        let mut elements = list.into_elements().unwrap_or_default();
+        let args: Vec<Value> = args.collect();
+
+        match operation {
+            ListOperation::Append => elements.push(args[0].clone()),
+            ListOperation::Remove => {
+                if let Some(position) = elements.iter().position(|element| *element == args[0]) {
+                    elements.remove(position);
+                }
+            }
+            ListOperation::SetAt => {
+                if let Some(element) = index(&args[0]).and_then(|index| elements.get_mut(index)) {
+                    *element = args[1].clone();
+                }
+            }
+            ListOperation::RemoveAt => {
+                if let Some(index) = index(&args[0]).filter(|&index| index < elements.len()) {
+                    elements.remove(index);
+                }
+            }
+            ListOperation::Sublist => {
+                let start = index(&args[0]).unwrap_or(0);
+                let len = index(&args[1]).unwrap_or(0);
+                elements = elements.into_iter().skip(start).take(len).collect();
+            }
+            ListOperation::Sort => elements.sort_by(Value::compare),
+            ListOperation::Shuffle => elements.shuffle(&mut self.rng),
+            ListOperation::Reverse => elements.reverse(),
+            ListOperation::First => {
+                return elements.into_iter().next().unwrap_or_else(|| Value::default_for(result_type));
+            }
+            ListOperation::Random => {
+                return elements.choose(&mut self.rng).cloned().unwrap_or_else(|| Value::default_for(result_type));
+            }
+            ListOperation::Element => {
+                return index(&args[0])
+                    .and_then(|index| elements.get(index))
+                    .cloned()
+                    .unwrap_or_else(|| Value::default_for(result_type));
+            }
+            ListOperation::Contains => return Value::Bool(elements.contains(&args[0])),
+            ListOperation::Count => return Value::Int(count(elements.len())),
+            ListOperation::IndexOf => {
+                return Value::Int(elements.iter().position(|element| *element == args[0]).map_or(-1, count));
+            }
+        }
+
+        Value::from_elements(list_type, elements)
+    }
+
+    fn random_int(&mut self, a: i32, b: i32) -> i32 {
+        self.rng.random_range(a.min(b)..=a.max(b))
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn random_float(&mut self, a: f32, b: f32) -> f32 {
+        if a == b || !a.is_finite() || !b.is_finite() {
+            a
+        } else {
+            self.rng.random_range(a.min(b)..=a.max(b))
+        }
+    }
+
+    fn global_position(&self, entity_id: i32) -> Vec2 {
+        world::global_transform(&self.objects, entity_id).position
+    }
+
+    /// All objects of the level and the ones created by scripts, but not the player.
+    fn all_objects(&self) -> impl Iterator<Item = &world::ObjectState> {
+        self.objects.values().filter(|object| object.entity_id != PLAYER)
+    }
+
+    const fn player_flag(entity_id: i32, flag: bool) -> bool {
+        entity_id == PLAYER && flag
+    }
+
+    /// Evaluates every dynamic type that isn't a constant, variable, parameter or list operation.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn apply(&mut self, dynamic_type: DynamicType, args: &Args, scope: &Scope) -> Value {
+        use DynamicType as D;
+
+        let activation = scope.activation;
+
+        match dynamic_type {
+            D::BoolNot => Value::Bool(!args.bool(0)),
+            D::BoolAnd => Value::Bool(args.bool(0) && args.bool(1)),
+            D::BoolOr => Value::Bool(args.bool(0) || args.bool(1)),
+            D::BoolEqualBool
+            | D::BoolEqualNumber
+            | D::BoolEqualString
+            | D::BoolEqualColor
+            | D::BoolEqualVector
+            | D::BoolEqualObject => Value::Bool(args.0[0] == args.0[1]),
+            D::BoolNotEqualBool
+            | D::BoolNotEqualNumber
+            | D::BoolNotEqualString
+            | D::BoolNotEqualColor
+            | D::BoolNotEqualVector
+            | D::BoolNotEqualObject => Value::Bool(args.0[0] != args.0[1]),
+            D::BoolLess => Value::Bool(args.float(0) < args.float(1)),
+            D::BoolLessOrEqual => Value::Bool(args.float(0) <= args.float(1)),
+            D::BoolGreater => Value::Bool(args.float(0) > args.float(1)),
+            D::BoolGreaterOrEqual => Value::Bool(args.float(0) >= args.float(1)),
+            D::BoolObjectDead => Value::Bool(self.objects.get(&args.id(0)).is_some_and(|object| object.dead)),
+            D::BoolObjectActivated => Value::Bool(self.objects.get(&args.id(0)).is_some_and(|object| object.activated)),
+            D::BoolPlayerOnGround => Value::Bool(Self::player_flag(args.id(0), self.player_state.on_ground)),
+            D::BoolPlayerOnWalljump => Value::Bool(Self::player_flag(args.id(0), self.player_state.on_walljump)),
+            D::BoolPlayerOnBooster => Value::Bool(Self::player_flag(args.id(0), self.player_state.on_booster)),
+            D::BoolPlayerOnSwing => Value::Bool(Self::player_flag(args.id(0), self.player_state.on_swing)),
+            D::BoolPlayerInFloatingZone => {
+                Value::Bool(Self::player_flag(args.id(0), self.player_state.in_floating_zone))
+            }
+            D::BoolPlayerUsingGlider => Value::Bool(Self::player_flag(args.id(0), self.player_state.using_glider)),
+            D::BoolPlayerJumpLocked => Value::Bool(Self::player_flag(args.id(0), self.player_state.jump_locked)),
+            D::BoolObjectsColliding => {
+                let (a, b) = (args.id(0), args.id(1));
+                Value::Bool(self.collisions.contains(&(a.min(b), a.max(b))))
+            }
+            // Object shapes aren't simulated.
+            D::BoolObjectsCollidingWithPoint => Value::Bool(false),
+            D::BoolInputDown => Value::Bool(self.input.down.contains(&true)),
+            D::BoolInputDownLeft => Value::Bool(self.input.down[0]),
+            D::BoolInputDownRight => Value::Bool(self.input.down[1]),
+            D::BoolInputHeld => Value::Bool(self.input.held.contains(&true)),
+            D::BoolInputHeldLeft => Value::Bool(self.input.held[0]),
+            D::BoolInputHeldRight => Value::Bool(self.input.held[1]),
+            D::BoolInputReleased => Value::Bool(self.input.released.contains(&true)),
+            D::BoolInputReleasedLeft => Value::Bool(self.input.released[0]),
+            D::BoolInputReleasedRight => Value::Bool(self.input.released[1]),
+            D::BoolPointerDown => Value::Bool(self.input.pointer_down),
+            D::BoolPointerHeld => Value::Bool(self.input.pointer_held),
+            D::BoolPointerReleased => Value::Bool(self.input.pointer_released),
+
+            D::IntAdd => Value::Int(args.int(0).wrapping_add(args.int(1))),
+            D::IntSubtract => Value::Int(args.int(0).wrapping_sub(args.int(1))),
+            D::IntMultiply => Value::Int(args.int(0).wrapping_mul(args.int(1))),
+            D::IntDivide => Value::Int(args.int(0).checked_div(args.int(1)).unwrap_or(0)),
+            D::IntModulo => Value::Int(args.int(0).checked_rem(args.int(1)).unwrap_or(0)),
+            D::IntMin => Value::Int(args.int(0).min(args.int(1))),
+            D::IntMax => Value::Int(args.int(0).max(args.int(1))),
+            D::IntAbs => Value::Int(args.int(0).wrapping_abs()),
+            D::IntSign => Value::Int(args.int(0).signum()),
+            D::IntRound => Value::Int(args.float(0).round_ties_even() as i32),
+            D::IntCeil => Value::Int(args.float(0).ceil() as i32),
+            D::IntFloor => Value::Int(args.float(0).floor() as i32),
+            D::IntRandom => Value::Int(self.random_int(args.int(0), args.int(1))),
+            D::IntRepeatCount => Value::Int(scope.repeat_count),
+            D::IntObjectDirection => Value::Int(self.objects.get(&args.id(0)).map_or(0, |object| object.direction)),
+            D::IntLastSoundInstance => Value::Int(self.last_sound_instance),
+            D::IntStringLength => Value::Int(count(args.string(0).chars().count())),
+            D::IntFromString => Value::Int(args.string(0).trim().parse().unwrap_or(0)),
+
+            D::FloatAdd => Value::Float(args.float(0) + args.float(1)),
+            D::FloatSubtract => Value::Float(args.float(0) - args.float(1)),
+            D::FloatMultiply => Value::Float(args.float(0) * args.float(1)),
+            D::FloatDivide => Value::Float(args.float(0) / args.float(1)),
+            D::FloatModulo => Value::Float(args.float(0) % args.float(1)),
+            D::FloatMin => Value::Float(args.float(0).min(args.float(1))),
+            D::FloatMax => Value::Float(args.float(0).max(args.float(1))),
+            D::FloatAbs => Value::Float(args.float(0).abs()),
+            D::FloatSign => Value::Float(if args.float(0) == 0.0 { 0.0 } else { args.float(0).signum() }),
+            D::FloatRound => Value::Float(args.float(0).round_ties_even()),
+            D::FloatCeil => Value::Float(args.float(0).ceil()),
+            D::FloatFloor => Value::Float(args.float(0).floor()),
+            D::FloatRoundDecimals => {
+                let factor = 10f32.powi(args.int(1));
+                Value::Float((args.float(0) * factor).round_ties_even() / factor)
+            }
+            D::FloatCos => Value::Float(args.float(0).to_radians().cos()),
+            D::FloatSin => Value::Float(args.float(0).to_radians().sin()),
+            D::FloatTan => Value::Float(args.float(0).to_radians().tan()),
+            D::FloatAcos => Value::Float(args.float(0).acos().to_degrees()),
+            D::FloatAsin => Value::Float(args.float(0).asin().to_degrees()),
+            D::FloatAtan => Value::Float(args.float(0).atan().to_degrees()),
+            D::FloatAtan2 => Value::Float(args.float(0).atan2(args.float(1)).to_degrees()),
+            D::FloatSqrt => Value::Float(args.float(0).sqrt()),
+            D::FloatPow => Value::Float(args.float(0).powf(args.float(1))),
+            D::FloatRandom => Value::Float(self.random_float(args.float(0), args.float(1))),
+            D::FloatTime => Value::Float((self.time() - scope.start_time) as f32),
+            D::FloatLevelTime => Value::Float(self.time() as f32),
+            D::FloatSemitones => Value::Float((args.float(0) / 12.0).exp2()),
+            D::FloatVectorX => Value::Float(args.vector(0).x.0),
+            D::FloatVectorY => Value::Float(args.vector(0).y.0),
+            D::FloatVectorLength => Value::Float(args.vector(0).length()),
+            D::FloatVectorLengthSqr => Value::Float(args.vector(0).length_squared()),
+            D::FloatVectorDistance => Value::Float(args.vector(0).distance(args.vector(1))),
+            D::FloatVectorDistanceSqr => Value::Float(args.vector(0).distance_squared(args.vector(1))),
+            D::FloatVectorDot => Value::Float(args.vector(0).dot(args.vector(1))),
+            D::FloatVectorAngle => Value::Float(args.vector(0).to_angle().to_degrees()),
+            D::FloatVectorAngleBetween => {
+                Value::Float(args.vector(0).angle_to(args.vector(1)).abs().to_degrees())
+            }
+            D::FloatObjectRotation => Value::Float(self.objects.get(&args.id(0)).map_or(0.0, |object| object.rotation)),
+            D::FloatObjectGlobalRotation => {
+                Value::Float(world::global_transform(&self.objects, args.id(0)).rotation)
+            }
+            D::FloatCameraViewportSize => Value::Float(self.camera.viewport_size),
+            D::FloatDamageAmount => Value::Float(activation.damage),
+            D::FloatColorR => Value::Float(args.color(0).r.0),
+            D::FloatColorG => Value::Float(args.color(0).g.0),
+            D::FloatColorB => Value::Float(args.color(0).b.0),
+            D::FloatColorA => Value::Float(args.color(0).a.0),
+            D::FloatFromString => Value::Float(args.string(0).trim().parse().unwrap_or(0.0)),
+            D::FloatFromInt => Value::Float(args.int(0) as f32),
+
+            D::StringFromInt => Value::String(args.int(0).to_string()),
+            D::StringFromFloat => Value::String(args.float(0).to_string()),
+            D::StringFromVector => {
+                let vector = args.vector(0);
+                Value::String(format!("({:.2}, {:.2})", vector.x.0, vector.y.0))
+            }
+            D::StringConcat => Value::String(format!("{}{}", args.string(0), args.string(1))),
+            D::StringObjectTag => Value::String(self.objects.get(&args.id(0)).map(|object| object.tag.clone()).unwrap_or_default()),
+            D::StringSubstring => {
+                let start = usize::try_from(args.int(1)).unwrap_or(0);
+                let len = usize::try_from(args.int(2)).unwrap_or(0);
+                Value::String(args.string(0).chars().skip(start).take(len).collect())
+            }
+
+            D::ColorValues => Value::Color(Color::new(args.float(0), args.float(1), args.float(2), args.float(3))),
+            D::ColorObjectColor => Value::Color(self.objects.get(&args.id(0)).map(|object| object.color).unwrap_or_default()),
+
+            D::VectorValues => Value::Vector(Vec2::new(args.float(0), args.float(1))),
+            D::VectorAdd => Value::Vector(args.vector(0) + args.vector(1)),
+            D::VectorSubtract => Value::Vector(args.vector(0) - args.vector(1)),
+            D::VectorMultiply => Value::Vector(args.vector(0) * args.float(1)),
+            D::VectorDivide => Value::Vector(args.vector(0) / args.float(1)),
+            D::VectorNormalize => Value::Vector(args.vector(0).normalize_or_zero()),
+            D::VectorPerpendicular => Value::Vector(args.vector(0).perp()),
+            D::VectorReflect => {
+                let normal = args.vector(1).normalize_or_zero();
+                Value::Vector(args.vector(0) - normal * (2.0 * args.vector(0).dot(normal)))
+            }
+            D::VectorRotate => Value::Vector(world::rotate(args.vector(0), args.float(1))),
+            D::VectorObjectPos => Value::Vector(self.objects.get(&args.id(0)).map_or(Vec2::ZERO, |object| object.position)),
+            D::VectorObjectGlobalPos => Value::Vector(self.global_position(args.id(0))),
+            D::VectorObjectScale => Value::Vector(self.objects.get(&args.id(0)).map_or(Vec2::ONE, |object| object.scale)),
+            D::VectorObjectGlobalScale => Value::Vector(world::global_transform(&self.objects, args.id(0)).scale),
+            D::VectorObjectVelocity => Value::Vector(self.objects.get(&args.id(0)).map_or(Vec2::ZERO, |object| object.velocity)),
+            D::VectorCameraPos => Value::Vector(self.camera.position),
+            D::VectorPointerPosition | D::VectorPointerPositionDeprecated => {
+                Value::Vector(self.input.pointer_position)
+            }
+            D::VectorPointerWorldPosition | D::VectorPointerWorldPositionDeprecated => {
+                Value::Vector(self.input.pointer_world_position)
+            }
+            D::VectorCollisionPoint => Value::Vector(activation.collision_point),
+            D::VectorCollisionNormal => Value::Vector(activation.collision_normal),
+
+            D::ObjectSourceObject => Value::Object(activation.source),
+            D::ObjectCollidedObject => Value::Object(activation.collided),
+            D::ObjectTargetObject => Value::Object(activation.target),
+            D::ObjectPlayer => Value::Object(PLAYER),
+            D::ObjectParent => Value::Object(self.objects.get(&args.id(0)).map_or(0, |object| object.parent)),
+            D::ObjectRepeatObject => Value::Object(scope.repeat_object),
+            D::ObjectLastCreatedObject => Value::Object(self.last_created_object),
+            D::ObjectClosestFromPoint => {
+                let point = args.vector(1);
+
+                Value::Object(
+                    args.objects(0)
+                        .into_iter()
+                        .min_by(|a, b| {
+                            self.global_position(*a)
+                                .distance_squared(point)
+                                .total_cmp(&self.global_position(*b).distance_squared(point))
+                        })
+                        .unwrap_or(0),
+                )
+            }
+
+            D::ObjectSetConcat => Value::ObjectSet([args.objects(0), args.objects(1)].concat()),
+            D::ObjectSetUnion => Value::ObjectSet(union(args.objects(0), &args.objects(1))),
+            D::ObjectSetIntersection => {
+                let b = args.objects(1);
+                Value::ObjectSet(args.objects(0).into_iter().filter(|id| b.contains(id)).collect())
+            }
+            D::ObjectSetDifference => {
+                let b = args.objects(1);
+                Value::ObjectSet(args.objects(0).into_iter().filter(|id| !b.contains(id)).collect())
+            }
+            D::ObjectSetPlayers => Value::ObjectSet(vec![PLAYER]),
+            D::ObjectSetAllObjects | D::ObjectSetAllObjectsDeprecated => {
+                Value::ObjectSet(self.all_objects().map(|object| object.entity_id).collect())
+            }
+            D::ObjectSetAllRootObjects => Value::ObjectSet(
+                self.all_objects().filter(|object| object.parent == 0).map(|object| object.entity_id).collect(),
+            ),
+            D::ObjectSetObjectsWithTag | D::ObjectSetObjectsWithTagDeprecated => {
+                let tag = args.string(0);
+                Value::ObjectSet(self.all_objects().filter(|object| object.tag == tag).map(|object| object.entity_id).collect())
+            }
+            D::ObjectSetObjectsInLayer => {
+                let layer = args.id(0);
+                Value::ObjectSet(
+                    self.all_objects().filter(|object| object.layer == layer).map(|object| object.entity_id).collect(),
+                )
+            }
+            D::ObjectSetObjectsInCircle => {
+                let (center, radius) = (args.vector(0), args.float(1));
+                Value::ObjectSet(
+                    self.all_objects()
+                        .map(|object| object.entity_id)
+                        .filter(|id| self.global_position(*id).distance(center) <= radius)
+                        .collect(),
+                )
+            }
+            D::ObjectSetChildren => Value::ObjectSet(
+                self.objects.get(&args.id(0))
+                    .map(|object| object.children.iter().copied().filter(|id| self.objects.contains_key(id)).collect())
+                    .unwrap_or_default(),
+            ),
+
+            // Matches any object when used as an activator parameter, see `Interpreter::matches_object`.
+            D::ObjectAnyObject => Value::Object(0),
+
+            _ => Value::default_for(dynamic_type.result_type()),
+        }
+    }
+}
@@ -0,0 +1,1135 @@
+use std::collections::BTreeMap;
+
+use super::{
+    easing::Easing,
+    eval::Scope,
+    value::Value,
+    world::{self, ColorSlot, ObjectState, Tween, TweenTarget, PLAYER},
+    Activation, Interpreter, ObjectChange, RuntimeError, TraceEvent, Trigger, VariableRef, FRAME_RATE,
+};
+use crate::type_check::TypeErrorKind;
+use crate::types::{
+    color::Color,
+    nova_script::{
+        action::Action, action_type::ActionType, activator::ActivatorKind, dynamic_type::DynamicType,
+        function_call::FunctionCall, nova_value::NovaValue, static_type::StaticType, NovaScript,
+    },
+    vec2::Vec2,
+};
+
+/// Slack for comparing game times, which are computed from frame numbers.
+const TIME_EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Wait {
+    /// Until the game time reaches this value.
+    Until(f64),
+    /// Until this frame.
+    Frame(u64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum BlockKind<'a> {
+    Plain,
+    Repeat { iteration: i32, count: i32 },
+    While(&'a NovaValue),
+    ForEach { objects: Vec<i32>, iteration: usize },
+}
+
+/// An action list that is being run.
+#[derive(Clone, Debug, PartialEq)]
+struct Block<'a> {
+    actions: &'a [Action],
+    index: usize,
+    kind: BlockKind<'a>,
+}
+
+impl<'a> Block<'a> {
+    const fn new(actions: &'a [Action], kind: BlockKind<'a>) -> Self {
+        Self { actions, index: 0, kind }
+    }
+}
+
+/// The body of a script or of a function called with `RunFunction`.
+#[derive(Clone, Debug, PartialEq)]
+struct CallFrame<'a> {
+    script_id: i32,
+    parameters: BTreeMap<i32, Value>,
+    blocks: Vec<Block<'a>>,
+}
+
+/// One activation of a script.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Thread<'a> {
+    pub script_id: i32,
+    activation: Activation,
+    start_time: f64,
+    frames: Vec<CallFrame<'a>>,
+    wait: Option<Wait>,
+}
+
+impl Thread<'_> {
+    fn scope(&self) -> Scope<'_> {
+        let frame = self.frames.last();
+        let blocks = frame.map(|frame| frame.blocks.as_slice()).unwrap_or_default();
+
+        Scope {
+            script_id: frame.map_or(self.script_id, |frame| frame.script_id),
+            parameters: frame.map(|frame| &frame.parameters),
+            activation: self.activation,
+            start_time: self.start_time,
+            repeat_count: blocks
+                .iter()
+                .rev()
+                .find_map(|block| match block.kind {
+                    BlockKind::Repeat { iteration, .. } => Some(iteration),
+                    _ => None,
+                })
+                .unwrap_or(0),
+            repeat_object: blocks
+                .iter()
+                .rev()
+                .find_map(|block| match &block.kind {
+                    BlockKind::ForEach { objects, iteration } => objects.get(*iteration).copied(),
+                    _ => None,
+                })
+                .unwrap_or(0),
+        }
+    }
+}
+
+impl<'a> Thread<'a> {
+    fn push(&mut self, actions: &'a [Action], kind: BlockKind<'a>) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.blocks.push(Block::new(actions, kind));
+        }
+    }
+}
+
+/// Evaluates to a value of a specific type, or fails with a type error.
+macro_rules! eval_as {
+    ($interpreter:expr, $value:expr, $scope:expr, $static_type:ident, $extract:ident) => {{
+        let scope = $scope;
+        let value = $interpreter.eval($value, scope)?;
+
+        value.$extract().ok_or(RuntimeError::Type {
+            script_id: scope.script_id,
+            kind: TypeErrorKind::Mismatch { expected: StaticType::$static_type, found: value.static_type() },
+        })?
+    }};
+}
+
+impl<'a> Interpreter<'a> {
+    /// Starts the scripts whose activators match the pending triggers and the timers that are due.
+    pub(super) fn dispatch(&mut self) -> Result<(), RuntimeError> {
+        for trigger in std::mem::take(&mut self.pending) {
+            self.fire(Some(trigger))?;
+        }
+
+        self.fire(None)
+    }
+
+    /// Starts every script with an activator matching `trigger`, or every due timer for `None`. Activators that
+    /// don't fit their [`ActivatorKind`] never fire.
+    fn fire(&mut self, trigger: Option<Trigger>) -> Result<(), RuntimeError> {
+        let level = self.level;
+
+        for script in level.nova_scripts.iter().filter(|script| !script.is_function) {
+            for activator in &script.activation_list {
+                let Ok(kind) = activator.kind() else {
+                    continue;
+                };
+
+                let activation = match trigger {
+                    Some(trigger) => self.activation(script, &kind, trigger)?,
+                    None => self.timer(script, &kind)?,
+                };
+
+                if let Some(activation) = activation {
+                    self.start(script, activation)?;
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn activation(
+        &mut self,
+        script: &NovaScript,
+        kind: &ActivatorKind,
+        trigger: Trigger,
+    ) -> Result<Option<Activation>, RuntimeError> {
+        let scope = Scope { script_id: script.script_id, ..Default::default() };
+        let on_object = |source: i32| Activation { source, target: source, ..Default::default() };
+
+        Ok(match (kind, trigger) {
+            (ActivatorKind::LevelStart, Trigger::LevelStart)
+            | (ActivatorKind::InputDown, Trigger::InputDown)
+            | (ActivatorKind::InputReleased, Trigger::InputReleased)
+            | (ActivatorKind::PointerDown, Trigger::PointerDown)
+            | (ActivatorKind::PointerReleased, Trigger::PointerReleased) => Some(Activation::default()),
+            (ActivatorKind::ObjectActivated { target_objects }, Trigger::Activated(id))
+            | (ActivatorKind::ObjectDeactivated { target_objects }, Trigger::Deactivated(id))
+            | (ActivatorKind::ObjectKilled { target_objects }, Trigger::Killed(id)) => {
+                self.matches_object(target_objects, id, &scope)?.then(|| on_object(id))
+            }
+            (ActivatorKind::ObjectDamaged { target_objects }, Trigger::Damaged { entity_id, amount }) => self
+                .matches_object(target_objects, entity_id, &scope)?
+                .then(|| Activation { damage: amount, ..on_object(entity_id) }),
+            (
+                ActivatorKind::CollisionEnter { target_objects, other_objects },
+                Trigger::CollisionEnter { a, b, point, normal },
+            ) => self.matches_collision(target_objects, other_objects, a, b, &scope)?.map(|(source, collided)| {
+                Activation {
+                    source,
+                    collided,
+                    target: source,
+                    collision_point: point,
+                    collision_normal: normal,
+                    ..Default::default()
+                }
+            }),
+            (ActivatorKind::CollisionExit { target_objects, other_objects }, Trigger::CollisionExit { a, b }) => self
+                .matches_collision(target_objects, other_objects, a, b, &scope)?
+                .map(|(source, collided)| Activation { collided, ..on_object(source) }),
+            (ActivatorKind::VariableChanged { variable: watched }, Trigger::VariableChanged(variable)) => self
+                .variable_ref(script.script_id, watched.int_value)
+                .is_some_and(|watched| watched == variable)
+                .then(Activation::default),
+            _ => None,
+        })
+    }
+
+    /// Whether an object parameter of an activator includes the object. `ObjectAnyObject` matches every object.
+    fn matches_object(&mut self, parameter: &NovaValue, entity_id: i32, scope: &Scope) -> Result<bool, RuntimeError> {
+        if parameter.dynamic_type == DynamicType::ObjectAnyObject {
+            return Ok(true);
+        }
+
+        Ok(self.eval(parameter, scope)?.as_objects().is_some_and(|objects| objects.contains(&entity_id)))
+    }
+
+    /// Matches a collision in either order, returning the object matching `target_objects` first.
+    fn matches_collision(
+        &mut self,
+        target_objects: &NovaValue,
+        other_objects: &NovaValue,
+        a: i32,
+        b: i32,
+        scope: &Scope,
+    ) -> Result<Option<(i32, i32)>, RuntimeError> {
+        for (source, collided) in [(a, b), (b, a)] {
+            if self.matches_object(target_objects, source, scope)?
+                && self.matches_object(other_objects, collided, scope)?
+            {
+                return Ok(Some((source, collided)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Fires a timer activator if a multiple of its interval was reached this frame.
+    fn timer(&mut self, script: &NovaScript, kind: &ActivatorKind) -> Result<Option<Activation>, RuntimeError> {
+        let ActivatorKind::Timer { interval } = kind else {
+            return Ok(None);
+        };
+
+        if self.frame == 0 {
+            return Ok(None);
+        }
+
+        let scope = Scope { script_id: script.script_id, ..Default::default() };
+        let interval = f64::from(eval_as!(self, interval, &scope, Float, as_float));
+
+        if interval <= 0.0 {
+            return Ok(None);
+        }
+
+        let now = self.time();
+        let previous = now - 1.0 / f64::from(FRAME_RATE);
+        let ticks = |time: f64| ((time + TIME_EPSILON) / interval).floor();
+
+        Ok((ticks(now) > ticks(previous)).then(Activation::default))
+    }
+
+    /// Starts an activation of a script unless its activation count is used up or its condition is false.
+    fn start(&mut self, script: &'a NovaScript, activation: Activation) -> Result<(), RuntimeError> {
+        let activations = self.activations.get(&script.script_id).copied().unwrap_or(0);
+
+        if script.activation_count > 0 && activations >= script.activation_count {
+            return Ok(());
+        }
+
+        let scope = Scope { script_id: script.script_id, activation, start_time: self.time(), ..Default::default() };
+
+        if !eval_as!(self, &script.condition, &scope, Bool, as_bool) {
+            return Ok(());
+        }
+
+        self.activations.insert(script.script_id, activations + 1);
+        self.threads.push(Thread {
+            script_id: script.script_id,
+            activation,
+            start_time: self.time(),
+            frames: vec![CallFrame {
+                script_id: script.script_id,
+                parameters: BTreeMap::new(),
+                blocks: vec![Block::new(&script.actions, BlockKind::Plain)],
+            }],
+            wait: None,
+        });
+        self.record(TraceEvent::ScriptStarted { script_id: script.script_id });
+
+        Ok(())
+    }
+
+    /// Runs every thread until it waits or ends.
+    pub(super) fn run_threads(&mut self) -> Result<(), RuntimeError> {
+        let mut threads = std::mem::take(&mut self.threads);
+        let mut result = Ok(());
+
+        threads.retain_mut(|thread| {
+            if result.is_err() {
+                return true;
+            }
+
+            self.resume(thread).unwrap_or_else(|error| {
+                result = Err(error);
+                false
+            })
+        });
+
+        // Threads that ran before a `StopScript` this frame are still in the list, the others were removed by
+        // `resume`.
+        let stopped = std::mem::take(&mut self.stopped);
+        threads.retain(|thread| {
+            let keep = !stopped.contains(&thread.script_id);
+            if !keep {
+                self.record(TraceEvent::ScriptStopped { script_id: thread.script_id });
+            }
+            keep
+        });
+
+        threads.append(&mut self.threads);
+        self.threads = threads;
+
+        if self.finished {
+            self.threads.clear();
+            self.tweens.clear();
+        }
+
+        result
+    }
+
+    /// Runs a thread until it waits or ends. Returns whether it is still running.
+    fn resume(&mut self, thread: &mut Thread<'a>) -> Result<bool, RuntimeError> {
+        if self.stopped.contains(&thread.script_id) {
+            self.record(TraceEvent::ScriptStopped { script_id: thread.script_id });
+            return Ok(false);
+        }
+
+        match thread.wait {
+            Some(Wait::Until(time)) if self.time() + TIME_EPSILON < time => return Ok(true),
+            Some(Wait::Frame(frame)) if self.frame < frame => return Ok(true),
+            _ => thread.wait = None,
+        }
+
+        let mut budget = self.instruction_limit;
+
+        loop {
+            if self.finished {
+                return Ok(false);
+            }
+
+            if self.stopped.contains(&thread.script_id) {
+                self.record(TraceEvent::ScriptStopped { script_id: thread.script_id });
+                return Ok(false);
+            }
+
+            budget = budget.checked_sub(1).ok_or(RuntimeError::InstructionLimit { script_id: thread.script_id })?;
+
+            let Some(frame) = thread.frames.last_mut() else {
+                self.record(TraceEvent::ScriptFinished { script_id: thread.script_id });
+                return Ok(false);
+            };
+
+            let Some(block) = frame.blocks.last_mut() else {
+                thread.frames.pop();
+                continue;
+            };
+
+            let actions: &'a [Action] = block.actions;
+
+            if let Some(action) = actions.get(block.index) {
+                block.index += 1;
+                self.execute(thread, action)?;
+
+                if thread.wait.is_some() {
+                    return Ok(true);
+                }
+
+                continue;
+            }
+
+            let again = match &mut block.kind {
+                BlockKind::Plain => false,
+                BlockKind::Repeat { iteration, count } => {
+                    *iteration += 1;
+                    iteration < count
+                }
+                BlockKind::ForEach { objects, iteration } => {
+                    *iteration += 1;
+                    *iteration < objects.len()
+                }
+                BlockKind::While(condition) => {
+                    let condition: &'a NovaValue = condition;
+                    eval_as!(self, condition, &thread.scope(), Bool, as_bool)
+                }
+            };
+
+            if let Some(block) = thread.frames.last_mut().and_then(|frame| frame.blocks.last_mut()) {
+                if again {
+                    block.index = 0;
+                } else {
+                    thread.frames.last_mut().map(|frame| frame.blocks.pop());
+                }
+            }
+        }
+    }
+
+    fn execute(&mut self, thread: &mut Thread<'a>, action: &'a Action) -> Result<(), RuntimeError> {
+        let scope = thread.scope();
+
+        macro_rules! eval {
+            ($value:expr, $static_type:ident, $extract:ident) => {
+                eval_as!(self, $value, &scope, $static_type, $extract)
+            };
+        }
+
+        macro_rules! objects {
+            ($value:expr) => {
+                eval!($value, ObjectSet, as_objects)
+            };
+        }
+
+        macro_rules! easing {
+            ($value:expr) => {
+                Easing::from_id(eval!($value, Easing, as_id))
+            };
+        }
+
+        let duration = match &action.action_type {
+            ActionType::Repeat { actions, count } => {
+                let count = eval!(count, Int, as_int);
+
+                if count > 0 {
+                    thread.push(actions, BlockKind::Repeat { iteration: 0, count });
+                }
+
+                None
+            }
+            ActionType::RepeatWhile { actions, condition } => {
+                if eval!(condition, Bool, as_bool) {
+                    thread.push(actions, BlockKind::While(condition));
+                }
+
+                None
+            }
+            ActionType::ConditionBlock { if_actions, else_actions, condition } => {
+                let actions = if eval!(condition, Bool, as_bool) { if_actions } else { else_actions };
+                thread.push(actions, BlockKind::Plain);
+                None
+            }
+            ActionType::RepeatForEachObject { actions, target_objects } => {
+                let objects = objects!(target_objects);
+
+                if !objects.is_empty() {
+                    thread.push(actions, BlockKind::ForEach { objects, iteration: 0 });
+                }
+
+                None
+            }
+            ActionType::Wait { duration } => {
+                let duration = eval!(duration, Float, as_float);
+
+                if duration > 0.0 {
+                    thread.wait = Some(Wait::Until(self.time() + f64::from(duration)));
+                }
+
+                None
+            }
+            ActionType::WaitFrames { frames } => {
+                let frames = eval!(frames, Int, as_int);
+
+                if let Ok(frames @ 1..) = u64::try_from(frames) {
+                    thread.wait = Some(Wait::Frame(self.frame + frames));
+                }
+
+                None
+            }
+            ActionType::RunFunction { function } => {
+                let frame = self.call(function, &scope, thread.frames.len())?;
+                thread.frames.push(frame);
+                None
+            }
+            ActionType::StopScript { script } => {
+                let script = eval!(script, Script, as_id);
+                self.stopped.insert(script);
+                None
+            }
+            ActionType::VariableSet { variable, value } => {
+                let variable = self.variable_ref_or_error(scope.script_id, *variable)?;
+                let value = self.assigned_value(variable, value.as_ref(), &scope)?;
+                self.write_variable(variable, value);
+                None
+            }
+            ActionType::VariableReset { variable } => {
+                let variable = self.variable_ref_or_error(scope.script_id, *variable)?;
+                let value = self.declared_initial_value(variable);
+                self.write_variable(variable, value);
+                None
+            }
+            ActionType::VariableSetOverTime { variable, value, duration, easing } => {
+                let variable = self.variable_ref_or_error(scope.script_id, *variable)?;
+                let value = self.assigned_value(variable, value.as_ref(), &scope)?;
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                self.start_tween(TweenTarget::Variable(variable), value, duration, easing);
+                Some(duration)
+            }
+            ActionType::Move { target_objects, position, global, duration, easing } => {
+                let (objects, position, global) =
+                    (objects!(target_objects), eval!(position, Vector, as_vector), eval!(global, Bool, as_bool));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+
+                for id in objects {
+                    let target =
+                        if global { world::parent_transform(&self.objects, id).inverse(position) } else { position };
+                    self.start_tween(TweenTarget::Position(id), Value::Vector(target), duration, easing);
+                }
+
+                Some(duration)
+            }
+            ActionType::Scale { target_objects, scale, duration, easing } => {
+                let (objects, scale) = (objects!(target_objects), eval!(scale, Vector, as_vector));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+
+                for id in objects {
+                    self.start_tween(TweenTarget::Scale(id), Value::Vector(scale), duration, easing);
+                }
+
+                Some(duration)
+            }
+            ActionType::Rotate { target_objects, rotation, shortest_path, global, duration, easing } => {
+                let (objects, rotation) = (objects!(target_objects), eval!(rotation, Float, as_float));
+                let (shortest_path, global) = (eval!(shortest_path, Bool, as_bool), eval!(global, Bool, as_bool));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+
+                for id in objects {
+                    let Some(current) = self.objects.get(&id).map(|object| object.rotation) else {
+                        continue;
+                    };
+
+                    let mut target =
+                        if global { rotation - world::parent_transform(&self.objects, id).rotation } else { rotation };
+
+                    if shortest_path {
+                        target = current + world::wrap_degrees(target - current);
+                    }
+
+                    self.start_tween(TweenTarget::Rotation(id), Value::Float(target), duration, easing);
+                }
+
+                Some(duration)
+            }
+            ActionType::RotateAround { target_objects, pivot, rotation, is_global, rotate_target, duration, easing } => {
+                let (objects, pivot, rotation) =
+                    (objects!(target_objects), eval!(pivot, Vector, as_vector), eval!(rotation, Float, as_float));
+                let (is_global, rotate_target) = (eval!(is_global, Bool, as_bool), eval!(rotate_target, Bool, as_bool));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+
+                for id in objects {
+                    let Some(object) = self.objects.get(&id) else {
+                        continue;
+                    };
+
+                    let target = TweenTarget::Orbit {
+                        entity_id: id,
+                        pivot: if is_global { world::parent_transform(&self.objects, id).inverse(pivot) } else { pivot },
+                        position: object.position,
+                        rotation: object.rotation,
+                        rotate_target,
+                    };
+
+                    self.start_tween(target, Value::Float(rotation), duration, easing);
+                }
+
+                Some(duration)
+            }
+            ActionType::ObjectReset { target_objects } => {
+                for id in objects!(target_objects) {
+                    self.reset_object(id);
+                }
+
+                None
+            }
+            ActionType::SetColor { target_objects, color, duration, easing, .. } => {
+                let (objects, color) = (objects!(target_objects), eval!(color, Color, as_color));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                self.tween_colors(&objects, ColorSlot::Primary, |_| color, duration, easing);
+                Some(duration)
+            }
+            ActionType::SetSecondaryColor { target_objects, color, duration, easing } => {
+                let (objects, color) = (objects!(target_objects), eval!(color, Color, as_color));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                self.tween_colors(&objects, ColorSlot::Secondary, |_| color, duration, easing);
+                Some(duration)
+            }
+            ActionType::SetBorderColor { target_objects, color, duration, easing } => {
+                let (objects, color) = (objects!(target_objects), eval!(color, Color, as_color));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                self.tween_colors(&objects, ColorSlot::Border, |_| color, duration, easing);
+                Some(duration)
+            }
+            ActionType::SetTransparency { target_objects, transparency, duration, easing, .. }
+            | ActionType::SetSecondaryTransparency { target_objects, transparency, duration, easing }
+            | ActionType::SetBorderTransparency { target_objects, transparency, duration, easing } => {
+                let slot = match action.action_type {
+                    ActionType::SetSecondaryTransparency { .. } => ColorSlot::Secondary,
+                    ActionType::SetBorderTransparency { .. } => ColorSlot::Border,
+                    _ => ColorSlot::Primary,
+                };
+                let (objects, alpha) = (objects!(target_objects), eval!(transparency, Float, as_float));
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                let with_alpha = |color: Color| Color::new(color.r.0, color.g.0, color.b.0, alpha);
+                self.tween_colors(&objects, slot, with_alpha, duration, easing);
+                Some(duration)
+            }
+            ActionType::SetSprite { target_objects, sprite } => {
+                let (objects, sprite) = (objects!(target_objects), eval!(sprite, Sprite, as_id));
+                self.update_objects(&objects, |object| {
+                    object.sprite = sprite;
+                    ObjectChange::Sprite(sprite)
+                });
+                None
+            }
+            ActionType::SetText { target_objects, text } => {
+                let objects = objects!(target_objects);
+                let text = match self.eval(text, &scope)? {
+                    Value::String(text) => text,
+                    other => {
+                        return Err(RuntimeError::Type {
+                            script_id: scope.script_id,
+                            kind: TypeErrorKind::Mismatch { expected: StaticType::String, found: other.static_type() },
+                        })
+                    }
+                };
+                self.update_objects(&objects, |object| {
+                    object.text.clone_from(&text);
+                    ObjectChange::Text(text.clone())
+                });
+                None
+            }
+            ActionType::SetEnabled { target_objects, enabled } => {
+                let (objects, enabled) = (objects!(target_objects), eval!(enabled, Bool, as_bool));
+                self.update_objects(&objects, |object| {
+                    object.enabled = enabled;
+                    ObjectChange::Enabled(enabled)
+                });
+                None
+            }
+            ActionType::SetDirection { target_objects, direction } => {
+                let (objects, direction) = (objects!(target_objects), eval!(direction, Int, as_int));
+                self.update_objects(&objects, |object| {
+                    object.direction = direction;
+                    ObjectChange::Direction(direction)
+                });
+                None
+            }
+            ActionType::SetGravity { target_objects, gravity } => {
+                let (objects, gravity) = (objects!(target_objects), eval!(gravity, Vector, as_vector));
+                self.update_objects(&objects, |object| {
+                    object.gravity = gravity;
+                    ObjectChange::Gravity(gravity)
+                });
+                None
+            }
+            ActionType::SetVelocity { target_objects, velocity } => {
+                let (objects, velocity) = (objects!(target_objects), eval!(velocity, Vector, as_vector));
+                self.update_objects(&objects, |object| {
+                    object.velocity = velocity;
+                    ObjectChange::Velocity(velocity)
+                });
+                None
+            }
+            ActionType::Activate { target_objects } | ActionType::Deactivate { target_objects } => {
+                let active = matches!(action.action_type, ActionType::Activate { .. });
+
+                for id in objects!(target_objects) {
+                    self.set_activated(id, active);
+                }
+
+                None
+            }
+            ActionType::Damage { target_objects, damage } => {
+                let (objects, damage) = (objects!(target_objects), eval!(damage, Float, as_float));
+
+                for id in objects {
+                    self.apply_damage(id, damage);
+                }
+
+                None
+            }
+            ActionType::Kill { target_objects } => {
+                for id in objects!(target_objects) {
+                    self.apply_kill(id);
+                }
+
+                None
+            }
+            ActionType::GameFinish => {
+                self.finished = true;
+                self.record(TraceEvent::GameFinished);
+                None
+            }
+            ActionType::CameraPan { position, duration, easing } => {
+                let (position, duration) = (eval!(position, Vector, as_vector), eval!(duration, Float, as_float));
+                let easing = easing!(easing);
+                self.start_tween(TweenTarget::CameraPosition, Value::Vector(position), duration, easing);
+                Some(duration)
+            }
+            ActionType::CameraZoom { viewport_size, duration, easing } => {
+                let (size, duration) = (eval!(viewport_size, Float, as_float), eval!(duration, Float, as_float));
+                let easing = easing!(easing);
+                self.start_tween(TweenTarget::CameraViewportSize, Value::Float(size), duration, easing);
+                Some(duration)
+            }
+            ActionType::CameraZoomReset { duration, easing } => {
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                let size = self.initial_camera.viewport_size;
+                self.start_tween(TweenTarget::CameraViewportSize, Value::Float(size), duration, easing);
+                Some(duration)
+            }
+            ActionType::CameraOffset { offset, duration, easing } => {
+                let (offset, duration) = (eval!(offset, Vector, as_vector), eval!(duration, Float, as_float));
+                let easing = easing!(easing);
+                self.start_tween(TweenTarget::CameraOffset, Value::Vector(offset), duration, easing);
+                Some(duration)
+            }
+            ActionType::CameraOffsetReset { duration, easing } => {
+                let (duration, easing) = (eval!(duration, Float, as_float), easing!(easing));
+                self.start_tween(TweenTarget::CameraOffset, Value::Vector(Vec2::ZERO), duration, easing);
+                Some(duration)
+            }
+            ActionType::ObjectCreate { prefab, position, .. } => {
+                let (prefab, position) = (eval!(prefab, Prefab, as_id), eval!(position, Vector, as_vector));
+                self.create_objects(prefab, position);
+                None
+            }
+            ActionType::ObjectDelete { target_objects } => {
+                for id in objects!(target_objects) {
+                    self.delete_object(id);
+                }
+
+                None
+            }
+            other => {
+                if matches!(other, ActionType::PlaySound { .. } | ActionType::PlaySoundDeprecated { .. }) {
+                    self.last_sound_instance += 1;
+                }
+
+                self.record(TraceEvent::Action { script_id: scope.script_id, action_type: i32::from(other) });
+
+                match other.values().into_iter().find(|(name, _)| *name == "duration") {
+                    Some((_, duration)) if action.wait => Some(eval!(duration, Float, as_float)),
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some(duration) = duration.filter(|duration| action.wait && *duration > 0.0) {
+            thread.wait = Some(Wait::Until(self.time() + f64::from(duration)));
+        }
+
+        Ok(())
+    }
+
+    /// Builds the call frame for a `RunFunction` action, evaluating the arguments in the caller's scope.
+    fn call(&mut self, function: &FunctionCall, scope: &Scope, depth: usize) -> Result<CallFrame<'a>, RuntimeError> {
+        let level = self.level;
+        let error = |kind| RuntimeError::Type { script_id: scope.script_id, kind };
+
+        let script = level
+            .nova_scripts
+            .iter()
+            .find(|script| script.script_id == function.id && script.is_function)
+            .ok_or_else(|| error(TypeErrorKind::UnknownFunction(function.id)))?;
+
+        if depth >= Self::MAX_CALL_DEPTH {
+            return Err(RuntimeError::StackOverflow { script_id: script.script_id });
+        }
+
+        let mut parameters = BTreeMap::new();
+        let defaults_scope = Scope { script_id: script.script_id, ..Default::default() };
+
+        for parameter in &script.parameters {
+            let value = self
+                .eval(&parameter.default_value, &defaults_scope)
+                .unwrap_or_else(|_| Value::default_for(parameter.static_type));
+            parameters.insert(parameter.parameter_id, value);
+        }
+
+        for argument in &function.parameters {
+            let Some(declared) = parameters.get(&argument.parameter_id).map(Value::static_type) else {
+                return Err(error(TypeErrorKind::UnknownArgument {
+                    function_id: function.id,
+                    parameter_id: argument.parameter_id,
+                }));
+            };
+
+            let value = self.eval(&argument.value, scope)?;
+
+            if value.static_type() != declared {
+                return Err(error(TypeErrorKind::Mismatch { expected: declared, found: value.static_type() }));
+            }
+
+            parameters.insert(argument.parameter_id, value);
+        }
+
+        Ok(CallFrame {
+            script_id: script.script_id,
+            parameters,
+            blocks: vec![Block::new(&script.actions, BlockKind::Plain)],
+        })
+    }
+
+    /// Resolves a variable id as seen from a script: its own variables shadow the global ones.
+    pub(super) fn variable_ref(&self, script_id: i32, variable_id: i32) -> Option<VariableRef> {
+        if self.locals.get(&script_id).is_some_and(|locals| locals.contains_key(&variable_id)) {
+            Some(VariableRef::Local { script_id, variable_id })
+        } else if self.globals.contains_key(&variable_id) {
+            Some(VariableRef::Global(variable_id))
+        } else {
+            None
+        }
+    }
+
+    fn variable_ref_or_error(&self, script_id: i32, variable_id: i32) -> Result<VariableRef, RuntimeError> {
+        self.variable_ref(script_id, variable_id)
+            .ok_or(RuntimeError::Type { script_id, kind: TypeErrorKind::UnknownVariable(variable_id) })
+    }
+
+    fn variable_value(&self, variable: VariableRef) -> Option<&Value> {
+        match variable {
+            VariableRef::Global(variable_id) => self.globals.get(&variable_id),
+            VariableRef::Local { script_id, variable_id } => self.locals.get(&script_id)?.get(&variable_id),
+        }
+    }
+
+    /// Evaluates the value a `VariableSet` assigns. A missing value resets the variable to the default of its type.
+    fn assigned_value(
+        &mut self,
+        variable: VariableRef,
+        value: Option<&NovaValue>,
+        scope: &Scope,
+    ) -> Result<Value, RuntimeError> {
+        let declared = self.variable_value(variable).map_or(StaticType::Bool, Value::static_type);
+
+        let Some(value) = value else {
+            return Ok(Value::default_for(declared));
+        };
+
+        let value = self.eval(value, scope)?;
+
+        if value.static_type() != declared {
+            return Err(RuntimeError::Type {
+                script_id: scope.script_id,
+                kind: TypeErrorKind::Mismatch { expected: declared, found: value.static_type() },
+            });
+        }
+
+        Ok(value)
+    }
+
+    fn declared_initial_value(&mut self, variable: VariableRef) -> Value {
+        let level = self.level;
+
+        let declaration = match variable {
+            VariableRef::Global(variable_id) => {
+                level.global_variables.iter().find(|declared| declared.variable_id == variable_id)
+            }
+            VariableRef::Local { script_id, variable_id } => level
+                .nova_scripts
+                .iter()
+                .find(|script| script.script_id == script_id)
+                .and_then(|script| script.variables.iter().find(|declared| declared.variable_id == variable_id)),
+        };
+
+        declaration.map_or(Value::Bool(false), |declaration| self.initial_value(declaration))
+    }
+
+    /// Sets a variable, recording the change and triggering `VARIABLE_CHANGED` activators if the value differs.
+    fn write_variable(&mut self, variable: VariableRef, value: Value) {
+        let slot = match variable {
+            VariableRef::Global(variable_id) => self.globals.get_mut(&variable_id),
+            VariableRef::Local { script_id, variable_id } => {
+                self.locals.get_mut(&script_id).and_then(|locals| locals.get_mut(&variable_id))
+            }
+        };
+
+        let Some(slot) = slot.filter(|slot| **slot != value) else {
+            return;
+        };
+
+        let old = std::mem::replace(slot, value.clone());
+        self.record(TraceEvent::VariableChanged { variable, old, new: value });
+        self.pending.push(Trigger::VariableChanged(variable));
+    }
+
+    pub(super) fn set_activated(&mut self, entity_id: i32, activated: bool) {
+        let Some(object) = self.objects.get_mut(&entity_id).filter(|object| object.activated != activated) else {
+            return;
+        };
+
+        object.activated = activated;
+
+        if activated {
+            self.record_object(entity_id, ObjectChange::Activated);
+            self.pending.push(Trigger::Activated(entity_id));
+        } else {
+            self.record_object(entity_id, ObjectChange::Deactivated);
+            self.pending.push(Trigger::Deactivated(entity_id));
+        }
+    }
+
+    /// Lowers the health of an object, killing it if the health runs out.
+    pub(super) fn apply_damage(&mut self, entity_id: i32, amount: f32) {
+        let Some(object) = self.objects.get_mut(&entity_id).filter(|object| !object.dead) else {
+            return;
+        };
+
+        let had_health = object.health > 0.0;
+        object.health -= amount;
+        let killed = had_health && object.health <= 0.0;
+
+        self.record_object(entity_id, ObjectChange::Damaged(amount));
+        self.pending.push(Trigger::Damaged { entity_id, amount });
+
+        if killed {
+            self.apply_kill(entity_id);
+        }
+    }
+
+    pub(super) fn apply_kill(&mut self, entity_id: i32) {
+        let Some(object) = self.objects.get_mut(&entity_id).filter(|object| !object.dead) else {
+            return;
+        };
+
+        object.dead = true;
+        self.record_object(entity_id, ObjectChange::Killed);
+        self.pending.push(Trigger::Killed(entity_id));
+    }
+
+    /// Applies an instant change to each existing object and records it.
+    fn update_objects(&mut self, objects: &[i32], mut update: impl FnMut(&mut ObjectState) -> ObjectChange) {
+        for id in objects {
+            if let Some(object) = self.objects.get_mut(id) {
+                let change = update(object);
+                self.record_object(*id, change);
+            }
+        }
+    }
+
+    fn reset_object(&mut self, entity_id: i32) {
+        let Some(initial) = self.initial_objects.get(&entity_id).cloned() else {
+            return;
+        };
+
+        self.tweens.retain(|tween| tween.target.entity_id() != Some(entity_id));
+        self.objects.insert(entity_id, initial);
+        self.record_object(entity_id, ObjectChange::Reset);
+    }
+
+    /// Instantiates the items of a prefab, offset by `position`.
+    fn create_objects(&mut self, prefab_id: i32, position: Vec2) {
+        let level = self.level;
+        self.last_created_object = 0;
+
+        let Some(prefab) = level.prefabs.iter().find(|prefab| prefab.prefab_id == prefab_id) else {
+            return;
+        };
+
+        for item in &prefab.items {
+            let entity_id = self.next_entity_id;
+            self.next_entity_id += 1;
+
+            let mut object = ObjectState::new(item, level.gravity);
+            object.entity_id = entity_id;
+            object.prefab_id = prefab_id;
+            object.position = item.position + position;
+            object.parent = 0;
+            object.children.clear();
+
+            self.objects.insert(entity_id, object);
+            self.record(TraceEvent::ObjectCreated { entity_id, prefab_id });
+
+            if self.last_created_object == 0 {
+                self.last_created_object = entity_id;
+            }
+        }
+    }
+
+    fn delete_object(&mut self, entity_id: i32) {
+        if entity_id == PLAYER {
+            return;
+        }
+
+        let Some(object) = self.objects.remove(&entity_id) else {
+            return;
+        };
+
+        if let Some(parent) = self.objects.get_mut(&object.parent) {
+            parent.children.retain(|child| *child != entity_id);
+        }
+
+        self.tweens.retain(|tween| tween.target.entity_id() != Some(entity_id));
+        self.record(TraceEvent::ObjectDeleted { entity_id });
+    }
+
+    fn tween_colors(
+        &mut self,
+        objects: &[i32],
+        slot: ColorSlot,
+        target: impl Fn(Color) -> Color,
+        duration: f32,
+        easing: Easing,
+    ) {
+        for &id in objects {
+            if let Some(Value::Color(current)) = self.tween_value(&TweenTarget::Color(id, slot)) {
+                self.start_tween(TweenTarget::Color(id, slot), Value::Color(target(current)), duration, easing);
+            }
+        }
+    }
+
+    /// Starts animating a target, replacing any tween already running on it. Without a duration the target jumps to
+    /// `to` immediately.
+    fn start_tween(&mut self, target: TweenTarget, to: Value, duration: f32, easing: Easing) {
+        let Some(from) = self.tween_value(&target) else {
+            return;
+        };
+
+        self.tweens.retain(|tween| match (&tween.target, &target) {
+            (TweenTarget::Orbit { entity_id: a, .. }, TweenTarget::Orbit { entity_id: b, .. }) => a != b,
+            (a, b) => a != b,
+        });
+
+        if duration > 0.0 {
+            self.tweens.push(Tween { target, from, to, start: self.time(), duration, easing });
+        } else {
+            self.set_tween_value(&target, to);
+        }
+    }
+
+    /// Advances every tween to the current time.
+    pub(super) fn update_tweens(&mut self) {
+        let time = self.time();
+        let mut running = Vec::new();
+
+        for tween in std::mem::take(&mut self.tweens) {
+            let (value, done) = tween.sample(time);
+            self.set_tween_value(&tween.target, value);
+
+            if !done {
+                running.push(tween);
+            }
+        }
+
+        running.append(&mut self.tweens);
+        self.tweens = running;
+    }
+
+    /// The current value of a tween target, or `None` if it doesn't exist.
+    fn tween_value(&self, target: &TweenTarget) -> Option<Value> {
+        let object = |id: &i32| self.objects.get(id);
+
+        Some(match target {
+            TweenTarget::Position(id) => Value::Vector(object(id)?.position),
+            TweenTarget::Scale(id) => Value::Vector(object(id)?.scale),
+            TweenTarget::Rotation(id) => Value::Float(object(id)?.rotation),
+            TweenTarget::Color(id, ColorSlot::Primary) => Value::Color(object(id)?.color),
+            TweenTarget::Color(id, ColorSlot::Secondary) => Value::Color(object(id)?.secondary_color),
+            TweenTarget::Color(id, ColorSlot::Border) => Value::Color(object(id)?.border_color),
+            TweenTarget::Orbit { entity_id, .. } => {
+                object(entity_id)?;
+                Value::Float(0.0)
+            }
+            TweenTarget::CameraPosition => Value::Vector(self.camera.position),
+            TweenTarget::CameraViewportSize => Value::Float(self.camera.viewport_size),
+            TweenTarget::CameraOffset => Value::Vector(self.camera.offset),
+            TweenTarget::Variable(variable) => self.variable_value(*variable)?.clone(),
+        })
+    }
+
+    /// Sets a tween target and records the change.
+    fn set_tween_value(&mut self, target: &TweenTarget, value: Value) {
+        match (target, value) {
+            (TweenTarget::Position(id), Value::Vector(position)) => self.update_objects(&[*id], |object| {
+                object.position = position;
+                ObjectChange::Position(position)
+            }),
+            (TweenTarget::Scale(id), Value::Vector(scale)) => self.update_objects(&[*id], |object| {
+                object.scale = scale;
+                ObjectChange::Scale(scale)
+            }),
+            (TweenTarget::Rotation(id), Value::Float(rotation)) => self.update_objects(&[*id], |object| {
+                object.rotation = rotation;
+                ObjectChange::Rotation(rotation)
+            }),
+            (TweenTarget::Color(id, slot), Value::Color(color)) => self.update_objects(&[*id], |object| match slot {
+                ColorSlot::Primary => {
+                    object.color = color;
+                    ObjectChange::Color(color)
+                }
+                ColorSlot::Secondary => {
+                    object.secondary_color = color;
+                    ObjectChange::SecondaryColor(color)
+                }
+                ColorSlot::Border => {
+                    object.border_color = color;
+                    ObjectChange::BorderColor(color)
+                }
+            }),
+            (&TweenTarget::Orbit { entity_id, pivot, position, rotation, rotate_target }, Value::Float(angle)) => {
+                let position = pivot + world::rotate(position - pivot, angle);
+
+                self.update_objects(&[entity_id], |object| {
+                    object.position = position;
+                    ObjectChange::Position(position)
+                });
+
+                if rotate_target {
+                    self.update_objects(&[entity_id], |object| {
+                        object.rotation = rotation + angle;
+                        ObjectChange::Rotation(object.rotation)
+                    });
+                }
+            }
+            (TweenTarget::CameraPosition, Value::Vector(position)) => {
+                self.camera.position = position;
+                self.record(TraceEvent::Camera(self.camera));
+            }
+            (TweenTarget::CameraViewportSize, Value::Float(size)) => {
+                self.camera.viewport_size = size;
+                self.record(TraceEvent::Camera(self.camera));
+            }
+            (TweenTarget::CameraOffset, Value::Vector(offset)) => {
+                self.camera.offset = offset;
+                self.record(TraceEvent::Camera(self.camera));
+            }
+            (TweenTarget::Variable(variable), value) => self.write_variable(*variable, value),
+            _ => {}
+        }
+    }
+}
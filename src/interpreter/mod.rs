@@ -0,0 +1,764 @@
+//! A headless interpreter for nova scripts, for testing level logic without the game.
+//!
+//! [`Interpreter`] runs the scripts of a [`LevelData`] frame by frame at [`FRAME_RATE`] frames per second. Input,
+//! pointer, collision and damage events are injected between frames, and every observable change is recorded in a
+//! [`TraceEntry`] list that tests can assert on:
+//!
+//! ```no_run
+//! # let level = exolvl::types::level_data::LevelData::default();
+//! use exolvl::interpreter::{Interpreter, Side, Value};
+//!
+//! let mut interpreter = Interpreter::new(&level);
+//! interpreter.press(Side::Left);
+//! interpreter.run_frames(60).unwrap();
+//!
+//! assert_eq!(interpreter.variable(1), Some(&Value::Int(3)));
+//! ```
+//!
+//! Only script logic is simulated. Physics, rendering and sound aren't: actions that only affect those are recorded
+//! in the trace as [`TraceEvent::Action`], and player state like [`PlayerState::on_ground`] is set by the caller.
+//!
+//! Scripts are started by their activators, as described by
+//! [`ActivatorKind`](crate::types::nova_script::activator::ActivatorKind). Activators that don't fit their kind never
+//! fire.
+//!
+//! `Variable::persistance` decides what survives a reset: 0 (or missing) resets on every [`Interpreter::respawn`],
+//! 1 survives respawns but not [`Interpreter::restart`]s, and anything higher survives both.
+
+mod easing;
+mod eval;
+mod exec;
+mod value;
+mod world;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+pub use easing::{Easing, EasingFamily};
+pub use value::Value;
+pub use world::{Camera, ObjectState, PlayerState, PLAYER};
+pub use crate::types::nova_script::variable::VariableRef;
+
+use crate::type_check::TypeErrorKind;
+use crate::types::{color::Color, level_data::LevelData, nova_script::variable::Variable, vec2::Vec2};
+use exec::Thread;
+use world::Tween;
+
+/// The number of frames simulated per second.
+pub const FRAME_RATE: u32 = 60;
+
+/// A side of the screen, for input events.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A change to an object, see [`TraceEvent::Object`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectChange {
+    Position(Vec2),
+    Scale(Vec2),
+    /// In degrees.
+    Rotation(f32),
+    Color(Color),
+    SecondaryColor(Color),
+    BorderColor(Color),
+    Sprite(i32),
+    Text(String),
+    Enabled(bool),
+    Activated,
+    Deactivated,
+    Damaged(f32),
+    Killed,
+    Direction(i32),
+    Velocity(Vec2),
+    Gravity(Vec2),
+    /// The object went back to its initial state.
+    Reset,
+}
+
+/// Something observable that happened while running.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+    /// A script was activated.
+    ScriptStarted { script_id: i32 },
+    /// An activation of a script ran its last action.
+    ScriptFinished { script_id: i32 },
+    /// An activation of a script was stopped by a `StopScript` action.
+    ScriptStopped { script_id: i32 },
+    VariableChanged { variable: VariableRef, old: Value, new: Value },
+    Object { entity_id: i32, change: ObjectChange },
+    ObjectCreated { entity_id: i32, prefab_id: i32 },
+    ObjectDeleted { entity_id: i32 },
+    /// The camera moved or zoomed.
+    Camera(Camera),
+    /// A `GameFinish` action ran. Nothing runs after this.
+    GameFinished,
+    /// An action ran whose effect isn't simulated, e.g. `PlaySound`. `action_type` is its id in the binary format.
+    Action { script_id: i32, action_type: i32 },
+}
+
+/// A [`TraceEvent`] and the frame it happened in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceEntry {
+    pub frame: u64,
+    pub event: TraceEvent,
+}
+
+/// Why the interpreter had to stop a script.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RuntimeError {
+    /// A value couldn't be evaluated, e.g. because it has operands of the wrong type.
+    Type { script_id: i32, kind: TypeErrorKind },
+    /// A script ran more actions in a single frame than the instruction limit allows, e.g. a `RepeatWhile` without a
+    /// wait that never ends.
+    InstructionLimit { script_id: i32 },
+    /// `RunFunction` calls nested deeper than [`Interpreter::MAX_CALL_DEPTH`].
+    StackOverflow { script_id: i32 },
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type { script_id, kind } => write!(f, "script {script_id}: {kind}"),
+            Self::InstructionLimit { script_id } => {
+                write!(f, "script {script_id} exceeded the instruction limit in a single frame")
+            }
+            Self::StackOverflow { script_id } => write!(f, "script {script_id} exceeded the maximum call depth"),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+/// The state an activator hands to the script it starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Activation {
+    source: i32,
+    collided: i32,
+    target: i32,
+    damage: f32,
+    collision_point: Vec2,
+    collision_normal: Vec2,
+}
+
+/// An event waiting to be dispatched to activators at the start of the next frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Trigger {
+    LevelStart,
+    Activated(i32),
+    Deactivated(i32),
+    CollisionEnter { a: i32, b: i32, point: Vec2, normal: Vec2 },
+    CollisionExit { a: i32, b: i32 },
+    InputDown,
+    InputReleased,
+    PointerDown,
+    PointerReleased,
+    VariableChanged(VariableRef),
+    Damaged { entity_id: i32, amount: f32 },
+    Killed(i32),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct InputState {
+    held: [bool; 2],
+    down: [bool; 2],
+    released: [bool; 2],
+    pointer_held: bool,
+    pointer_down: bool,
+    pointer_released: bool,
+    pointer_position: Vec2,
+    pointer_world_position: Vec2,
+}
+
+/// Runs the nova scripts of a level, see the [module docs](self).
+#[derive(Debug)]
+pub struct Interpreter<'a> {
+    level: &'a LevelData,
+    frame: u64,
+    rng: StdRng,
+    instruction_limit: usize,
+    globals: BTreeMap<i32, Value>,
+    /// Script-local variables, by script id.
+    locals: BTreeMap<i32, BTreeMap<i32, Value>>,
+    objects: BTreeMap<i32, ObjectState>,
+    initial_objects: BTreeMap<i32, ObjectState>,
+    next_entity_id: i32,
+    last_created_object: i32,
+    last_sound_instance: i32,
+    player_state: PlayerState,
+    camera: Camera,
+    initial_camera: Camera,
+    input: InputState,
+    /// Pairs of colliding objects, smaller entity id first.
+    collisions: BTreeSet<(i32, i32)>,
+    threads: Vec<Thread<'a>>,
+    tweens: Vec<Tween>,
+    pending: Vec<Trigger>,
+    /// How often each script has been activated, by script id.
+    activations: BTreeMap<i32, i32>,
+    /// Scripts stopped by `StopScript` whose activations haven't all been removed yet.
+    stopped: BTreeSet<i32>,
+    finished: bool,
+    trace: Vec<TraceEntry>,
+}
+
+impl<'a> Interpreter<'a> {
+    /// The default for [`Interpreter::with_instruction_limit`].
+    pub const DEFAULT_INSTRUCTION_LIMIT: usize = 100_000;
+
+    /// How deeply `RunFunction` calls can nest.
+    pub const MAX_CALL_DEPTH: usize = 256;
+
+    /// Creates an interpreter for the level. The level start activators run on the first frame.
+    #[must_use]
+    pub fn new(level: &'a LevelData) -> Self {
+        let objects: BTreeMap<_, _> = level
+            .objects
+            .iter()
+            .map(|object| (object.entity_id, ObjectState::new(object, level.gravity)))
+            .chain(std::iter::once((PLAYER, ObjectState::player(level))))
+            .collect();
+
+        let camera = Camera {
+            position: objects[&PLAYER].position,
+            viewport_size: Camera::DEFAULT_VIEWPORT_SIZE,
+            offset: Vec2::ZERO,
+        };
+
+        let mut interpreter = Self {
+            level,
+            frame: 0,
+            rng: StdRng::seed_from_u64(0),
+            instruction_limit: Self::DEFAULT_INSTRUCTION_LIMIT,
+            globals: BTreeMap::new(),
+            locals: BTreeMap::new(),
+            next_entity_id: level.objects.iter().map(|object| object.entity_id).max().unwrap_or(0).max(0) + 1,
+            initial_objects: objects.clone(),
+            objects,
+            last_created_object: 0,
+            last_sound_instance: 0,
+            player_state: PlayerState::default(),
+            camera,
+            initial_camera: camera,
+            input: InputState::default(),
+            collisions: BTreeSet::new(),
+            threads: Vec::new(),
+            tweens: Vec::new(),
+            pending: vec![Trigger::LevelStart],
+            activations: BTreeMap::new(),
+            stopped: BTreeSet::new(),
+            finished: false,
+            trace: Vec::new(),
+        };
+
+        interpreter.reset_variables(|_| true);
+        interpreter
+    }
+
+    /// Seeds the random number generator used by the `*Random*` dynamic types and `Shuffle`. Defaults to 0.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Sets how many actions a single script may run in one frame before [`RuntimeError::InstructionLimit`].
+    #[must_use]
+    pub const fn with_instruction_limit(mut self, limit: usize) -> Self {
+        self.instruction_limit = limit;
+        self
+    }
+
+    /// Simulates one frame: dispatches pending events, runs every active script until it waits or ends, then
+    /// advances time and the running tweens.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RuntimeError`] if a script fails. The failing activation is dropped, everything else keeps its
+    /// state, so stepping again continues the other scripts.
+    pub fn step(&mut self) -> Result<(), RuntimeError> {
+        if self.finished {
+            return Ok(());
+        }
+
+        let result = self.dispatch().and_then(|()| self.run_threads());
+
+        self.frame += 1;
+        self.update_tweens();
+        self.input.down = [false; 2];
+        self.input.released = [false; 2];
+        self.input.pointer_down = false;
+        self.input.pointer_released = false;
+
+        result
+    }
+
+    /// Calls [`Interpreter::step`] `frames` times.
+    ///
+    /// # Errors
+    ///
+    /// Stops at and returns the first [`RuntimeError`].
+    pub fn run_frames(&mut self, frames: u32) -> Result<(), RuntimeError> {
+        for _ in 0..frames {
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    /// Steps until `seconds` of game time have passed.
+    ///
+    /// # Errors
+    ///
+    /// Stops at and returns the first [`RuntimeError`].
+    pub fn run_for(&mut self, seconds: f32) -> Result<(), RuntimeError> {
+        let end = self.time() + f64::from(seconds);
+
+        while self.time() < end && !self.finished {
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    /// Simulates the player dying: stops all scripts and tweens, resets every object, resets the variables that
+    /// don't persist across respawns and runs the level start activators again.
+    pub fn respawn(&mut self) {
+        self.reset(1);
+    }
+
+    /// Like [`Interpreter::respawn`], but only keeps variables with a persistance above 1.
+    pub fn restart(&mut self) {
+        self.reset(2);
+    }
+
+    fn reset(&mut self, kept_persistance: i32) {
+        self.threads.clear();
+        self.tweens.clear();
+        self.pending = vec![Trigger::LevelStart];
+        self.activations.clear();
+        self.collisions.clear();
+        self.objects.clone_from(&self.initial_objects);
+        self.camera = self.initial_camera;
+        self.finished = false;
+        self.reset_variables(|variable| variable.persistance.unwrap_or(0) < kept_persistance);
+    }
+
+    /// Sets every variable for which `reset` returns true to its initial value.
+    fn reset_variables(&mut self, reset: impl Fn(&Variable) -> bool) {
+        let level = self.level;
+
+        for variable in level.global_variables.iter().filter(|variable| reset(variable)) {
+            let value = self.initial_value(variable);
+            self.globals.insert(variable.variable_id, value);
+        }
+
+        for script in &level.nova_scripts {
+            for variable in script.variables.iter().filter(|variable| reset(variable)) {
+                let value = self.initial_value(variable);
+                self.locals.entry(script.script_id).or_default().insert(variable.variable_id, value);
+            }
+        }
+    }
+
+    fn initial_value(&mut self, variable: &Variable) -> Value {
+        let value = self
+            .eval(&variable.initial_value, &eval::Scope::default())
+            .unwrap_or_else(|_| Value::default_for(variable.static_type));
+
+        if value.static_type() == variable.static_type {
+            value
+        } else {
+            Value::default_for(variable.static_type)
+        }
+    }
+
+    /// Presses the input on one side of the screen.
+    pub fn press(&mut self, side: Side) {
+        let index = side as usize;
+
+        if !self.input.held[index] {
+            self.input.held[index] = true;
+            self.input.down[index] = true;
+            self.pending.push(Trigger::InputDown);
+        }
+    }
+
+    /// Releases the input on one side of the screen.
+    pub fn release(&mut self, side: Side) {
+        let index = side as usize;
+
+        if self.input.held[index] {
+            self.input.held[index] = false;
+            self.input.released[index] = true;
+            self.pending.push(Trigger::InputReleased);
+        }
+    }
+
+    /// Moves the pointer, in screen and world coordinates.
+    pub const fn set_pointer(&mut self, position: Vec2, world_position: Vec2) {
+        self.input.pointer_position = position;
+        self.input.pointer_world_position = world_position;
+    }
+
+    /// Presses the pointer at its current position.
+    pub fn pointer_press(&mut self) {
+        if !self.input.pointer_held {
+            self.input.pointer_held = true;
+            self.input.pointer_down = true;
+            self.pending.push(Trigger::PointerDown);
+        }
+    }
+
+    /// Releases the pointer.
+    pub fn pointer_release(&mut self) {
+        if self.input.pointer_held {
+            self.input.pointer_held = false;
+            self.input.pointer_released = true;
+            self.pending.push(Trigger::PointerReleased);
+        }
+    }
+
+    /// Starts a collision between two objects (or an object and [`PLAYER`]).
+    pub fn begin_collision(&mut self, a: i32, b: i32, point: Vec2, normal: Vec2) {
+        if self.collisions.insert((a.min(b), a.max(b))) {
+            self.pending.push(Trigger::CollisionEnter { a, b, point, normal });
+        }
+    }
+
+    /// Ends a collision started with [`Interpreter::begin_collision`].
+    pub fn end_collision(&mut self, a: i32, b: i32) {
+        if self.collisions.remove(&(a.min(b), a.max(b))) {
+            self.pending.push(Trigger::CollisionExit { a, b });
+        }
+    }
+
+    /// Activates an object, like an `Activate` action would.
+    pub fn activate(&mut self, entity_id: i32) {
+        self.set_activated(entity_id, true);
+    }
+
+    /// Deactivates an object, like a `Deactivate` action would.
+    pub fn deactivate(&mut self, entity_id: i32) {
+        self.set_activated(entity_id, false);
+    }
+
+    /// Damages an object, like a `Damage` action would.
+    pub fn damage(&mut self, entity_id: i32, amount: f32) {
+        self.apply_damage(entity_id, amount);
+    }
+
+    /// Kills an object, like a `Kill` action would.
+    pub fn kill(&mut self, entity_id: i32) {
+        self.apply_kill(entity_id);
+    }
+
+    /// The number of frames simulated so far.
+    #[must_use]
+    pub const fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The game time in seconds.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn time(&self) -> f64 {
+        self.frame as f64 / f64::from(FRAME_RATE)
+    }
+
+    /// The current value of a global variable.
+    #[must_use]
+    pub fn variable(&self, variable_id: i32) -> Option<&Value> {
+        self.globals.get(&variable_id)
+    }
+
+    /// The current value of a script-local variable.
+    #[must_use]
+    pub fn local_variable(&self, script_id: i32, variable_id: i32) -> Option<&Value> {
+        self.locals.get(&script_id)?.get(&variable_id)
+    }
+
+    /// The state of an object, or of the player for [`PLAYER`].
+    #[must_use]
+    pub fn object(&self, entity_id: i32) -> Option<&ObjectState> {
+        self.objects.get(&entity_id)
+    }
+
+    /// All existing objects, including the player and objects created by scripts.
+    pub fn objects(&self) -> impl Iterator<Item = &ObjectState> {
+        self.objects.values()
+    }
+
+    /// The simulated player state that scripts can read, e.g. with `BoolPlayerOnGround`.
+    pub const fn player_state_mut(&mut self) -> &mut PlayerState {
+        &mut self.player_state
+    }
+
+    #[must_use]
+    pub const fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Whether a `GameFinish` action ran.
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether an activation of the script is running or waiting.
+    #[must_use]
+    pub fn is_running(&self, script_id: i32) -> bool {
+        self.threads.iter().any(|thread| thread.script_id == script_id)
+    }
+
+    /// Everything that happened so far.
+    #[must_use]
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    /// Returns the trace and starts a new one.
+    pub fn take_trace(&mut self) -> Vec<TraceEntry> {
+        std::mem::take(&mut self.trace)
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        self.trace.push(TraceEntry { frame: self.frame, event });
+    }
+
+    fn record_object(&mut self, entity_id: i32, change: ObjectChange) {
+        self.record(TraceEvent::Object { entity_id, change });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+    use crate::types::nova_script::{
+        action_type::ActionType, nova_value::NovaValue, static_type::StaticType, variable::Variable,
+    };
+
+    fn global(variable_id: i32, name: &str, static_type: StaticType, persistance: Option<i32>) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type,
+            persistance,
+            initial_value: NovaValue::default_constant(static_type),
+        }
+    }
+
+    fn level(scripts: &str) -> LevelData {
+        let globals = vec![
+            global(1, "count", StaticType::Int, None),
+            global(2, "kept", StaticType::Int, Some(1)),
+            global(3, "saved", StaticType::Int, Some(2)),
+            global(4, "f", StaticType::Float, None),
+        ];
+
+        LevelData {
+            nova_scripts: compile_scripts(scripts, &globals).unwrap(),
+            global_variables: globals,
+            ..Default::default()
+        }
+    }
+
+    fn count(interpreter: &Interpreter) -> i32 {
+        interpreter.variable(1).and_then(Value::as_int).unwrap()
+    }
+
+    fn float(interpreter: &Interpreter) -> f32 {
+        interpreter.variable(4).and_then(Value::as_float).unwrap()
+    }
+
+    #[test]
+    fn activators() {
+        let level = level(
+            r#"
+            script 1 "Start" { on level_start(); $count = $count + 1; }
+            script 2 "Input" { on input_down(); $count = $count + 10; }
+            script 3 "Timer" { on timer(0.5); $count = $count + 100; }
+            "#,
+        );
+        let mut interpreter = Interpreter::new(&level);
+
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 1);
+
+        // Pressing a side that is already held doesn't trigger again.
+        interpreter.press(Side::Left);
+        interpreter.press(Side::Left);
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 11);
+
+        // The timer fires on the frame at 0.5 seconds, which is frame 30.
+        interpreter.run_frames(28).unwrap();
+        assert_eq!(count(&interpreter), 11);
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 111);
+
+        let started = interpreter
+            .trace()
+            .iter()
+            .filter(|entry| matches!(entry.event, TraceEvent::ScriptStarted { .. }))
+            .count();
+        assert_eq!(started, 3);
+    }
+
+    #[test]
+    fn waits() {
+        let level = level(r#"script 1 "A" { on level_start(); wait(0.5); $count = 1; wait_frames(2); $count = 2; }"#);
+        let mut interpreter = Interpreter::new(&level);
+
+        interpreter.run_frames(30).unwrap();
+        assert_eq!(count(&interpreter), 0);
+        assert!(interpreter.is_running(1));
+
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 1);
+
+        interpreter.run_frames(2).unwrap();
+        assert_eq!(count(&interpreter), 2);
+        assert!(!interpreter.is_running(1));
+        assert_eq!(interpreter.trace().last().unwrap().event, TraceEvent::ScriptFinished { script_id: 1 });
+    }
+
+    #[test]
+    fn easing_over_frames() {
+        let level = level(
+            r#"
+            script 1 "Linear" {
+                on level_start();
+                variable_set_over_time(variable: $f, value: 2.0, duration: 1.0, easing: Linear);
+            }
+            script 2 "Eased" {
+                on input_down();
+                variable_set_over_time(variable: $f, value: 0.0, duration: 1.0, easing: InQuad);
+            }
+            "#,
+        );
+        let mut interpreter = Interpreter::new(&level);
+
+        interpreter.run_frames(30).unwrap();
+        assert!((float(&interpreter) - 1.0).abs() < 1e-4);
+        interpreter.run_frames(30).unwrap();
+        assert!((float(&interpreter) - 2.0).abs() < 1e-4);
+        interpreter.run_frames(30).unwrap();
+        assert!((float(&interpreter) - 2.0).abs() < 1e-4);
+
+        // Halfway through an `InQuad` tween from 2 to 0 is a quarter of the way.
+        interpreter.press(Side::Right);
+        interpreter.run_frames(30).unwrap();
+        assert!((float(&interpreter) - 1.5).abs() < 1e-4);
+        interpreter.run_frames(30).unwrap();
+        assert!(float(&interpreter).abs() < 1e-4);
+    }
+
+    #[test]
+    fn stop_script() {
+        // The stopping script runs after the waiting one in the first level and before it in the second.
+        for scripts in [
+            r#"
+            script 1 "Waiting" { on level_start(); on input_released(); wait(1.0); $count = $count + 1; }
+            script 2 "Stop" { on input_down(); stop_script(script(1)); }
+            "#,
+            r#"
+            script 1 "Stop" { on input_down(); stop_script(script(2)); }
+            script 2 "Waiting" { on level_start(); on input_released(); wait(1.0); $count = $count + 1; }
+            "#,
+        ] {
+            let level = level(scripts);
+            let waiting = level.nova_scripts.iter().find(|script| script.script_name == "Waiting").unwrap().script_id;
+            let mut interpreter = Interpreter::new(&level);
+
+            interpreter.run_frames(10).unwrap();
+            interpreter.press(Side::Left);
+            interpreter.step().unwrap();
+            assert!(!interpreter.is_running(waiting));
+            assert!(interpreter.trace().contains(&TraceEntry {
+                frame: 10,
+                event: TraceEvent::ScriptStopped { script_id: waiting },
+            }));
+
+            interpreter.run_frames(120).unwrap();
+            assert_eq!(count(&interpreter), 0);
+
+            // Later activations aren't affected by the earlier stop.
+            interpreter.release(Side::Left);
+            interpreter.run_frames(61).unwrap();
+            assert_eq!(count(&interpreter), 1);
+        }
+    }
+
+    #[test]
+    fn persistance() {
+        let level = level(
+            r#"script 1 "A" { on level_start(); $count = $count + 1; $kept = $kept + 1; $saved = $saved + 1; }"#,
+        );
+        let mut interpreter = Interpreter::new(&level);
+        let values = |interpreter: &Interpreter| [1, 2, 3].map(|id| interpreter.variable(id).cloned().unwrap());
+
+        interpreter.step().unwrap();
+        assert_eq!(values(&interpreter), [Value::Int(1), Value::Int(1), Value::Int(1)]);
+
+        interpreter.respawn();
+        interpreter.step().unwrap();
+        assert_eq!(values(&interpreter), [Value::Int(1), Value::Int(2), Value::Int(2)]);
+
+        interpreter.restart();
+        interpreter.step().unwrap();
+        assert_eq!(values(&interpreter), [Value::Int(1), Value::Int(1), Value::Int(3)]);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let level = level(
+            r#"
+            script 1 "A" { on level_start(); $count = 7 / 0; }
+            script 2 "B" { on input_down(); $count = 7 % 0; }
+            script 3 "C" { on input_released(); $count = int_divide(-2147483647 - 1, -1); }
+            "#,
+        );
+        let mut interpreter = Interpreter::new(&level);
+
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 0);
+
+        interpreter.globals.insert(1, Value::Int(5));
+        interpreter.press(Side::Left);
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 0);
+
+        interpreter.globals.insert(1, Value::Int(5));
+        interpreter.release(Side::Left);
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 0);
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let mut level = level(r#"script 1 "A" { on level_start(); $count = 1; wait(1.0); $count = 2; }"#);
+        level.nova_scripts[0].actions[1].action_type =
+            ActionType::Wait { duration: NovaValue::default_constant(StaticType::Bool) };
+        let mut interpreter = Interpreter::new(&level);
+
+        assert_eq!(
+            interpreter.step(),
+            Err(RuntimeError::Type {
+                script_id: 1,
+                kind: TypeErrorKind::Mismatch { expected: StaticType::Float, found: StaticType::Bool },
+            })
+        );
+        assert_eq!(count(&interpreter), 1);
+        assert!(!interpreter.is_running(1));
+
+        // The failing activation was dropped, stepping again works.
+        interpreter.step().unwrap();
+        assert_eq!(count(&interpreter), 1);
+    }
+}
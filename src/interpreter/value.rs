@@ -0,0 +1,284 @@
+use std::cmp::Ordering;
+
+use crate::types::{
+    color::Color,
+    nova_script::{nova_value::NovaValue, static_type::StaticType},
+    vec2::Vec2,
+};
+
+/// The result of evaluating a `NovaValue`.
+///
+/// Entities (objects, sounds, scripts, ...) are referred to by id, with 0 meaning none.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    String(String),
+    Color(Color),
+    Vector(Vec2),
+    Sound(i32),
+    Music(i32),
+    Object(i32),
+    ObjectSet(Vec<i32>),
+    Transition(i32),
+    Easing(i32),
+    Sprite(i32),
+    Script(i32),
+    Layer(i32),
+    Prefab(i32),
+    BoolList(Vec<bool>),
+    IntList(Vec<i32>),
+    FloatList(Vec<f32>),
+    StringList(Vec<String>),
+    ColorList(Vec<Color>),
+    VectorList(Vec<Vec2>),
+    PrefabList(Vec<i32>),
+}
+
+impl Value {
+    /// The value a variable of this type starts with if it has no usable initial value.
+    #[must_use]
+    pub fn default_for(static_type: StaticType) -> Self {
+        match static_type {
+            StaticType::Bool => Self::Bool(false),
+            StaticType::Int => Self::Int(0),
+            StaticType::Float => Self::Float(0.0),
+            StaticType::String => Self::String(String::new()),
+            StaticType::Color => Self::Color(Color::default()),
+            StaticType::Vector => Self::Vector(Vec2::ZERO),
+            StaticType::Sound => Self::Sound(0),
+            StaticType::Music => Self::Music(0),
+            StaticType::Object => Self::Object(0),
+            StaticType::ObjectSet => Self::ObjectSet(Vec::new()),
+            StaticType::Transition => Self::Transition(0),
+            StaticType::Easing => Self::Easing(0),
+            StaticType::Sprite => Self::Sprite(0),
+            StaticType::Script => Self::Script(0),
+            StaticType::Layer => Self::Layer(0),
+            StaticType::Prefab => Self::Prefab(0),
+            StaticType::BoolList => Self::BoolList(Vec::new()),
+            StaticType::IntList => Self::IntList(Vec::new()),
+            StaticType::FloatList => Self::FloatList(Vec::new()),
+            StaticType::StringList => Self::StringList(Vec::new()),
+            StaticType::ColorList => Self::ColorList(Vec::new()),
+            StaticType::VectorList => Self::VectorList(Vec::new()),
+            StaticType::PrefabList => Self::PrefabList(Vec::new()),
+        }
+    }
+
+    /// Reads the slot a constant of this type is stored in.
+    #[must_use]
+    pub fn constant(value: &NovaValue, static_type: StaticType) -> Self {
+        let id = value.int_value;
+
+        match static_type {
+            StaticType::Bool => Self::Bool(value.bool_value),
+            StaticType::Int => Self::Int(id),
+            StaticType::Float => Self::Float(value.float_value.0),
+            StaticType::String => Self::String(value.string_value.clone().unwrap_or_default()),
+            StaticType::Color => Self::Color(value.color_value),
+            StaticType::Vector => Self::Vector(value.vector_value),
+            StaticType::Sound => Self::Sound(id),
+            StaticType::Music => Self::Music(id),
+            StaticType::Object => Self::Object(id),
+            StaticType::ObjectSet => Self::ObjectSet(value.int_list_values.clone().unwrap_or_default()),
+            StaticType::Transition => Self::Transition(id),
+            StaticType::Easing => Self::Easing(id),
+            StaticType::Sprite => Self::Sprite(id),
+            StaticType::Script => Self::Script(id),
+            StaticType::Layer => Self::Layer(id),
+            StaticType::Prefab => Self::Prefab(id),
+            StaticType::BoolList => Self::BoolList(value.bool_list_values.clone().unwrap_or_default()),
+            StaticType::IntList => Self::IntList(value.int_list_values.clone().unwrap_or_default()),
+            StaticType::FloatList => Self::FloatList(
+                value.float_list_values.iter().flatten().map(|float| float.0).collect(),
+            ),
+            StaticType::StringList => Self::StringList(value.string_list_values.clone().unwrap_or_default()),
+            StaticType::ColorList => Self::ColorList(value.color_list_values.clone().unwrap_or_default()),
+            StaticType::VectorList => Self::VectorList(value.vector_list_values.clone().unwrap_or_default()),
+            StaticType::PrefabList => Self::PrefabList(value.int_list_values.clone().unwrap_or_default()),
+        }
+    }
+
+    /// The type of this value.
+    #[must_use]
+    pub const fn static_type(&self) -> StaticType {
+        match self {
+            Self::Bool(_) => StaticType::Bool,
+            Self::Int(_) => StaticType::Int,
+            Self::Float(_) => StaticType::Float,
+            Self::String(_) => StaticType::String,
+            Self::Color(_) => StaticType::Color,
+            Self::Vector(_) => StaticType::Vector,
+            Self::Sound(_) => StaticType::Sound,
+            Self::Music(_) => StaticType::Music,
+            Self::Object(_) => StaticType::Object,
+            Self::ObjectSet(_) => StaticType::ObjectSet,
+            Self::Transition(_) => StaticType::Transition,
+            Self::Easing(_) => StaticType::Easing,
+            Self::Sprite(_) => StaticType::Sprite,
+            Self::Script(_) => StaticType::Script,
+            Self::Layer(_) => StaticType::Layer,
+            Self::Prefab(_) => StaticType::Prefab,
+            Self::BoolList(_) => StaticType::BoolList,
+            Self::IntList(_) => StaticType::IntList,
+            Self::FloatList(_) => StaticType::FloatList,
+            Self::StringList(_) => StaticType::StringList,
+            Self::ColorList(_) => StaticType::ColorList,
+            Self::VectorList(_) => StaticType::VectorList,
+            Self::PrefabList(_) => StaticType::PrefabList,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_int(&self) -> Option<i32> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_float(&self) -> Option<f32> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_color(&self) -> Option<Color> {
+        match self {
+            Self::Color(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_vector(&self) -> Option<Vec2> {
+        match self {
+            Self::Vector(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The id of an entity value, e.g. the entity id of an `Object` or the script id of a `Script`.
+    #[must_use]
+    pub const fn as_id(&self) -> Option<i32> {
+        match self {
+            Self::Sound(id)
+            | Self::Music(id)
+            | Self::Object(id)
+            | Self::Transition(id)
+            | Self::Easing(id)
+            | Self::Sprite(id)
+            | Self::Script(id)
+            | Self::Layer(id)
+            | Self::Prefab(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// The entity ids of an `Object` or `ObjectSet`. A `None` object gives an empty list.
+    #[must_use]
+    pub fn as_objects(&self) -> Option<Vec<i32>> {
+        match self {
+            Self::Object(0) => Some(Vec::new()),
+            Self::Object(id) => Some(vec![*id]),
+            Self::ObjectSet(ids) => Some(ids.clone()),
+            _ => None,
+        }
+    }
+
+    /// Splits a list (or an object set) into its elements.
+    pub(crate) fn into_elements(self) -> Option<Vec<Self>> {
+        Some(match self {
+            Self::ObjectSet(ids) => ids.into_iter().map(Self::Object).collect(),
+            Self::BoolList(values) => values.into_iter().map(Self::Bool).collect(),
+            Self::IntList(values) => values.into_iter().map(Self::Int).collect(),
+            Self::FloatList(values) => values.into_iter().map(Self::Float).collect(),
+            Self::StringList(values) => values.into_iter().map(Self::String).collect(),
+            Self::ColorList(values) => values.into_iter().map(Self::Color).collect(),
+            Self::VectorList(values) => values.into_iter().map(Self::Vector).collect(),
+            Self::PrefabList(ids) => ids.into_iter().map(Self::Prefab).collect(),
+            _ => return None,
+        })
+    }
+
+    /// Builds a list of type `list_type` from its elements. Elements of the wrong type are skipped.
+    pub(crate) fn from_elements(list_type: StaticType, elements: Vec<Self>) -> Self {
+        macro_rules! collect {
+            ($list:ident, $element:ident) => {
+                Self::$list(
+                    elements
+                        .into_iter()
+                        .filter_map(|element| match element {
+                            Self::$element(value) => Some(value),
+                            _ => None,
+                        })
+                        .collect(),
+                )
+            };
+        }
+
+        match list_type {
+            StaticType::ObjectSet => collect!(ObjectSet, Object),
+            StaticType::BoolList => collect!(BoolList, Bool),
+            StaticType::IntList => collect!(IntList, Int),
+            StaticType::FloatList => collect!(FloatList, Float),
+            StaticType::StringList => collect!(StringList, String),
+            StaticType::ColorList => collect!(ColorList, Color),
+            StaticType::VectorList => collect!(VectorList, Vector),
+            StaticType::PrefabList => collect!(PrefabList, Prefab),
+            _ => Self::default_for(list_type),
+        }
+    }
+
+    /// Orders two elements of a sortable list.
+    pub(crate) fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Bool(a), Self::Bool(b)) => a.cmp(b),
+            (Self::Int(a), Self::Int(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+
+    /// Interpolates between two values of the same numeric type. Other values jump to `to` once `t` reaches 1.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub(crate) fn lerp(&self, to: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| (b - a).mul_add(t, a);
+
+        match (self, to) {
+            (Self::Int(a), Self::Int(b)) => Self::Int(mix(*a as f32, *b as f32).round() as i32),
+            (Self::Float(a), Self::Float(b)) => Self::Float(mix(*a, *b)),
+            (Self::Vector(a), Self::Vector(b)) => Self::Vector(a.lerp(*b, t)),
+            (Self::Color(a), Self::Color(b)) => Self::Color(Color::new(
+                mix(a.r.0, b.r.0),
+                mix(a.g.0, b.g.0),
+                mix(a.b.0, b.b.0),
+                mix(a.a.0, b.a.0),
+            )),
+            _ if t >= 1.0 => to.clone(),
+            _ => self.clone(),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+
+use crate::types::{
+    color::Color,
+    level_data::LevelData,
+    object::Object,
+    object_id::ObjectId,
+    object_property::ObjectProperty,
+    vec2::Vec2,
+};
+
+use super::{easing::Easing, value::Value, VariableRef};
+
+/// The entity id the interpreter gives the player, which isn't an object of the level.
+pub const PLAYER: i32 = -1;
+
+/// The simulated state of an object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectState {
+    pub entity_id: i32,
+    pub prefab_id: i32,
+    pub tag: String,
+    pub layer: i32,
+    /// The entity id of the group the object is in, or 0.
+    pub parent: i32,
+    pub children: Vec<i32>,
+    pub position: Vec2,
+    pub scale: Vec2,
+    /// In degrees.
+    pub rotation: f32,
+    pub color: Color,
+    pub secondary_color: Color,
+    pub border_color: Color,
+    pub sprite: i32,
+    pub text: String,
+    pub enabled: bool,
+    pub activated: bool,
+    pub dead: bool,
+    pub health: f32,
+    pub direction: i32,
+    pub velocity: Vec2,
+    pub gravity: Vec2,
+}
+
+impl ObjectState {
+    pub(crate) fn new(object: &Object, gravity: Vec2) -> Self {
+        let mut state = Self {
+            entity_id: object.entity_id,
+            prefab_id: object.prefab_id,
+            tag: object.tag.clone(),
+            layer: object.in_layer,
+            parent: object.in_group,
+            children: object.group_members.clone(),
+            position: object.position,
+            scale: object.scale,
+            rotation: object.rotation.0,
+            color: Color::default(),
+            secondary_color: Color::default(),
+            border_color: Color::default(),
+            sprite: 0,
+            text: String::new(),
+            enabled: true,
+            activated: false,
+            dead: false,
+            health: 0.0,
+            direction: 1,
+            velocity: Vec2::ZERO,
+            gravity,
+        };
+
+        for property in &object.properties {
+            match property {
+                ObjectProperty::Color(color) => state.color = *color,
+                ObjectProperty::SecondaryColor(color) => state.secondary_color = *color,
+                ObjectProperty::BorderColor(color) => state.border_color = *color,
+                ObjectProperty::Text(text) => state.text.clone_from(text),
+                ObjectProperty::Health(health) => state.health = health.0,
+                ObjectProperty::Direction(direction) => state.direction = *direction,
+                _ => {}
+            }
+        }
+
+        state
+    }
+
+    /// The player, standing at the level's spawn point.
+    pub(crate) fn player(level: &LevelData) -> Self {
+        let spawn = level
+            .objects
+            .iter()
+            .find(|object| matches!(object.object_id, ObjectId::SpawnRight | ObjectId::SpawnLeft));
+
+        Self {
+            entity_id: PLAYER,
+            prefab_id: 0,
+            tag: String::new(),
+            layer: 0,
+            parent: 0,
+            children: Vec::new(),
+            position: spawn.map_or(Vec2::ZERO, |spawn| spawn.position),
+            scale: Vec2::ONE,
+            rotation: 0.0,
+            color: Color::default(),
+            secondary_color: Color::default(),
+            border_color: Color::default(),
+            sprite: 0,
+            text: String::new(),
+            enabled: true,
+            activated: false,
+            dead: false,
+            health: 0.0,
+            direction: if spawn.is_some_and(|spawn| spawn.object_id == ObjectId::SpawnLeft) { -1 } else { 1 },
+            velocity: Vec2::ZERO,
+            gravity: level.gravity,
+        }
+    }
+}
+
+/// Player physics isn't simulated, so these are set by the test through [`super::Interpreter::player_state_mut`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct PlayerState {
+    pub on_ground: bool,
+    pub on_walljump: bool,
+    pub on_booster: bool,
+    pub on_swing: bool,
+    pub in_floating_zone: bool,
+    pub using_glider: bool,
+    pub jump_locked: bool,
+}
+
+/// The simulated camera.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub position: Vec2,
+    pub viewport_size: f32,
+    pub offset: Vec2,
+}
+
+impl Camera {
+    pub const DEFAULT_VIEWPORT_SIZE: f32 = 10.0;
+}
+
+/// Which color of an object a tween changes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ColorSlot {
+    Primary,
+    Secondary,
+    Border,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TweenTarget {
+    Position(i32),
+    Scale(i32),
+    Rotation(i32),
+    Color(i32, ColorSlot),
+    /// Rotates the object around `pivot`, starting from the position and rotation it had when the tween started.
+    /// The tweened value is the angle turned so far.
+    Orbit { entity_id: i32, pivot: Vec2, position: Vec2, rotation: f32, rotate_target: bool },
+    CameraPosition,
+    CameraViewportSize,
+    CameraOffset,
+    Variable(VariableRef),
+}
+
+impl TweenTarget {
+    pub const fn entity_id(&self) -> Option<i32> {
+        match self {
+            Self::Position(id) | Self::Scale(id) | Self::Rotation(id) | Self::Color(id, _) => Some(*id),
+            Self::Orbit { entity_id, .. } => Some(*entity_id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween {
+    pub target: TweenTarget,
+    pub from: Value,
+    pub to: Value,
+    pub start: f64,
+    pub duration: f32,
+    pub easing: Easing,
+}
+
+impl Tween {
+    /// The value at `time`, and whether the tween is done.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn sample(&self, time: f64) -> (Value, bool) {
+        let t = if self.duration > 0.0 { ((time - self.start) / f64::from(self.duration)) as f32 } else { 1.0 };
+
+        (self.from.lerp(&self.to, self.easing.apply(t)), t >= 1.0)
+    }
+}
+
+/// Rotates `point` by `degrees` counter-clockwise.
+pub fn rotate(point: Vec2, degrees: f32) -> Vec2 {
+    Vec2::from_angle(degrees.to_radians()).rotate(point)
+}
+
+/// Wraps an angle in degrees into `-180..=180`.
+#[allow(clippy::float_cmp)]
+pub fn wrap_degrees(degrees: f32) -> f32 {
+    let wrapped = (degrees + 180.0).rem_euclid(360.0) - 180.0;
+
+    if wrapped == -180.0 { 180.0 } else { wrapped }
+}
+
+/// A global position, rotation and scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self { position: Vec2::ZERO, rotation: 0.0, scale: Vec2::ONE };
+
+    /// Maps a point from the local space of this transform to global space.
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        self.position + rotate(point * self.scale, self.rotation)
+    }
+
+    /// Maps a point from global space to the local space of this transform.
+    pub fn inverse(&self, point: Vec2) -> Vec2 {
+        let scale = Vec2::new(
+            if self.scale.x.0 == 0.0 { 1.0 } else { self.scale.x.0 },
+            if self.scale.y.0 == 0.0 { 1.0 } else { self.scale.y.0 },
+        );
+
+        rotate(point - self.position, -self.rotation) / scale
+    }
+}
+
+/// Computes the global transform of an object by walking up its groups.
+pub fn global_transform(objects: &BTreeMap<i32, ObjectState>, entity_id: i32) -> Transform {
+    let mut chain = Vec::new();
+    let mut current = entity_id;
+
+    while let Some(object) = objects.get(&current) {
+        // Broken levels can contain group cycles.
+        if chain.len() > objects.len() {
+            break;
+        }
+
+        chain.push(object);
+        current = object.parent;
+    }
+
+    chain.iter().rev().fold(Transform::IDENTITY, |parent, object| Transform {
+        position: parent.apply(object.position),
+        rotation: parent.rotation + object.rotation,
+        scale: parent.scale * object.scale,
+    })
+}
+
+/// The global transform of the group the object is in.
+pub fn parent_transform(objects: &BTreeMap<i32, ObjectState>, entity_id: i32) -> Transform {
+    objects
+        .get(&entity_id)
+        .map_or(Transform::IDENTITY, |object| global_transform(objects, object.parent))
+}
@@ -9,13 +9,32 @@
 #![cfg_attr(target_os = "windows", doc=include_str!("..\\README.md"))]
 #![cfg_attr(not(target_os = "windows"), doc=include_str!("../README.md"))]
 
+pub mod boolean;
+pub mod call_graph;
+pub mod convert;
 pub mod error;
+pub mod gc;
+pub mod geometrize;
+pub mod geometry;
+pub mod interpreter;
+pub mod lint;
+#[cfg(feature = "image")]
+pub mod pixel_art;
 mod private;
 pub mod traits;
 pub mod types;
-#[cfg(feature = "image")]
-pub mod geometrize;
 pub mod primitive_impls;
+pub mod refactor;
+pub mod references;
+pub mod render;
+pub mod shapes;
+#[cfg(feature = "svg")]
+pub mod svg_import;
+pub mod text;
+#[cfg(feature = "image")]
+pub mod trace;
+pub mod type_check;
+pub mod viz;
 
 // ! These modules are only included when the "private-modules" feature is enabled
 #[cfg(feature = "private-modules")]
@@ -27,6 +46,7 @@ pub mod gui;
 
 
 use error::Error;
+pub use lint::lint;
 pub use traits::{Read, ReadContext, ReadVersioned, Write};
 use uuid::Uuid;
 use glam::IVec2;
@@ -0,0 +1,667 @@
+//! A linter that checks a level for common mistakes before publishing.
+//!
+//! Every check is a [`Rule`] with a stable code (e.g. `L003`) and a default [`Severity`].
+//! Rules can be disabled or given a different severity with a [`LintConfig`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::geometry;
+use crate::references::{EntityRef, ReferenceKind};
+use crate::types::{
+    exolvl::Exolvl, nova_script::variable::VariableRef, object_id::ObjectId, object_property::ObjectProperty,
+};
+
+/// How serious a diagnostic is.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing about, but usually fine.
+    Info,
+    /// Probably a mistake.
+    Warning,
+    /// The level is broken or can't be finished.
+    Error,
+}
+
+macro_rules! define_rules {
+    ($($name:ident = $code:literal, $severity:ident, $description:literal);*$(;)?) => {
+        /// A single check performed by the linter.
+        #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        pub enum Rule {
+            $(
+                #[doc = $description]
+                $name
+            ),*
+        }
+
+        impl Rule {
+            /// Every rule, in code order.
+            pub const ALL: &'static [Self] = &[$(Self::$name),*];
+
+            /// The stable code of this rule.
+            pub const fn code(self) -> &'static str {
+                match self {
+                    $(Self::$name => $code),*
+                }
+            }
+
+            /// The severity of this rule unless it's overridden in the [`LintConfig`].
+            pub const fn default_severity(self) -> Severity {
+                match self {
+                    $(Self::$name => Severity::$severity),*
+                }
+            }
+
+            /// A short description of what this rule checks.
+            pub const fn description(self) -> &'static str {
+                match self {
+                    $(Self::$name => $description),*
+                }
+            }
+
+            /// Looks up a rule by its code.
+            pub fn from_code(code: &str) -> Option<Self> {
+                match code {
+                    $($code => Some(Self::$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+define_rules!(
+    MissingSpawn = "L001", Error, "The level has no `SpawnRight` or `SpawnLeft` object.";
+    MissingEndFlag = "L002", Error, "The level has no `EndFlag` object.";
+    DuplicateEntityId = "L003", Error, "Several objects share the same `entity_id`.";
+    DanglingLayer = "L004", Error, "An object's `in_layer` points at a layer that doesn't exist.";
+    DanglingLinkedObject = "L005", Warning, "`LinkedObjects` contains an object that doesn't exist.";
+    DanglingPrefab = "L006", Warning, "An object's `prefab_id` points at a prefab that doesn't exist.";
+    LapTimesMismatch = "L007", Warning, "The number of author lap times doesn't match the number of laps.";
+    InconsistentMedalTimes = "L008", Warning, "The gold medal time is slower than the silver medal time, or the author time is slower than gold.";
+    ZeroAuthorTime = "L009", Warning, "The author time is zero, the level hasn't been verified.";
+    MetadataMismatch = "L010", Error, "`LocalLevel` and `LevelData` disagree on a duplicated field.";
+    DegenerateTerrain = "L011", Warning, "A `TerrainCorners` ring has fewer than three corners or no area.";
+    SelfIntersectingTerrain = "L012", Warning, "A `TerrainCorners` ring intersects itself.";
+    UnusedVariable = "L013", Info, "A variable is never read nor written.";
+    WriteOnlyVariable = "L014", Warning, "A variable is written but never read.";
+    UnusedFunction = "L015", Info, "A function is never called from a script that can run.";
+    RecursiveFunction = "L016", Info, "A function calls itself, directly or through other functions.";
+);
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Which rules the linter runs and with which severity.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    overrides: BTreeMap<Rule, Option<Severity>>,
+}
+
+impl LintConfig {
+    /// Disables a rule.
+    #[must_use]
+    pub fn disable(mut self, rule: Rule) -> Self {
+        self.overrides.insert(rule, None);
+        self
+    }
+
+    /// Runs a rule with a different severity than its default.
+    #[must_use]
+    pub fn with_severity(mut self, rule: Rule, severity: Severity) -> Self {
+        self.overrides.insert(rule, Some(severity));
+        self
+    }
+
+    /// Returns the severity the rule runs with, or `None` if it's disabled.
+    #[must_use]
+    pub fn severity(&self, rule: Rule) -> Option<Severity> {
+        self.overrides.get(&rule).copied().unwrap_or_else(|| Some(rule.default_severity()))
+    }
+}
+
+/// A problem found by the linter.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The rule that produced this diagnostic.
+    pub rule: Rule,
+    /// The severity, after applying the [`LintConfig`].
+    pub severity: Severity,
+    /// A human readable description of the problem.
+    pub message: String,
+    /// The entity the problem was found on, if any.
+    pub entity: Option<EntityRef>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(f, "{severity}[{}]: {}", self.rule.code(), self.message)
+    }
+}
+
+/// Checks the level with every rule at its default severity.
+#[must_use]
+pub fn lint(level: &Exolvl) -> Vec<Diagnostic> {
+    lint_with(level, &LintConfig::default())
+}
+
+/// Checks the level with the rules enabled in `config`.
+#[must_use]
+pub fn lint_with(level: &Exolvl, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        config,
+        diagnostics: Vec::new(),
+    };
+
+    linter.check_spawn_and_finish(level);
+    linter.check_entity_ids(level);
+    linter.check_dangling_references(level);
+    linter.check_medal_times(level);
+    linter.check_metadata(level);
+    linter.check_terrain(level);
+    linter.check_variables(level);
+    linter.check_functions(level);
+
+    linter.diagnostics
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: Rule, entity: Option<EntityRef>, message: String) {
+        if let Some(severity) = self.config.severity(rule) {
+            self.diagnostics.push(Diagnostic {
+                rule,
+                severity,
+                message,
+                entity,
+            });
+        }
+    }
+
+    fn check_spawn_and_finish(&mut self, level: &Exolvl) {
+        let data = &level.level_data;
+
+        if !data.nova_level {
+            return;
+        }
+
+        let has = |ids: &[ObjectId]| data.objects.iter().any(|object| ids.contains(&object.object_id));
+
+        if !has(&[ObjectId::SpawnRight, ObjectId::SpawnLeft]) {
+            self.report(Rule::MissingSpawn, None, "the level has no spawn point".to_string());
+        }
+
+        if !has(&[ObjectId::EndFlag, ObjectId::EndFlag90, ObjectId::EndFlag180, ObjectId::EndFlag270]) {
+            self.report(Rule::MissingEndFlag, None, "the level has no end flag".to_string());
+        }
+    }
+
+    fn check_entity_ids(&mut self, level: &Exolvl) {
+        let mut seen = BTreeSet::new();
+        let mut reported = BTreeSet::new();
+
+        for object in &level.level_data.objects {
+            if !seen.insert(object.entity_id) && reported.insert(object.entity_id) {
+                let count = level.level_data.objects.iter().filter(|other| other.entity_id == object.entity_id).count();
+
+                self.report(
+                    Rule::DuplicateEntityId,
+                    Some(EntityRef::Object(object.entity_id)),
+                    format!("{count} objects share the entity id {}", object.entity_id),
+                );
+            }
+        }
+    }
+
+    fn check_dangling_references(&mut self, level: &Exolvl) {
+        let data = &level.level_data;
+        let references = data.references();
+
+        for reference in references.dangling(data) {
+            let (rule, message) = match (reference.kind, reference.to) {
+                (ReferenceKind::InLayer, EntityRef::Layer(layer)) => {
+                    (Rule::DanglingLayer, format!("the object is in layer {layer}, which doesn't exist"))
+                }
+                (ReferenceKind::LinkedObject, EntityRef::Object(object)) => {
+                    (Rule::DanglingLinkedObject, format!("the object is linked to object {object}, which doesn't exist"))
+                }
+                (ReferenceKind::PrefabInstance, EntityRef::Prefab(prefab)) => {
+                    (Rule::DanglingPrefab, format!("the object is an instance of prefab {prefab}, which doesn't exist"))
+                }
+                _ => continue,
+            };
+
+            self.report(rule, Some(reference.from), message);
+        }
+    }
+
+    fn check_medal_times(&mut self, level: &Exolvl) {
+        let data = &level.level_data;
+
+        if data.author_time == 0 {
+            self.report(Rule::ZeroAuthorTime, None, "the author time is zero".to_string());
+        }
+
+        let laps = usize::try_from(data.laps).unwrap_or_default();
+
+        if data.author_time != 0 && data.author_lap_times.len() != laps {
+            self.report(
+                Rule::LapTimesMismatch,
+                None,
+                format!("the level has {} laps but {} author lap times", data.laps, data.author_lap_times.len()),
+            );
+        }
+
+        if data.gold_medal_time != 0 && data.silver_medal_time != 0 && data.gold_medal_time > data.silver_medal_time {
+            self.report(
+                Rule::InconsistentMedalTimes,
+                None,
+                format!(
+                    "the gold medal time ({} ms) is slower than the silver medal time ({} ms)",
+                    data.gold_medal_time, data.silver_medal_time
+                ),
+            );
+        }
+
+        if data.author_time != 0 && data.gold_medal_time != 0 && data.author_time > data.gold_medal_time {
+            self.report(
+                Rule::InconsistentMedalTimes,
+                None,
+                format!(
+                    "the author time ({} ms) is slower than the gold medal time ({} ms)",
+                    data.author_time, data.gold_medal_time
+                ),
+            );
+        }
+    }
+
+    fn check_metadata(&mut self, level: &Exolvl) {
+        let local = &level.local_level;
+        let data = &level.level_data;
+
+        let mut mismatch = |field: &str, local: &dyn fmt::Debug, data: &dyn fmt::Debug| {
+            self.report(
+                Rule::MetadataMismatch,
+                Some(EntityRef::Level),
+                format!("`{field}` is {local:?} in the local level but {data:?} in the level data"),
+            );
+        };
+
+        if local.level_id != data.level_id {
+            mismatch("level_id", &local.level_id, &data.level_id);
+        }
+        if local.level_version != data.level_version {
+            mismatch("level_version", &local.level_version, &data.level_version);
+        }
+        if local.nova_level != data.nova_level {
+            mismatch("nova_level", &local.nova_level, &data.nova_level);
+        }
+        if local.author_time != data.author_time {
+            mismatch("author_time", &local.author_time, &data.author_time);
+        }
+        if local.author_lap_times != data.author_lap_times {
+            mismatch("author_lap_times", &local.author_lap_times, &data.author_lap_times);
+        }
+        if local.silver_medal_time != data.silver_medal_time {
+            mismatch("silver_medal_time", &local.silver_medal_time, &data.silver_medal_time);
+        }
+        if local.gold_medal_time != data.gold_medal_time {
+            mismatch("gold_medal_time", &local.gold_medal_time, &data.gold_medal_time);
+        }
+        if local.laps != data.laps {
+            mismatch("laps", &local.laps, &data.laps);
+        }
+    }
+
+    fn check_terrain(&mut self, level: &Exolvl) {
+        for object in &level.level_data.objects {
+            for property in &object.properties {
+                let ObjectProperty::TerrainCorners(rings) = property else {
+                    continue;
+                };
+
+                for (index, ring) in rings.iter().enumerate() {
+                    let entity = Some(EntityRef::Object(object.entity_id));
+
+                    if geometry::is_degenerate(ring) {
+                        self.report(Rule::DegenerateTerrain, entity, format!("ring {index} of object {} is degenerate", object.entity_id));
+                    } else if geometry::is_self_intersecting(ring) {
+                        self.report(
+                            Rule::SelfIntersectingTerrain,
+                            entity,
+                            format!("ring {index} of object {} intersects itself", object.entity_id),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_variables(&mut self, level: &Exolvl) {
+        let data = &level.level_data;
+        let describe = |variable: VariableRef| match variable {
+            VariableRef::Global(id) => format!("global variable {id}"),
+            VariableRef::Local { script_id, variable_id } => format!("variable {variable_id} of script {script_id}"),
+        };
+
+        for variable in data.unused_variables() {
+            let entity = Some(EntityRef::Variable(variable));
+            self.report(Rule::UnusedVariable, entity, format!("{} is never used", describe(variable)));
+        }
+
+        for variable in data.write_only_variables() {
+            let entity = Some(EntityRef::Variable(variable));
+            self.report(Rule::WriteOnlyVariable, entity, format!("{} is written but never read", describe(variable)));
+        }
+    }
+
+    fn check_functions(&mut self, level: &Exolvl) {
+        let call_graph = level.level_data.call_graph();
+
+        for function in call_graph.unused_functions() {
+            self.report(Rule::UnusedFunction, Some(EntityRef::Script(function)), format!("function {function} is never called"));
+        }
+
+        for cycle in call_graph.cycles() {
+            let message = match cycle.as_slice() {
+                [function] => format!("function {function} calls itself"),
+                functions => {
+                    let names: Vec<String> = functions.iter().map(ToString::to_string).collect();
+                    format!("functions {} call each other", names.join(", "))
+                }
+            };
+
+            self.report(Rule::RecursiveFunction, cycle.first().map(|&function| EntityRef::Script(function)), message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+    use crate::types::{
+        author_replay::AuthorReplay,
+        image::Image,
+        layer::Layer,
+        level_data::LevelData,
+        local_level::LocalLevel,
+        nova_script::{nova_value::NovaValue, static_type::StaticType, variable::Variable},
+        object::Object,
+        prefab::Prefab,
+        vec2::Vec2,
+    };
+
+    const SCRIPTS: &str = r#"
+        script 1 "A" {
+            on level_start();
+            $score = $score + 1;
+            call 10();
+        }
+        function 10 "f" { wait(1.0); }
+    "#;
+
+    fn global(variable_id: i32, name: &str) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type: StaticType::Int,
+            persistance: None,
+            initial_value: NovaValue::default_constant(StaticType::Int),
+        }
+    }
+
+    fn object(entity_id: i32, object_id: ObjectId) -> Object {
+        Object { entity_id, object_id, in_layer: 1, ..Default::default() }
+    }
+
+    fn terrain(entity_id: i32, corners: &[(f32, f32)]) -> Object {
+        let ring = corners.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+
+        Object { properties: vec![ObjectProperty::TerrainCorners(vec![ring])], ..object(entity_id, ObjectId::Terrain) }
+    }
+
+    fn set_scripts(level: &mut Exolvl, globals: Vec<Variable>, scripts: &str) {
+        level.level_data.nova_scripts = compile_scripts(scripts, &globals).unwrap();
+        level.level_data.global_variables = globals;
+    }
+
+    /// Sets the times on both the local level and the level data, so that they stay in sync.
+    fn set_times(level: &mut Exolvl, laps: i32, author_time: i64, author_lap_times: &[i64], gold: i64, silver: i64) {
+        let (local, data) = (&mut level.local_level, &mut level.level_data);
+
+        (local.laps, data.laps) = (laps, laps);
+        (local.author_time, data.author_time) = (author_time, author_time);
+        (local.author_lap_times, data.author_lap_times) = (author_lap_times.to_vec(), author_lap_times.to_vec());
+        (local.gold_medal_time, data.gold_medal_time) = (gold, gold);
+        (local.silver_medal_time, data.silver_medal_time) = (silver, silver);
+    }
+
+    /// A verified level with a spawn, an end flag, a square of terrain and scripts that use everything.
+    fn clean() -> Exolvl {
+        let mut level = Exolvl {
+            local_level: LocalLevel { nova_level: true, ..Default::default() },
+            level_data: LevelData {
+                nova_level: true,
+                layers: vec![Layer { layer_id: 1, children: vec![1, 2, 3], ..Default::default() }],
+                objects: vec![
+                    object(1, ObjectId::SpawnRight),
+                    object(2, ObjectId::EndFlag),
+                    terrain(3, &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]),
+                ],
+                ..Default::default()
+            },
+            author_replay: AuthorReplay::default(),
+        };
+
+        set_times(&mut level, 1, 10_000, &[10_000], 12_000, 15_000);
+        set_scripts(&mut level, vec![global(1, "score")], SCRIPTS);
+
+        level
+    }
+
+    fn rules(level: &Exolvl) -> Vec<Rule> {
+        lint(level).into_iter().map(|diagnostic| diagnostic.rule).collect()
+    }
+
+    /// Applies `edit` to a clean level and checks which rules fire.
+    fn check(edit: impl FnOnce(&mut Exolvl), expected: &[Rule]) {
+        let mut level = clean();
+        edit(&mut level);
+
+        assert_eq!(rules(&level), expected);
+    }
+
+    #[test]
+    fn clean_level() {
+        assert_eq!(lint(&clean()), Vec::new());
+    }
+
+    #[test]
+    fn missing_spawn() {
+        check(|level| level.level_data.objects[0].object_id = ObjectId::Terrain, &[Rule::MissingSpawn]);
+        check(|level| level.level_data.objects[0].object_id = ObjectId::SpawnLeft, &[]);
+    }
+
+    #[test]
+    fn missing_end_flag() {
+        check(|level| level.level_data.objects[1].object_id = ObjectId::Terrain, &[Rule::MissingEndFlag]);
+        check(|level| level.level_data.objects[1].object_id = ObjectId::EndFlag90, &[]);
+    }
+
+    #[test]
+    fn legacy_levels_need_no_spawn_or_end_flag() {
+        check(
+            |level| {
+                level.level_data.objects.clear();
+                (level.local_level.nova_level, level.level_data.nova_level) = (false, false);
+            },
+            &[],
+        );
+    }
+
+    #[test]
+    fn duplicate_entity_id() {
+        check(|level| level.level_data.objects[1].entity_id = 1, &[Rule::DuplicateEntityId]);
+        check(|level| level.level_data.objects[1].entity_id = 4, &[]);
+    }
+
+    #[test]
+    fn dangling_layer() {
+        check(|level| level.level_data.objects[0].in_layer = 2, &[Rule::DanglingLayer]);
+        check(|level| level.level_data.objects[0].in_layer = 0, &[]);
+    }
+
+    #[test]
+    fn dangling_linked_object() {
+        let link = |object: i32| move |level: &mut Exolvl| {
+            level.level_data.objects[0].properties.push(ObjectProperty::LinkedObjects(vec![object]));
+        };
+
+        check(link(7), &[Rule::DanglingLinkedObject]);
+        check(link(2), &[]);
+    }
+
+    #[test]
+    fn dangling_prefab() {
+        check(|level| level.level_data.objects[0].prefab_id = 5, &[Rule::DanglingPrefab]);
+        check(
+            |level| {
+                level.level_data.objects[0].prefab_id = 5;
+                level.level_data.prefabs.push(Prefab {
+                    prefab_id: 5,
+                    prefab_image_data: Image::blank(1),
+                    items: Vec::new(),
+                });
+            },
+            &[],
+        );
+    }
+
+    #[test]
+    fn lap_times_mismatch() {
+        check(|level| set_times(level, 2, 20_000, &[10_000], 20_000, 30_000), &[Rule::LapTimesMismatch]);
+        check(|level| set_times(level, 2, 20_000, &[10_000, 10_000], 20_000, 30_000), &[]);
+    }
+
+    #[test]
+    fn lap_times_are_not_checked_before_verification() {
+        check(|level| set_times(level, 2, 0, &[], 0, 0), &[Rule::ZeroAuthorTime]);
+    }
+
+    #[test]
+    fn inconsistent_medal_times() {
+        check(|level| set_times(level, 1, 10_000, &[10_000], 16_000, 15_000), &[Rule::InconsistentMedalTimes]);
+        check(|level| set_times(level, 1, 13_000, &[13_000], 12_000, 15_000), &[Rule::InconsistentMedalTimes]);
+        check(|level| set_times(level, 1, 12_000, &[12_000], 12_000, 12_000), &[]);
+    }
+
+    #[test]
+    fn zero_author_time() {
+        check(|level| set_times(level, 1, 0, &[], 12_000, 15_000), &[Rule::ZeroAuthorTime]);
+        check(|level| set_times(level, 1, 1, &[1], 12_000, 15_000), &[]);
+    }
+
+    #[test]
+    fn metadata_mismatch() {
+        check(|level| level.local_level.laps = 3, &[Rule::MetadataMismatch]);
+        check(|level| level.local_level.level_name = "Renamed".to_string(), &[]);
+    }
+
+    #[test]
+    fn degenerate_terrain() {
+        check(|level| level.level_data.objects[2] = terrain(3, &[(0.0, 0.0), (1.0, 1.0)]), &[Rule::DegenerateTerrain]);
+        check(|level| level.level_data.objects[2] = terrain(3, &[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]), &[]);
+    }
+
+    #[test]
+    fn self_intersecting_terrain() {
+        let bow_tie = [(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 1.0)];
+        let concave = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (1.0, 1.0), (0.0, 2.0)];
+
+        check(|level| level.level_data.objects[2] = terrain(3, &bow_tie), &[Rule::SelfIntersectingTerrain]);
+        check(|level| level.level_data.objects[2] = terrain(3, &concave), &[]);
+    }
+
+    #[test]
+    fn unused_variable() {
+        let globals = vec![global(1, "score"), global(2, "unused")];
+
+        check(|level| set_scripts(level, globals, SCRIPTS), &[Rule::UnusedVariable]);
+        check(|level| set_scripts(level, vec![global(1, "score")], SCRIPTS), &[]);
+    }
+
+    #[test]
+    fn write_only_variable() {
+        let scripts = format!(r#"{SCRIPTS} script 2 "B" {{ $other = 1; }}"#);
+        let read = format!(r#"{SCRIPTS} script 2 "B" {{ wait_frames($other); }}"#);
+
+        let globals = vec![global(1, "score"), global(2, "other")];
+
+        check(|level| set_scripts(level, globals.clone(), &scripts), &[Rule::WriteOnlyVariable]);
+        check(|level| set_scripts(level, globals, &read), &[]);
+    }
+
+    #[test]
+    fn unused_function() {
+        let scripts = format!(r#"{SCRIPTS} function 11 "g" {{ wait(1.0); }}"#);
+        let called = format!(r#"{scripts} script 2 "B" {{ call 11(); }}"#);
+
+        check(|level| set_scripts(level, vec![global(1, "score")], &scripts), &[Rule::UnusedFunction]);
+        check(|level| set_scripts(level, vec![global(1, "score")], &called), &[]);
+    }
+
+    #[test]
+    fn recursive_function() {
+        let recursive = SCRIPTS.replace("wait(1.0);", "call 10();");
+
+        check(|level| set_scripts(level, vec![global(1, "score")], &recursive), &[Rule::RecursiveFunction]);
+    }
+
+    #[test]
+    fn config() {
+        let mut level = clean();
+        level.level_data.objects.remove(0);
+
+        let config = LintConfig::default();
+        assert_eq!(config.severity(Rule::MissingSpawn), Some(Severity::Error));
+        assert_eq!(lint_with(&level, &config).len(), 1);
+
+        let disabled = LintConfig::default().disable(Rule::MissingSpawn);
+        assert_eq!(disabled.severity(Rule::MissingSpawn), None);
+        assert_eq!(lint_with(&level, &disabled), Vec::new());
+
+        let downgraded = LintConfig::default().with_severity(Rule::MissingSpawn, Severity::Info);
+        let diagnostics = lint_with(&level, &downgraded);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Info);
+        assert_eq!(diagnostics[0].to_string(), "info[L001]: the level has no spawn point");
+
+        // Re-enabling a disabled rule keeps the last setting.
+        let reenabled = disabled.with_severity(Rule::MissingSpawn, Severity::Warning);
+        assert_eq!(lint_with(&level, &reenabled)[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn codes() {
+        for (index, &rule) in Rule::ALL.iter().enumerate() {
+            assert_eq!(rule.code(), format!("L{:03}", index + 1));
+            assert_eq!(Rule::from_code(rule.code()), Some(rule));
+        }
+
+        assert_eq!(Rule::from_code("L999"), None);
+    }
+}
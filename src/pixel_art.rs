@@ -0,0 +1,226 @@
+//! Importing small images pixel for pixel, for retro-style decorations.
+//!
+//! Pixels of the same color are merged into rectangles, each of which becomes a `SpriteSquare` stretched by its
+//! `Width` and `Height`, so an image with large single-colored areas doesn't turn into thousands of objects.
+
+use image::{Rgba, RgbaImage};
+
+use crate::types::{
+    color::Color, level_data::LevelData, object::Object, object_id::ObjectId, object_property::ObjectProperty,
+    vec2::Vec2,
+};
+
+/// How [`LevelData::import_pixel_art`] places the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelArtOptions {
+    /// The size of a pixel in world units.
+    pub pixel_size: f32,
+    /// Where the top left corner of the image ends up in the world.
+    pub origin: Vec2,
+    /// Whether the sprites become the members of a new group, centered on the image.
+    pub group: bool,
+}
+
+impl Default for PixelArtOptions {
+    fn default() -> Self {
+        Self { pixel_size: 0.25, origin: Vec2::ZERO, group: false }
+    }
+}
+
+/// A rectangle of pixels of a single color.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PixelRect {
+    /// The left column.
+    pub x: u32,
+    /// The top row.
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub color: Rgba<u8>,
+}
+
+/// Splits the pixels of the image that aren't fully transparent into rectangles of a single color.
+///
+/// Going through the pixels row by row, every pixel that isn't covered yet starts a rectangle that's grown to the
+/// right as far as possible, and then down as long as the whole width matches. That isn't always the fewest
+/// rectangles, but it's close for pixel art and takes linear time in the number of pixels.
+#[must_use]
+pub fn pixel_rects(image: &RgbaImage) -> Vec<PixelRect> {
+    let (width, height) = image.dimensions();
+    let mut covered = vec![false; width as usize * height as usize];
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+    let mut rects = vec![];
+
+    for y in 0..height {
+        for x in 0..width {
+            let color = *image.get_pixel(x, y);
+
+            if covered[index(x, y)] || color[3] == 0 {
+                continue;
+            }
+
+            let fits = |column: u32, row: u32, covered: &[bool]| {
+                !covered[index(column, row)] && *image.get_pixel(column, row) == color
+            };
+
+            let right = (x..width).take_while(|&column| fits(column, y, &covered)).last().unwrap_or(x);
+            let bottom = (y..height)
+                .take_while(|&row| (x..=right).all(|column| fits(column, row, &covered)))
+                .last()
+                .unwrap_or(y);
+
+            for row in y..=bottom {
+                for column in x..=right {
+                    covered[index(column, row)] = true;
+                }
+            }
+
+            rects.push(PixelRect { x, y, width: right - x + 1, height: bottom - y + 1, color });
+        }
+    }
+
+    rects
+}
+
+impl LevelData {
+    /// Adds the image to the layer as `SpriteSquare`s, one for every rectangle of [`pixel_rects`], and returns
+    /// their entity ids. Fully transparent pixels are left out.
+    ///
+    /// With `group` set the sprites are the members of a new group, whose entity id comes first. The layer is
+    /// created if the level doesn't have it.
+    pub fn import_pixel_art(&mut self, image: &RgbaImage, layer_id: i32, options: &PixelArtOptions) -> Vec<i32> {
+        let rects = pixel_rects(image);
+
+        if rects.is_empty() {
+            return vec![];
+        }
+
+        let first_entity_id = self.next_entity_id();
+        let group_id = options.group.then_some(first_entity_id);
+        let mut entity_ids: Vec<i32> = group_id.into_iter().collect();
+
+        for (rect, entity_id) in rects.iter().zip(first_entity_id + i32::from(options.group)..) {
+            #[allow(clippy::cast_precision_loss)]
+            let (x, y, width, height) = (rect.x as f32, rect.y as f32, rect.width as f32, rect.height as f32);
+            let center = Vec2::new(width.mul_add(0.5, x), -height.mul_add(0.5, y));
+            let [red, green, blue, alpha] = rect.color.0.map(|channel| f32::from(channel) / 255.0);
+
+            let mut object = Object::get_default_with_id(ObjectId::SpriteSquare, layer_id);
+            object.entity_id = entity_id;
+            object.position = options.origin + center * options.pixel_size;
+            object.in_group = group_id.unwrap_or_default();
+            object.properties.extend([
+                ObjectProperty::Width((width * options.pixel_size).into()),
+                ObjectProperty::Height((height * options.pixel_size).into()),
+                ObjectProperty::Color(Color::new(red, green, blue, alpha)),
+            ]);
+
+            self.objects.push(object);
+            entity_ids.push(entity_id);
+        }
+
+        let layer = self.layer_or_insert(layer_id, "Pixel art");
+
+        if let Some(group_id) = group_id {
+            #[allow(clippy::cast_precision_loss)]
+            let size = Vec2::new(image.width() as f32, -(image.height() as f32)) * options.pixel_size;
+
+            layer.children.push(group_id);
+
+            let mut group = Object::get_default_with_id(ObjectId::Group, layer_id);
+            group.entity_id = group_id;
+            group.position = options.origin + size / 2.0;
+            group.group_members = entity_ids[1..].to_vec();
+            self.objects.push(group);
+        } else {
+            layer.children.extend(&entity_ids);
+        }
+
+        entity_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::layer::Layer;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 128]);
+    const CLEAR: Rgba<u8> = Rgba([0, 0, 255, 0]);
+
+    fn image(rows: &[&[Rgba<u8>]]) -> RgbaImage {
+        #[allow(clippy::cast_possible_truncation)]
+        let (width, height) = (rows[0].len() as u32, rows.len() as u32);
+
+        RgbaImage::from_fn(width, height, |x, y| rows[y as usize][x as usize])
+    }
+
+    fn rect(x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) -> PixelRect {
+        PixelRect { x, y, width, height, color }
+    }
+
+    #[test]
+    fn single_color() {
+        assert_eq!(pixel_rects(&image(&[&[RED, RED], &[RED, RED]])), [rect(0, 0, 2, 2, RED)]);
+    }
+
+    #[test]
+    fn rects() {
+        let pixels = image(&[&[RED, RED, BLUE, CLEAR], &[RED, RED, RED, CLEAR], &[CLEAR, BLUE, BLUE, BLUE]]);
+
+        assert_eq!(
+            pixel_rects(&pixels),
+            [
+                rect(0, 0, 2, 2, RED),
+                rect(2, 0, 1, 1, BLUE),
+                rect(2, 1, 1, 1, RED),
+                rect(1, 2, 3, 1, BLUE),
+            ]
+        );
+        assert!(pixel_rects(&image(&[&[CLEAR]])).is_empty());
+    }
+
+    #[test]
+    fn import() {
+        let mut level = LevelData {
+            objects: vec![Object { entity_id: 4, ..Default::default() }],
+            layers: vec![Layer { layer_id: 1, children: vec![4], ..Default::default() }],
+            ..Default::default()
+        };
+        let options = PixelArtOptions { pixel_size: 0.5, origin: Vec2::new(1.0, 1.0), group: false };
+
+        assert_eq!(level.import_pixel_art(&image(&[&[RED, RED], &[RED, BLUE]]), 1, &options), [5, 6, 7]);
+        assert_eq!(level.layers[0].children, [4, 5, 6, 7]);
+
+        // The top row spans the full width of the image, which starts at the origin and goes down.
+        let sprite = &level.objects[1];
+        assert_eq!(sprite.object_id, ObjectId::SpriteSquare);
+        assert_eq!((sprite.position, sprite.in_layer), (Vec2::new(1.5, 0.75), 1));
+        assert!(sprite.properties.contains(&ObjectProperty::Width(1.0.into())));
+        assert!(sprite.properties.contains(&ObjectProperty::Height(0.5.into())));
+        assert!(sprite.properties.contains(&ObjectProperty::Color(Color::new(1.0, 0.0, 0.0, 1.0))));
+        assert_eq!(
+            sprite.world_rings()[0],
+            [Vec2::new(1.0, 0.5), Vec2::new(2.0, 0.5), Vec2::new(2.0, 1.0), Vec2::new(1.0, 1.0)]
+        );
+
+        assert!(level.import_pixel_art(&image(&[&[CLEAR]]), 1, &options).is_empty());
+        assert_eq!(level.objects.len(), 4);
+    }
+
+    #[test]
+    fn group() {
+        let mut level = LevelData::default();
+        let options = PixelArtOptions { group: true, ..PixelArtOptions::default() };
+
+        assert_eq!(level.import_pixel_art(&image(&[&[RED, BLUE]]), 3, &options), [1, 2, 3]);
+
+        let group = level.objects.iter().find(|object| object.entity_id == 1).unwrap();
+        assert_eq!((group.object_id, group.position), (ObjectId::Group, Vec2::new(0.25, -0.125)));
+        assert_eq!(group.group_members, [2, 3]);
+        assert!(level.objects.iter().filter(|object| object.entity_id != 1).all(|object| object.in_group == 1));
+        assert_eq!((level.layers[0].layer_id, level.layers[0].layer_name.as_str()), (3, "Pixel art"));
+        assert_eq!(level.layers[0].children, [1]);
+    }
+}
@@ -9,13 +9,16 @@ use crate::types::{
     level_data::LevelData,
     local_level::LocalLevel,
     nova_script::{
-        action::Action, activator::Activator, nova_value::NovaValue, parameter::Parameter,
+        action::Action, activator::{Activator, ActivatorKind}, nova_value::NovaValue, parameter::Parameter,
         static_type::StaticType, variable::Variable, NovaScript, function_call::{CallParameter, FunctionCall},
         dynamic_type::DynamicType, action_type::ActionType, scripts_folder::ScriptsFolder, variables_folder::VariablesFolder,
     },
     object::Object,
     object_property::ObjectProperty,
-    old_script::{old_action::OldAction, old_action_property::OldActionProperty, old_action_type::OldActionType, Script},
+    old_script::{
+        old_action::OldAction, old_action_kind::TypedOldAction, old_action_property::OldActionProperty,
+        old_action_type::OldActionType, Script,
+    },
     old_editor_types::{object_tile::ObjectTile, object_tile_property::ObjectTileProperty, simple_tile::SimpleTile},
     pattern::Pattern,
     prefab::Prefab,
@@ -73,6 +76,7 @@ impl_sealed!(
     Script,
     NovaScript,
     OldAction,
+    TypedOldAction,
     OldActionType,
     OldActionProperty,
     Action,
@@ -84,6 +88,7 @@ impl_sealed!(
     Variable,
     StaticType,
     Activator,
+    ActivatorKind,
     Parameter,
     Uuid,
     Theme,
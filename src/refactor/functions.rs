@@ -0,0 +1,297 @@
+use crate::types::{
+    level_data::LevelData,
+    nova_script::{
+        function_call::{CallParameter, FunctionCall},
+        nova_value::NovaValue,
+        parameter::Parameter,
+        path::ScriptPath,
+        static_type::StaticType,
+        visit::{walk_value, Visit, VisitMut},
+        NovaScript,
+    },
+};
+
+use super::RefactorError;
+
+impl LevelData {
+    /// Returns the path of every `RunFunction` action that calls the function.
+    #[must_use]
+    pub fn function_call_sites(&self, function_id: i32) -> Vec<ScriptPath> {
+        let mut call_sites = CallSites { function_id, sites: Vec::new() };
+        call_sites.visit_level_data(self);
+        call_sites.sites
+    }
+
+    /// Adds a parameter to a function and passes `default_value` for it at every call site. Returns the id of the
+    /// new parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function doesn't exist or already has a parameter with this name.
+    pub fn add_function_parameter(
+        &mut self,
+        function_id: i32,
+        name: &str,
+        static_type: StaticType,
+        default_value: &NovaValue,
+    ) -> Result<i32, RefactorError> {
+        let function = self.function_mut(function_id)?;
+
+        if function.parameters.iter().any(|parameter| parameter.name == name) {
+            return Err(RefactorError::NameTaken(name.to_string()));
+        }
+
+        let parameter_id = function.parameters.iter().map(|parameter| parameter.parameter_id).max().unwrap_or(0) + 1;
+
+        function.parameters.push(Parameter {
+            parameter_id,
+            name: name.to_string(),
+            static_type,
+            default_value: default_value.clone(),
+        });
+
+        self.for_each_call_mut(function_id, |arguments| {
+            arguments.push(CallParameter { parameter_id, value: default_value.clone() });
+        });
+
+        Ok(parameter_id)
+    }
+
+    /// Removes a parameter from a function and its argument from every call site.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function or the parameter doesn't exist, or the function still reads the
+    /// parameter.
+    pub fn remove_function_parameter(&mut self, function_id: i32, parameter_id: i32) -> Result<(), RefactorError> {
+        let function = self.function_mut(function_id)?;
+
+        if !function.parameters.iter().any(|parameter| parameter.parameter_id == parameter_id) {
+            return Err(RefactorError::UnknownParameter { function_id, parameter_id });
+        }
+
+        let reads = parameter_reads(function, parameter_id);
+
+        if !reads.is_empty() {
+            return Err(RefactorError::ParameterInUse { parameter_id, paths: reads });
+        }
+
+        function.parameters.retain(|parameter| parameter.parameter_id != parameter_id);
+
+        self.for_each_call_mut(function_id, |arguments| {
+            arguments.retain(|argument| argument.parameter_id != parameter_id);
+        });
+
+        Ok(())
+    }
+
+    /// Reorders the parameters of a function, and the arguments at every call site to match. `order` lists every
+    /// parameter id of the function once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function doesn't exist or `order` isn't a permutation of its parameter ids.
+    pub fn reorder_function_parameters(&mut self, function_id: i32, order: &[i32]) -> Result<(), RefactorError> {
+        let function = self.function_mut(function_id)?;
+
+        let mut sorted_order = order.to_vec();
+        sorted_order.sort_unstable();
+        let mut parameter_ids: Vec<i32> = function.parameters.iter().map(|parameter| parameter.parameter_id).collect();
+        parameter_ids.sort_unstable();
+
+        if sorted_order != parameter_ids {
+            return Err(RefactorError::InvalidParameterOrder { function_id });
+        }
+
+        // Arguments for parameters that don't exist go last, in their old order.
+        let position = |parameter_id: i32| order.iter().position(|&id| id == parameter_id).unwrap_or(order.len());
+
+        function.parameters.sort_by_key(|parameter| position(parameter.parameter_id));
+
+        self.for_each_call_mut(function_id, |arguments| {
+            arguments.sort_by_key(|argument| position(argument.parameter_id));
+        });
+
+        Ok(())
+    }
+
+    fn function_mut(&mut self, function_id: i32) -> Result<&mut NovaScript, RefactorError> {
+        self.nova_scripts
+            .iter_mut()
+            .find(|script| script.script_id == function_id && script.is_function)
+            .ok_or(RefactorError::UnknownFunction(function_id))
+    }
+
+    /// Calls `f` on the arguments of every call of the function.
+    fn for_each_call_mut(&mut self, function_id: i32, f: impl FnMut(&mut Vec<CallParameter>)) {
+        CallRewriter { function_id, f }.visit_level_data_mut(self);
+    }
+}
+
+struct CallSites {
+    function_id: i32,
+    sites: Vec<ScriptPath>,
+}
+
+impl Visit for CallSites {
+    fn visit_function_call(&mut self, function_call: &FunctionCall, path: &ScriptPath) {
+        if function_call.id == self.function_id {
+            self.sites.push(path.clone());
+        }
+    }
+}
+
+struct CallRewriter<F> {
+    function_id: i32,
+    f: F,
+}
+
+impl<F: FnMut(&mut Vec<CallParameter>)> VisitMut for CallRewriter<F> {
+    fn visit_function_call_mut(&mut self, function_call: &mut FunctionCall, _path: &ScriptPath) {
+        if function_call.id == self.function_id {
+            (self.f)(&mut function_call.parameters);
+        }
+    }
+}
+
+struct ParameterReads {
+    parameter_id: i32,
+    paths: Vec<ScriptPath>,
+}
+
+impl Visit for ParameterReads {
+    fn visit_value(&mut self, value: &NovaValue, path: &ScriptPath) {
+        if value.dynamic_type.parameter_static_type().is_some() && value.int_value == self.parameter_id {
+            self.paths.push(path.clone());
+        }
+
+        walk_value(self, value, path);
+    }
+}
+
+/// The paths of every value in the function that reads the parameter.
+fn parameter_reads(function: &NovaScript, parameter_id: i32) -> Vec<ScriptPath> {
+    let mut reads = ParameterReads { parameter_id, paths: Vec::new() };
+    reads.visit_script(function, &ScriptPath::script(function.script_id));
+    reads.paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+
+    /// A function that reads its second parameter, called with every argument at the top level and with only the
+    /// second one in a nested block of another function.
+    const LEVEL: &str = r#"
+        function 10 "move" {
+            param 1 "count": int = 0;
+            param 2 "delay": float = 0.5;
+            wait(@delay);
+        }
+        script 1 "Start" {
+            on level_start();
+            call 10(1: 3, 2: 1.5);
+        }
+        function 11 "retry" {
+            repeat 2 {
+                if true {
+                    call 10(2: 2.5);
+                }
+            }
+        }
+    "#;
+
+    fn level(source: &str) -> LevelData {
+        LevelData { nova_scripts: compile_scripts(source, &[]).unwrap(), ..Default::default() }
+    }
+
+    #[test]
+    fn omitted_arguments_are_not_errors() {
+        assert_eq!(level(LEVEL).type_check(), Vec::new());
+    }
+
+    #[test]
+    fn add_parameter() {
+        let mut level = level(LEVEL);
+
+        assert_eq!(
+            level.add_function_parameter(10, "loud", StaticType::Bool, &NovaValue::default_constant(StaticType::Bool)),
+            Ok(3)
+        );
+        assert_eq!(
+            level.nova_scripts,
+            compile_scripts(
+                &LEVEL
+                    .replace("0.5;", r#"0.5; param 3 "loud": bool = false;"#)
+                    .replace("2: 1.5)", "2: 1.5, 3: false)")
+                    .replace("2: 2.5)", "2: 2.5, 3: false)"),
+                &[]
+            )
+            .unwrap()
+        );
+        assert_eq!(level.type_check(), Vec::new());
+
+        let unchanged = level.clone();
+
+        assert_eq!(
+            level.add_function_parameter(10, "count", StaticType::Int, &NovaValue::default_constant(StaticType::Int)),
+            Err(RefactorError::NameTaken("count".to_string()))
+        );
+        assert_eq!(
+            level.add_function_parameter(1, "other", StaticType::Int, &NovaValue::default_constant(StaticType::Int)),
+            Err(RefactorError::UnknownFunction(1))
+        );
+        assert_eq!(level, unchanged);
+    }
+
+    #[test]
+    fn remove_parameter() {
+        let mut level = level(LEVEL);
+        let unchanged = level.clone();
+
+        assert!(matches!(
+            level.remove_function_parameter(10, 2),
+            Err(RefactorError::ParameterInUse { parameter_id: 2, paths }) if paths.len() == 1
+        ));
+        assert_eq!(
+            level.remove_function_parameter(10, 3),
+            Err(RefactorError::UnknownParameter { function_id: 10, parameter_id: 3 })
+        );
+        assert_eq!(level, unchanged);
+
+        assert_eq!(level.remove_function_parameter(10, 1), Ok(()));
+        assert_eq!(
+            level.nova_scripts,
+            compile_scripts(&LEVEL.replace(r#"param 1 "count": int = 0;"#, "").replace("1: 3, ", ""), &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn reorder_parameters() {
+        let mut level = level(LEVEL);
+        let unchanged = level.clone();
+
+        for order in [&[1][..], &[1, 1], &[1, 3], &[1, 2, 3]] {
+            assert_eq!(
+                level.reorder_function_parameters(10, order),
+                Err(RefactorError::InvalidParameterOrder { function_id: 10 })
+            );
+        }
+        assert_eq!(level, unchanged);
+
+        assert_eq!(level.reorder_function_parameters(10, &[2, 1]), Ok(()));
+        assert_eq!(
+            level.nova_scripts,
+            compile_scripts(
+                &LEVEL
+                    .replace(r#"param 1 "count": int = 0;"#, "")
+                    .replace("0.5;", r#"0.5; param 1 "count": int = 0;"#)
+                    .replace("1: 3, 2: 1.5", "2: 1.5, 1: 3"),
+                &[]
+            )
+            .unwrap()
+        );
+        assert_eq!(level.function_call_sites(10).len(), 2);
+    }
+}
@@ -0,0 +1,64 @@
+//! Refactorings that rewrite every place an entity of a level's scripts is used.
+//!
+//! The methods are defined on [`LevelData`](crate::types::level_data::LevelData) and either apply completely or
+//! leave the level untouched and return a [`RefactorError`].
+
+mod functions;
+mod variables;
+
+use std::fmt;
+
+use crate::types::nova_script::{path::ScriptPath, variable::VariableRef};
+
+pub use variables::{VariableAccess, VariableUsage};
+
+/// Why a refactoring couldn't be applied.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum RefactorError {
+    /// The variable isn't declared.
+    UnknownVariable(VariableRef),
+    /// The script doesn't exist.
+    UnknownScript(i32),
+    /// The script doesn't exist or isn't a function.
+    UnknownFunction(i32),
+    /// The function has no parameter with this id.
+    UnknownParameter { function_id: i32, parameter_id: i32 },
+    /// Another variable in the same scope, or another parameter of the function, already has this name.
+    NameTaken(String),
+    /// A global variable can't be made local to a script because it's also used at these places outside of it.
+    UsedOutsideScript { variable_id: i32, paths: Vec<ScriptPath> },
+    /// A parameter can't be removed because the function still reads it at these places.
+    ParameterInUse { parameter_id: i32, paths: Vec<ScriptPath> },
+    /// The new order of the parameters doesn't list every parameter of the function exactly once.
+    InvalidParameterOrder { function_id: i32 },
+}
+
+impl fmt::Display for RefactorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVariable(VariableRef::Global(variable_id)) => {
+                write!(f, "global variable {variable_id} doesn't exist")
+            }
+            Self::UnknownVariable(VariableRef::Local { script_id, variable_id }) => {
+                write!(f, "script {script_id} has no variable {variable_id}")
+            }
+            Self::UnknownScript(script_id) => write!(f, "script {script_id} doesn't exist"),
+            Self::UnknownFunction(function_id) => write!(f, "function {function_id} doesn't exist"),
+            Self::UnknownParameter { function_id, parameter_id } => {
+                write!(f, "function {function_id} has no parameter {parameter_id}")
+            }
+            Self::NameTaken(name) => write!(f, "the name {name:?} is already taken"),
+            Self::UsedOutsideScript { variable_id, paths } => {
+                write!(f, "variable {variable_id} is used outside the script at {} place(s)", paths.len())
+            }
+            Self::ParameterInUse { parameter_id, paths } => {
+                write!(f, "parameter {parameter_id} is still read at {} place(s)", paths.len())
+            }
+            Self::InvalidParameterOrder { function_id } => {
+                write!(f, "the new order doesn't list every parameter of function {function_id} once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RefactorError {}
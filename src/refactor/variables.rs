@@ -0,0 +1,452 @@
+use std::collections::BTreeSet;
+
+use crate::type_check::TypeError;
+use crate::types::{
+    level_data::LevelData,
+    nova_script::{
+        action::Action,
+        dynamic_type::DynamicType,
+        nova_value::NovaValue,
+        path::ScriptPath,
+        static_type::StaticType,
+        variable::{Variable, VariableRef},
+        visit::{walk_action, walk_action_mut, walk_script, walk_script_mut, walk_value, walk_value_mut, Visit, VisitMut},
+        NovaScript,
+    },
+};
+
+use super::RefactorError;
+
+/// Whether a usage reads or writes the variable.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VariableAccess {
+    /// A `NovaValue` with a `*Variable` dynamic type, including the parameter of a variable changed activator.
+    Read,
+    /// A `VariableSet`, `VariableReset` or `VariableSetOverTime` action.
+    Write,
+}
+
+/// A place where a variable is read or written.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VariableUsage {
+    /// The variable the id resolves to at this place. Local variables shadow global ones with the same id.
+    pub variable: VariableRef,
+    pub access: VariableAccess,
+    /// The path of the reading value or the writing action.
+    pub path: ScriptPath,
+}
+
+/// Collects every usage of every declared variable.
+struct UsageCollector {
+    globals: BTreeSet<i32>,
+    script: Option<(i32, BTreeSet<i32>)>,
+    usages: Vec<VariableUsage>,
+}
+
+impl UsageCollector {
+    fn push(&mut self, variable_id: i32, access: VariableAccess, path: &ScriptPath) {
+        let variable = match &self.script {
+            Some((script_id, locals)) if locals.contains(&variable_id) => {
+                VariableRef::Local { script_id: *script_id, variable_id }
+            }
+            _ if self.globals.contains(&variable_id) => VariableRef::Global(variable_id),
+            _ => return,
+        };
+
+        self.usages.push(VariableUsage { variable, access, path: path.clone() });
+    }
+}
+
+impl Visit for UsageCollector {
+    fn visit_script(&mut self, script: &NovaScript, path: &ScriptPath) {
+        self.script = Some((script.script_id, script.variables.iter().map(|variable| variable.variable_id).collect()));
+        walk_script(self, script, path);
+    }
+
+    fn visit_action(&mut self, action: &Action, path: &ScriptPath) {
+        if let Some(variable_id) = action.action_type.target_variable() {
+            self.push(variable_id, VariableAccess::Write, path);
+        }
+
+        walk_action(self, action, path);
+    }
+
+    fn visit_value(&mut self, value: &NovaValue, path: &ScriptPath) {
+        if value.dynamic_type.variable_static_type().is_some() {
+            self.push(value.int_value, VariableAccess::Read, path);
+        }
+
+        walk_value(self, value, path);
+    }
+}
+
+/// Rewrites every usage of one variable.
+struct UsageRewriter {
+    target: VariableRef,
+    /// Whether ids in the script being visited resolve to the target.
+    in_scope: bool,
+    variable_id: i32,
+    read_type: Option<DynamicType>,
+}
+
+impl UsageRewriter {
+    const fn new(target: VariableRef) -> Self {
+        Self {
+            target,
+            in_scope: matches!(target, VariableRef::Global(_)),
+            variable_id: target.variable_id(),
+            read_type: None,
+        }
+    }
+}
+
+impl VisitMut for UsageRewriter {
+    fn visit_script_mut(&mut self, script: &mut NovaScript, path: &ScriptPath) {
+        self.in_scope = match self.target {
+            VariableRef::Global(variable_id) => {
+                !script.variables.iter().any(|variable| variable.variable_id == variable_id)
+            }
+            VariableRef::Local { script_id, .. } => script.script_id == script_id,
+        };
+
+        walk_script_mut(self, script, path);
+    }
+
+    fn visit_action_mut(&mut self, action: &mut Action, path: &ScriptPath) {
+        if let Some(variable_id) = action.action_type.target_variable_mut() {
+            if self.in_scope && *variable_id == self.target.variable_id() {
+                *variable_id = self.variable_id;
+            }
+        }
+
+        walk_action_mut(self, action, path);
+    }
+
+    fn visit_value_mut(&mut self, value: &mut NovaValue, path: &ScriptPath) {
+        if self.in_scope
+            && value.dynamic_type.variable_static_type().is_some()
+            && value.int_value == self.target.variable_id()
+        {
+            value.int_value = self.variable_id;
+
+            if let Some(read_type) = self.read_type {
+                value.dynamic_type = read_type;
+            }
+        }
+
+        walk_value_mut(self, value, path);
+    }
+}
+
+impl LevelData {
+    /// Returns every read and write of a variable, in the global variables and in every script.
+    ///
+    /// If a script declares a local variable with the same id as a global one, the usages of both are returned,
+    /// told apart by [`VariableUsage::variable`].
+    #[must_use]
+    pub fn variable_usages(&self, variable_id: i32) -> Vec<VariableUsage> {
+        let mut usages = self.all_variable_usages();
+        usages.retain(|usage| usage.variable.variable_id() == variable_id);
+        usages
+    }
+
+    /// Returns every declared variable that is never read nor written.
+    #[must_use]
+    pub fn unused_variables(&self) -> Vec<VariableRef> {
+        let used: BTreeSet<VariableRef> = self.all_variable_usages().into_iter().map(|usage| usage.variable).collect();
+
+        self.declared_variables().into_iter().filter(|variable| !used.contains(variable)).collect()
+    }
+
+    /// Returns every declared variable that is written but never read.
+    #[must_use]
+    pub fn write_only_variables(&self) -> Vec<VariableRef> {
+        let usages = self.all_variable_usages();
+        let accessed = |access| -> BTreeSet<VariableRef> {
+            usages.iter().filter(|usage| usage.access == access).map(|usage| usage.variable).collect()
+        };
+        let (reads, writes) = (accessed(VariableAccess::Read), accessed(VariableAccess::Write));
+
+        self.declared_variables()
+            .into_iter()
+            .filter(|variable| writes.contains(variable) && !reads.contains(variable))
+            .collect()
+    }
+
+    /// Renames a variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variable doesn't exist or another variable in the same scope has this name.
+    pub fn rename_variable(&mut self, variable: VariableRef, name: &str) -> Result<(), RefactorError> {
+        let siblings = match variable {
+            VariableRef::Global(_) => &self.global_variables,
+            VariableRef::Local { script_id, .. } => &self.script(script_id)?.variables,
+        };
+
+        if siblings.iter().any(|sibling| sibling.variable_id != variable.variable_id() && sibling.name == name) {
+            return Err(RefactorError::NameTaken(name.to_string()));
+        }
+
+        self.declaration_mut(variable)?.name = name.to_string();
+
+        Ok(())
+    }
+
+    /// Changes the type of a variable.
+    ///
+    /// Reads of the variable are changed to the `*Variable` dynamic type of the new type, and the initial value is
+    /// replaced by the default constant of the new type if it doesn't fit anymore. Returns the type errors that
+    /// the change causes at the usages of the variable, e.g. a read that is now used as an operand of the wrong
+    /// type, or a `VariableSet` whose value has the old type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variable doesn't exist.
+    pub fn change_variable_type(
+        &mut self,
+        variable: VariableRef,
+        static_type: StaticType,
+    ) -> Result<Vec<TypeError>, RefactorError> {
+        let declaration = self.declaration_mut(variable)?;
+        declaration.static_type = static_type;
+
+        if declaration.initial_value.dynamic_type.result_type() != static_type {
+            declaration.initial_value = NovaValue::default_constant(static_type);
+        }
+
+        let mut rewriter = UsageRewriter::new(variable);
+        rewriter.read_type = Some(static_type.variable_type());
+        rewriter.visit_level_data_mut(self);
+
+        let usages: Vec<ScriptPath> = self
+            .all_variable_usages()
+            .into_iter()
+            .filter(|usage| usage.variable == variable)
+            .map(|usage| usage.path)
+            .collect();
+
+        Ok(self
+            .type_check()
+            .into_iter()
+            .filter(|error| usages.iter().any(|usage| is_within(&error.path, usage)))
+            .collect())
+    }
+
+    /// Turns a local variable of a script into a global variable with a new id, returning the new id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the script or the variable doesn't exist, or a global variable has the same name.
+    pub fn promote_variable(&mut self, script_id: i32, variable_id: i32) -> Result<i32, RefactorError> {
+        let variable = VariableRef::Local { script_id, variable_id };
+        let declaration = self.declaration_mut(variable)?.clone();
+
+        if self.global_variables.iter().any(|global| global.name == declaration.name) {
+            return Err(RefactorError::NameTaken(declaration.name));
+        }
+
+        let new_id = self.next_variable_id();
+        self.move_variable(variable, new_id);
+
+        let script = self.script_mut(script_id)?;
+        script.variables.retain(|local| local.variable_id != variable_id);
+        self.global_variables.push(Variable { variable_id: new_id, ..declaration });
+
+        Ok(new_id)
+    }
+
+    /// Turns a global variable that is only used by one script into a local variable of that script with a new
+    /// id, returning the new id. The variable is removed from its variables folder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the script or the variable doesn't exist, the variable is used outside of the script,
+    /// or the script has a local variable with the same name.
+    pub fn demote_variable(&mut self, variable_id: i32, script_id: i32) -> Result<i32, RefactorError> {
+        let variable = VariableRef::Global(variable_id);
+        let declaration = self.declaration_mut(variable)?.clone();
+        let script = self.script(script_id)?;
+
+        if script.variables.iter().any(|local| local.name == declaration.name) {
+            return Err(RefactorError::NameTaken(declaration.name));
+        }
+
+        let outside: Vec<ScriptPath> = self
+            .all_variable_usages()
+            .into_iter()
+            .filter(|usage| usage.variable == variable && usage.path.script_id != Some(script_id))
+            .map(|usage| usage.path)
+            .collect();
+
+        if !outside.is_empty() {
+            return Err(RefactorError::UsedOutsideScript { variable_id, paths: outside });
+        }
+
+        let new_id = self.next_variable_id();
+        self.move_variable(variable, new_id);
+
+        self.global_variables.retain(|global| global.variable_id != variable_id);
+
+        for folder in self.variables_folders.iter_mut().flatten() {
+            folder.variables.retain(|&id| id != variable_id);
+        }
+
+        self.script_mut(script_id)?.variables.push(Variable { variable_id: new_id, ..declaration });
+
+        Ok(new_id)
+    }
+
+    fn all_variable_usages(&self) -> Vec<VariableUsage> {
+        let mut collector = UsageCollector {
+            globals: self.global_variables.iter().map(|variable| variable.variable_id).collect(),
+            script: None,
+            usages: Vec::new(),
+        };
+
+        collector.visit_level_data(self);
+        collector.usages
+    }
+
+    fn declared_variables(&self) -> Vec<VariableRef> {
+        let globals = self.global_variables.iter().map(|variable| VariableRef::Global(variable.variable_id));
+        let locals = self.nova_scripts.iter().flat_map(|script| {
+            script
+                .variables
+                .iter()
+                .map(|variable| VariableRef::Local { script_id: script.script_id, variable_id: variable.variable_id })
+        });
+
+        globals.chain(locals).collect()
+    }
+
+    fn script(&self, script_id: i32) -> Result<&NovaScript, RefactorError> {
+        self.nova_scripts
+            .iter()
+            .find(|script| script.script_id == script_id)
+            .ok_or(RefactorError::UnknownScript(script_id))
+    }
+
+    fn script_mut(&mut self, script_id: i32) -> Result<&mut NovaScript, RefactorError> {
+        self.nova_scripts
+            .iter_mut()
+            .find(|script| script.script_id == script_id)
+            .ok_or(RefactorError::UnknownScript(script_id))
+    }
+
+    fn declaration_mut(&mut self, variable: VariableRef) -> Result<&mut Variable, RefactorError> {
+        let variables = match variable {
+            VariableRef::Global(_) => &mut self.global_variables,
+            VariableRef::Local { script_id, .. } => &mut self.script_mut(script_id)?.variables,
+        };
+
+        variables
+            .iter_mut()
+            .find(|declared| declared.variable_id == variable.variable_id())
+            .ok_or(RefactorError::UnknownVariable(variable))
+    }
+
+    /// An id that no global or local variable uses.
+    fn next_variable_id(&self) -> i32 {
+        self.declared_variables().iter().map(|variable| variable.variable_id()).max().unwrap_or(0) + 1
+    }
+
+    /// Points every usage of `variable` at `new_id`.
+    fn move_variable(&mut self, variable: VariableRef, new_id: i32) {
+        let mut rewriter = UsageRewriter::new(variable);
+        rewriter.variable_id = new_id;
+        rewriter.visit_level_data_mut(self);
+    }
+}
+
+/// Whether `path` is `ancestor` or inside of it.
+fn is_within(path: &ScriptPath, ancestor: &ScriptPath) -> bool {
+    path.script_id == ancestor.script_id && path.segments.starts_with(&ancestor.segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+
+    /// Script 1 declares a local that shadows the global `score` by id and uses it in nested blocks.
+    const SCRIPTS: &str = r#"
+        script 1 "A" {
+            var 2 "lives": int = 3;
+            on level_start();
+            $count = $count + 1;
+            if $lives > 0 {
+                repeat 2 {
+                    $lives = $lives - 1;
+                }
+            }
+        }
+        script 2 "B" {
+            $score = $score + 1;
+            while false {
+                if true {
+                    $score = $count;
+                }
+            }
+        }
+    "#;
+
+    fn global(variable_id: i32, name: &str, initial_value: i32) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type: StaticType::Int,
+            persistance: None,
+            initial_value: NovaValue { int_value: initial_value, ..NovaValue::default_constant(StaticType::Int) },
+        }
+    }
+
+    fn level(globals: Vec<Variable>, scripts: &str) -> LevelData {
+        LevelData {
+            nova_scripts: compile_scripts(scripts, &globals).unwrap(),
+            global_variables: globals,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn promote_shadowing_local() {
+        let mut level = level(vec![global(1, "count", 0), global(2, "score", 0)], SCRIPTS);
+
+        assert_eq!(
+            level.promote_variable(1, 1),
+            Err(RefactorError::UnknownVariable(VariableRef::Local { script_id: 1, variable_id: 1 }))
+        );
+        assert_eq!(level.promote_variable(1, 2), Ok(3));
+
+        // Script 1 now reads the new global, script 2 still reads the global the local used to shadow.
+        let expected = self::level(
+            vec![global(1, "count", 0), global(2, "score", 0), global(3, "lives", 3)],
+            &SCRIPTS.replace(r#"var 2 "lives": int = 3;"#, ""),
+        );
+
+        assert_eq!(level, expected);
+    }
+
+    #[test]
+    fn demote_shadowed_global() {
+        let mut level = level(vec![global(1, "count", 0), global(2, "score", 5)], SCRIPTS);
+        let unchanged = level.clone();
+
+        assert!(matches!(
+            level.demote_variable(1, 2),
+            Err(RefactorError::UsedOutsideScript { variable_id: 1, paths }) if paths.len() == 2
+        ));
+        assert_eq!(level, unchanged);
+
+        // Script 1 uses id 2 as well, but only for its own local.
+        assert_eq!(level.demote_variable(2, 2), Ok(3));
+
+        let expected = self::level(
+            vec![global(1, "count", 0)],
+            &SCRIPTS.replace(r#"script 2 "B" {"#, r#"script 2 "B" { var 3 "score": int = 5;"#),
+        );
+
+        assert_eq!(level, expected);
+    }
+}
@@ -0,0 +1,357 @@
+//! A cross-reference index over the ids that entities of a level use to point at each other.
+//!
+//! Objects, layers, prefabs, patterns, scripts and variables all refer to each other by `i32` ids.
+//! [`References`] collects every one of those links so that you can ask which entities use a given entity
+//! (inbound references) and which entities it uses (outbound references).
+
+use std::collections::BTreeMap;
+
+use crate::types::{
+    level_data::LevelData,
+    nova_script::{action::Action, dynamic_type::DynamicType, nova_value::NovaValue, variable::VariableRef, NovaScript},
+    object::Object,
+    object_property::ObjectProperty,
+};
+
+/// An entity of a level that can be the source or target of a reference.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntityRef {
+    /// The level itself, used for level wide settings like `custom_terrain_pattern_id`.
+    Level,
+    /// An object, by `entity_id`.
+    Object(i32),
+    /// A layer, by `layer_id`.
+    Layer(i32),
+    /// A prefab, by `prefab_id`.
+    Prefab(i32),
+    /// A pattern, by `pattern_id`.
+    Pattern(i32),
+    /// A nova script or function, by `script_id`.
+    Script(i32),
+    /// A global or script-local variable.
+    Variable(VariableRef),
+    /// A scripts folder, by `folder_id`.
+    ScriptsFolder(i32),
+    /// A variables folder, by `folder_id`.
+    VariablesFolder(i32),
+}
+
+/// The way in which one entity refers to another.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReferenceKind {
+    /// `ObjectProperty::LinkedObjects` of an object.
+    LinkedObject,
+    /// `Object::in_group` of a group member.
+    InGroup,
+    /// `Object::group_members` of a group.
+    GroupMember,
+    /// `Object::in_layer` of an object.
+    InLayer,
+    /// `Layer::children` of a layer.
+    LayerChild,
+    /// `Object::prefab_id` of a prefab instance.
+    PrefabInstance,
+    /// `ObjectProperty::Pattern` of an object or of an item of a prefab.
+    Pattern,
+    /// `LevelData::custom_terrain_pattern_id`.
+    CustomTerrainPattern,
+    /// `ScriptsFolder::scripts`.
+    FolderScript,
+    /// `VariablesFolder::variables`.
+    FolderVariable,
+    /// A variable declared in `NovaScript::variables`.
+    LocalVariable,
+    /// A `NovaValue` that reads a variable or holds a constant object, script, prefab or layer id.
+    Value,
+    /// A `VariableSet`, `VariableReset` or `VariableSetOverTime` action.
+    VariableWrite,
+    /// `FunctionCall::id` of a `RunFunction` action.
+    FunctionCall,
+}
+
+impl ReferenceKind {
+    /// Whether the source of this reference merely contains the target (a layer listing its children,
+    /// a folder listing its scripts, ...) instead of using it.
+    ///
+    /// Containment references don't keep an entity from being deleted.
+    #[must_use]
+    pub const fn is_containment(self) -> bool {
+        matches!(
+            self,
+            Self::LayerChild | Self::FolderScript | Self::FolderVariable | Self::LocalVariable
+        )
+    }
+}
+
+/// A single reference from one entity to another.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Reference {
+    /// The entity holding the id.
+    pub from: EntityRef,
+    /// The entity the id points at.
+    pub to: EntityRef,
+    /// Where the id is stored.
+    pub kind: ReferenceKind,
+}
+
+/// An index of every reference between the entities of a level.
+///
+/// The index is a snapshot, rebuild it after modifying the level.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct References {
+    entries: Vec<Reference>,
+    inbound: BTreeMap<EntityRef, Vec<usize>>,
+    outbound: BTreeMap<EntityRef, Vec<usize>>,
+}
+
+/// The id that fields like `prefab_id` or `in_group` hold when they don't point at anything.
+const NO_ID: i32 = 0;
+
+impl References {
+    /// Builds the index for the given level.
+    #[must_use]
+    pub fn new(level: &LevelData) -> Self {
+        let mut references = Self::default();
+
+        for layer in &level.layers {
+            for &child in &layer.children {
+                references.push(EntityRef::Layer(layer.layer_id), EntityRef::Object(child), ReferenceKind::LayerChild);
+            }
+        }
+
+        for object in &level.objects {
+            references.add_object(object);
+        }
+
+        for prefab in &level.prefabs {
+            for item in &prefab.items {
+                for property in &item.properties {
+                    if let ObjectProperty::Pattern(pattern_id) = property {
+                        references.push(EntityRef::Prefab(prefab.prefab_id), EntityRef::Pattern(*pattern_id), ReferenceKind::Pattern);
+                    }
+                }
+            }
+        }
+
+        if level.custom_terrain_pattern_id != NO_ID {
+            references.push(EntityRef::Level, EntityRef::Pattern(level.custom_terrain_pattern_id), ReferenceKind::CustomTerrainPattern);
+        }
+
+        for folder in level.scripts_folders.iter().flatten() {
+            for &script in &folder.scripts {
+                references.push(EntityRef::ScriptsFolder(folder.folder_id), EntityRef::Script(script), ReferenceKind::FolderScript);
+            }
+        }
+
+        for folder in level.variables_folders.iter().flatten() {
+            for &variable in &folder.variables {
+                let variable = EntityRef::Variable(VariableRef::Global(variable));
+                references.push(EntityRef::VariablesFolder(folder.folder_id), variable, ReferenceKind::FolderVariable);
+            }
+        }
+
+        for variable in &level.global_variables {
+            let from = EntityRef::Variable(VariableRef::Global(variable.variable_id));
+            variable.initial_value.for_each(&mut |value| references.add_value(from, value, None));
+        }
+
+        for script in &level.nova_scripts {
+            let from = EntityRef::Script(script.script_id);
+
+            for variable in &script.variables {
+                let variable = VariableRef::Local { script_id: script.script_id, variable_id: variable.variable_id };
+                references.push(from, EntityRef::Variable(variable), ReferenceKind::LocalVariable);
+            }
+
+            script.for_each_value(&mut |value| references.add_value(from, value, Some(script)));
+            script.for_each_action(&mut |action| references.add_action(from, action, script));
+        }
+
+        references
+    }
+
+    fn push(&mut self, from: EntityRef, to: EntityRef, kind: ReferenceKind) {
+        let index = self.entries.len();
+        self.entries.push(Reference { from, to, kind });
+        self.outbound.entry(from).or_default().push(index);
+        self.inbound.entry(to).or_default().push(index);
+    }
+
+    fn add_object(&mut self, object: &Object) {
+        let from = EntityRef::Object(object.entity_id);
+
+        if object.in_layer != NO_ID {
+            self.push(from, EntityRef::Layer(object.in_layer), ReferenceKind::InLayer);
+        }
+
+        if object.in_group != NO_ID {
+            self.push(from, EntityRef::Object(object.in_group), ReferenceKind::InGroup);
+        }
+
+        for &member in &object.group_members {
+            self.push(from, EntityRef::Object(member), ReferenceKind::GroupMember);
+        }
+
+        if object.prefab_id != NO_ID {
+            self.push(from, EntityRef::Prefab(object.prefab_id), ReferenceKind::PrefabInstance);
+        }
+
+        for property in &object.properties {
+            match property {
+                ObjectProperty::LinkedObjects(linked) => {
+                    for &linked in linked {
+                        self.push(from, EntityRef::Object(linked), ReferenceKind::LinkedObject);
+                    }
+                }
+                ObjectProperty::Pattern(pattern_id) => {
+                    self.push(from, EntityRef::Pattern(*pattern_id), ReferenceKind::Pattern);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Adds the references of a value inside of `script`, or outside of any script if it's `None`.
+    fn add_value(&mut self, from: EntityRef, value: &NovaValue, script: Option<&NovaScript>) {
+        if value.dynamic_type.variable_static_type().is_some() {
+            let variable = VariableRef::resolve(value.int_value, script);
+            self.push(from, EntityRef::Variable(variable), ReferenceKind::Value);
+            return;
+        }
+
+        match value.dynamic_type {
+            DynamicType::ObjectConstant if value.int_value != NO_ID => {
+                self.push(from, EntityRef::Object(value.int_value), ReferenceKind::Value);
+            }
+            DynamicType::ObjectSetConstant => {
+                for &object in value.int_list_values.iter().flatten() {
+                    self.push(from, EntityRef::Object(object), ReferenceKind::Value);
+                }
+            }
+            DynamicType::ScriptConstant if value.int_value != NO_ID => {
+                self.push(from, EntityRef::Script(value.int_value), ReferenceKind::Value);
+            }
+            DynamicType::PrefabConstant if value.int_value != NO_ID => {
+                self.push(from, EntityRef::Prefab(value.int_value), ReferenceKind::Value);
+            }
+            DynamicType::PrefabListConstant => {
+                for &prefab in value.int_list_values.iter().flatten() {
+                    self.push(from, EntityRef::Prefab(prefab), ReferenceKind::Value);
+                }
+            }
+            DynamicType::LayerConstant if value.int_value != NO_ID => {
+                self.push(from, EntityRef::Layer(value.int_value), ReferenceKind::Value);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_action(&mut self, from: EntityRef, action: &Action, script: &NovaScript) {
+        if let Some(variable_id) = action.action_type.target_variable() {
+            let variable = VariableRef::resolve(variable_id, Some(script));
+            self.push(from, EntityRef::Variable(variable), ReferenceKind::VariableWrite);
+        }
+
+        if let Some(function) = action.action_type.function_call() {
+            self.push(from, EntityRef::Script(function.id), ReferenceKind::FunctionCall);
+        }
+    }
+
+    /// Returns every reference in the level.
+    pub fn iter(&self) -> impl Iterator<Item = &Reference> {
+        self.entries.iter()
+    }
+
+    /// Returns every reference pointing at `entity`.
+    pub fn inbound(&self, entity: EntityRef) -> impl Iterator<Item = &Reference> {
+        self.inbound
+            .get(&entity)
+            .into_iter()
+            .flatten()
+            .map(|&index| &self.entries[index])
+    }
+
+    /// Returns every reference held by `entity`.
+    pub fn outbound(&self, entity: EntityRef) -> impl Iterator<Item = &Reference> {
+        self.outbound
+            .get(&entity)
+            .into_iter()
+            .flatten()
+            .map(|&index| &self.entries[index])
+    }
+
+    /// Returns the references that use `entity`, i.e. the inbound references that aren't
+    /// [containment](ReferenceKind::is_containment) and don't come from `entity` itself.
+    pub fn users(&self, entity: EntityRef) -> impl Iterator<Item = &Reference> {
+        self.inbound(entity)
+            .filter(move |reference| reference.from != entity && !reference.kind.is_containment())
+    }
+
+    /// Whether anything [uses](References::users) `entity`.
+    #[must_use]
+    pub fn is_used(&self, entity: EntityRef) -> bool {
+        self.users(entity).next().is_some()
+    }
+
+    /// Returns the references whose target doesn't exist in the level the index was built from.
+    pub fn dangling<'a>(&'a self, level: &'a LevelData) -> impl Iterator<Item = &'a Reference> {
+        self.entries.iter().filter(move |reference| !entity_exists(level, reference.to))
+    }
+}
+
+/// Whether the entity exists in the level.
+#[must_use]
+pub fn entity_exists(level: &LevelData, entity: EntityRef) -> bool {
+    match entity {
+        EntityRef::Level => true,
+        EntityRef::Object(id) => level.objects.iter().any(|object| object.entity_id == id),
+        EntityRef::Layer(id) => level.layers.iter().any(|layer| layer.layer_id == id),
+        EntityRef::Prefab(id) => level.prefabs.iter().any(|prefab| prefab.prefab_id == id),
+        EntityRef::Pattern(id) => level.patterns.iter().any(|pattern| pattern.pattern_id == id),
+        EntityRef::Script(id) => level.nova_scripts.iter().any(|script| script.script_id == id),
+        EntityRef::Variable(VariableRef::Global(id)) => {
+            level.global_variables.iter().any(|variable| variable.variable_id == id)
+        }
+        EntityRef::Variable(VariableRef::Local { script_id, variable_id }) => level
+            .nova_scripts
+            .iter()
+            .filter(|script| script.script_id == script_id)
+            .any(|script| script.variables.iter().any(|variable| variable.variable_id == variable_id)),
+        EntityRef::ScriptsFolder(id) => level.scripts_folders.iter().flatten().any(|folder| folder.folder_id == id),
+        EntityRef::VariablesFolder(id) => level.variables_folders.iter().flatten().any(|folder| folder.folder_id == id),
+    }
+}
+
+/// What the `LevelData::delete_*` methods should do when the entity is still in use.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeletePolicy {
+    /// Remove every reference to the entity along with it.
+    #[default]
+    Cascade,
+    /// Don't delete anything and return [`Error::DanglingReferences`](crate::error::Error::DanglingReferences) listing the users.
+    Refuse,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objects_outside_of_layers() {
+        let level = LevelData {
+            objects: vec![
+                Object { entity_id: 1, in_layer: 0, ..Default::default() },
+                Object { entity_id: 2, in_layer: 3, ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        let references = level.references();
+
+        assert_eq!(references.outbound(EntityRef::Object(1)).count(), 0);
+        assert_eq!(
+            references.iter().copied().collect::<Vec<_>>(),
+            vec![Reference { from: EntityRef::Object(2), to: EntityRef::Layer(3), kind: ReferenceKind::InLayer }]
+        );
+        assert_eq!(references.dangling(&level).count(), 1);
+    }
+}
@@ -0,0 +1,203 @@
+//! Anti-aliased filling and stroking of polygons given in pixel coordinates.
+
+// Pixel coordinates stay far below the 2^24 where `f32` loses integer precision.
+#![allow(clippy::cast_precision_loss)]
+
+use glam::Vec2;
+use image::{Rgba, RgbaImage};
+
+use super::Paint;
+
+/// The number of scanlines sampled per pixel row.
+const SUBSAMPLES: u32 = 4;
+
+/// The number of edges a round line join is approximated with.
+const JOIN_SEGMENTS: usize = 12;
+
+/// An image that polygons are drawn onto.
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    pub image: RgbaImage,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: Paint) -> Self {
+        Self { image: RgbaImage::from_pixel(width, height, to_pixel(background)) }
+    }
+
+    /// Fills the rings with the even-odd rule. `paint` gives the color of each covered pixel.
+    pub fn fill(&mut self, rings: &[Vec<Vec2>], paint: impl Fn(u32, u32) -> Paint) {
+        let Some(mut mask) = Mask::covering(rings, 0.0, &self.image) else {
+            return;
+        };
+
+        mask.add_polygon(rings);
+        self.composite(&mask, paint);
+    }
+
+    /// Draws a line of the given width along every ring, with round joins.
+    pub fn stroke(&mut self, rings: &[Vec<Vec2>], width: f32, paint: impl Fn(u32, u32) -> Paint) {
+        let radius = width / 2.0;
+
+        let Some(mut mask) = Mask::covering(rings, radius, &self.image) else {
+            return;
+        };
+
+        for ring in rings {
+            for (index, &start) in ring.iter().enumerate() {
+                let end = ring[(index + 1) % ring.len()];
+                let normal = (end - start).perp().normalize_or_zero() * radius;
+
+                mask.add_polygon(&[vec![start + normal, end + normal, end - normal, start - normal]]);
+                mask.add_polygon(&[circle(start, radius)]);
+            }
+        }
+
+        self.composite(&mask, paint);
+    }
+
+    fn composite(&mut self, mask: &Mask, paint: impl Fn(u32, u32) -> Paint) {
+        for row in 0..mask.height {
+            for column in 0..mask.width {
+                let coverage = mask.coverage[(row * mask.width + column) as usize];
+
+                if coverage <= 0.0 {
+                    continue;
+                }
+
+                let (x, y) = (mask.x + column, mask.y + row);
+                let [red, green, blue, alpha] = paint(x, y);
+                let pixel = self.image.get_pixel_mut(x, y);
+                *pixel = blend(from_pixel(*pixel), [red, green, blue, alpha * coverage]);
+            }
+        }
+    }
+}
+
+/// How much of each pixel in a rectangle of the canvas is covered.
+///
+/// Shapes added to the same mask are combined by taking the larger coverage, so overlapping parts of a line
+/// aren't drawn twice.
+#[derive(Clone, Debug)]
+struct Mask {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    coverage: Vec<f32>,
+}
+
+impl Mask {
+    /// Returns a mask for the bounds of the rings grown by `margin`, or `None` if they are outside the canvas.
+    fn covering(rings: &[Vec<Vec2>], margin: f32, image: &RgbaImage) -> Option<Self> {
+        let points = rings.iter().flatten();
+        let min = points.clone().fold(Vec2::INFINITY, |min, &point| min.min(point)) - margin;
+        let max = points.fold(Vec2::NEG_INFINITY, |max, &point| max.max(point)) + margin;
+        let size = Vec2::new(image.width() as f32, image.height() as f32);
+
+        let min = min.floor().max(Vec2::ZERO);
+        let max = max.ceil().min(size);
+
+        if !(min.x < max.x && min.y < max.y) {
+            return None;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y, width, height) = (min.x as u32, min.y as u32, (max.x - min.x) as u32, (max.y - min.y) as u32);
+
+        Some(Self { x, y, width, height, coverage: vec![0.0; (width * height) as usize] })
+    }
+
+    /// Adds the area inside the rings, using the even-odd rule.
+    fn add_polygon(&mut self, rings: &[Vec<Vec2>]) {
+        let (top, bottom) = rings
+            .iter()
+            .flatten()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(top, bottom), point| (top.min(point.y), bottom.max(point.y)));
+
+        if top >= bottom {
+            return;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let rows = (top.floor().max(self.y as f32) as u32)..(bottom.ceil().min((self.y + self.height) as f32) as u32);
+        let mut row_coverage = vec![0.0; self.width as usize];
+        let mut crossings = Vec::new();
+
+        for y in rows {
+            row_coverage.fill(0.0);
+
+            for sample in 0..SUBSAMPLES {
+                let scanline = (sample as f32 + 0.5).mul_add(1.0 / SUBSAMPLES as f32, y as f32);
+                crossings.clear();
+
+                for ring in rings {
+                    for (index, &start) in ring.iter().enumerate() {
+                        let end = ring[(index + 1) % ring.len()];
+
+                        if (start.y <= scanline) != (end.y <= scanline) {
+                            let along = (scanline - start.y) / (end.y - start.y);
+                            crossings.push(along.mul_add(end.x - start.x, start.x));
+                        }
+                    }
+                }
+
+                crossings.sort_by(f32::total_cmp);
+
+                for span in crossings.chunks_exact(2) {
+                    self.cover_span(&mut row_coverage, span[0], span[1], 1.0 / SUBSAMPLES as f32);
+                }
+            }
+
+            let offset = ((y - self.y) * self.width) as usize;
+
+            for (coverage, &row) in self.coverage[offset..offset + self.width as usize].iter_mut().zip(&row_coverage) {
+                *coverage = coverage.max(row.min(1.0));
+            }
+        }
+    }
+
+    /// Adds `weight` times the part of each pixel that the span from `left` to `right` covers.
+    fn cover_span(&self, row: &mut [f32], left: f32, right: f32, weight: f32) {
+        let width = self.width as f32;
+        let (left, right) = ((left - self.x as f32).clamp(0.0, width), (right - self.x as f32).clamp(0.0, width));
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (first, last) = (left.floor() as usize, right.ceil() as usize);
+
+        for (column, coverage) in row.iter_mut().enumerate().take(last).skip(first) {
+            let overlap = right.min(column as f32 + 1.0) - left.max(column as f32);
+            *coverage += overlap.max(0.0) * weight;
+        }
+    }
+}
+
+fn circle(center: Vec2, radius: f32) -> Vec<Vec2> {
+    (0..JOIN_SEGMENTS)
+        .map(|step| center + Vec2::from_angle(std::f32::consts::TAU * step as f32 / JOIN_SEGMENTS as f32) * radius)
+        .collect()
+}
+
+/// Draws `source` over `destination`.
+fn blend(destination: Paint, source: Paint) -> Rgba<u8> {
+    let alpha = destination[3].mul_add(1.0 - source[3], source[3]);
+
+    if alpha <= 0.0 {
+        return Rgba([0; 4]);
+    }
+
+    let channel = |index: usize| {
+        source[index].mul_add(source[3], destination[index] * destination[3] * (1.0 - source[3])) / alpha
+    };
+
+    to_pixel([channel(0), channel(1), channel(2), alpha])
+}
+
+fn from_pixel(pixel: Rgba<u8>) -> Paint {
+    pixel.0.map(|channel| f32::from(channel) / 255.0)
+}
+
+fn to_pixel(paint: Paint) -> Rgba<u8> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Rgba(paint.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8))
+}
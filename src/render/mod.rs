@@ -0,0 +1,373 @@
+//! Drawing the objects of a level, for previews and exports without the game.
+//!
+//! Terrain-like objects are filled from their `TerrainCorners` with their color or pattern and get their border,
+//! sprites are drawn from the outlines in [`crate::shapes`], and gameplay objects and editor helpers are drawn as
+//! icons. Layers are drawn in order, so later layers end up in front.
+//!
+//! Every object is first turned into primitives in world space, which the backends then draw:
+//! `LevelData::render` rasterizes them into an image (with the `image` feature) and [`LevelData::to_svg`] writes
+//! them as SVG elements.
+
+#[cfg(feature = "image")]
+mod canvas;
+#[cfg(feature = "image")]
+mod raster;
+mod svg;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::geometry::round_corners;
+use crate::shapes::ShapeKind;
+use crate::types::{
+    color::Color, layer::Layer, level_data::LevelData, object::Object, object_id::ObjectId,
+    object_property::ObjectProperty, theme::Theme, vec2::Vec2,
+};
+
+#[cfg(feature = "image")]
+pub use raster::RenderOptions;
+pub use svg::SvgOptions;
+
+/// A color with straight alpha, every channel in `0..=1`.
+pub type Paint = [f32; 4];
+
+/// The part of the world that ends up in the image.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Camera {
+    /// The bottom left corner.
+    pub min: Vec2,
+    /// The top right corner.
+    pub max: Vec2,
+}
+
+impl Camera {
+    #[must_use]
+    pub const fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    /// A camera showing `size` world units around `center`.
+    #[must_use]
+    pub fn around(center: Vec2, size: Vec2) -> Self {
+        Self { min: center - size / 2.0, max: center + size / 2.0 }
+    }
+
+    /// Grows the camera around its center until its width divided by its height is `aspect_ratio`.
+    #[must_use]
+    pub fn with_aspect_ratio(self, aspect_ratio: f32) -> Self {
+        let size = self.max - self.min;
+        let center = (self.min + self.max) / 2.0;
+        let (width, height) = (size.x.0, size.y.0);
+
+        if width < height * aspect_ratio {
+            Self::around(center, Vec2::new(height * aspect_ratio, height))
+        } else {
+            Self::around(center, Vec2::new(width, width / aspect_ratio))
+        }
+    }
+
+    /// The camera that shows `bounds` with a margin of one unit, or a 16 unit square around the origin if there's
+    /// nothing to show.
+    fn fit(bounds: Option<Self>) -> Self {
+        bounds.map_or_else(
+            || Self::around(Vec2::ZERO, Vec2::splat(16.0)),
+            |bounds| Self::new(bounds.min - 1.0, bounds.max + 1.0),
+        )
+    }
+}
+
+impl LevelData {
+    /// Returns the smallest camera that shows every object in a visible layer, or `None` if there are none.
+    #[must_use]
+    pub fn visible_bounds(&self) -> Option<Camera> {
+        let hidden: BTreeSet<i32> =
+            self.layers.iter().filter(|layer| layer.invisible).map(|layer| layer.layer_id).collect();
+
+        bounds(self.objects.iter().filter(|object| !hidden.contains(&object.in_layer)))
+    }
+}
+
+/// Returns the smallest camera that shows all of the objects, or `None` if there are none.
+fn bounds<'a>(objects: impl IntoIterator<Item = &'a Object>) -> Option<Camera> {
+    objects.into_iter().flat_map(Object::world_rings).flatten().fold(None, |bounds: Option<Camera>, point| {
+        Some(bounds.map_or_else(
+            || Camera::new(point, point),
+            |bounds| Camera::new(bounds.min.min(point), bounds.max.max(point)),
+        ))
+    })
+}
+
+/// The colors a theme draws a level with where the objects don't set their own.
+#[derive(Clone, Copy, Debug)]
+struct Palette {
+    background: Paint,
+    terrain: Paint,
+    terrain_border: Paint,
+    border_thickness: f32,
+    corner_radius: f32,
+}
+
+impl Palette {
+    /// The background is `custom_background_color` for levels with the custom theme, and a color close to the
+    /// theme's sky otherwise. Terrain without its own color falls back to the theme as well.
+    const fn of(level: &LevelData) -> Self {
+        let (background, terrain, terrain_border) = match level.theme {
+            Theme::Mountains => ([0.55, 0.76, 0.92, 1.0], [0.25, 0.27, 0.33, 1.0], [0.15, 0.16, 0.2, 1.0]),
+            Theme::Halloween => ([0.2, 0.12, 0.27, 1.0], [0.16, 0.12, 0.14, 1.0], [0.55, 0.27, 0.1, 1.0]),
+            Theme::Christmas => ([0.12, 0.2, 0.38, 1.0], [0.9, 0.93, 0.97, 1.0], [0.6, 0.7, 0.8, 1.0]),
+            Theme::Custom => {
+                return Self {
+                    background: paint(level.custom_background_color),
+                    terrain: paint(level.custom_terrain_color),
+                    terrain_border: paint(level.custom_terrain_border_color),
+                    border_thickness: level.custom_terrain_border_thickness.0,
+                    corner_radius: level.custom_terrain_border_corner_radius.0,
+                };
+            }
+        };
+
+        Self { background, terrain, terrain_border, border_thickness: 0.1, corner_radius: 0.0 }
+    }
+}
+
+/// The look of a terrain-like object.
+#[derive(Clone, Copy, Debug)]
+struct TerrainStyle {
+    fill: Paint,
+    border: Paint,
+    border_thickness: f32,
+    corner_radius: f32,
+    round_reflex_angles: bool,
+}
+
+impl TerrainStyle {
+    fn of(object: &Object, kind: ShapeKind, palette: &Palette) -> Self {
+        let mut style = match kind {
+            ShapeKind::Ice => Self::plain([0.72, 0.9, 1.0, 1.0], [0.45, 0.7, 0.9, 1.0], 0.1),
+            ShapeKind::Area => Self::plain([1.0, 0.85, 0.3, 0.2], [1.0, 0.85, 0.3, 0.8], 0.05),
+            ShapeKind::FloatingZone => Self::plain([0.45, 0.75, 1.0, 0.3], [0.45, 0.75, 1.0, 0.6], 0.05),
+            _ => Self {
+                corner_radius: palette.corner_radius,
+                ..Self::plain(palette.terrain, palette.terrain_border, palette.border_thickness)
+            },
+        };
+
+        for property in &object.properties {
+            match property {
+                ObjectProperty::Color(color) => style.fill = paint(*color),
+                ObjectProperty::BorderColor(color) => style.border = paint(*color),
+                ObjectProperty::BorderThickness(thickness) => style.border_thickness = thickness.0,
+                ObjectProperty::CornerRadius(radius) => style.corner_radius = radius.0,
+                ObjectProperty::RoundReflexAngles(round) => style.round_reflex_angles = *round,
+                _ => {}
+            }
+        }
+
+        style
+    }
+
+    const fn plain(fill: Paint, border: Paint, border_thickness: f32) -> Self {
+        Self { fill, border, border_thickness, corner_radius: 0.0, round_reflex_angles: false }
+    }
+}
+
+/// A pattern of the level laid over the world, starting at `offset` and repeating every `1 / tiling` units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PatternRef {
+    pattern_id: i32,
+    tiling: Vec2,
+    offset: Vec2,
+}
+
+impl PatternRef {
+    /// Returns the pattern the object is filled with, if it has a `Pattern` property.
+    fn of(object: &Object) -> Option<Self> {
+        let mut pattern_id = None;
+        let (mut tiling, mut offset) = (Vec2::ONE, Vec2::ZERO);
+
+        for property in &object.properties {
+            match property {
+                ObjectProperty::Pattern(id) => pattern_id = Some(*id),
+                ObjectProperty::PatternTiling(value) if *value != Vec2::ZERO => tiling = *value,
+                ObjectProperty::PatternOffset(value) => offset = *value,
+                _ => {}
+            }
+        }
+
+        Some(Self { pattern_id: pattern_id?, tiling, offset })
+    }
+}
+
+/// Something an object is drawn as, in world space.
+#[derive(Clone, Debug, PartialEq)]
+enum Primitive {
+    /// Rings filled with the even-odd rule, with the pattern tinted by `paint` if there is one.
+    Fill { rings: Vec<Vec<Vec2>>, paint: Paint, pattern: Option<PatternRef> },
+    /// A line of the given width along every ring, with round joins.
+    Stroke { rings: Vec<Vec<Vec2>>, paint: Paint, width: f32 },
+    /// A line of text centered on `position` and rotated counter-clockwise by `rotation` degrees.
+    Text { text: String, position: Vec2, rotation: f32, size: f32, paint: Paint },
+}
+
+/// Returns what the object is drawn as, not counting the members of groups.
+///
+/// Areas, gameplay objects and editor helpers other than texts are left out unless `gameplay_objects` is set.
+fn primitives(object: &Object, palette: &Palette, gameplay_objects: bool) -> Vec<Primitive> {
+    let shape = object.shape();
+    let rings = shape.to_world(object);
+    let scale = mean_scale(object);
+
+    match shape.kind {
+        kind if kind.is_terrain_like() && (gameplay_objects || kind != ShapeKind::Area) => {
+            let style = TerrainStyle::of(object, shape.kind, palette);
+            let rings: Vec<Vec<Vec2>> = rings
+                .iter()
+                .map(|ring| round_corners(ring, style.corner_radius * scale, style.round_reflex_angles, 8))
+                .collect();
+            let fill = Primitive::Fill { rings: rings.clone(), paint: style.fill, pattern: PatternRef::of(object) };
+
+            if style.border_thickness > 0.0 {
+                vec![fill, Primitive::Stroke { rings, paint: style.border, width: style.border_thickness * scale }]
+            } else {
+                vec![fill]
+            }
+        }
+        ShapeKind::Sprite => {
+            let paint = color(object).unwrap_or([1.0; 4]);
+
+            vec![match shape.outline {
+                Some(thickness) => Primitive::Stroke { rings, paint, width: thickness * scale },
+                None => Primitive::Fill { rings, paint, pattern: None },
+            }]
+        }
+        ShapeKind::Marker if object.object_id == ObjectId::Text => {
+            let mut text = String::new();
+            let mut size = 1.0;
+
+            for property in &object.properties {
+                match property {
+                    ObjectProperty::Text(value) => text.clone_from(value),
+                    ObjectProperty::FontSize(value) => size = value.0,
+                    _ => {}
+                }
+            }
+
+            vec![Primitive::Text {
+                text,
+                position: object.position,
+                rotation: object.rotation.0,
+                size: size * scale,
+                paint: color(object).unwrap_or([1.0; 4]),
+            }]
+        }
+        ShapeKind::Gameplay | ShapeKind::Marker if gameplay_objects => {
+            let [red, green, blue, _] = icon_color(object, shape.kind);
+            let stroke =
+                Primitive::Stroke { rings: rings.clone(), paint: [red, green, blue, 1.0], width: 0.06 * scale };
+
+            if shape.kind == ShapeKind::Gameplay {
+                vec![Primitive::Fill { rings, paint: [red, green, blue, 0.35], pattern: None }, stroke]
+            } else {
+                vec![stroke]
+            }
+        }
+        // Groups are drawn through their members.
+        _ => vec![],
+    }
+}
+
+/// The objects of a layer in drawing order. `layer` is `None` for the objects outside of every layer.
+#[derive(Clone, Debug)]
+struct SceneLayer<'a> {
+    layer: Option<&'a Layer>,
+    items: Vec<SceneItem<'a>>,
+}
+
+/// An object together with the members it draws if it's a group.
+#[derive(Clone, Debug)]
+struct SceneItem<'a> {
+    object: &'a Object,
+    members: Vec<Self>,
+}
+
+/// Orders the objects of a level, or the items of a prefab without any layers, for drawing.
+///
+/// The objects outside of every layer come first, followed by every layer with the objects in its `children` and
+/// then the ones that only name it as their `in_layer`. Every object appears once, members of groups inside their
+/// group.
+fn scene<'a>(objects: &'a [Object], layers: &'a [Layer]) -> Vec<SceneLayer<'a>> {
+    fn item<'a>(
+        entity_id: i32,
+        objects: &BTreeMap<i32, &'a Object>,
+        placed: &mut BTreeSet<i32>,
+    ) -> Option<SceneItem<'a>> {
+        let object = *objects.get(&entity_id)?;
+
+        if !placed.insert(entity_id) {
+            return None;
+        }
+
+        let members = object.group_members.iter().filter_map(|&member| item(member, objects, placed)).collect();
+
+        Some(SceneItem { object, members })
+    }
+
+    let by_id: BTreeMap<i32, &Object> = objects.iter().map(|object| (object.entity_id, object)).collect();
+    let layer_ids: BTreeSet<i32> = layers.iter().map(|layer| layer.layer_id).collect();
+    let mut placed = BTreeSet::new();
+
+    let unlayered = objects
+        .iter()
+        .filter(|object| !layer_ids.contains(&object.in_layer))
+        .filter_map(|object| item(object.entity_id, &by_id, &mut placed))
+        .collect();
+    let mut scene = vec![SceneLayer { layer: None, items: unlayered }];
+
+    for layer in layers {
+        let in_layer = objects.iter().filter(|object| object.in_layer == layer.layer_id);
+        let items = layer
+            .children
+            .iter()
+            .copied()
+            .chain(in_layer.map(|object| object.entity_id))
+            .filter_map(|entity_id| item(entity_id, &by_id, &mut placed))
+            .collect();
+
+        scene.push(SceneLayer { layer: Some(layer), items });
+    }
+
+    scene
+}
+
+fn color(object: &Object) -> Option<Paint> {
+    object.properties.iter().find_map(|property| match property {
+        ObjectProperty::Color(color) => Some(paint(*color)),
+        _ => None,
+    })
+}
+
+/// The average size of the object's scale, for line widths.
+fn mean_scale(object: &Object) -> f32 {
+    f32::midpoint(object.scale.x.abs(), object.scale.y.abs())
+}
+
+/// Killers are red, spawns and checkpoints green, the end flag gold, every other gameplay object orange and editor
+/// helpers grey.
+fn icon_color(object: &Object, kind: ShapeKind) -> Paint {
+    let name = object.object_id.to_string();
+
+    if kind == ShapeKind::Marker {
+        [0.6, 0.6, 0.6, 1.0]
+    } else if name.starts_with("Killer") {
+        [0.9, 0.2, 0.2, 1.0]
+    } else if name.starts_with("Spawn") || name.starts_with("Checkpoint") || name.starts_with("RevivePad") {
+        [0.2, 0.8, 0.3, 1.0]
+    } else if name.starts_with("EndFlag") {
+        [1.0, 0.8, 0.1, 1.0]
+    } else {
+        [1.0, 0.55, 0.1, 1.0]
+    }
+}
+
+const fn paint(color: Color) -> Paint {
+    [color.r.0, color.g.0, color.b.0, color.a.0]
+}
@@ -0,0 +1,284 @@
+//! The image backend, which rasterizes the primitives with [`Canvas`].
+
+use image::RgbaImage;
+
+use super::canvas::Canvas;
+use super::{bounds, primitives, scene, Camera, Paint, Palette, PatternRef, Primitive, SceneItem, SceneLayer};
+use crate::types::{level_data::LevelData, prefab::Prefab, vec2::Vec2};
+
+/// How [`LevelData::render`] draws a level.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub width: u32,
+    pub height: u32,
+    /// The part of the world to draw, stretched to the image. `None` shows every visible object with a margin of
+    /// one unit, keeping the aspect ratio of the image.
+    pub camera: Option<Camera>,
+    /// Whether gameplay objects, areas and editor helpers are drawn. Without them the image shows what the level
+    /// looks like in the game before it starts, minus the player.
+    pub gameplay_objects: bool,
+    /// The frame of patterns to draw, wrapping around for patterns with fewer frames.
+    pub pattern_frame: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self { width: 1280, height: 720, camera: None, gameplay_objects: true, pattern_frame: 0 }
+    }
+}
+
+impl LevelData {
+    /// Draws the level into an image, leaving out invisible layers.
+    ///
+    /// There are no fonts, so texts are drawn as boxes of about their size when `gameplay_objects` is set.
+    #[must_use]
+    pub fn render(&self, options: &RenderOptions) -> RgbaImage {
+        self.render_scene(scene(&self.objects, &self.layers), self.visible_bounds(), options)
+    }
+
+    /// Draws the items of a prefab into an image, with the theme and patterns of this level.
+    ///
+    /// Without a camera in the options, the image shows every item with a margin of one unit.
+    #[must_use]
+    pub fn render_prefab(&self, prefab: &Prefab, options: &RenderOptions) -> RgbaImage {
+        self.render_scene(scene(&prefab.items, &[]), bounds(&prefab.items), options)
+    }
+
+    fn render_scene(&self, scene: Vec<SceneLayer>, bounds: Option<Camera>, options: &RenderOptions) -> RgbaImage {
+        let (width, height) = (options.width.max(1), options.height.max(1));
+        #[allow(clippy::cast_precision_loss)]
+        let size = Vec2::new(width as f32, height as f32);
+        let camera = options.camera.unwrap_or_else(|| Camera::fit(bounds).with_aspect_ratio(size.x.0 / size.y.0));
+        let palette = Palette::of(self);
+
+        let mut renderer = Renderer {
+            level: self,
+            options,
+            palette,
+            camera,
+            zoom: size / (camera.max - camera.min),
+            canvas: Canvas::new(width, height, palette.background),
+        };
+
+        for layer in scene {
+            if layer.layer.is_some_and(|layer| layer.invisible) {
+                continue;
+            }
+
+            for item in &layer.items {
+                renderer.draw_item(item);
+            }
+        }
+
+        renderer.canvas.image
+    }
+}
+
+/// A pattern frame laid over the world, as described by a [`PatternRef`].
+#[derive(Clone, Copy, Debug)]
+struct PatternFill<'a> {
+    frame: &'a RgbaImage,
+    tiling: Vec2,
+    offset: Vec2,
+}
+
+impl<'a> PatternFill<'a> {
+    /// Returns the given frame of the pattern, if it's one of the level's patterns and the frame isn't empty.
+    fn find(level: &'a LevelData, pattern: PatternRef, frame: usize) -> Option<Self> {
+        let pattern_id = pattern.pattern_id;
+        let frames = &level.patterns.iter().find(|candidate| candidate.pattern_id == pattern_id)?.pattern_frames;
+        let frame = &frames.get(frame % frames.len().max(1))?.0;
+
+        let (tiling, offset) = (pattern.tiling, pattern.offset);
+
+        (frame.width() > 0 && frame.height() > 0).then_some(Self { frame, tiling, offset })
+    }
+
+    /// Returns the pattern's color at a point of the world, tinted by `tint`.
+    fn sample(&self, point: Vec2, tint: Paint) -> Paint {
+        let uv = ((point - self.offset) * self.tiling).fract_gl();
+        let (width, height) = self.frame.dimensions();
+
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y) = (
+            ((uv.x.0 * width as f32) as u32).min(width - 1),
+            (((1.0 - uv.y.0) * height as f32) as u32).min(height - 1),
+        );
+        let texel = self.frame.get_pixel(x, y).0.map(|channel| f32::from(channel) / 255.0);
+
+        [texel[0] * tint[0], texel[1] * tint[1], texel[2] * tint[2], texel[3] * tint[3]]
+    }
+}
+
+struct Renderer<'a> {
+    level: &'a LevelData,
+    options: &'a RenderOptions,
+    palette: Palette,
+    camera: Camera,
+    /// Pixels per world unit, along each axis.
+    zoom: Vec2,
+    canvas: Canvas,
+}
+
+impl Renderer<'_> {
+    fn draw_item(&mut self, item: &SceneItem) {
+        for primitive in primitives(item.object, &self.palette, self.options.gameplay_objects) {
+            self.draw(primitive);
+        }
+
+        for member in &item.members {
+            self.draw_item(member);
+        }
+    }
+
+    fn draw(&mut self, primitive: Primitive) {
+        match primitive {
+            Primitive::Fill { rings, paint, pattern } => {
+                let pixels = self.to_pixels(&rings);
+                let frame = self.options.pattern_frame;
+
+                if let Some(pattern) = pattern.and_then(|pattern| PatternFill::find(self.level, pattern, frame)) {
+                    let (camera, zoom) = (self.camera, self.zoom);
+                    #[allow(clippy::cast_precision_loss)]
+                    let to_world = move |x: u32, y: u32| {
+                        Vec2::new(
+                            camera.min.x.0 + (x as f32 + 0.5) / zoom.x.0,
+                            camera.max.y.0 - (y as f32 + 0.5) / zoom.y.0,
+                        )
+                    };
+
+                    self.canvas.fill(&pixels, |x, y| pattern.sample(to_world(x, y), paint));
+                } else {
+                    self.canvas.fill(&pixels, |_, _| paint);
+                }
+            }
+            Primitive::Stroke { rings, paint, width } => {
+                let pixels = self.to_pixels(&rings);
+                self.canvas.stroke(&pixels, self.to_pixel_width(width).max(1.0), |_, _| paint);
+            }
+            Primitive::Text { text, position, rotation, size, paint: [red, green, blue, _] } => {
+                if !self.options.gameplay_objects {
+                    return;
+                }
+
+                #[allow(clippy::cast_precision_loss)]
+                let half_size = Vec2::new(text.chars().count().max(1) as f32 * 0.3, 0.5) * size;
+                let turn = Vec2::from_angle(rotation.to_radians());
+                let corners = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::ONE, Vec2::new(-1.0, 1.0)];
+                let ring = corners.map(|corner| position + (corner * half_size).rotate(turn)).to_vec();
+                let pixels = self.to_pixels(&[ring]);
+
+                self.canvas.fill(&pixels, |_, _| [red, green, blue, 0.35]);
+                self.canvas.stroke(&pixels, 1.0, |_, _| [red, green, blue, 1.0]);
+            }
+        }
+    }
+
+    fn to_pixels(&self, rings: &[Vec<Vec2>]) -> Vec<Vec<glam::Vec2>> {
+        rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .map(|point| {
+                        glam::Vec2::new(
+                            (point.x.0 - self.camera.min.x.0) * self.zoom.x.0,
+                            (self.camera.max.y.0 - point.y.0) * self.zoom.y.0,
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Converts a line width in world units to pixels.
+    fn to_pixel_width(&self, width: f32) -> f32 {
+        width * f32::midpoint(self.zoom.x.0, self.zoom.y.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        color::Color, object::Object, object_id::ObjectId, object_property::ObjectProperty, theme::Theme,
+    };
+    use ordered_float::OrderedFloat;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const WHITE: [u8; 4] = [255; 4];
+
+    /// Draws the objects on black, 10 pixels per unit with the origin in the middle of the image.
+    fn render(objects: Vec<Object>) -> RgbaImage {
+        let level = LevelData {
+            theme: Theme::Custom,
+            custom_background_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            objects,
+            ..Default::default()
+        };
+        let camera = Camera::around(Vec2::ZERO, Vec2::splat(8.0));
+
+        level.render(&RenderOptions { width: 80, height: 80, camera: Some(camera), ..Default::default() })
+    }
+
+    fn terrain(properties: Vec<ObjectProperty>) -> Object {
+        let mut object = Object::get_default_with_id(ObjectId::Terrain, 0);
+        object.scale = Vec2::splat(4.0);
+        object.properties.extend(properties);
+        object
+    }
+
+    #[test]
+    fn terrain_fill() {
+        let image = render(vec![terrain(vec![
+            ObjectProperty::Color(Color::new(1.0, 0.0, 0.0, 1.0)),
+            ObjectProperty::BorderThickness(OrderedFloat(0.0)),
+        ])]);
+
+        for (x, y) in [(40, 40), (21, 21), (58, 58), (21, 58)] {
+            assert_eq!(image.get_pixel(x, y).0, RED, "({x}, {y})");
+        }
+        for (x, y) in [(10, 40), (40, 10), (70, 70), (18, 40)] {
+            assert_eq!(image.get_pixel(x, y).0, BLACK, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn terrain_border() {
+        let image = render(vec![terrain(vec![
+            ObjectProperty::Color(Color::new(1.0, 0.0, 0.0, 1.0)),
+            ObjectProperty::BorderColor(Color::new(0.0, 0.0, 1.0, 1.0)),
+            ObjectProperty::BorderThickness(OrderedFloat(0.1)),
+        ])]);
+
+        // The border is 0.4 units wide around the edges at pixels 20 and 60.
+        for (x, y) in [(20, 40), (59, 40), (40, 20), (40, 59)] {
+            assert_eq!(image.get_pixel(x, y).0, BLUE, "({x}, {y})");
+        }
+        assert_eq!(image.get_pixel(40, 40).0, RED);
+        assert_eq!(image.get_pixel(10, 40).0, BLACK);
+    }
+
+    #[test]
+    fn rotated_sprite() {
+        let mut sprite =
+            Object { object_id: ObjectId::SpriteRightTriangle, scale: Vec2::splat(4.0), ..Default::default() };
+
+        // The triangle covers the bottom left half of its square, so (-1, -1) is inside and (1, 1) isn't.
+        let image = render(vec![sprite.clone()]);
+        assert_eq!(image.get_pixel(30, 50).0, WHITE);
+        assert_eq!(image.get_pixel(50, 30).0, BLACK);
+
+        sprite.rotation = OrderedFloat(180.0);
+        let image = render(vec![sprite.clone()]);
+        assert_eq!(image.get_pixel(30, 50).0, BLACK);
+        assert_eq!(image.get_pixel(50, 30).0, WHITE);
+
+        // A quarter turn counter-clockwise moves it to the bottom right.
+        sprite.rotation = OrderedFloat(90.0);
+        let image = render(vec![sprite]);
+        assert_eq!(image.get_pixel(50, 50).0, WHITE);
+        assert_eq!(image.get_pixel(30, 30).0, BLACK);
+    }
+}
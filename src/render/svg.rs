@@ -0,0 +1,280 @@
+//! The SVG backend, which writes the primitives as SVG elements.
+
+use std::fmt::Write as _;
+
+use super::{primitives, scene, Camera, Paint, Palette, Primitive, SceneItem};
+use crate::types::{level_data::LevelData, vec2::Vec2};
+
+/// How [`LevelData::to_svg`] draws a level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// The part of the world to draw. `None` shows every visible object with a margin of one unit.
+    pub camera: Option<Camera>,
+    /// The size of a world unit in the `width` and `height` of the document.
+    pub pixels_per_unit: f32,
+    /// Whether gameplay objects, areas and editor helpers are drawn.
+    pub gameplay_objects: bool,
+    /// Whether invisible layers are written as well, hidden with `display="none"`.
+    pub invisible_layers: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self { camera: None, pixels_per_unit: 32.0, gameplay_objects: true, invisible_layers: false }
+    }
+}
+
+impl LevelData {
+    /// Writes the level as an SVG document.
+    ///
+    /// Every layer becomes a `<g>` element with `data-layer-id` and `data-layer-name`, and every group object a
+    /// nested `<g>`. The elements drawn for an object carry its `data-entity-id` and the name of its `ObjectId` in
+    /// `data-object-id`. The document uses world units with the y axis flipped, and patterns are drawn with just
+    /// their tint color.
+    #[must_use]
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let camera = options.camera.unwrap_or_else(|| Camera::fit(self.visible_bounds()));
+        let size = camera.max - camera.min;
+        let palette = Palette::of(self);
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+            number(camera.min.x.0),
+            number(-camera.max.y.0),
+            number(size.x.0),
+            number(size.y.0),
+            number(size.x.0 * options.pixels_per_unit),
+            number(size.y.0 * options.pixels_per_unit),
+        );
+        let _ = writeln!(
+            out,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            number(camera.min.x.0),
+            number(-camera.max.y.0),
+            number(size.x.0),
+            number(size.y.0),
+            paint_attributes("fill", palette.background),
+        );
+
+        let mut writer = Writer { out, palette, options };
+
+        for layer in scene(&self.objects, &self.layers) {
+            let hidden = layer.layer.is_some_and(|layer| layer.invisible);
+
+            if layer.items.is_empty() || (hidden && !options.invisible_layers) {
+                continue;
+            }
+
+            match layer.layer {
+                Some(layer) => {
+                    let _ = writeln!(
+                        writer.out,
+                        r#"  <g data-layer-id="{}" data-layer-name="{}"{}>"#,
+                        layer.layer_id,
+                        escape(&layer.layer_name),
+                        if hidden { r#" display="none""# } else { "" },
+                    );
+                }
+                None => writer.out.push_str("  <g>\n"),
+            }
+
+            for item in &layer.items {
+                writer.write_item(item, 2);
+            }
+
+            writer.out.push_str("  </g>\n");
+        }
+
+        writer.out.push_str("</svg>\n");
+        writer.out
+    }
+}
+
+struct Writer<'a> {
+    out: String,
+    palette: Palette,
+    options: &'a SvgOptions,
+}
+
+impl Writer<'_> {
+    fn write_item(&mut self, item: &SceneItem, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let object = item.object;
+        let annotations = format!(r#"data-entity-id="{}" data-object-id="{}""#, object.entity_id, object.object_id);
+
+        for primitive in primitives(object, &self.palette, self.options.gameplay_objects) {
+            let _ = match primitive {
+                Primitive::Fill { rings, paint, .. } => writeln!(
+                    self.out,
+                    r#"{indent}<path {annotations} d="{}" fill-rule="evenodd"{}/>"#,
+                    path_data(&rings),
+                    paint_attributes("fill", paint),
+                ),
+                Primitive::Stroke { rings, paint, width } => writeln!(
+                    self.out,
+                    r#"{indent}<path {annotations} d="{}" fill="none"{} stroke-width="{}" stroke-linejoin="round"/>"#,
+                    path_data(&rings),
+                    paint_attributes("stroke", paint),
+                    number(width),
+                ),
+                Primitive::Text { text, position, rotation, size, paint } => {
+                    let (x, y) = (number(position.x.0), number(-position.y.0));
+                    let transform = format!("translate({x} {y}) rotate({})", number(-rotation));
+                    let font = format!(
+                        r#"font-size="{}" text-anchor="middle" dominant-baseline="central""#,
+                        number(size)
+                    );
+
+                    writeln!(
+                        self.out,
+                        r#"{indent}<text {annotations} transform="{transform}" {font}{}>{}</text>"#,
+                        paint_attributes("fill", paint),
+                        escape(&text),
+                    )
+                }
+            };
+        }
+
+        if !item.members.is_empty() {
+            let _ = writeln!(self.out, "{indent}<g {annotations}>");
+
+            for member in &item.members {
+                self.write_item(member, depth + 1);
+            }
+
+            let _ = writeln!(self.out, "{indent}</g>");
+        }
+    }
+}
+
+/// Returns the path data for the rings, with the y axis flipped.
+fn path_data(rings: &[Vec<Vec2>]) -> String {
+    let mut data = String::new();
+
+    for ring in rings.iter().filter(|ring| !ring.is_empty()) {
+        for (index, point) in ring.iter().enumerate() {
+            let command = if index == 0 { "M" } else { "L" };
+            let separator = if data.is_empty() { "" } else { " " };
+            let _ = write!(data, "{separator}{command}{} {}", number(point.x.0), number(-point.y.0));
+        }
+
+        data.push_str(" Z");
+    }
+
+    data
+}
+
+/// Returns the color attribute, and the opacity attribute if the paint isn't opaque.
+fn paint_attributes(name: &str, [red, green, blue, alpha]: Paint) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut attributes = format!(r##" {name}="#{:02x}{:02x}{:02x}""##, channel(red), channel(green), channel(blue));
+
+    if alpha < 1.0 {
+        let _ = write!(attributes, r#" {name}-opacity="{}""#, number(alpha.max(0.0)));
+    }
+
+    attributes
+}
+
+/// Formats a number with at most three decimals and without trailing zeros.
+fn number(value: f32) -> String {
+    let formatted = format!("{value:.3}");
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        color::Color, layer::Layer, object::Object, object_id::ObjectId, object_property::ObjectProperty,
+        theme::Theme,
+    };
+
+    fn level() -> LevelData {
+        let mut terrain = Object::get_default_with_id(ObjectId::Terrain, 1);
+        terrain.entity_id = 7;
+        terrain.position = Vec2::new(1.0, 2.0);
+        terrain.scale = Vec2::splat(2.0);
+        terrain.properties.push(ObjectProperty::Color(Color::new(1.0, 0.0, 0.0, 0.5)));
+
+        let mut hidden = Object::get_default_with_id(ObjectId::Terrain, 2);
+        hidden.entity_id = 8;
+
+        LevelData {
+            theme: Theme::Custom,
+            custom_background_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            custom_terrain_border_color: Color::new(0.0, 0.0, 1.0, 1.0),
+            custom_terrain_border_thickness: 0.25.into(),
+            layers: vec![
+                Layer { layer_id: 1, layer_name: "<Front>".to_string(), ..Default::default() },
+                Layer { layer_id: 2, layer_name: "Hidden".to_string(), invisible: true, ..Default::default() },
+            ],
+            objects: vec![terrain, hidden],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn document() {
+        let svg = level().to_svg(&SvgOptions::default());
+
+        // The camera fits the square from (0, 1) to (2, 3) with a margin of one unit, and y points down.
+        assert_eq!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-1 -4 4 4" width="128" height="128">
+  <rect x="-1" y="-4" width="4" height="4" fill="#000000"/>
+  <g data-layer-id="1" data-layer-name="&lt;Front&gt;">
+    <path data-entity-id="7" data-object-id="Terrain" d="M0 -1 L2 -1 L2 -3 L0 -3 Z" fill-rule="evenodd" fill="#ff0000" fill-opacity="0.5"/>
+    <path data-entity-id="7" data-object-id="Terrain" d="M0 -1 L2 -1 L2 -3 L0 -3 Z" fill="none" stroke="#0000ff" stroke-width="0.5" stroke-linejoin="round"/>
+  </g>
+</svg>
+"##
+        );
+    }
+
+    #[test]
+    fn invisible_layers_and_camera() {
+        let camera = Camera::new(Vec2::new(-0.5, -0.25), Vec2::new(0.5, 0.25));
+        let options =
+            SvgOptions { camera: Some(camera), pixels_per_unit: 10.0, invisible_layers: true, ..Default::default() };
+        let svg = level().to_svg(&options);
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-0.5 -0.25 1 0.5" width="10" height="5">"#
+        ));
+        assert!(svg.contains(
+            r#"  <g data-layer-id="2" data-layer-name="Hidden" display="none">
+    <path data-entity-id="8" data-object-id="Terrain" d="M-0.5 0.5 L0.5 0.5 L0.5 -0.5 L-0.5 -0.5 Z" fill-rule="evenodd""#
+        ));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(-0.0001), "0");
+        assert_eq!(number(0.12345), "0.123");
+        assert_eq!(number(-2.5), "-2.5");
+    }
+}
@@ -0,0 +1,403 @@
+//! The outlines that objects are drawn with, shared by the renderers and exporters.
+//!
+//! Outlines are given in the object's local space, where an object with scale one covers the unit square centered
+//! on the origin. [`Object::local_to_world`] applies the object's flip, scale, rotation and position.
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::geometry::round_corners;
+use crate::types::{object::Object, object_id::ObjectId, object_property::ObjectProperty, vec2::Vec2};
+
+/// The number of edges a full circle is approximated with.
+const CIRCLE_SEGMENTS: usize = 64;
+
+/// The line thickness of outline sprites without a `Thickness` property.
+const DEFAULT_OUTLINE_THICKNESS: f32 = 0.1;
+
+/// How an object is drawn.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ShapeKind {
+    /// Solid ground, drawn from its `TerrainCorners`.
+    Terrain,
+    /// Slippery ground, drawn from its `TerrainCorners`.
+    Ice,
+    /// A trigger area, drawn from its `TerrainCorners`.
+    Area,
+    /// A zone the player floats in, drawn from its `TerrainCorners`.
+    FloatingZone,
+    /// A decoration sprite, filled with its `Color`, or outlined for the `*Outline` sprites.
+    Sprite,
+    /// A gameplay object like a booster or a spike, drawn as an icon.
+    Gameplay,
+    /// An editor helper like a point, a text or a particle system, drawn as a small outline.
+    Marker,
+    /// A group, which is drawn through its members.
+    Group,
+}
+
+impl ShapeKind {
+    #[must_use]
+    pub fn of(object_id: ObjectId) -> Self {
+        let name = object_id.to_string();
+
+        match object_id {
+            ObjectId::Ice => Self::Ice,
+            ObjectId::Area => Self::Area,
+            ObjectId::FloatingZone => Self::FloatingZone,
+            ObjectId::Group => Self::Group,
+            ObjectId::Image | ObjectId::Text | ObjectId::Unit | ObjectId::Point | ObjectId::ParticleSystem => {
+                Self::Marker
+            }
+            _ if name.starts_with("Terrain") => Self::Terrain,
+            _ if name.starts_with("Ice") => Self::Ice,
+            _ if name.starts_with("Sprite") => Self::Sprite,
+            _ => Self::Gameplay,
+        }
+    }
+
+    /// Whether the shape comes from the object's `TerrainCorners`.
+    #[must_use]
+    pub const fn is_terrain_like(self) -> bool {
+        matches!(self, Self::Terrain | Self::Ice | Self::Area | Self::FloatingZone)
+    }
+}
+
+/// The outline of an object in its local space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectShape {
+    pub kind: ShapeKind,
+    /// The rings of the shape, filled with the even-odd rule so that inner rings become holes.
+    pub rings: Vec<Vec<Vec2>>,
+    /// The line thickness of outline sprites, which are drawn as a line along their rings instead of filled.
+    pub outline: Option<f32>,
+}
+
+impl ObjectShape {
+    /// Returns the rings moved to world space by [`Object::local_to_world`].
+    #[must_use]
+    pub fn to_world(&self, object: &Object) -> Vec<Vec<Vec2>> {
+        self.rings.iter().map(|ring| ring.iter().map(|&point| object.local_to_world(point)).collect()).collect()
+    }
+}
+
+impl Object {
+    /// Returns the outline this object is drawn with.
+    ///
+    /// Terrain-like objects without `TerrainCorners` fall back to the corners of their default object, sprites are
+    /// stretched by their `Width` and `Height`, and groups have no rings.
+    pub fn shape(&self) -> ObjectShape {
+        let kind = ShapeKind::of(self.object_id);
+        let name = self.object_id.to_string();
+        let variant = Variant::parse(&name);
+        let mut outline = None;
+
+        let rings = match kind {
+            ShapeKind::Terrain | ShapeKind::Ice | ShapeKind::Area | ShapeKind::FloatingZone => self.terrain_corners(),
+            ShapeKind::Sprite => {
+                if variant.outline {
+                    outline = Some(self.property(thickness).unwrap_or(DEFAULT_OUTLINE_THICKNESS));
+                }
+
+                let size = Vec2::new(self.property(width).unwrap_or(1.0), self.property(height).unwrap_or(1.0));
+                let rings = variant.apply(self.sprite_rings(variant.base));
+
+                rings.into_iter().map(|ring| ring.into_iter().map(|point| point * size).collect()).collect()
+            }
+            ShapeKind::Gameplay => variant.apply(vec![icon_ring(variant.base)]),
+            ShapeKind::Marker => match self.object_id {
+                ObjectId::Point | ObjectId::ParticleSystem => vec![regular_polygon(4, 0.2)],
+                _ => vec![square()],
+            },
+            ShapeKind::Group => vec![],
+        };
+
+        ObjectShape { kind, rings, outline }
+    }
+
+    /// Converts a point from this object's local space to world space.
+    ///
+    /// The point is flipped by `FlipX` and `FlipY`, scaled, rotated counter-clockwise by the rotation in degrees and
+    /// moved to the object's position.
+    pub fn local_to_world(&self, point: Vec2) -> Vec2 {
+        let sign = |flipped: Option<bool>| if flipped.unwrap_or(false) { -1.0 } else { 1.0 };
+        let flip = Vec2::new(sign(self.property(flip_x)), sign(self.property(flip_y)));
+
+        self.position + (point * flip * self.scale).rotate(Vec2::from_angle(self.rotation.0.to_radians()))
+    }
+
+    /// Returns the rings of [`Object::shape`] in world space.
+    #[must_use]
+    pub fn world_rings(&self) -> Vec<Vec<Vec2>> {
+        self.shape().to_world(self)
+    }
+
+    fn terrain_corners(&self) -> Vec<Vec<Vec2>> {
+        let corners = |object: &Self| {
+            object.properties.iter().find_map(|property| match property {
+                ObjectProperty::TerrainCorners(rings) => Some(rings.clone()),
+                _ => None,
+            })
+        };
+
+        corners(self)
+            .or_else(|| corners(&Self::get_default_with_id(self.object_id, self.in_layer)))
+            .unwrap_or_else(|| vec![square()])
+    }
+
+    fn property<T>(&self, property: fn(&ObjectProperty) -> Option<T>) -> Option<T> {
+        self.properties.iter().find_map(property)
+    }
+
+    fn sprite_rings(&self, base: &str) -> Vec<Vec<Vec2>> {
+        let ring = match base {
+            "SpriteRoundedSquare" => {
+                let radius = self.property(corner_radius).unwrap_or(0.1);
+                round_corners(&square(), radius, true, CIRCLE_SEGMENTS / 4)
+            }
+            "SpriteCircle" => {
+                let total_angle = self.property(total_angle).unwrap_or(360.0);
+
+                if total_angle >= 360.0 {
+                    regular_polygon(CIRCLE_SEGMENTS, 0.5)
+                } else {
+                    let mut pie = arc(Vec2::ZERO, 0.5, FRAC_PI_2, FRAC_PI_2 + total_angle.max(0.0).to_radians());
+                    pie.push(Vec2::ZERO);
+                    pie
+                }
+            }
+            "SpriteSemicircle" => arc(Vec2::ZERO, 0.5, 0.0, PI),
+            "SpriteQuartercircle" => {
+                let mut quarter = arc(Vec2::splat(-0.5), 1.0, 0.0, FRAC_PI_2);
+                quarter.push(Vec2::splat(-0.5));
+                quarter
+            }
+            "SpriteTriangle" => vec![Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.0, 0.5)],
+            "SpriteRightTriangle" => vec![Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(-0.5, 0.5)],
+            "SpritePentagon" => regular_polygon(5, 0.5),
+            "SpriteHexagon" => regular_polygon(6, 0.5),
+            "SpriteHeptagon" => regular_polygon(7, 0.5),
+            "SpriteOctagon" => regular_polygon(8, 0.5),
+            "SpriteTrapezoid" => {
+                vec![Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.25, 0.5), Vec2::new(-0.25, 0.5)]
+            }
+            "SpriteStar" => star(5, 0.2),
+            "SpriteStar4" => star(4, 0.2),
+            "SpriteStar6" => star(6, 0.25),
+            "SpriteCross" => cross(1.0 / 3.0),
+            "SpriteHeart" => heart(),
+            "SpriteMoon" => {
+                let inner_radius = self.property(moon_inner_radius).unwrap_or(0.8);
+                let offset = self.property(moon_offset).unwrap_or(0.5);
+                return moon(0.5 * inner_radius, 0.5 * offset);
+            }
+            "SpriteRhombus" => regular_polygon(4, 0.5),
+            "SpriteDrop" => drop(),
+            "SpriteSlope" => {
+                let mut slope = vec![Vec2::new(-0.5, -0.5)];
+                slope.extend(arc(Vec2::splat(0.5), 1.0, 1.5 * PI, PI).into_iter().rev());
+                slope
+            }
+            _ => square(),
+        };
+
+        vec![ring]
+    }
+}
+
+const fn flip_x(property: &ObjectProperty) -> Option<bool> {
+    if let ObjectProperty::FlipX(value) = property { Some(*value) } else { None }
+}
+
+const fn flip_y(property: &ObjectProperty) -> Option<bool> {
+    if let ObjectProperty::FlipY(value) = property { Some(*value) } else { None }
+}
+
+const fn width(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::Width(value) = property { Some(value.0) } else { None }
+}
+
+const fn height(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::Height(value) = property { Some(value.0) } else { None }
+}
+
+const fn thickness(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::Thickness(value) = property { Some(value.0) } else { None }
+}
+
+const fn corner_radius(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::CornerRadius(value) = property { Some(value.0) } else { None }
+}
+
+const fn total_angle(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::TotalAngle(value) = property { Some(value.0) } else { None }
+}
+
+const fn moon_inner_radius(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::MoonInnerRadius(value) = property { Some(value.0) } else { None }
+}
+
+const fn moon_offset(property: &ObjectProperty) -> Option<f32> {
+    if let ObjectProperty::MoonOffset(value) = property { Some(value.0) } else { None }
+}
+
+/// The parts of an object id name like `SpriteSemicircleOutline90` or `BoosterFlip180`.
+#[derive(Clone, Copy, Debug)]
+struct Variant<'a> {
+    /// The name without the outline, flip and rotation suffixes.
+    base: &'a str,
+    outline: bool,
+    flip: bool,
+    /// The counter-clockwise rotation in degrees.
+    rotation: f32,
+}
+
+impl<'a> Variant<'a> {
+    fn parse(name: &'a str) -> Self {
+        let (mut base, mut rotation) = [("90", 90.0), ("180", 180.0), ("270", 270.0)]
+            .into_iter()
+            .find_map(|(suffix, rotation)| Some((name.strip_suffix(suffix)?, rotation)))
+            .unwrap_or((name, 0.0));
+        let flip = base.strip_suffix("Flip").map(|stripped| base = stripped).is_some();
+        let outline = base.strip_suffix("Outline").map(|stripped| base = stripped).is_some();
+
+        // Spawns point right by default, the other icons point up.
+        if let Some(stripped) = base.strip_suffix("Right") {
+            (base, rotation) = (stripped, 270.0);
+        } else if let Some(stripped) = base.strip_suffix("Left") {
+            (base, rotation) = (stripped, 90.0);
+        }
+
+        Self { base, outline, flip, rotation }
+    }
+
+    fn apply(self, rings: Vec<Vec<Vec2>>) -> Vec<Vec<Vec2>> {
+        let turn = Vec2::from_angle(self.rotation.to_radians());
+        let flip = if self.flip { Vec2::new(-1.0, 1.0) } else { Vec2::ONE };
+
+        rings.into_iter().map(|ring| ring.into_iter().map(|point| (point * flip).rotate(turn)).collect()).collect()
+    }
+}
+
+fn square() -> Vec<Vec2> {
+    vec![Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.5, 0.5), Vec2::new(-0.5, 0.5)]
+}
+
+/// Points on a circle from the `start` angle to the `end` angle in radians, both included.
+fn arc(center: Vec2, radius: f32, start: f32, end: f32) -> Vec<Vec2> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    let steps = (((end - start).abs() / TAU * CIRCLE_SEGMENTS as f32).ceil() as usize).max(1);
+
+    (0..=steps)
+        .map(|step| {
+            #[allow(clippy::cast_precision_loss)]
+            let angle = start + (end - start) * step as f32 / steps as f32;
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+/// A polygon with its first corner at the top.
+fn regular_polygon(corners: usize, radius: f32) -> Vec<Vec2> {
+    (0..corners)
+        .map(|corner| {
+            #[allow(clippy::cast_precision_loss)]
+            let angle = FRAC_PI_2 + TAU * corner as f32 / corners as f32;
+            Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+fn star(points: usize, inner_radius: f32) -> Vec<Vec2> {
+    (0..points * 2)
+        .map(|corner| {
+            #[allow(clippy::cast_precision_loss)]
+            let angle = FRAC_PI_2 + PI * corner as f32 / points as f32;
+            let radius = if corner % 2 == 0 { 0.5 } else { inner_radius };
+            Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+fn cross(arm_width: f32) -> Vec<Vec2> {
+    let (a, b) = (arm_width / 2.0, 0.5);
+
+    [(a, -b), (a, -a), (b, -a), (b, a), (a, a), (a, b), (-a, b), (-a, a), (-b, a), (-b, -a), (-a, -a), (-a, -b)]
+        .into_iter()
+        .map(Vec2::from)
+        .collect()
+}
+
+fn heart() -> Vec<Vec2> {
+    (0..CIRCLE_SEGMENTS)
+        .map(|step| {
+            #[allow(clippy::cast_precision_loss)]
+            let t = TAU * step as f32 / CIRCLE_SEGMENTS as f32;
+            let x = 16.0 * t.sin().powi(3);
+            let tail = 5.0f32.mul_add((2.0 * t).cos(), 2.0f32.mul_add((3.0 * t).cos(), (4.0 * t).cos()));
+            let y = 13.0f32.mul_add(t.cos(), -tail);
+            Vec2::new(x / 32.0, (y + 2.6) / 29.0)
+        })
+        .collect()
+}
+
+/// A circle of radius one half with the circle at `(offset, 0)` cut out of it. The sign of the offset is ignored.
+fn moon(inner_radius: f32, offset: f32) -> Vec<Vec<Vec2>> {
+    let offset = offset.abs();
+    let outer = regular_polygon(CIRCLE_SEGMENTS, 0.5);
+
+    if offset >= 0.5 + inner_radius {
+        return vec![outer];
+    }
+
+    if inner_radius >= offset + 0.5 {
+        return vec![];
+    }
+
+    if offset + inner_radius <= 0.5 {
+        // The inner circle lies completely inside the outer one and becomes a hole.
+        let hole = regular_polygon(CIRCLE_SEGMENTS, inner_radius);
+        return vec![outer, hole.into_iter().map(|point| point + Vec2::new(offset, 0.0)).collect()];
+    }
+
+    // Where the two circles cross, the upper crossing is at `(x, y)` and the lower one at `(x, -y)`.
+    let x = offset.mul_add(offset, inner_radius.mul_add(-inner_radius, 0.25)) / (2.0 * offset);
+    let y = x.mul_add(-x, 0.25).sqrt();
+    let outer_angle = y.atan2(x);
+    let inner_angle = y.atan2(x - offset);
+    let mut ring = arc(Vec2::ZERO, 0.5, outer_angle, TAU - outer_angle);
+    ring.extend(arc(Vec2::new(offset, 0.0), inner_radius, TAU - inner_angle, inner_angle).into_iter().skip(1));
+    ring.pop();
+
+    vec![ring]
+}
+
+fn drop() -> Vec<Vec2> {
+    let (center, radius, tip) = (Vec2::new(0.0, -0.15), 0.35, Vec2::new(0.0, 0.5));
+    let spread = (radius / tip.distance(center)).acos();
+    let mut ring = arc(center, radius, FRAC_PI_2 + spread, FRAC_PI_2 - spread + TAU);
+    ring.push(tip);
+    ring
+}
+
+/// The icon of a gameplay object, pointing up before its variant's rotation.
+fn icon_ring(base: &str) -> Vec<Vec2> {
+    let points: &[(f32, f32)] = match base {
+        "Spawn" | "Booster" | "Dasher" | "DoubleJumper" | "Fan" | "Slingshot" | "Switcher" | "Bumper" => &[
+            (0.0, 0.5),
+            (0.45, 0.05),
+            (0.18, 0.05),
+            (0.18, -0.45),
+            (-0.18, -0.45),
+            (-0.18, 0.05),
+            (-0.45, 0.05),
+        ],
+        "EndFlag" => &[(-0.35, -0.5), (-0.25, -0.5), (-0.25, 0.05), (0.4, 0.275), (-0.25, 0.5), (-0.35, 0.5)],
+        "KillerSpike" => &[(-0.5, -0.5), (0.5, -0.5), (0.0, 0.5)],
+        "KillerSaw" | "KillerSawBig" | "KillerSawFull" | "KillerSawFullBig" => return star(12, 0.4),
+        "HookAnchor" | "CheckpointOrb" | "Grabber" => return regular_polygon(CIRCLE_SEGMENTS / 2, 0.5),
+        "Door" => &[(-0.25, -0.5), (0.25, -0.5), (0.25, 0.5), (-0.25, 0.5)],
+        _ => return square(),
+    };
+
+    points.iter().copied().map(Vec2::from).collect()
+}
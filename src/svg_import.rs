@@ -0,0 +1,702 @@
+//! Importing SVG drawings, so level layouts can be drawn in a vector editor like Inkscape.
+//!
+//! See [`LevelData::import_svg`] for how elements become objects and layers.
+
+use std::str::FromStr;
+
+use glam::{DAffine2, DVec2};
+use roxmltree::{Document, Node};
+use svgtypes::{Length, Paint, PaintFallback, PointsParser, SimplePathSegment, SimplifyingPathParser, Transform};
+
+use crate::error::Error;
+use crate::geometry::is_degenerate;
+use crate::shapes::ShapeKind;
+use crate::types::{
+    color::Color, level_data::LevelData, object::Object, object_id::ObjectId, object_property::ObjectProperty,
+    vec2::Vec2,
+};
+
+/// The namespace of the attributes Inkscape adds, like the names of its layers.
+const INKSCAPE: &str = "http://www.inkscape.org/namespaces/inkscape";
+
+const XLINK: &str = "http://www.w3.org/1999/xlink";
+
+/// How often a curve is split in half at most while flattening it.
+const MAX_SUBDIVISIONS: u32 = 16;
+
+/// How [`LevelData::import_svg`] places a drawing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SvgImportOptions {
+    /// The object filled shapes become, which has to be terrain-like, like `Terrain`, `Ice`, `Area` or
+    /// `FloatingZone`.
+    pub object_id: ObjectId,
+    /// The size of an SVG user unit in world units.
+    pub scale: f32,
+    /// Where the origin of the drawing ends up in the world. The y axis is flipped, so the drawing stays upright.
+    pub origin: Vec2,
+    /// How far in world units flattened curves may stray from the curves.
+    pub tolerance: f32,
+    /// Whether rectangles, circles and ellipses become sprites where they can, instead of terrain.
+    pub sprites: bool,
+}
+
+impl Default for SvgImportOptions {
+    fn default() -> Self {
+        Self { object_id: ObjectId::Terrain, scale: 1.0 / 32.0, origin: Vec2::ZERO, tolerance: 0.05, sprites: true }
+    }
+}
+
+impl LevelData {
+    /// Adds the shapes of an SVG document to the level and returns the entity ids of the new objects.
+    ///
+    /// - `<path>`, `<polygon>`, `<polyline>`, `<rect>`, `<circle>` and `<ellipse>` elements with a fill become
+    ///   objects with `TerrainCorners`, one ring per subpath, with curves and arcs flattened. Their fill becomes
+    ///   the `Color`, their stroke the `BorderColor` and `BorderThickness`.
+    /// - With `sprites` set, rectangles without rounded corners, circles and ellipses that are either filled or
+    ///   stroked become `SpriteSquare`s and `SpriteCircle`s, or their outline variants, with a `Width` and
+    ///   `Height`. This only works if their transform doesn't skew or mirror them.
+    /// - Inkscape layers and the groups directly inside the root element become new layers after the existing
+    ///   ones, named after their label or id. Hidden ones become invisible. Elements outside of these go to a new
+    ///   layer named "SVG", and other groups are flattened into the layer they're in.
+    /// - The id of an element becomes the `tag` of its object.
+    ///
+    /// Transforms, `style` attributes and inherited paints are applied, and gradients are drawn with their first
+    /// stop color. Lengths are read as user units, and holes follow the even-odd rule of `TerrainCorners` whatever
+    /// the `fill-rule`. Other elements like text, images and `<use>` are skipped, and so are stroked shapes
+    /// without a fill that don't become sprites.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Xml`] if the document can't be parsed, and [`Error::NotTerrainLike`] if the object id of
+    /// the options isn't drawn from `TerrainCorners`.
+    pub fn import_svg(&mut self, svg: &str, options: &SvgImportOptions) -> Result<Vec<i32>, Error> {
+        if !ShapeKind::of(options.object_id).is_terrain_like() {
+            return Err(Error::NotTerrainLike(options.object_id));
+        }
+
+        let document = Document::parse(svg)?;
+        let (scale, origin) = (f64::from(options.scale), options.origin);
+        let to_world = DAffine2::from_cols_array(&[scale, 0.0, 0.0, -scale, origin.x.0.into(), origin.y.0.into()]);
+
+        let mut importer = Importer { level: self, document: &document, options, layer_id: None, entity_ids: vec![] };
+        importer.visit_children(document.root_element(), &Style::default(), &to_world);
+
+        Ok(importer.entity_ids)
+    }
+}
+
+struct Importer<'a, 'input> {
+    level: &'a mut LevelData,
+    document: &'a Document<'input>,
+    options: &'a SvgImportOptions,
+    /// The layer that shapes are added to, if they're inside of one.
+    layer_id: Option<i32>,
+    entity_ids: Vec<i32>,
+}
+
+impl Importer<'_, '_> {
+    fn visit_children(&mut self, node: Node, style: &Style, transform: &DAffine2) {
+        for child in node.children().filter(Node::is_element) {
+            self.visit(child, style, transform);
+        }
+    }
+
+    fn visit(&mut self, node: Node, inherited: &Style, transform: &DAffine2) {
+        let style = inherited.cascade(node, self.document);
+        let own = node.attribute("transform").and_then(|value| Transform::from_str(value).ok());
+        let transform = own.map_or(*transform, |own| {
+            *transform * DAffine2::from_cols_array(&[own.a, own.b, own.c, own.d, own.e, own.f])
+        });
+        let hidden = property(node, "display") == Some("none")
+            || matches!(property(node, "visibility"), Some("hidden" | "collapse"));
+
+        match node.tag_name().name() {
+            "g" if is_layer(node) => {
+                let name = node.attribute((INKSCAPE, "label")).or_else(|| node.attribute("id"));
+                let layer_id = self.add_layer(name, hidden);
+                let previous = self.layer_id.replace(layer_id);
+                self.visit_children(node, &style, &transform);
+
+                // Shapes after a top-level layer go to a new layer, so they stay on top of it.
+                let nested = node.parent_element().is_some_and(|parent| parent.parent_element().is_some());
+                self.layer_id = previous.filter(|_| nested);
+            }
+            "g" | "a" if !hidden => self.visit_children(node, &style, &transform),
+            "path" | "polygon" | "polyline" | "rect" | "circle" | "ellipse" if !hidden => {
+                self.add_shape(node, &style, &transform);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_layer(&mut self, name: Option<&str>, invisible: bool) -> i32 {
+        let layer_id = self.level.layers.iter().map(|layer| layer.layer_id).max().unwrap_or(0) + 1;
+        let layer = self.level.layer_or_insert(layer_id, name.unwrap_or("SVG"));
+        layer.invisible = invisible;
+
+        layer_id
+    }
+
+    fn add_shape(&mut self, node: Node, style: &Style, transform: &DAffine2) {
+        let fill = style.fill.map(|color| with_alpha(color, style.fill_opacity * style.opacity));
+        let stroke = style
+            .stroke
+            .filter(|_| style.stroke_width > 0.0)
+            .map(|color| with_alpha(color, style.stroke_opacity * style.opacity));
+        #[allow(clippy::cast_possible_truncation)]
+        let scale = transform.matrix2.determinant().abs().sqrt() as f32;
+        let stroke_width = style.stroke_width * scale;
+
+        let sprite = if self.options.sprites { Sprite::of(node, transform) } else { None };
+
+        let mut object = match (sprite, fill, stroke) {
+            (Some(sprite), Some(color), None) => sprite.object(sprite.object_id, color, None),
+            (Some(sprite), None, Some(color)) => sprite.object(sprite.outline_id, color, Some(stroke_width)),
+            (_, Some(color), _) => {
+                let rings = shape_rings(node, transform, self.options.tolerance);
+
+                if rings.is_empty() {
+                    return;
+                }
+
+                terrain(self.options.object_id, rings, color, stroke.map(|color| (color, stroke_width)))
+            }
+            _ => return,
+        };
+
+        if self.layer_id.is_none() {
+            self.layer_id = Some(self.add_layer(None, false));
+        }
+
+        let Some(layer_id) = self.layer_id else { return };
+
+        object.entity_id = self.level.next_entity_id();
+        object.in_layer = layer_id;
+        object.tag = node.attribute("id").unwrap_or_default().to_string();
+
+        self.level.layer_or_insert(layer_id, "SVG").children.push(object.entity_id);
+        self.entity_ids.push(object.entity_id);
+        self.level.objects.push(object);
+    }
+}
+
+/// Whether the group is an Inkscape layer or directly inside the root element.
+fn is_layer(node: Node) -> bool {
+    node.attribute((INKSCAPE, "groupmode")) == Some("layer")
+        || node.parent_element().is_some_and(|parent| parent.parent_element().is_none())
+}
+
+/// The inherited paint properties of an element, as RGBA colors from zero to one.
+#[derive(Clone, Copy, Debug)]
+struct Style {
+    fill: Option<[f32; 4]>,
+    stroke: Option<[f32; 4]>,
+    stroke_width: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    /// The product of the `opacity` of the element and its ancestors.
+    opacity: f32,
+    /// The value of `currentColor`.
+    color: [f32; 4],
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Some([0.0, 0.0, 0.0, 1.0]),
+            stroke: None,
+            stroke_width: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            opacity: 1.0,
+            color: [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+}
+
+impl Style {
+    /// Returns the style of the element, with what it doesn't set inherited from this one.
+    fn cascade(&self, node: Node, document: &Document) -> Self {
+        let mut style = *self;
+        #[allow(clippy::cast_possible_truncation)]
+        let number = |name: &str| Some(Length::from_str(property(node, name)?).ok()?.number as f32);
+
+        if let Some(color) = property(node, "color").and_then(|value| svgtypes::Color::from_str(value).ok()) {
+            style.color = rgba(color);
+        }
+
+        if let Some(value) = property(node, "fill") {
+            style.fill = style.paint(value, self.fill, document);
+        }
+
+        if let Some(value) = property(node, "stroke") {
+            style.stroke = style.paint(value, self.stroke, document);
+        }
+
+        style.stroke_width = number("stroke-width").unwrap_or(style.stroke_width);
+        style.fill_opacity = number("fill-opacity").map_or(style.fill_opacity, |value| value.clamp(0.0, 1.0));
+        style.stroke_opacity = number("stroke-opacity").map_or(style.stroke_opacity, |value| value.clamp(0.0, 1.0));
+        style.opacity *= number("opacity").map_or(1.0, |value| value.clamp(0.0, 1.0));
+
+        style
+    }
+
+    fn paint(&self, value: &str, inherited: Option<[f32; 4]>, document: &Document) -> Option<[f32; 4]> {
+        let fallback = |fallback: Option<PaintFallback>| match fallback? {
+            PaintFallback::Color(color) => Some(rgba(color)),
+            PaintFallback::CurrentColor => Some(self.color),
+            PaintFallback::None => None,
+        };
+
+        match Paint::from_str(value) {
+            Ok(Paint::None) => None,
+            Ok(Paint::CurrentColor) => Some(self.color),
+            Ok(Paint::Color(color)) => Some(rgba(color)),
+            Ok(Paint::FuncIRI(id, paint)) => gradient_color(document, id).or_else(|| fallback(paint)),
+            _ => inherited,
+        }
+    }
+}
+
+/// Returns the color of the first stop of the gradient with the given id, following the gradients it links to.
+fn gradient_color(document: &Document, id: &str) -> Option<[f32; 4]> {
+    let mut node = document.descendants().find(|node| node.attribute("id") == Some(id))?;
+
+    for _ in 0..8 {
+        if let Some(stop) = node.children().find(|child| child.has_tag_name("stop")) {
+            let color = property(stop, "stop-color").and_then(|value| svgtypes::Color::from_str(value).ok());
+            let opacity = property(stop, "stop-opacity").and_then(|value| value.parse::<f32>().ok());
+
+            let opacity = opacity.map_or(1.0, |opacity| opacity.clamp(0.0, 1.0));
+
+            return Some(with_alpha(color.map_or([0.0, 0.0, 0.0, 1.0], rgba), opacity));
+        }
+
+        let href = node.attribute((XLINK, "href")).or_else(|| node.attribute("href"))?;
+        let linked = href.strip_prefix('#')?;
+        node = document.descendants().find(|node| node.attribute("id") == Some(linked))?;
+    }
+
+    None
+}
+
+/// Returns a presentation property of the element, from its `style` attribute or else its own attribute.
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').filter_map(|declaration| declaration.split_once(':')).find_map(|(key, value)| {
+            (key.trim() == name).then(|| value.trim().trim_end_matches("!important").trim())
+        })
+    });
+
+    from_style.or_else(|| node.attribute(name))
+}
+
+/// Returns a length attribute in user units, or zero if it's missing or invalid.
+fn length(node: Node, name: &str) -> f64 {
+    node.attribute(name).and_then(|value| Length::from_str(value).ok()).map_or(0.0, |length| length.number)
+}
+
+fn rgba(color: svgtypes::Color) -> [f32; 4] {
+    [color.red, color.green, color.blue, color.alpha].map(|channel| f32::from(channel) / 255.0)
+}
+
+fn with_alpha([red, green, blue, alpha]: [f32; 4], opacity: f32) -> [f32; 4] {
+    [red, green, blue, alpha * opacity]
+}
+
+fn color([red, green, blue, alpha]: [f32; 4]) -> Color {
+    Color::new(red, green, blue, alpha)
+}
+
+fn apply(transform: &DAffine2, x: f64, y: f64) -> Vec2 {
+    to_vec2(transform.transform_point2(DVec2::new(x, y)))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn to_vec2(vector: DVec2) -> Vec2 {
+    Vec2::new(vector.x as f32, vector.y as f32)
+}
+
+/// A rectangle or ellipse that can be drawn as a sprite.
+#[derive(Clone, Copy, Debug)]
+struct Sprite {
+    object_id: ObjectId,
+    outline_id: ObjectId,
+    position: Vec2,
+    size: Vec2,
+    rotation: f32,
+}
+
+impl Sprite {
+    /// Returns the sprite for the element, unless it isn't a plain rectangle, circle or ellipse, or its transform
+    /// skews or mirrors it.
+    fn of(node: Node, transform: &DAffine2) -> Option<Self> {
+        let (object_id, outline_id, center, size) = match node.tag_name().name() {
+            "rect" if length(node, "rx") <= 0.0 && length(node, "ry") <= 0.0 => {
+                let (width, height) = (length(node, "width"), length(node, "height"));
+                let center = (length(node, "x") + width / 2.0, length(node, "y") + height / 2.0);
+                (ObjectId::SpriteSquare, ObjectId::SpriteSquareOutline, center, (width, height))
+            }
+            "circle" | "ellipse" => {
+                let center = (length(node, "cx"), length(node, "cy"));
+                let radii = match node.tag_name().name() {
+                    "circle" => (length(node, "r"), length(node, "r")),
+                    _ => (length(node, "rx"), length(node, "ry")),
+                };
+                (ObjectId::SpriteCircle, ObjectId::SpriteCircleOutline, center, (radii.0 * 2.0, radii.1 * 2.0))
+            }
+            _ => return None,
+        };
+
+        // The axes of the sprite in the world. The y axis of the drawing points down, so it's flipped.
+        let x_axis = to_vec2(transform.transform_vector2(DVec2::X));
+        let y_axis = to_vec2(-transform.transform_vector2(DVec2::Y));
+        let skewed = x_axis.dot(y_axis).abs() > 1e-4 * x_axis.length() * y_axis.length();
+
+        if size.0 <= 0.0 || size.1 <= 0.0 || skewed || x_axis.perp_dot(y_axis) <= 0.0 {
+            return None;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let size = Vec2::new(size.0 as f32 * x_axis.length(), size.1 as f32 * y_axis.length());
+
+        Some(Self {
+            object_id,
+            outline_id,
+            position: apply(transform, center.0, center.1),
+            size,
+            rotation: x_axis.to_angle().to_degrees(),
+        })
+    }
+
+    fn object(&self, object_id: ObjectId, paint: [f32; 4], thickness: Option<f32>) -> Object {
+        let mut object = Object::get_default_with_id(object_id, 0);
+        object.position = self.position;
+        object.rotation = self.rotation.rem_euclid(360.0).into();
+        object.properties.retain(|property| !matches!(property, ObjectProperty::Color(_)));
+        object.properties.extend([
+            ObjectProperty::Width(self.size.x),
+            ObjectProperty::Height(self.size.y),
+            ObjectProperty::Color(color(paint)),
+        ]);
+
+        if let Some(thickness) = thickness {
+            object.properties.push(ObjectProperty::Thickness(thickness.into()));
+        }
+
+        object
+    }
+}
+
+/// Returns a terrain-like object centered on its rings, with the fill as its color and the stroke as its border.
+fn terrain(object_id: ObjectId, rings: Vec<Vec<Vec2>>, fill: [f32; 4], stroke: Option<([f32; 4], f32)>) -> Object {
+    let points = rings.iter().flatten();
+    let min = points.clone().fold(rings[0][0], |min, &point| min.min(point));
+    let max = points.fold(rings[0][0], |max, &point| max.max(point));
+    let position = min.midpoint(max);
+
+    let mut object = Object::get_default_with_id(object_id, 0);
+    object.position = position;
+    object.properties = vec![
+        ObjectProperty::TerrainCorners(
+            rings.into_iter().map(|ring| ring.into_iter().map(|point| point - position).collect()).collect(),
+        ),
+        ObjectProperty::Color(color(fill)),
+    ];
+
+    match stroke {
+        Some((paint, width)) => object.properties.extend([
+            ObjectProperty::BorderColor(color(paint)),
+            ObjectProperty::BorderThickness(width.into()),
+        ]),
+        None => object.properties.push(ObjectProperty::BorderThickness(0.0.into())),
+    }
+
+    object
+}
+
+/// Returns the outline of a shape element in the world, without the rings that have no area.
+fn shape_rings(node: Node, transform: &DAffine2, tolerance: f32) -> Vec<Vec<Vec2>> {
+    let rings = match node.tag_name().name() {
+        "path" => flatten(node.attribute("d").unwrap_or_default(), transform, tolerance),
+        "polygon" | "polyline" => {
+            let points = PointsParser::from(node.attribute("points").unwrap_or_default());
+            vec![points.map(|(x, y)| apply(transform, x, y)).collect()]
+        }
+        "rect" => {
+            let (x, y) = (length(node, "x"), length(node, "y"));
+            let (width, height) = (length(node, "width"), length(node, "height"));
+
+            // A missing radius takes the value of the other one, and neither can exceed half of the side.
+            let (rx, ry) = match (node.attribute("rx").is_some(), node.attribute("ry").is_some()) {
+                (true, false) => (length(node, "rx"), length(node, "rx")),
+                (false, true) => (length(node, "ry"), length(node, "ry")),
+                _ => (length(node, "rx"), length(node, "ry")),
+            };
+            let (rx, ry) = (rx.clamp(0.0, width / 2.0), ry.clamp(0.0, height / 2.0));
+
+            if width <= 0.0 || height <= 0.0 {
+                return vec![];
+            }
+
+            let arc = |x: f64, y: f64| format!("A {rx} {ry} 0 0 1 {x} {y}");
+            let data = format!(
+                "M {} {y} H {} {} V {} {} H {} {} V {} {} Z",
+                x + rx,
+                x + width - rx,
+                arc(x + width, y + ry),
+                y + height - ry,
+                arc(x + width - rx, y + height),
+                x + rx,
+                arc(x, y + height - ry),
+                y + ry,
+                arc(x + rx, y),
+            );
+
+            flatten(&data, transform, tolerance)
+        }
+        _ => {
+            let (cx, cy) = (length(node, "cx"), length(node, "cy"));
+            let (rx, ry) = match node.tag_name().name() {
+                "circle" => (length(node, "r"), length(node, "r")),
+                _ => (length(node, "rx"), length(node, "ry")),
+            };
+
+            if rx <= 0.0 || ry <= 0.0 {
+                return vec![];
+            }
+
+            let data = format!(
+                "M {} {cy} A {rx} {ry} 0 1 0 {} {cy} A {rx} {ry} 0 1 0 {} {cy} Z",
+                cx - rx,
+                cx + rx,
+                cx - rx
+            );
+
+            flatten(&data, transform, tolerance)
+        }
+    };
+
+    rings.into_iter().filter(|ring| !is_degenerate(ring)).collect()
+}
+
+/// Flattens path data into one ring per subpath, which are closed whether they end with `Z` or not.
+///
+/// Invalid path data is read up to the first error, like SVG renderers do.
+fn flatten(data: &str, transform: &DAffine2, tolerance: f32) -> Vec<Vec<Vec2>> {
+    let mut rings = vec![];
+    let mut ring: Vec<Vec2> = vec![];
+
+    for segment in SimplifyingPathParser::from(data).map_while(Result::ok) {
+        let current = ring.last().copied().unwrap_or(Vec2::ZERO);
+
+        match segment {
+            SimplePathSegment::MoveTo { x, y } => {
+                rings.push(std::mem::take(&mut ring));
+                ring.push(apply(transform, x, y));
+            }
+            SimplePathSegment::LineTo { x, y } => ring.push(apply(transform, x, y)),
+            SimplePathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+                let controls = [apply(transform, x1, y1), apply(transform, x2, y2), apply(transform, x, y)];
+                flatten_cubic([current, controls[0], controls[1], controls[2]], tolerance, 0, &mut ring);
+            }
+            SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                let (control, end) = (apply(transform, x1, y1), apply(transform, x, y));
+                let elevate = |point: Vec2| point + (control - point) * (2.0 / 3.0);
+                flatten_cubic([current, elevate(current), elevate(end), end], tolerance, 0, &mut ring);
+            }
+            SimplePathSegment::ClosePath => rings.push(std::mem::take(&mut ring)),
+        }
+    }
+
+    rings.push(ring);
+
+    rings
+        .into_iter()
+        .map(|mut ring| {
+            ring.dedup();
+
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+
+            ring
+        })
+        .filter(|ring| ring.len() >= 3)
+        .collect()
+}
+
+/// Adds the corners of a cubic Bézier curve after its start to the ring, splitting it in half until its control
+/// points are within the tolerance of the line between its ends.
+fn flatten_cubic([start, control1, control2, end]: [Vec2; 4], tolerance: f32, depth: u32, ring: &mut Vec<Vec2>) {
+    let chord = end - start;
+    let distance = |point: Vec2| {
+        if chord.length() <= f32::EPSILON {
+            point.distance(start)
+        } else {
+            chord.perp_dot(point - start).abs() / chord.length()
+        }
+    };
+
+    if depth >= MAX_SUBDIVISIONS || distance(control1).max(distance(control2)) <= tolerance {
+        ring.push(end);
+        return;
+    }
+
+    let (first, second, third) = (start.midpoint(control1), control1.midpoint(control2), control2.midpoint(end));
+    let (left, right) = (first.midpoint(second), second.midpoint(third));
+    let middle = left.midpoint(right);
+
+    flatten_cubic([start, first, left, middle], tolerance, depth + 1, ring);
+    flatten_cubic([middle, right, third, end], tolerance, depth + 1, ring);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::signed_area;
+
+    /// Imports the elements inside an SVG root element, with one world unit for every 32 user units.
+    fn import(elements: &str, options: &SvgImportOptions) -> LevelData {
+        let svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="{INKSCAPE}">{elements}</svg>"#);
+        let mut level = LevelData::default();
+        let entity_ids = level.import_svg(&svg, options).unwrap();
+
+        assert_eq!(entity_ids, level.objects.iter().map(|object| object.entity_id).collect::<Vec<_>>());
+        level
+    }
+
+    fn terrain_options() -> SvgImportOptions {
+        SvgImportOptions { sprites: false, ..SvgImportOptions::default() }
+    }
+
+    fn corners(object: &Object) -> &Vec<Vec<Vec2>> {
+        object
+            .properties
+            .iter()
+            .find_map(|property| match property {
+                ObjectProperty::TerrainCorners(rings) => Some(rings),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn sorted(mut points: Vec<Vec2>) -> Vec<Vec2> {
+        points.sort_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
+        points
+    }
+
+    #[test]
+    fn rect_corners() {
+        let rect = r##"<rect id="floor" x="32" y="0" width="64" height="32" fill="#ff0000"/>"##;
+        let level = import(rect, &terrain_options());
+        let object = &level.objects[0];
+
+        // The drawing's y axis points down, so the rectangle ends up below the origin.
+        assert_eq!((object.object_id, object.tag.as_str()), (ObjectId::Terrain, "floor"));
+        assert_eq!(object.position, Vec2::new(2.0, -0.5));
+        assert_eq!(corners(object).len(), 1);
+        assert_eq!(
+            sorted(corners(object)[0].clone()),
+            [Vec2::new(-1.0, -0.5), Vec2::new(-1.0, 0.5), Vec2::new(1.0, -0.5), Vec2::new(1.0, 0.5)]
+        );
+        assert!((signed_area(&corners(object)[0]).abs() - 2.0).abs() < 1e-5);
+        assert!(object.properties.contains(&ObjectProperty::Color(Color::new(1.0, 0.0, 0.0, 1.0))));
+        assert!(object.properties.contains(&ObjectProperty::BorderThickness(0.0.into())));
+    }
+
+    #[test]
+    fn circle_corners() {
+        let options = SvgImportOptions { tolerance: 0.01, ..terrain_options() };
+        let level = import(r#"<circle cx="32" cy="-32" r="32" stroke="blue" stroke-width="4"/>"#, &options);
+        let object = &level.objects[0];
+        let ring = &corners(object)[0];
+
+        assert_eq!(object.position, Vec2::new(1.0, 1.0));
+        assert!(ring.len() >= 16);
+        assert!(ring.iter().all(|point| (point.length() - 1.0).abs() < 0.02), "{ring:?}");
+        assert!((signed_area(ring).abs() - std::f32::consts::PI).abs() < 0.05);
+        assert!(object.properties.contains(&ObjectProperty::BorderColor(Color::new(0.0, 0.0, 1.0, 1.0))));
+        assert!(object.properties.contains(&ObjectProperty::BorderThickness(0.125.into())));
+    }
+
+    #[test]
+    fn holes() {
+        let level = import(r#"<path d="M0 0 H64 V64 H0 Z M16 16 V48 H48 V16 Z"/>"#, &terrain_options());
+        let rings = corners(&level.objects[0]);
+
+        let sizes: Vec<(usize, f32)> = rings.iter().map(|ring| (ring.len(), signed_area(ring).abs())).collect();
+
+        assert_eq!(sizes, [(4, 4.0), (4, 1.0)]);
+    }
+
+    #[test]
+    fn sprites() {
+        let level = import(
+            r#"<rect width="64" height="32" fill="lime"/>
+            <circle cx="32" cy="32" r="16" fill="none" stroke="black" stroke-width="2"/>
+            <g transform="rotate(90)"><ellipse rx="32" ry="16"/></g>
+            <rect width="64" height="32" transform="scale(-1 1)"/>
+            <rect width="64" height="32" rx="4"/>"#,
+            &SvgImportOptions::default(),
+        );
+        let placements: Vec<(ObjectId, Vec2, f32)> =
+            level.objects.iter().map(|object| (object.object_id, object.position, object.rotation.0)).collect();
+
+        // Mirrored and rounded rectangles can't be sprites.
+        assert_eq!(
+            placements,
+            [
+                (ObjectId::SpriteSquare, Vec2::new(1.0, -0.5), 0.0),
+                (ObjectId::SpriteCircleOutline, Vec2::new(1.0, -1.0), 0.0),
+                (ObjectId::SpriteCircle, Vec2::ZERO, 270.0),
+                (ObjectId::Terrain, Vec2::new(-1.0, -0.5), 0.0),
+                (ObjectId::Terrain, Vec2::new(1.0, -0.5), 0.0),
+            ]
+        );
+        assert!(level.objects[0].properties.contains(&ObjectProperty::Width(2.0.into())));
+        assert!(level.objects[0].properties.contains(&ObjectProperty::Height(1.0.into())));
+        assert!(level.objects[1].properties.contains(&ObjectProperty::Thickness(0.0625.into())));
+        assert!(level.objects[2].properties.contains(&ObjectProperty::Width(2.0.into())));
+    }
+
+    #[test]
+    fn layers() {
+        let level = import(
+            r#"<rect width="32" height="32"/>
+            <g inkscape:groupmode="layer" inkscape:label="Walls"><rect id="a" width="32" height="32"/></g>
+            <g id="hidden" style="display: none"><g><rect id="b" width="32" height="32"/></g></g>
+            <rect id="c" width="32" height="32" fill="none"/>
+            <rect id="d" width="32" height="32"/>"#,
+            &SvgImportOptions::default(),
+        );
+        let layers: Vec<(i32, &str, bool, &[i32])> = level
+            .layers
+            .iter()
+            .map(|layer| (layer.layer_id, layer.layer_name.as_str(), layer.invisible, layer.children.as_slice()))
+            .collect();
+
+        // The unfilled rectangle is skipped, and the shapes after the layers go to a new layer on top.
+        assert_eq!(
+            layers,
+            [
+                (1, "SVG", false, &[1][..]),
+                (2, "Walls", false, &[2]),
+                (3, "hidden", true, &[3]),
+                (4, "SVG", false, &[4]),
+            ]
+        );
+        assert_eq!(level.objects.iter().map(|object| object.tag.as_str()).collect::<Vec<_>>(), ["", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut level = LevelData::default();
+
+        assert!(matches!(level.import_svg("<svg>", &SvgImportOptions::default()), Err(Error::Xml(_))));
+        assert!(matches!(
+            level.import_svg("<svg/>", &SvgImportOptions { object_id: ObjectId::SpriteSquare, ..terrain_options() }),
+            Err(Error::NotTerrainLike(ObjectId::SpriteSquare))
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+use super::{Position, Span, SyntaxError};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Int(i64),
+    Float(f32),
+    Str(String),
+    /// Punctuation and operators, e.g. `(`, `==` or `&&`.
+    Symbol(&'static str),
+    Eof,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// Longer symbols come first so that e.g. `==` isn't lexed as two `=`.
+const SYMBOLS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "(", ")", "{", "}", "[", "]", ",", ";", ":", "=", "<", ">", "+", "-", "*", "/", "%",
+    "!", "$", "@",
+];
+
+struct Lexer<'a> {
+    source: &'a str,
+    offset: usize,
+    position: Position,
+}
+
+impl Lexer<'_> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.offset..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.source[self.offset..].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn error(&self, start: Position, message: impl Into<String>) -> SyntaxError {
+        SyntaxError {
+            message: message.into(),
+            span: Span { start, end: self.position },
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            match (self.peek(), self.peek_second()) {
+                (Some(c), _) if c.is_whitespace() => {
+                    self.bump();
+                }
+                (Some('/'), Some('/')) => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token, SyntaxError> {
+        self.skip_whitespace_and_comments();
+
+        let start = self.position;
+
+        let Some(c) = self.peek() else {
+            return Ok(Token {
+                kind: TokenKind::Eof,
+                span: Span { start, end: start },
+            });
+        };
+
+        let kind = if c.is_ascii_alphabetic() || c == '_' {
+            let begin = self.offset;
+
+            while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || c == '_') {
+                self.bump();
+            }
+
+            TokenKind::Ident(self.source[begin..self.offset].to_string())
+        } else if c.is_ascii_digit() {
+            self.number(start)?
+        } else if c == '"' {
+            self.string(start)?
+        } else {
+            let rest = &self.source[self.offset..];
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| self.error(start, format!("unexpected character `{c}`")))?;
+
+            for _ in 0..symbol.len() {
+                self.bump();
+            }
+
+            TokenKind::Symbol(symbol)
+        };
+
+        Ok(Token {
+            kind,
+            span: Span { start, end: self.position },
+        })
+    }
+
+    fn number(&mut self, start: Position) -> Result<TokenKind, SyntaxError> {
+        let begin = self.offset;
+        let mut is_float = false;
+
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+
+        if self.peek() == Some('.') && self.peek_second().is_some_and(|c| c.is_ascii_digit()) {
+            is_float = true;
+            self.bump();
+
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+
+        if matches!(self.peek(), Some('e' | 'E')) {
+            is_float = true;
+            self.bump();
+
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+
+        let text = &self.source[begin..self.offset];
+
+        if is_float {
+            text.parse()
+                .map(TokenKind::Float)
+                .map_err(|_| self.error(start, format!("invalid float literal `{text}`")))
+        } else {
+            text.parse()
+                .map(TokenKind::Int)
+                .map_err(|_| self.error(start, format!("integer literal `{text}` is too large")))
+        }
+    }
+
+    fn string(&mut self, start: Position) -> Result<TokenKind, SyntaxError> {
+        self.bump();
+
+        let mut value = String::new();
+
+        loop {
+            match self.bump() {
+                None => return Err(self.error(start, "unterminated string literal")),
+                Some('"') => return Ok(TokenKind::Str(value)),
+                Some('\\') => {
+                    let escaped = match self.bump() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('u') => self.unicode_escape(start)?,
+                        _ => return Err(self.error(start, "invalid escape sequence")),
+                    };
+
+                    value.push(escaped);
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, start: Position) -> Result<char, SyntaxError> {
+        if self.bump() != Some('{') {
+            return Err(self.error(start, "expected `{` after `\\u`"));
+        }
+
+        let begin = self.offset;
+
+        while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            self.bump();
+        }
+
+        let digits = &self.source[begin..self.offset];
+
+        if self.bump() != Some('}') {
+            return Err(self.error(start, "expected `}` after unicode escape"));
+        }
+
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(start, format!("invalid unicode escape `{digits}`")))
+    }
+}
+
+/// Splits the source into tokens. The last token is always [`TokenKind::Eof`].
+pub fn tokenize(source: &str) -> Result<Vec<Token>, SyntaxError> {
+    let mut lexer = Lexer {
+        source,
+        offset: 0,
+        position: Position { line: 1, column: 1 },
+    };
+
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token()?;
+        let is_eof = token.kind == TokenKind::Eof;
+        tokens.push(token);
+
+        if is_eof {
+            return Ok(tokens);
+        }
+    }
+}
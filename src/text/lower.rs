@@ -0,0 +1,590 @@
+//! Turns the syntax tree into nova script types.
+
+use std::collections::BTreeMap;
+
+use ordered_float::OrderedFloat;
+
+use super::parser::{ActionKind, ActionStmt, Arg, BinaryOp, Expr, ExprKind, Reference, ScriptAst, Stmt};
+use super::{
+    action_template, activator_by_name, dynamic_type_by_name, id_by_name, static_type_by_name, Scope, Span,
+    SyntaxError, ID_CONSTANTS, LIST_CONSTANTS,
+};
+use crate::interpreter::Easing;
+use crate::types::{
+    color::Color,
+    nova_script::{
+        action::Action,
+        action_type::ActionType,
+        activator::Activator,
+        dynamic_type::DynamicType,
+        function_call::{CallParameter, FunctionCall},
+        nova_value::NovaValue,
+        parameter::Parameter,
+        static_type::StaticType,
+        variable::Variable,
+        NovaScript,
+    },
+    vec2::Vec2,
+};
+
+fn error<T>(span: Span, message: impl Into<String>) -> Result<T, SyntaxError> {
+    Err(SyntaxError {
+        message: message.into(),
+        span,
+    })
+}
+
+/// Returns the dynamic type that `lhs <op> rhs` compiles to, if the operator supports these operand types.
+pub const fn binary_dynamic_type(op: BinaryOp, lhs: StaticType, rhs: StaticType) -> Option<DynamicType> {
+    use StaticType as S;
+
+    Some(match (op, lhs, rhs) {
+        (BinaryOp::Or, S::Bool, S::Bool) => DynamicType::BoolOr,
+        (BinaryOp::And, S::Bool, S::Bool) => DynamicType::BoolAnd,
+        (BinaryOp::Equal, S::Bool, S::Bool) => DynamicType::BoolEqualBool,
+        (BinaryOp::Equal, S::Int, S::Int) | (BinaryOp::Equal, S::Float, S::Float) => DynamicType::BoolEqualNumber,
+        (BinaryOp::Equal, S::String, S::String) => DynamicType::BoolEqualString,
+        (BinaryOp::Equal, S::Color, S::Color) => DynamicType::BoolEqualColor,
+        (BinaryOp::Equal, S::Vector, S::Vector) => DynamicType::BoolEqualVector,
+        (BinaryOp::Equal, S::Object, S::Object) => DynamicType::BoolEqualObject,
+        (BinaryOp::NotEqual, S::Bool, S::Bool) => DynamicType::BoolNotEqualBool,
+        (BinaryOp::NotEqual, S::Int, S::Int) | (BinaryOp::NotEqual, S::Float, S::Float) => DynamicType::BoolNotEqualNumber,
+        (BinaryOp::NotEqual, S::String, S::String) => DynamicType::BoolNotEqualString,
+        (BinaryOp::NotEqual, S::Color, S::Color) => DynamicType::BoolNotEqualColor,
+        (BinaryOp::NotEqual, S::Vector, S::Vector) => DynamicType::BoolNotEqualVector,
+        (BinaryOp::NotEqual, S::Object, S::Object) => DynamicType::BoolNotEqualObject,
+        (BinaryOp::Less, S::Int, S::Int) | (BinaryOp::Less, S::Float, S::Float) => DynamicType::BoolLess,
+        (BinaryOp::LessOrEqual, S::Int, S::Int) | (BinaryOp::LessOrEqual, S::Float, S::Float) => DynamicType::BoolLessOrEqual,
+        (BinaryOp::Greater, S::Int, S::Int) | (BinaryOp::Greater, S::Float, S::Float) => DynamicType::BoolGreater,
+        (BinaryOp::GreaterOrEqual, S::Int, S::Int) | (BinaryOp::GreaterOrEqual, S::Float, S::Float) => {
+            DynamicType::BoolGreaterOrEqual
+        }
+        (BinaryOp::Add, S::Int, S::Int) => DynamicType::IntAdd,
+        (BinaryOp::Add, S::Float, S::Float) => DynamicType::FloatAdd,
+        (BinaryOp::Add, S::Vector, S::Vector) => DynamicType::VectorAdd,
+        (BinaryOp::Add, S::String, S::String) => DynamicType::StringConcat,
+        (BinaryOp::Subtract, S::Int, S::Int) => DynamicType::IntSubtract,
+        (BinaryOp::Subtract, S::Float, S::Float) => DynamicType::FloatSubtract,
+        (BinaryOp::Subtract, S::Vector, S::Vector) => DynamicType::VectorSubtract,
+        (BinaryOp::Multiply, S::Int, S::Int) => DynamicType::IntMultiply,
+        (BinaryOp::Multiply, S::Float, S::Float) => DynamicType::FloatMultiply,
+        (BinaryOp::Multiply, S::Vector, S::Float) => DynamicType::VectorMultiply,
+        (BinaryOp::Divide, S::Int, S::Int) => DynamicType::IntDivide,
+        (BinaryOp::Divide, S::Float, S::Float) => DynamicType::FloatDivide,
+        (BinaryOp::Divide, S::Vector, S::Float) => DynamicType::VectorDivide,
+        (BinaryOp::Modulo, S::Int, S::Int) => DynamicType::IntModulo,
+        (BinaryOp::Modulo, S::Float, S::Float) => DynamicType::FloatModulo,
+        _ => return None,
+    })
+}
+
+/// Whether `lhs <op> rhs` compiles to `dynamic_type` with the operands wrapped in [`float_from_int`]. The
+/// comparisons of numbers take floats, so ints are converted first, like `IntExpr::compare` does.
+pub fn compares_ints(dynamic_type: DynamicType, lhs: StaticType) -> bool {
+    lhs == StaticType::Int && dynamic_type.operand_types() == [StaticType::Float; 2]
+}
+
+/// Converts an int operand of a comparison to a float.
+pub fn float_from_int(value: NovaValue) -> NovaValue {
+    NovaValue {
+        dynamic_type: DynamicType::FloatFromInt,
+        sub_values: Some(vec![value]),
+        ..Default::default()
+    }
+}
+
+fn no_overrides(expr: &Expr) -> Result<(), SyntaxError> {
+    match expr.overrides.first() {
+        Some((_, span, _)) => error(*span, "`with` isn't allowed here"),
+        None => Ok(()),
+    }
+}
+
+fn is_none(expr: &Expr) -> bool {
+    expr.kind == ExprKind::None && expr.overrides.is_empty()
+}
+
+fn to_i32(value: i64, span: Span) -> Result<i32, SyntaxError> {
+    i32::try_from(value).or_else(|_| error(span, format!("`{value}` doesn't fit in a 32 bit integer")))
+}
+
+fn int_literal(expr: &Expr) -> Result<i32, SyntaxError> {
+    no_overrides(expr)?;
+
+    match expr.kind {
+        ExprKind::Int(value) => to_i32(value, expr.span),
+        _ => error(expr.span, "expected an integer literal"),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn float_literal(expr: &Expr) -> Result<OrderedFloat<f32>, SyntaxError> {
+    no_overrides(expr)?;
+
+    match expr.kind {
+        ExprKind::Float(value) => Ok(OrderedFloat(value)),
+        ExprKind::Int(value) => Ok(OrderedFloat(value as f32)),
+        _ => error(expr.span, "expected a number literal"),
+    }
+}
+
+fn bool_literal(expr: &Expr) -> Result<bool, SyntaxError> {
+    no_overrides(expr)?;
+
+    match expr.kind {
+        ExprKind::Bool(value) => Ok(value),
+        _ => error(expr.span, "expected `true` or `false`"),
+    }
+}
+
+fn string_literal(expr: &Expr) -> Result<String, SyntaxError> {
+    no_overrides(expr)?;
+
+    match &expr.kind {
+        ExprKind::Str(value) => Ok(value.clone()),
+        _ => error(expr.span, "expected a string literal"),
+    }
+}
+
+fn constructor_args<'a>(expr: &'a Expr, name: &str, count: usize) -> Result<&'a [Expr], SyntaxError> {
+    no_overrides(expr)?;
+
+    match &expr.kind {
+        ExprKind::Call(found, args) if found == name && args.len() == count => Ok(args),
+        _ => error(expr.span, format!("expected `{name}(...)` with {count} arguments")),
+    }
+}
+
+fn color_literal(expr: &Expr) -> Result<Color, SyntaxError> {
+    let args = constructor_args(expr, "rgba", 4)?;
+
+    Ok(Color {
+        r: float_literal(&args[0])?,
+        g: float_literal(&args[1])?,
+        b: float_literal(&args[2])?,
+        a: float_literal(&args[3])?,
+    })
+}
+
+fn vector_literal(expr: &Expr) -> Result<Vec2, SyntaxError> {
+    let args = constructor_args(expr, "vec", 2)?;
+
+    Ok(Vec2 {
+        x: float_literal(&args[0])?,
+        y: float_literal(&args[1])?,
+    })
+}
+
+/// Parses `none` as `None` and `[a, b]` (with or without a list name) as `Some` of the parsed elements.
+fn optional_list<T>(expr: &Expr, element: impl Fn(&Expr) -> Result<T, SyntaxError>) -> Result<Option<Vec<T>>, SyntaxError> {
+    if is_none(expr) {
+        return Ok(None);
+    }
+
+    no_overrides(expr)?;
+
+    match &expr.kind {
+        ExprKind::List(_, elements) => elements.iter().map(element).collect::<Result<_, _>>().map(Some),
+        _ => error(expr.span, "expected a list or `none`"),
+    }
+}
+
+/// Resolves `$name` or `@name` to the id of the only variable or parameter in scope with that name.
+fn resolve_reference(
+    reference: &Reference,
+    names: &BTreeMap<i32, String>,
+    what: &str,
+    span: Span,
+) -> Result<i32, SyntaxError> {
+    match reference {
+        Reference::Id(id) => to_i32(*id, span),
+        Reference::Name(name) => match id_by_name(names, name) {
+            Some(id) => Ok(id),
+            None if names.values().any(|found| found == name) => {
+                error(span, format!("more than one {what} is called `{name}`, refer to it by id"))
+            }
+            None => error(span, format!("unknown {what} `{name}`")),
+        },
+    }
+}
+
+/// The id of the variable in `variable: $name` or `variable: id`.
+fn variable_argument(expr: &Expr, scope: &Scope) -> Result<i32, SyntaxError> {
+    no_overrides(expr)?;
+
+    match &expr.kind {
+        ExprKind::Variable(reference, None) => {
+            resolve_reference(reference, &scope.variable_names, "variable", expr.span)
+        }
+        _ => int_literal(expr),
+    }
+}
+
+fn resolve_type(name: &str, span: Span) -> Result<StaticType, SyntaxError> {
+    static_type_by_name(name).map_or_else(|| error(span, format!("unknown type `{name}`")), Ok)
+}
+
+pub fn value(expr: &Expr, scope: &Scope) -> Result<NovaValue, SyntaxError> {
+    let mut value = NovaValue::default();
+
+    match &expr.kind {
+        ExprKind::Bool(bool_value) => {
+            value.dynamic_type = DynamicType::BoolConstant;
+            value.bool_value = *bool_value;
+        }
+        ExprKind::Int(int_value) => {
+            value.dynamic_type = DynamicType::IntConstant;
+            value.int_value = to_i32(*int_value, expr.span)?;
+        }
+        ExprKind::Float(float_value) => {
+            value.dynamic_type = DynamicType::FloatConstant;
+            value.float_value = OrderedFloat(*float_value);
+        }
+        ExprKind::Str(string_value) => {
+            value.dynamic_type = DynamicType::StringConstant;
+            value.string_value = Some(string_value.clone());
+        }
+        ExprKind::None => return error(expr.span, "`none` can only be used for slots and variable values"),
+        ExprKind::Call(name, args) => {
+            let without_overrides = Expr {
+                overrides: Vec::new(),
+                ..expr.clone()
+            };
+
+            if name == "rgba" {
+                value.dynamic_type = DynamicType::ColorConstant;
+                value.color_value = color_literal(&without_overrides)?;
+            } else if name == "vec" {
+                value.dynamic_type = DynamicType::VectorConstant;
+                value.vector_value = vector_literal(&without_overrides)?;
+            } else if let Some(&(_, dynamic_type)) = ID_CONSTANTS.iter().find(|(constant, _)| constant == name) {
+                let [id] = args.as_slice() else {
+                    return error(expr.span, format!("`{name}` takes a single id"));
+                };
+
+                value.dynamic_type = dynamic_type;
+                value.int_value = int_literal(id)?;
+            } else if let Some(dynamic_type) = dynamic_type_by_name(name) {
+                value.dynamic_type = dynamic_type;
+
+                if !args.is_empty() {
+                    value.sub_values = Some(args.iter().map(|arg| self::value(arg, scope)).collect::<Result<_, _>>()?);
+                }
+            } else {
+                return error(expr.span, format!("unknown function `{name}`"));
+            }
+        }
+        ExprKind::List(None, _) => return error(expr.span, "a list constant needs a type, e.g. `ints[1, 2]`"),
+        ExprKind::List(Some(name), elements) => {
+            let Some(&(_, dynamic_type)) = LIST_CONSTANTS.iter().find(|(constant, _)| constant == name) else {
+                return error(expr.span, format!("unknown list type `{name}`"));
+            };
+
+            value.dynamic_type = dynamic_type;
+
+            match dynamic_type {
+                DynamicType::BoolListConstant => value.bool_list_values = Some(elements.iter().map(bool_literal).collect::<Result<_, _>>()?),
+                DynamicType::FloatListConstant => value.float_list_values = Some(elements.iter().map(float_literal).collect::<Result<_, _>>()?),
+                DynamicType::StringListConstant => value.string_list_values = Some(elements.iter().map(string_literal).collect::<Result<_, _>>()?),
+                DynamicType::ColorListConstant => value.color_list_values = Some(elements.iter().map(color_literal).collect::<Result<_, _>>()?),
+                DynamicType::VectorListConstant => value.vector_list_values = Some(elements.iter().map(vector_literal).collect::<Result<_, _>>()?),
+                _ => value.int_list_values = Some(elements.iter().map(int_literal).collect::<Result<_, _>>()?),
+            }
+        }
+        ExprKind::Variable(reference, explicit) => {
+            let id = resolve_reference(reference, &scope.variable_names, "variable", expr.span)?;
+            let static_type = match explicit {
+                Some(name) => resolve_type(name, expr.span)?,
+                None => match scope.variables.get(&id) {
+                    Some(&static_type) => static_type,
+                    None => return error(expr.span, format!("unknown variable {id}, give its type with `${id}:type`")),
+                },
+            };
+
+            value.dynamic_type = static_type.variable_type();
+            value.int_value = id;
+        }
+        ExprKind::Parameter(reference, explicit) => {
+            let id = resolve_reference(reference, &scope.parameter_names, "parameter", expr.span)?;
+            let static_type = match explicit {
+                Some(name) => resolve_type(name, expr.span)?,
+                None => match scope.parameters.get(&id) {
+                    Some(&static_type) => static_type,
+                    None => return error(expr.span, format!("unknown parameter {id}, give its type with `@{id}:type`")),
+                },
+            };
+
+            value.dynamic_type = static_type.parameter_type();
+            value.int_value = id;
+        }
+        ExprKind::Name(name) => {
+            let Some(easing) = Easing::from_name(name) else {
+                return error(expr.span, format!("unknown identifier `{name}`"));
+            };
+
+            value.dynamic_type = DynamicType::EasingConstant;
+            value.int_value = easing.id();
+        }
+        ExprKind::Binary(op, lhs, rhs) => {
+            let lhs = self::value(lhs, scope)?;
+            let rhs = self::value(rhs, scope)?;
+            let (lhs_type, rhs_type) = (lhs.dynamic_type.result_type(), rhs.dynamic_type.result_type());
+
+            let Some(dynamic_type) = binary_dynamic_type(*op, lhs_type, rhs_type) else {
+                return error(
+                    expr.span,
+                    format!("`{}` can't be used on {lhs_type:?} and {rhs_type:?}", op.symbol()),
+                );
+            };
+
+            let (lhs, rhs) = if compares_ints(dynamic_type, lhs_type) {
+                (float_from_int(lhs), float_from_int(rhs))
+            } else {
+                (lhs, rhs)
+            };
+
+            value.dynamic_type = dynamic_type;
+            value.sub_values = Some(vec![lhs, rhs]);
+        }
+        ExprKind::Not(operand) => {
+            let operand = self::value(operand, scope)?;
+
+            if operand.dynamic_type.result_type() != StaticType::Bool {
+                return error(expr.span, "`!` can only be used on bool values");
+            }
+
+            value.dynamic_type = DynamicType::BoolNot;
+            value.sub_values = Some(vec![operand]);
+        }
+    }
+
+    for (slot, span, slot_value) in &expr.overrides {
+        match slot.as_str() {
+            "bool" => value.bool_value = bool_literal(slot_value)?,
+            "int" => value.int_value = int_literal(slot_value)?,
+            "float" => value.float_value = float_literal(slot_value)?,
+            "string" => value.string_value = if is_none(slot_value) { None } else { Some(string_literal(slot_value)?) },
+            "color" => value.color_value = color_literal(slot_value)?,
+            "vector" => value.vector_value = vector_literal(slot_value)?,
+            "bools" => value.bool_list_values = optional_list(slot_value, bool_literal)?,
+            "ints" => value.int_list_values = optional_list(slot_value, int_literal)?,
+            "floats" => value.float_list_values = optional_list(slot_value, float_literal)?,
+            "strings" => value.string_list_values = optional_list(slot_value, string_literal)?,
+            "colors" => value.color_list_values = optional_list(slot_value, color_literal)?,
+            "vectors" => value.vector_list_values = optional_list(slot_value, vector_literal)?,
+            "sub" => value.sub_values = optional_list(slot_value, |sub_value| self::value(sub_value, scope))?,
+            _ => return error(*span, format!("unknown slot `{slot}`")),
+        }
+    }
+
+    Ok(value)
+}
+
+fn actions(stmts: &[ActionStmt], scope: &Scope) -> Result<Vec<Action>, SyntaxError> {
+    stmts.iter().map(|stmt| action(stmt, scope)).collect()
+}
+
+fn action(stmt: &ActionStmt, scope: &Scope) -> Result<Action, SyntaxError> {
+    let action_type = match &stmt.kind {
+        ActionKind::Repeat(count, body) => ActionType::Repeat {
+            actions: actions(body, scope)?,
+            count: value(count, scope)?,
+        },
+        ActionKind::While(condition, body) => ActionType::RepeatWhile {
+            actions: actions(body, scope)?,
+            condition: value(condition, scope)?,
+        },
+        ActionKind::If(condition, if_actions, else_actions) => ActionType::ConditionBlock {
+            if_actions: actions(if_actions, scope)?,
+            else_actions: actions(else_actions, scope)?,
+            condition: value(condition, scope)?,
+        },
+        ActionKind::ForEach(target_objects, body) => ActionType::RepeatForEachObject {
+            actions: actions(body, scope)?,
+            target_objects: value(target_objects, scope)?,
+        },
+        ActionKind::Set(variable, new_value) => ActionType::VariableSet {
+            variable: resolve_reference(variable, &scope.variable_names, "variable", stmt.span)?,
+            value: if is_none(new_value) { None } else { Some(value(new_value, scope)?) },
+        },
+        ActionKind::Call(function, arguments) => ActionType::RunFunction {
+            function: FunctionCall {
+                id: to_i32(*function, stmt.span)?,
+                parameters: arguments
+                    .iter()
+                    .map(|(parameter_id, argument)| {
+                        Ok(CallParameter {
+                            parameter_id: to_i32(*parameter_id, argument.span)?,
+                            value: value(argument, scope)?,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+        },
+        ActionKind::Named(name, arguments) => named_action(name, arguments, stmt.span, scope)?,
+    };
+
+    Ok(Action {
+        closed: stmt.closed,
+        wait: stmt.wait,
+        action_type,
+    })
+}
+
+fn named_action(name: &str, arguments: &[Arg], span: Span, scope: &Scope) -> Result<ActionType, SyntaxError> {
+    let Some(mut action) = action_template(name) else {
+        return error(span, format!("unknown action `{name}`"));
+    };
+
+    if !action.children().is_empty() || action.function_call().is_some() {
+        return error(span, format!("`{name}` has to be written with its block syntax"));
+    }
+
+    let sets_variable = action.target_variable().is_some();
+
+    if let ActionType::VariableSet { value, .. } | ActionType::VariableSetOverTime { value, .. } = &mut action {
+        *value = Some(NovaValue::default());
+    }
+
+    let fields: Vec<&'static str> = action.values().into_iter().map(|(field, _)| field).collect();
+    let mut assigned: BTreeMap<&'static str, NovaValue> = BTreeMap::new();
+    let mut variable = None;
+    let mut value_is_none = false;
+
+    for (index, argument) in arguments.iter().enumerate() {
+        let (field, field_span) = match &argument.name {
+            Some((field, field_span)) => (field.as_str(), *field_span),
+            None => match fields.get(index) {
+                Some(field) => (*field, argument.value.span),
+                None => return error(argument.value.span, format!("`{name}` takes at most {} arguments", fields.len())),
+            },
+        };
+
+        if sets_variable && field == "variable" {
+            variable = Some(variable_argument(&argument.value, scope)?);
+            continue;
+        }
+
+        if sets_variable && field == "value" && is_none(&argument.value) {
+            value_is_none = true;
+            continue;
+        }
+
+        let Some(&field) = fields.iter().find(|known| **known == field) else {
+            return error(field_span, format!("`{name}` has no argument `{field}`"));
+        };
+
+        if assigned.insert(field, value(&argument.value, scope)?).is_some() {
+            return error(field_span, format!("argument `{field}` is given twice"));
+        }
+    }
+
+    let variable_type = variable.and_then(|variable| scope.variables.get(&variable).copied());
+    let expected: Vec<Option<StaticType>> = fields
+        .iter()
+        .map(|field| if *field == "value" && sets_variable { variable_type } else { action.value_type(field) })
+        .collect();
+
+    for ((field, slot), expected) in action.values_mut().into_iter().zip(expected) {
+        *slot = assigned
+            .remove(field)
+            .unwrap_or_else(|| expected.map_or_else(NovaValue::default, NovaValue::default_constant));
+    }
+
+    if let Some(target) = action.target_variable_mut() {
+        match variable {
+            Some(variable) => *target = variable,
+            None => return error(span, format!("`{name}` needs a `variable` argument")),
+        }
+    }
+
+    if value_is_none {
+        if let ActionType::VariableSet { value, .. } | ActionType::VariableSetOverTime { value, .. } = &mut action {
+            *value = None;
+        }
+    }
+
+    Ok(action)
+}
+
+pub fn script(ast: &ScriptAst, globals: &Scope) -> Result<NovaScript, SyntaxError> {
+    let mut scope = globals.clone();
+
+    for stmt in &ast.stmts {
+        match stmt {
+            Stmt::Param { id, name, static_type: (type_name, span), default_value } => {
+                let id = to_i32(*id, default_value.span)?;
+                scope.parameters.insert(id, resolve_type(type_name, *span)?);
+                scope.parameter_names.insert(id, name.clone());
+            }
+            Stmt::Var { id, name, static_type: (type_name, span), initial_value, .. } => {
+                let id = to_i32(*id, initial_value.span)?;
+                scope.variables.insert(id, resolve_type(type_name, *span)?);
+                scope.variable_names.insert(id, name.clone());
+            }
+            _ => {}
+        }
+    }
+
+    let mut activation_count = None;
+    let mut condition = None;
+    let mut activation_list = Vec::new();
+    let mut parameters = Vec::new();
+    let mut variables = Vec::new();
+    let mut script_actions = Vec::new();
+
+    for stmt in &ast.stmts {
+        match stmt {
+            Stmt::ActivationCount(count, span) => {
+                if activation_count.replace(to_i32(*count, *span)?).is_some() {
+                    return error(*span, "`activation_count` is given twice");
+                }
+            }
+            Stmt::Condition(expr) => {
+                if condition.replace(value(expr, &scope)?).is_some() {
+                    return error(expr.span, "`condition` is given twice");
+                }
+            }
+            Stmt::On(activator_type, activator_parameters, span) => activation_list.push(Activator {
+                activator_type: match activator_type {
+                    Reference::Id(id) => to_i32(*id, *span)?,
+                    Reference::Name(name) => match activator_by_name(name) {
+                        Some(activator_type) => activator_type,
+                        None => return error(*span, format!("unknown activator `{name}`")),
+                    },
+                },
+                parameters: activator_parameters.iter().map(|parameter| value(parameter, &scope)).collect::<Result<_, _>>()?,
+            }),
+            Stmt::Param { id, name, static_type: (type_name, span), default_value } => parameters.push(Parameter {
+                parameter_id: to_i32(*id, default_value.span)?,
+                name: name.clone(),
+                static_type: resolve_type(type_name, *span)?,
+                default_value: value(default_value, &scope)?,
+            }),
+            Stmt::Var { id, name, static_type: (type_name, span), persistance, initial_value } => variables.push(Variable {
+                variable_id: to_i32(*id, initial_value.span)?,
+                name: name.clone(),
+                static_type: resolve_type(type_name, *span)?,
+                persistance: persistance.map(|persistance| to_i32(persistance, *span)).transpose()?,
+                initial_value: value(initial_value, &scope)?,
+            }),
+            Stmt::Action(stmt) => script_actions.push(action(stmt, &scope)?),
+        }
+    }
+
+    Ok(NovaScript {
+        script_id: to_i32(ast.id, ast.span)?,
+        script_name: ast.name.clone(),
+        is_function: ast.is_function,
+        activation_count: activation_count.unwrap_or_default(),
+        condition: condition.unwrap_or_else(|| NovaValue {
+            dynamic_type: DynamicType::BoolConstant,
+            bool_value: true,
+            ..Default::default()
+        }),
+        activation_list,
+        parameters,
+        variables,
+        actions: script_actions,
+    })
+}
@@ -0,0 +1,578 @@
+//! A readable text syntax for nova scripts.
+//!
+//! [`decompile_script`] prints a `NovaScript` as text and [`compile_script`] parses it back. Decompiling and
+//! then compiling again gives back exactly the same script, including slots of a `NovaValue` that its
+//! `DynamicType` doesn't use.
+//!
+//! ```text
+//! script 4 "Door" {
+//!     var 1 "opened": bool = false;
+//!     activation_count 0;
+//!     condition !$opened;
+//!     on collision_enter(object(12), object_player());
+//!     await move(objects[7], vec(0.0, 3.0), false, 1.5, easing: InOutSine);
+//!     $opened = true;
+//!     if $opened && object_player() == object(9) {
+//!         game_text_show("Opened!", 2.0);
+//!     }
+//! }
+//! ```
+//!
+//! - Constants are literals (`true`, `3`, `1.5`, `"text"`), `rgba(r, g, b, a)`, `vec(x, y)`, id constants like
+//!   `object(12)`, and lists like `ints[1, 2]` or `objects[7, 8]`. Easings are written by name, like `Linear` or
+//!   `InOutSine`, and `easing(id)` is left for ids that don't have one.
+//! - `$name` reads a variable and `@name` reads a parameter of the script. Variables and parameters whose name
+//!   isn't an identifier, or is shared with another one in scope, are written by id instead, as `$id` and `@id`.
+//!   The type comes from the declaration, or can be given explicitly with `$id:type`.
+//! - `+ - * / % == != < <= > >= && || !` pick the matching dynamic type from the types of their operands.
+//!   Comparisons of two ints convert both of them with `float_from_int`, as numbers are compared as floats.
+//!   Every dynamic type can also be written as a call of its snake case name, e.g. `float_sin(@2)`.
+//! - `with { slot: value }` after an expression sets a slot of the `NovaValue` explicitly. The decompiler
+//!   uses it for slots that the dynamic type doesn't use.
+//! - `on name(parameters);` adds an activator, named after the snake case name of its
+//!   [`ActivatorKind`](crate::types::nova_script::activator::ActivatorKind), like `on level_start();`. Unknown
+//!   activator types are written by id, like `on 42();`.
+//! - Actions are calls of their snake case name with positional or named arguments, missing arguments get a
+//!   default constant. `repeat`, `while`, `if`/`else` and `for_each` take a block, `$name = value;` sets a
+//!   variable and `call id(parameter_id: value, ...);` runs a function. `closed` and `await` set the
+//!   `closed` and `wait` flags of an action.
+
+mod lexer;
+mod lower;
+mod parser;
+mod printer;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::interpreter::Easing;
+use crate::types::nova_script::{
+    action_type::ActionType, activator::ActivatorKind, dynamic_type::DynamicType, nova_value::NovaValue,
+    static_type::StaticType, variable::Variable, NovaScript,
+};
+
+pub use printer::{decompile_action, decompile_value};
+
+/// A line and column in the source text, both starting at 1.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column in characters, starting at 1.
+    pub column: usize,
+}
+
+/// A range in the source text.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    /// The position of the first character.
+    pub start: Position,
+    /// The position right after the last character.
+    pub end: Position,
+}
+
+/// An error in the source text.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// What went wrong.
+    pub message: String,
+    /// Where it went wrong.
+    pub span: Span,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.span.start.line, self.span.start.column, self.message)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// The types and names of the variables and parameters that `$name` and `@name` refer to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scope {
+    /// Variable types by `variable_id`.
+    pub variables: BTreeMap<i32, StaticType>,
+    /// Parameter types by `parameter_id`.
+    pub parameters: BTreeMap<i32, StaticType>,
+    /// Variable names by `variable_id`.
+    pub variable_names: BTreeMap<i32, String>,
+    /// Parameter names by `parameter_id`.
+    pub parameter_names: BTreeMap<i32, String>,
+}
+
+impl Scope {
+    /// A scope containing the given global variables.
+    #[must_use]
+    pub fn new(globals: &[Variable]) -> Self {
+        Self {
+            variables: globals.iter().map(|variable| (variable.variable_id, variable.static_type)).collect(),
+            parameters: BTreeMap::new(),
+            variable_names: globals.iter().map(|variable| (variable.variable_id, variable.name.clone())).collect(),
+            parameter_names: BTreeMap::new(),
+        }
+    }
+
+    /// This scope extended with the local variables and parameters of `script`, which shadow global variables
+    /// with the same id.
+    #[must_use]
+    pub fn with_script(&self, script: &NovaScript) -> Self {
+        let mut scope = self.clone();
+
+        for variable in &script.variables {
+            scope.variables.insert(variable.variable_id, variable.static_type);
+            scope.variable_names.insert(variable.variable_id, variable.name.clone());
+        }
+
+        for parameter in &script.parameters {
+            scope.parameters.insert(parameter.parameter_id, parameter.static_type);
+            scope.parameter_names.insert(parameter.parameter_id, parameter.name.clone());
+        }
+
+        scope
+    }
+}
+
+/// Whether `name` can be written as a single identifier token.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The id that has this name, if exactly one has it.
+fn id_by_name(names: &BTreeMap<i32, String>, name: &str) -> Option<i32> {
+    let mut ids = names.iter().filter(|(_, found)| *found == name).map(|(&id, _)| id);
+
+    ids.next().filter(|_| ids.next().is_none())
+}
+
+/// How `$` or `@` refer to the id: by name if that name leads back to the id, by the id itself otherwise.
+fn reference_text(names: &BTreeMap<i32, String>, id: i32) -> String {
+    match names.get(&id) {
+        Some(name) if is_identifier(name) && id_by_name(names, name) == Some(id) => name.clone(),
+        _ => id.to_string(),
+    }
+}
+
+/// Parses a single script, resolving `$id` against `globals` and the script's own variables.
+///
+/// # Errors
+///
+/// Returns a [`SyntaxError`] if the source doesn't parse or refers to unknown names.
+pub fn compile_script(source: &str, globals: &[Variable]) -> Result<NovaScript, SyntaxError> {
+    let mut parser = parser::Parser::new(lexer::tokenize(source)?);
+    let script = parser.script()?;
+    parser.expect_end()?;
+
+    lower::script(&script, &Scope::new(globals))
+}
+
+/// Parses any number of scripts, e.g. the output of [`decompile_scripts`].
+///
+/// # Errors
+///
+/// Returns a [`SyntaxError`] if the source doesn't parse or refers to unknown names.
+pub fn compile_scripts(source: &str, globals: &[Variable]) -> Result<Vec<NovaScript>, SyntaxError> {
+    let mut parser = parser::Parser::new(lexer::tokenize(source)?);
+    let scope = Scope::new(globals);
+    let mut scripts = Vec::new();
+
+    while !parser.at_end() {
+        scripts.push(lower::script(&parser.script()?, &scope)?);
+    }
+
+    Ok(scripts)
+}
+
+/// Parses a single expression.
+///
+/// # Errors
+///
+/// Returns a [`SyntaxError`] if the source doesn't parse or refers to unknown names.
+pub fn compile_value(source: &str, scope: &Scope) -> Result<NovaValue, SyntaxError> {
+    let mut parser = parser::Parser::new(lexer::tokenize(source)?);
+    let expr = parser.expr()?;
+    parser.expect_end()?;
+
+    lower::value(&expr, scope)
+}
+
+/// Prints a script as text.
+#[must_use]
+pub fn decompile_script(script: &NovaScript, globals: &[Variable]) -> String {
+    printer::script(script, &Scope::new(globals).with_script(script))
+}
+
+/// Prints several scripts as text, separated by blank lines.
+#[must_use]
+pub fn decompile_scripts(scripts: &[NovaScript], globals: &[Variable]) -> String {
+    scripts
+        .iter()
+        .map(|script| decompile_script(script, globals))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Converts a `PascalCase` enum variant name to `snake_case`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (index, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
+
+fn all_dynamic_types() -> impl Iterator<Item = DynamicType> {
+    (0..).map_while(|value| DynamicType::try_from(value).ok())
+}
+
+fn all_static_types() -> impl Iterator<Item = StaticType> {
+    (0..).map_while(|value| StaticType::try_from(value).ok())
+}
+
+pub(crate) fn dynamic_type_name(dynamic_type: DynamicType) -> String {
+    snake_case(&format!("{dynamic_type:?}"))
+}
+
+fn dynamic_type_by_name(name: &str) -> Option<DynamicType> {
+    all_dynamic_types().find(|&dynamic_type| dynamic_type_name(dynamic_type) == name)
+}
+
+pub(crate) fn static_type_name(static_type: StaticType) -> String {
+    snake_case(&format!("{static_type:?}"))
+}
+
+fn static_type_by_name(name: &str) -> Option<StaticType> {
+    all_static_types().find(|&static_type| static_type_name(static_type) == name)
+}
+
+/// The text name of an activator type, e.g. `object_activated`.
+fn activator_name(activator_type: i32) -> Option<String> {
+    ActivatorKind::name(activator_type).map(|name| name.replace(' ', "_"))
+}
+
+fn activator_by_name(name: &str) -> Option<i32> {
+    (0..)
+        .map_while(|activator_type| activator_name(activator_type).map(|found| (activator_type, found)))
+        .find(|(_, found)| found == name)
+        .map(|(activator_type, _)| activator_type)
+}
+
+/// The name an easing id is written as, if it has one that compiles back to the same id.
+fn easing_name(id: i32) -> Option<String> {
+    let easing = Easing::from_id(id);
+
+    (easing.id() == id).then(|| easing.name())
+}
+
+/// The id constants that are written as `name(id)`, they store the id in `int_value`.
+const ID_CONSTANTS: &[(&str, DynamicType)] = &[
+    ("sound", DynamicType::SoundConstant),
+    ("music", DynamicType::MusicConstant),
+    ("object", DynamicType::ObjectConstant),
+    ("transition", DynamicType::TransitionConstant),
+    ("easing", DynamicType::EasingConstant),
+    ("sprite", DynamicType::SpriteConstant),
+    ("script", DynamicType::ScriptConstant),
+    ("layer", DynamicType::LayerConstant),
+    ("prefab", DynamicType::PrefabConstant),
+];
+
+/// The list constants that are written as `name[elements]`.
+const LIST_CONSTANTS: &[(&str, DynamicType)] = &[
+    ("bools", DynamicType::BoolListConstant),
+    ("ints", DynamicType::IntListConstant),
+    ("floats", DynamicType::FloatListConstant),
+    ("strings", DynamicType::StringListConstant),
+    ("colors", DynamicType::ColorListConstant),
+    ("vectors", DynamicType::VectorListConstant),
+    ("objects", DynamicType::ObjectSetConstant),
+    ("prefabs", DynamicType::PrefabListConstant),
+];
+
+macro_rules! action_names {
+    ($($name:literal => $variant:ident $({ $($field:ident),* })?),* $(,)?) => {
+        /// Returns the text name of an action.
+        pub(crate) const fn action_name(action: &ActionType) -> &'static str {
+            match action {
+                $(ActionType::$variant { .. } => $name,)*
+            }
+        }
+
+        /// The name of every action.
+        #[cfg(test)]
+        const ACTION_NAMES: &[&str] = &[$($name),*];
+
+        /// Returns an action of the given kind with every field left at its default.
+        fn action_template(name: &str) -> Option<ActionType> {
+            Some(match name {
+                $($name => ActionType::$variant $({ $($field: Default::default()),* })?,)*
+                _ => return None,
+            })
+        }
+    };
+}
+
+action_names!(
+    "repeat" => Repeat { actions, count },
+    "while" => RepeatWhile { actions, condition },
+    "if" => ConditionBlock { if_actions, else_actions, condition },
+    "wait" => Wait { duration },
+    "wait_frames" => WaitFrames { frames },
+    "move" => Move { target_objects, position, global, duration, easing },
+    "scale" => Scale { target_objects, scale, duration, easing },
+    "rotate" => Rotate { target_objects, rotation, shortest_path, global, duration, easing },
+    "rotate_around" => RotateAround { target_objects, pivot, rotation, is_global, rotate_target, duration, easing },
+    "variable_set" => VariableSet { variable, value },
+    "variable_reset" => VariableReset { variable },
+    "object_reset" => ObjectReset { target_objects },
+    "set_color" => SetColor { target_objects, color, channel, duration, easing },
+    "set_transparency" => SetTransparency { target_objects, transparency, channel, duration, easing },
+    "set_secondary_color" => SetSecondaryColor { target_objects, color, duration, easing },
+    "set_secondary_transparency" => SetSecondaryTransparency { target_objects, transparency, duration, easing },
+    "set_border_color" => SetBorderColor { target_objects, color, duration, easing },
+    "set_border_transparency" => SetBorderTransparency { target_objects, transparency, duration, easing },
+    "set_sprite" => SetSprite { target_objects, sprite },
+    "set_text" => SetText { target_objects, text },
+    "set_enabled" => SetEnabled { target_objects, enabled },
+    "activate" => Activate { target_objects },
+    "deactivate" => Deactivate { target_objects },
+    "damage" => Damage { target_objects, damage },
+    "kill" => Kill { target_objects },
+    "game_finish" => GameFinish,
+    "camera_pan" => CameraPan { position, duration, easing },
+    "camera_follow_player" => CameraFollowPlayer,
+    "camera_zoom" => CameraZoom { viewport_size, duration, easing },
+    "camera_zoom_reset" => CameraZoomReset { duration, easing },
+    "camera_offset" => CameraOffset { offset, duration, easing },
+    "camera_offset_reset" => CameraOffsetReset { duration, easing },
+    "camera_shake" => CameraShake { strength, roughness, fade_in, fade_out, duration },
+    "play_sound_deprecated" => PlaySoundDeprecated { sound, volume, pitch },
+    "play_sound" => PlaySound { sound, volume, pitch, do_loop, fade_in },
+    "play_music" => PlayMusic { music, volume, pitch },
+    "set_direction" => SetDirection { target_objects, direction },
+    "set_gravity" => SetGravity { target_objects, gravity },
+    "set_velocity" => SetVelocity { target_objects, velocity },
+    "set_cinematic" => SetCinematic { enabled },
+    "set_input_enabled" => SetInputEnabled { enabled },
+    "set_timer_enabled" => SetTimerEnabled { enabled },
+    "game_text_show" => GameTextShow { text, duration },
+    "dialogue_show" => DialogueShow { text, position, reverse_direction },
+    "stop_script" => StopScript { script },
+    "transition_in" => TransitionIn { type_, color, duration, easing },
+    "transition_out" => TransitionOut { type_, color, duration, easing },
+    "time_scale" => TimeScale { time_scale, duration, easing },
+    "call" => RunFunction { function },
+    "variable_set_over_time" => VariableSetOverTime { variable, value, duration, easing },
+    "for_each" => RepeatForEachObject { actions, target_objects },
+    "stop_sound" => StopSound { sound_instance, fade_out },
+    "play_particle_system" => PlayParticleSystem { target_objects },
+    "stop_particle_system" => StopParticleSystem { target_objects, clear },
+    "object_create" => ObjectCreate { prefab, position, foreground },
+    "object_delete" => ObjectDelete { target_objects },
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+    use crate::types::level_data::LevelData;
+    use crate::types::nova_script::{
+        action::Action,
+        activator::Activator,
+        function_call::{CallParameter, FunctionCall},
+        parameter::Parameter,
+    };
+
+    fn variable(variable_id: i32, name: &str, static_type: StaticType) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type,
+            persistance: None,
+            initial_value: NovaValue::default_constant(static_type),
+        }
+    }
+
+    fn globals() -> Vec<Variable> {
+        vec![
+            variable(1, "count", StaticType::Int),
+            variable(2, "two words", StaticType::Float),
+            variable(3, "x", StaticType::Bool),
+        ]
+    }
+
+    /// A value of every dynamic type, once with its operands and once with slots it doesn't use.
+    fn every_value() -> Vec<NovaValue> {
+        all_dynamic_types()
+            .flat_map(|dynamic_type| {
+                let operands: Vec<NovaValue> =
+                    dynamic_type.operand_types().iter().map(|&operand| NovaValue::default_constant(operand)).collect();
+
+                [
+                    NovaValue {
+                        dynamic_type,
+                        int_value: 2,
+                        sub_values: (!operands.is_empty()).then_some(operands),
+                        ..Default::default()
+                    },
+                    NovaValue {
+                        dynamic_type,
+                        bool_value: true,
+                        float_value: OrderedFloat(0.5),
+                        string_value: Some("a \"quoted\" string".to_string()),
+                        int_list_values: Some(vec![4, -5]),
+                        ..Default::default()
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// A script with an action of every type, reading every kind of value.
+    fn script() -> NovaScript {
+        let mut values = every_value().into_iter().cycle();
+        let mut actions: Vec<Action> = ACTION_NAMES
+            .iter()
+            .map(|name| {
+                let mut action_type = action_template(name).unwrap();
+
+                for (_, value) in action_type.values_mut() {
+                    *value = values.next().unwrap();
+                }
+
+                for (_, children) in action_type.children_mut() {
+                    children.push(Action { closed: false, wait: false, action_type: ActionType::GameFinish });
+                }
+
+                if let Some(variable) = action_type.target_variable_mut() {
+                    *variable = 1;
+                }
+
+                if let Some(function) = action_type.function_call_mut() {
+                    *function = FunctionCall {
+                        id: 9,
+                        parameters: vec![CallParameter { parameter_id: 1, value: values.next().unwrap() }],
+                    };
+                }
+
+                Action { closed: name.len() % 2 == 0, wait: name.len() % 3 == 0, action_type }
+            })
+            .collect();
+
+        actions.extend([1, 2, 3, 4, 5].map(|variable| Action {
+            closed: false,
+            wait: false,
+            action_type: ActionType::VariableSet { variable, value: (variable != 5).then(|| values.next().unwrap()) },
+        }));
+
+        NovaScript {
+            script_id: 7,
+            script_name: "Everything".to_string(),
+            is_function: false,
+            activation_count: 3,
+            condition: values.next().unwrap(),
+            activation_list: (0..=13)
+                .chain([42])
+                .map(|activator_type| Activator { activator_type, parameters: vec![values.next().unwrap()] })
+                .collect(),
+            parameters: vec![Parameter {
+                parameter_id: 1,
+                name: "speed".to_string(),
+                static_type: StaticType::Float,
+                default_value: NovaValue::default_constant(StaticType::Float),
+            }],
+            // `x` is shadowed by id and `count` by name, which leaves `count` to be written by id.
+            variables: vec![variable(3, "y", StaticType::Vector), variable(4, "count", StaticType::Int)],
+            actions,
+        }
+    }
+
+    #[test]
+    fn every_dynamic_type_round_trips() {
+        let mut scope = Scope::new(&globals());
+        scope.parameters.insert(2, StaticType::Int);
+        scope.parameter_names.insert(2, "amount".to_string());
+
+        for value in every_value() {
+            let text = decompile_value(&value, &scope);
+            assert_eq!(compile_value(&text, &scope), Ok(value), "{text}");
+        }
+    }
+
+    #[test]
+    fn every_action_type_round_trips() {
+        let globals = globals();
+        let script = script();
+        let text = decompile_script(&script, &globals);
+
+        assert_eq!(compile_script(&text, &globals), Ok(script), "{text}");
+    }
+
+    #[test]
+    fn int_comparisons_type_check() {
+        let globals = globals();
+        let comparisons = ["$count == 1", "$count != 2", "$count < 3", "$count <= 4", "$count > 0", "$count >= @n"];
+        let source = format!(
+            "function 1 \"A\" {{ param 2 \"n\": int = 0; if {} {{ }} }}",
+            comparisons.join(" && ")
+        );
+        let script = compile_script(&source, &globals).unwrap();
+        let level = LevelData {
+            nova_scripts: vec![script.clone()],
+            global_variables: globals.clone(),
+            ..Default::default()
+        };
+
+        assert_eq!(level.type_check(), Vec::new());
+
+        let text = decompile_script(&script, &globals);
+
+        for comparison in comparisons {
+            assert!(text.contains(comparison), "missing `{comparison}` in\n{text}");
+        }
+        assert_eq!(compile_script(&text, &globals), Ok(script));
+
+        // ints compared without a conversion don't type check, but still round trip
+        let unconverted = NovaValue {
+            dynamic_type: DynamicType::BoolLess,
+            sub_values: Some(vec![NovaValue::default_constant(StaticType::Int); 2]),
+            ..Default::default()
+        };
+        let scope = Scope::new(&globals);
+
+        assert_eq!(compile_value(&decompile_value(&unconverted, &scope), &scope), Ok(unconverted));
+    }
+
+    #[test]
+    fn names_are_readable() {
+        let globals = globals();
+        let text = decompile_script(&script(), &globals);
+
+        for expected in ["on level_start(", "on collision_enter(", "on 42(", "$1 = ", "$y = ", "$4 = "] {
+            assert!(text.contains(expected), "missing `{expected}` in\n{text}");
+        }
+        assert!(!text.contains("$x"), "shadowed global written by name in\n{text}");
+
+        let source = "script 1 \"A\" { on object_activated(object(2)); move(easing: InOutSine); $count = $count + 1; }";
+        let script = compile_script(source, &globals).unwrap();
+
+        assert_eq!(script.activation_list[0].activator_type, 1);
+        let easing = script.actions[0].action_type.values().last().unwrap().1.int_value;
+
+        assert_eq!(easing, Easing::from_name("InOutSine").unwrap().id());
+        assert_eq!(script.actions[1].action_type.target_variable(), Some(1));
+        assert!(compile_script("script 1 \"A\" { $nothing = 1; }", &globals).is_err());
+    }
+}
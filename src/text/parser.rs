@@ -0,0 +1,673 @@
+use super::lexer::{Token, TokenKind};
+use super::{Span, SyntaxError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+}
+
+impl BinaryOp {
+    pub const ALL: [Self; 13] = [
+        Self::Or,
+        Self::And,
+        Self::Equal,
+        Self::NotEqual,
+        Self::Less,
+        Self::LessOrEqual,
+        Self::Greater,
+        Self::GreaterOrEqual,
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Modulo,
+    ];
+
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Or => "||",
+            Self::And => "&&",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulo => "%",
+        }
+    }
+
+    /// Operators with a higher precedence bind tighter. All binary operators are left associative.
+    pub const fn precedence(self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::Equal | Self::NotEqual => 3,
+            Self::Less | Self::LessOrEqual | Self::Greater | Self::GreaterOrEqual => 4,
+            Self::Add | Self::Subtract => 5,
+            Self::Multiply | Self::Divide | Self::Modulo => 6,
+        }
+    }
+}
+
+/// The precedence of `!` and of everything that isn't a binary operation.
+pub const UNARY_PRECEDENCE: u8 = 7;
+
+/// An id or a name, as written after `$`, `@` or `on`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reference {
+    Id(i64),
+    Name(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprKind {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Str(String),
+    None,
+    /// `name(args)`, used for constructors like `vec(1.0, 2.0)` as well as for every dynamic type.
+    Call(String, Vec<Expr>),
+    /// `name[elements]` for list constants, or `[elements]` without a name.
+    List(Option<String>, Vec<Expr>),
+    /// `$name`, `$id` or `$id:type`.
+    Variable(Reference, Option<String>),
+    /// `@name`, `@id` or `@id:type`.
+    Parameter(Reference, Option<String>),
+    /// A bare identifier, like the name of an easing.
+    Name(String),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+    /// The slots set explicitly with `with { slot: value, ... }`.
+    pub overrides: Vec<(String, Span, Self)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arg {
+    pub name: Option<(String, Span)>,
+    pub value: Expr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionKind {
+    Repeat(Expr, Vec<ActionStmt>),
+    While(Expr, Vec<ActionStmt>),
+    If(Expr, Vec<ActionStmt>, Vec<ActionStmt>),
+    ForEach(Expr, Vec<ActionStmt>),
+    Set(Reference, Expr),
+    Call(i64, Vec<(i64, Expr)>),
+    Named(String, Vec<Arg>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionStmt {
+    pub closed: bool,
+    pub wait: bool,
+    pub kind: ActionKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stmt {
+    ActivationCount(i64, Span),
+    Condition(Expr),
+    On(Reference, Vec<Expr>, Span),
+    Param {
+        id: i64,
+        name: String,
+        static_type: (String, Span),
+        default_value: Expr,
+    },
+    Var {
+        id: i64,
+        name: String,
+        static_type: (String, Span),
+        persistance: Option<i64>,
+        initial_value: Expr,
+    },
+    Action(ActionStmt),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptAst {
+    pub is_function: bool,
+    pub id: i64,
+    pub name: String,
+    pub stmts: Vec<Stmt>,
+    pub span: Span,
+}
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    pub const fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, index: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index]
+    }
+
+    fn peek_kind_at(&self, offset: usize) -> &TokenKind {
+        let index = (self.index + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].kind
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.index].clone();
+
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+
+        token
+    }
+
+    fn previous_end(&self) -> super::Position {
+        self.tokens[self.index.saturating_sub(1)].span.end
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> SyntaxError {
+        SyntaxError {
+            message: message.into(),
+            span: self.peek().span,
+        }
+    }
+
+    fn describe(kind: &TokenKind) -> String {
+        match kind {
+            TokenKind::Ident(name) => format!("`{name}`"),
+            TokenKind::Int(value) => format!("`{value}`"),
+            TokenKind::Float(value) => format!("`{value:?}`"),
+            TokenKind::Str(value) => format!("{value:?}"),
+            TokenKind::Symbol(symbol) => format!("`{symbol}`"),
+            TokenKind::Eof => "end of input".to_string(),
+        }
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Symbol(found) if *found == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Ident(found) if found == keyword)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.is_symbol(symbol);
+
+        if found {
+            self.advance();
+        }
+
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+
+        if found {
+            self.advance();
+        }
+
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<Span, SyntaxError> {
+        if self.is_symbol(symbol) {
+            Ok(self.advance().span)
+        } else {
+            Err(self.error_here(format!("expected `{symbol}`, found {}", Self::describe(&self.peek().kind))))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<Span, SyntaxError> {
+        if self.is_keyword(keyword) {
+            Ok(self.advance().span)
+        } else {
+            Err(self.error_here(format!("expected `{keyword}`, found {}", Self::describe(&self.peek().kind))))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<(String, Span), SyntaxError> {
+        match self.peek().kind.clone() {
+            TokenKind::Ident(name) => Ok((name, self.advance().span)),
+            other => Err(self.error_here(format!("expected an identifier, found {}", Self::describe(&other)))),
+        }
+    }
+
+    fn expect_string(&mut self) -> Result<String, SyntaxError> {
+        match self.peek().kind.clone() {
+            TokenKind::Str(value) => {
+                self.advance();
+                Ok(value)
+            }
+            other => Err(self.error_here(format!("expected a string, found {}", Self::describe(&other)))),
+        }
+    }
+
+    /// An integer, optionally negative.
+    fn expect_int(&mut self) -> Result<i64, SyntaxError> {
+        let negative = self.eat_symbol("-");
+
+        match self.peek().kind.clone() {
+            TokenKind::Int(value) => {
+                self.advance();
+                Ok(if negative { -value } else { value })
+            }
+            other => Err(self.error_here(format!("expected an integer, found {}", Self::describe(&other)))),
+        }
+    }
+
+    /// A name or an integer.
+    fn expect_reference(&mut self) -> Result<Reference, SyntaxError> {
+        match self.peek().kind.clone() {
+            TokenKind::Ident(name) => {
+                self.advance();
+                Ok(Reference::Name(name))
+            }
+            _ => self.expect_int().map(Reference::Id),
+        }
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.peek().kind == TokenKind::Eof
+    }
+
+    pub fn expect_end(&self) -> Result<(), SyntaxError> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.error_here(format!("expected end of input, found {}", Self::describe(&self.peek().kind))))
+        }
+    }
+
+    /// `script <id> "<name>" { ... }` or `function <id> "<name>" { ... }`.
+    pub fn script(&mut self) -> Result<ScriptAst, SyntaxError> {
+        let start = self.peek().span.start;
+
+        let is_function = if self.eat_keyword("function") {
+            true
+        } else {
+            self.expect_keyword("script")?;
+            false
+        };
+
+        let id = self.expect_int()?;
+        let name = self.expect_string()?;
+
+        self.expect_symbol("{")?;
+
+        let mut stmts = Vec::new();
+
+        while !self.eat_symbol("}") {
+            stmts.push(self.stmt()?);
+        }
+
+        Ok(ScriptAst {
+            is_function,
+            id,
+            name,
+            stmts,
+            span: Span {
+                start,
+                end: self.previous_end(),
+            },
+        })
+    }
+
+    fn stmt(&mut self) -> Result<Stmt, SyntaxError> {
+        let start = self.peek().span.start;
+
+        if self.eat_keyword("activation_count") {
+            let count = self.expect_int()?;
+            self.expect_symbol(";")?;
+
+            return Ok(Stmt::ActivationCount(count, Span { start, end: self.previous_end() }));
+        }
+
+        if self.eat_keyword("condition") {
+            let condition = self.expr()?;
+            self.expect_symbol(";")?;
+
+            return Ok(Stmt::Condition(condition));
+        }
+
+        if self.eat_keyword("on") {
+            let activator_type = self.expect_reference()?;
+            let parameters = self.expr_list("(", ")")?;
+            self.expect_symbol(";")?;
+
+            return Ok(Stmt::On(activator_type, parameters, Span { start, end: self.previous_end() }));
+        }
+
+        if self.eat_keyword("param") {
+            let id = self.expect_int()?;
+            let name = self.expect_string()?;
+            self.expect_symbol(":")?;
+            let static_type = self.expect_ident()?;
+            self.expect_symbol("=")?;
+            let default_value = self.expr()?;
+            self.expect_symbol(";")?;
+
+            return Ok(Stmt::Param {
+                id,
+                name,
+                static_type,
+                default_value,
+            });
+        }
+
+        if self.eat_keyword("var") {
+            let id = self.expect_int()?;
+            let name = self.expect_string()?;
+            self.expect_symbol(":")?;
+            let static_type = self.expect_ident()?;
+            let persistance = if self.eat_keyword("persist") { Some(self.expect_int()?) } else { None };
+            self.expect_symbol("=")?;
+            let initial_value = self.expr()?;
+            self.expect_symbol(";")?;
+
+            return Ok(Stmt::Var {
+                id,
+                name,
+                static_type,
+                persistance,
+                initial_value,
+            });
+        }
+
+        self.action().map(Stmt::Action)
+    }
+
+    fn block(&mut self) -> Result<Vec<ActionStmt>, SyntaxError> {
+        self.expect_symbol("{")?;
+
+        let mut actions = Vec::new();
+
+        while !self.eat_symbol("}") {
+            actions.push(self.action()?);
+        }
+
+        Ok(actions)
+    }
+
+    fn action(&mut self) -> Result<ActionStmt, SyntaxError> {
+        let start = self.peek().span.start;
+        let mut closed = false;
+        let mut wait = false;
+
+        loop {
+            if self.eat_keyword("closed") {
+                closed = true;
+            } else if self.eat_keyword("await") {
+                wait = true;
+            } else {
+                break;
+            }
+        }
+
+        let kind = if self.eat_keyword("repeat") {
+            ActionKind::Repeat(self.expr()?, self.block()?)
+        } else if self.eat_keyword("while") {
+            ActionKind::While(self.expr()?, self.block()?)
+        } else if self.eat_keyword("for_each") {
+            ActionKind::ForEach(self.expr()?, self.block()?)
+        } else if self.eat_keyword("if") {
+            let condition = self.expr()?;
+            let if_actions = self.block()?;
+            let else_actions = if self.eat_keyword("else") { self.block()? } else { Vec::new() };
+
+            ActionKind::If(condition, if_actions, else_actions)
+        } else if self.eat_symbol("$") {
+            let variable = self.expect_reference()?;
+            self.expect_symbol("=")?;
+            let value = self.expr()?;
+            self.expect_symbol(";")?;
+
+            ActionKind::Set(variable, value)
+        } else if self.eat_keyword("call") {
+            let function = self.expect_int()?;
+            self.expect_symbol("(")?;
+
+            let mut arguments = Vec::new();
+
+            while !self.eat_symbol(")") {
+                let parameter = self.expect_int()?;
+                self.expect_symbol(":")?;
+                arguments.push((parameter, self.expr()?));
+
+                if !self.eat_symbol(",") {
+                    self.expect_symbol(")")?;
+                    break;
+                }
+            }
+
+            self.expect_symbol(";")?;
+
+            ActionKind::Call(function, arguments)
+        } else {
+            let (name, _) = self.expect_ident()?;
+            self.expect_symbol("(")?;
+
+            let mut arguments = Vec::new();
+
+            while !self.eat_symbol(")") {
+                let name = match (self.peek().kind.clone(), self.peek_kind_at(1)) {
+                    (TokenKind::Ident(name), TokenKind::Symbol(":")) => {
+                        let span = self.advance().span;
+                        self.advance();
+                        Some((name, span))
+                    }
+                    _ => None,
+                };
+
+                arguments.push(Arg { name, value: self.expr()? });
+
+                if !self.eat_symbol(",") {
+                    self.expect_symbol(")")?;
+                    break;
+                }
+            }
+
+            self.expect_symbol(";")?;
+
+            ActionKind::Named(name, arguments)
+        };
+
+        Ok(ActionStmt {
+            closed,
+            wait,
+            kind,
+            span: Span {
+                start,
+                end: self.previous_end(),
+            },
+        })
+    }
+
+    fn expr_list(&mut self, open: &str, close: &str) -> Result<Vec<Expr>, SyntaxError> {
+        self.expect_symbol(open)?;
+
+        let mut exprs = Vec::new();
+
+        while !self.eat_symbol(close) {
+            exprs.push(self.expr()?);
+
+            if !self.eat_symbol(",") {
+                self.expect_symbol(close)?;
+                break;
+            }
+        }
+
+        Ok(exprs)
+    }
+
+    pub fn expr(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(1)
+    }
+
+    fn binary_op(&self) -> Option<BinaryOp> {
+        BinaryOp::ALL.into_iter().find(|op| self.is_symbol(op.symbol()))
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.unary()?;
+
+        while let Some(op) = self.binary_op().filter(|op| op.precedence() >= min_precedence) {
+            self.advance();
+
+            let rhs = self.binary(op.precedence() + 1)?;
+            let span = Span {
+                start: lhs.span.start,
+                end: rhs.span.end,
+            };
+
+            lhs = Expr {
+                kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                span,
+                overrides: Vec::new(),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, SyntaxError> {
+        if self.is_symbol("!") {
+            let start = self.advance().span.start;
+            let operand = self.unary()?;
+            let span = Span {
+                start,
+                end: operand.span.end,
+            };
+
+            return Ok(Expr {
+                kind: ExprKind::Not(Box::new(operand)),
+                span,
+                overrides: Vec::new(),
+            });
+        }
+
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.primary()?;
+
+        while self.eat_keyword("with") {
+            self.expect_symbol("{")?;
+
+            while !self.eat_symbol("}") {
+                let (slot, span) = self.expect_ident()?;
+                self.expect_symbol(":")?;
+                expr.overrides.push((slot, span, self.expr()?));
+
+                if !self.eat_symbol(",") {
+                    self.expect_symbol("}")?;
+                    break;
+                }
+            }
+
+            expr.span.end = self.previous_end();
+        }
+
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, SyntaxError> {
+        let token = self.advance();
+        let start = token.span.start;
+
+        let kind = match token.kind {
+            TokenKind::Int(value) => ExprKind::Int(value),
+            TokenKind::Float(value) => ExprKind::Float(value),
+            TokenKind::Str(value) => ExprKind::Str(value),
+            TokenKind::Symbol("-") => match self.advance().kind {
+                TokenKind::Int(value) => ExprKind::Int(-value),
+                TokenKind::Float(value) => ExprKind::Float(-value),
+                TokenKind::Ident(name) if name == "inf" => ExprKind::Float(f32::NEG_INFINITY),
+                _ => {
+                    return Err(SyntaxError {
+                        message: "`-` can only be used on number literals, use subtraction instead".to_string(),
+                        span: token.span,
+                    })
+                }
+            },
+            TokenKind::Symbol("(") => {
+                let mut inner = self.expr()?;
+                self.expect_symbol(")")?;
+                inner.span = Span {
+                    start,
+                    end: self.previous_end(),
+                };
+                return Ok(inner);
+            }
+            TokenKind::Symbol("[") => {
+                self.index -= 1;
+                ExprKind::List(None, self.expr_list("[", "]")?)
+            }
+            TokenKind::Symbol(symbol @ ("$" | "@")) => {
+                let reference = self.expect_reference()?;
+                let static_type = if self.eat_symbol(":") { Some(self.expect_ident()?.0) } else { None };
+
+                if symbol == "$" {
+                    ExprKind::Variable(reference, static_type)
+                } else {
+                    ExprKind::Parameter(reference, static_type)
+                }
+            }
+            TokenKind::Ident(name) => match name.as_str() {
+                "true" => ExprKind::Bool(true),
+                "false" => ExprKind::Bool(false),
+                "none" => ExprKind::None,
+                "inf" => ExprKind::Float(f32::INFINITY),
+                "nan" => ExprKind::Float(f32::NAN),
+                _ if self.is_symbol("(") => ExprKind::Call(name, self.expr_list("(", ")")?),
+                _ if self.is_symbol("[") => ExprKind::List(Some(name), self.expr_list("[", "]")?),
+                _ => ExprKind::Name(name),
+            },
+            other => {
+                return Err(SyntaxError {
+                    message: format!("expected an expression, found {}", Self::describe(&other)),
+                    span: token.span,
+                })
+            }
+        };
+
+        Ok(Expr {
+            kind,
+            span: Span {
+                start,
+                end: self.previous_end(),
+            },
+            overrides: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,435 @@
+//! Turns nova script types back into text.
+
+use std::fmt::Write as _;
+
+use ordered_float::OrderedFloat;
+
+use super::lower::{binary_dynamic_type, compares_ints, float_from_int};
+use super::parser::{BinaryOp, UNARY_PRECEDENCE};
+use super::{
+    action_name, activator_name, dynamic_type_name, easing_name, reference_text, static_type_name, Scope, ID_CONSTANTS,
+    LIST_CONSTANTS,
+};
+use crate::types::{
+    color::Color,
+    nova_script::{
+        action::Action, action_type::ActionType, dynamic_type::DynamicType, nova_value::NovaValue,
+        static_type::StaticType, NovaScript,
+    },
+    vec2::Vec2,
+};
+
+const INDENT: &str = "    ";
+
+/// Precedence of primaries and of expressions followed by `with { ... }`.
+const PRIMARY_PRECEDENCE: u8 = UNARY_PRECEDENCE + 1;
+
+fn float(value: OrderedFloat<f32>) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else {
+        format!("{:?}", value.0)
+    }
+}
+
+fn color(value: Color) -> String {
+    format!("rgba({}, {}, {}, {})", float(value.r), float(value.g), float(value.b), float(value.a))
+}
+
+fn vector(value: Vec2) -> String {
+    format!("vec({}, {})", float(value.x), float(value.y))
+}
+
+fn list<T>(values: &[T], element: impl Fn(&T) -> String) -> String {
+    values.iter().map(element).collect::<Vec<_>>().join(", ")
+}
+
+fn optional_list<T>(values: Option<&Vec<T>>, element: impl Fn(&T) -> String) -> String {
+    values.map_or_else(|| "none".to_string(), |values| format!("[{}]", list(values, element)))
+}
+
+fn parenthesize(text: String, precedence: u8, required: u8) -> String {
+    if precedence < required {
+        format!("({text})")
+    } else {
+        text
+    }
+}
+
+/// Prints a value as an expression that compiles back to the same `NovaValue` in the given scope.
+#[must_use]
+pub fn decompile_value(value: &NovaValue, scope: &Scope) -> String {
+    expr(value, scope).0
+}
+
+/// Returns the text of the expression together with its precedence.
+fn expr(value: &NovaValue, scope: &Scope) -> (String, u8) {
+    let dynamic_type = value.dynamic_type;
+    let mut canonical = NovaValue {
+        dynamic_type,
+        ..Default::default()
+    };
+
+    let id_constant = ID_CONSTANTS.iter().find(|(_, constant)| *constant == dynamic_type);
+    let list_constant = LIST_CONSTANTS.iter().find(|(_, constant)| *constant == dynamic_type);
+
+    let (text, precedence) = if dynamic_type == DynamicType::BoolConstant {
+        canonical.bool_value = value.bool_value;
+        (value.bool_value.to_string(), PRIMARY_PRECEDENCE)
+    } else if dynamic_type == DynamicType::IntConstant {
+        canonical.int_value = value.int_value;
+        (value.int_value.to_string(), PRIMARY_PRECEDENCE)
+    } else if dynamic_type == DynamicType::FloatConstant {
+        canonical.float_value = value.float_value;
+        (float(value.float_value), PRIMARY_PRECEDENCE)
+    } else if let (DynamicType::StringConstant, Some(string)) = (dynamic_type, &value.string_value) {
+        canonical.string_value = Some(string.clone());
+        (format!("{string:?}"), PRIMARY_PRECEDENCE)
+    } else if dynamic_type == DynamicType::ColorConstant {
+        canonical.color_value = value.color_value;
+        (color(value.color_value), PRIMARY_PRECEDENCE)
+    } else if dynamic_type == DynamicType::VectorConstant {
+        canonical.vector_value = value.vector_value;
+        (vector(value.vector_value), PRIMARY_PRECEDENCE)
+    } else if let Some(name) = easing_name(value.int_value).filter(|_| dynamic_type == DynamicType::EasingConstant) {
+        canonical.int_value = value.int_value;
+        (name, PRIMARY_PRECEDENCE)
+    } else if let Some((name, _)) = id_constant {
+        canonical.int_value = value.int_value;
+        (format!("{name}({})", value.int_value), PRIMARY_PRECEDENCE)
+    } else if let Some(text) = list_constant.and_then(|(name, _)| list_constant_text(name, value, &mut canonical)) {
+        (text, PRIMARY_PRECEDENCE)
+    } else if let Some(static_type) = dynamic_type.variable_static_type() {
+        canonical.int_value = value.int_value;
+
+        let reference = reference_text(&scope.variable_names, value.int_value);
+        let text = if scope.variables.get(&value.int_value) == Some(&static_type) {
+            format!("${reference}")
+        } else {
+            format!("${reference}:{}", static_type_name(static_type))
+        };
+
+        (text, PRIMARY_PRECEDENCE)
+    } else if let Some(static_type) = dynamic_type.parameter_static_type() {
+        canonical.int_value = value.int_value;
+
+        let reference = reference_text(&scope.parameter_names, value.int_value);
+        let text = if scope.parameters.get(&value.int_value) == Some(&static_type) {
+            format!("@{reference}")
+        } else {
+            format!("@{reference}:{}", static_type_name(static_type))
+        };
+
+        (text, PRIMARY_PRECEDENCE)
+    } else {
+        let operands = value.sub_values.as_deref().unwrap_or_default();
+
+        if !operands.is_empty() {
+            canonical.sub_values.clone_from(&value.sub_values);
+        }
+
+        operation(dynamic_type, operands, scope)
+    };
+
+    let overrides = overrides(value, &canonical, scope);
+
+    if overrides.is_empty() {
+        (text, precedence)
+    } else {
+        (
+            format!("{} with {{ {} }}", parenthesize(text, precedence, PRIMARY_PRECEDENCE), overrides.join(", ")),
+            PRIMARY_PRECEDENCE,
+        )
+    }
+}
+
+fn list_constant_text(name: &str, value: &NovaValue, canonical: &mut NovaValue) -> Option<String> {
+    let elements = match value.dynamic_type {
+        DynamicType::BoolListConstant => {
+            canonical.bool_list_values.clone_from(&value.bool_list_values);
+            list(value.bool_list_values.as_ref()?, ToString::to_string)
+        }
+        DynamicType::FloatListConstant => {
+            canonical.float_list_values.clone_from(&value.float_list_values);
+            list(value.float_list_values.as_ref()?, |value| float(*value))
+        }
+        DynamicType::StringListConstant => {
+            canonical.string_list_values.clone_from(&value.string_list_values);
+            list(value.string_list_values.as_ref()?, |value| format!("{value:?}"))
+        }
+        DynamicType::ColorListConstant => {
+            canonical.color_list_values.clone_from(&value.color_list_values);
+            list(value.color_list_values.as_ref()?, |value| color(*value))
+        }
+        DynamicType::VectorListConstant => {
+            canonical.vector_list_values.clone_from(&value.vector_list_values);
+            list(value.vector_list_values.as_ref()?, |value| vector(*value))
+        }
+        _ => {
+            canonical.int_list_values.clone_from(&value.int_list_values);
+            list(value.int_list_values.as_ref()?, ToString::to_string)
+        }
+    };
+
+    Some(format!("{name}[{elements}]"))
+}
+
+/// Returns the int inside of a `FloatFromInt` that [`float_from_int`] would produce.
+fn int_operand(value: &NovaValue) -> Option<&NovaValue> {
+    match value.sub_values.as_deref() {
+        Some([int]) if int.dynamic_type.result_type() == StaticType::Int && *value == float_from_int(int.clone()) => {
+            Some(int)
+        }
+        _ => None,
+    }
+}
+
+fn operation(dynamic_type: DynamicType, operands: &[NovaValue], scope: &Scope) -> (String, u8) {
+    if let [lhs, rhs] = operands {
+        // comparisons of ints are written without the conversions to float that compiling them adds back
+        let unwrapped = int_operand(lhs).zip(int_operand(rhs)).map(|(lhs, rhs)| (lhs, rhs, true));
+
+        for (lhs, rhs, converted) in unwrapped.into_iter().chain([(lhs, rhs, false)]) {
+            let (lhs_type, rhs_type) = (lhs.dynamic_type.result_type(), rhs.dynamic_type.result_type());
+
+            if compares_ints(dynamic_type, lhs_type) != converted {
+                continue;
+            }
+
+            let Some(op) = BinaryOp::ALL
+                .into_iter()
+                .find(|&op| binary_dynamic_type(op, lhs_type, rhs_type) == Some(dynamic_type))
+            else {
+                continue;
+            };
+
+            let (lhs, lhs_precedence) = expr(lhs, scope);
+            let (rhs, rhs_precedence) = expr(rhs, scope);
+            let precedence = op.precedence();
+
+            return (
+                format!(
+                    "{} {} {}",
+                    parenthesize(lhs, lhs_precedence, precedence),
+                    op.symbol(),
+                    parenthesize(rhs, rhs_precedence, precedence + 1)
+                ),
+                precedence,
+            );
+        }
+    }
+
+    if let (DynamicType::BoolNot, [operand]) = (dynamic_type, operands) {
+        if operand.dynamic_type.result_type() == StaticType::Bool {
+            let (operand, operand_precedence) = expr(operand, scope);
+
+            return (format!("!{}", parenthesize(operand, operand_precedence, UNARY_PRECEDENCE)), UNARY_PRECEDENCE);
+        }
+    }
+
+    (
+        format!(
+            "{}({})",
+            dynamic_type_name(dynamic_type),
+            list(operands, |operand| decompile_value(operand, scope))
+        ),
+        PRIMARY_PRECEDENCE,
+    )
+}
+
+/// Returns `slot: value` for every slot in which `value` differs from what its expression compiles to.
+fn overrides(value: &NovaValue, canonical: &NovaValue, scope: &Scope) -> Vec<String> {
+    let mut overrides = Vec::new();
+
+    if value.bool_value != canonical.bool_value {
+        overrides.push(format!("bool: {}", value.bool_value));
+    }
+    if value.int_value != canonical.int_value {
+        overrides.push(format!("int: {}", value.int_value));
+    }
+    if value.float_value != canonical.float_value {
+        overrides.push(format!("float: {}", float(value.float_value)));
+    }
+    if value.string_value != canonical.string_value {
+        let string = value.string_value.as_ref().map_or_else(|| "none".to_string(), |string| format!("{string:?}"));
+        overrides.push(format!("string: {string}"));
+    }
+    if value.color_value != canonical.color_value {
+        overrides.push(format!("color: {}", color(value.color_value)));
+    }
+    if value.vector_value != canonical.vector_value {
+        overrides.push(format!("vector: {}", vector(value.vector_value)));
+    }
+    if value.bool_list_values != canonical.bool_list_values {
+        overrides.push(format!("bools: {}", optional_list(value.bool_list_values.as_ref(), ToString::to_string)));
+    }
+    if value.int_list_values != canonical.int_list_values {
+        overrides.push(format!("ints: {}", optional_list(value.int_list_values.as_ref(), ToString::to_string)));
+    }
+    if value.float_list_values != canonical.float_list_values {
+        overrides.push(format!("floats: {}", optional_list(value.float_list_values.as_ref(), |value| float(*value))));
+    }
+    if value.string_list_values != canonical.string_list_values {
+        overrides.push(format!(
+            "strings: {}",
+            optional_list(value.string_list_values.as_ref(), |value| format!("{value:?}"))
+        ));
+    }
+    if value.color_list_values != canonical.color_list_values {
+        overrides.push(format!("colors: {}", optional_list(value.color_list_values.as_ref(), |value| color(*value))));
+    }
+    if value.vector_list_values != canonical.vector_list_values {
+        overrides.push(format!("vectors: {}", optional_list(value.vector_list_values.as_ref(), |value| vector(*value))));
+    }
+    if value.sub_values != canonical.sub_values {
+        overrides.push(format!(
+            "sub: {}",
+            optional_list(value.sub_values.as_ref(), |value| decompile_value(value, scope))
+        ));
+    }
+
+    overrides
+}
+
+/// Prints an action, including the actions nested inside it, as a statement.
+#[must_use]
+pub fn decompile_action(action: &Action, scope: &Scope) -> String {
+    let mut out = String::new();
+    write_action(&mut out, action, scope, 0);
+    out
+}
+
+fn write_block(out: &mut String, actions: &[Action], scope: &Scope, depth: usize) {
+    out.push_str("{\n");
+
+    for action in actions {
+        write_action(out, action, scope, depth + 1);
+    }
+
+    out.push_str(&INDENT.repeat(depth));
+    out.push('}');
+}
+
+fn write_action(out: &mut String, action: &Action, scope: &Scope, depth: usize) {
+    out.push_str(&INDENT.repeat(depth));
+
+    if action.closed {
+        out.push_str("closed ");
+    }
+
+    if action.wait {
+        out.push_str("await ");
+    }
+
+    match &action.action_type {
+        ActionType::Repeat { actions, count } => {
+            let _ = write!(out, "repeat {} ", decompile_value(count, scope));
+            write_block(out, actions, scope, depth);
+        }
+        ActionType::RepeatWhile { actions, condition } => {
+            let _ = write!(out, "while {} ", decompile_value(condition, scope));
+            write_block(out, actions, scope, depth);
+        }
+        ActionType::RepeatForEachObject { actions, target_objects } => {
+            let _ = write!(out, "for_each {} ", decompile_value(target_objects, scope));
+            write_block(out, actions, scope, depth);
+        }
+        ActionType::ConditionBlock {
+            if_actions,
+            else_actions,
+            condition,
+        } => {
+            let _ = write!(out, "if {} ", decompile_value(condition, scope));
+            write_block(out, if_actions, scope, depth);
+
+            if !else_actions.is_empty() {
+                out.push_str(" else ");
+                write_block(out, else_actions, scope, depth);
+            }
+        }
+        ActionType::VariableSet { variable, value } => {
+            let value = value.as_ref().map_or_else(|| "none".to_string(), |value| decompile_value(value, scope));
+            let _ = write!(out, "${} = {value};", reference_text(&scope.variable_names, *variable));
+        }
+        ActionType::RunFunction { function } => {
+            let arguments = list(&function.parameters, |argument| {
+                format!("{}: {}", argument.parameter_id, decompile_value(&argument.value, scope))
+            });
+            let _ = write!(out, "call {}({arguments});", function.id);
+        }
+        action_type => {
+            let mut arguments = Vec::new();
+
+            if let Some(variable) = action_type.target_variable() {
+                arguments.push(format!("variable: ${}", reference_text(&scope.variable_names, variable)));
+            }
+
+            if let ActionType::VariableSetOverTime { value: None, .. } = action_type {
+                arguments.push("value: none".to_string());
+            }
+
+            arguments.extend(
+                action_type
+                    .values()
+                    .into_iter()
+                    .map(|(field, value)| format!("{field}: {}", decompile_value(value, scope))),
+            );
+
+            let _ = write!(out, "{}({});", action_name(action_type), arguments.join(", "));
+        }
+    }
+
+    out.push('\n');
+}
+
+pub fn script(script: &NovaScript, scope: &Scope) -> String {
+    let mut out = String::new();
+    let keyword = if script.is_function { "function" } else { "script" };
+
+    let _ = writeln!(out, "{keyword} {} {:?} {{", script.script_id, script.script_name);
+
+    for parameter in &script.parameters {
+        let _ = writeln!(
+            out,
+            "{INDENT}param {} {:?}: {} = {};",
+            parameter.parameter_id,
+            parameter.name,
+            static_type_name(parameter.static_type),
+            decompile_value(&parameter.default_value, scope)
+        );
+    }
+
+    for variable in &script.variables {
+        let persistance = variable
+            .persistance
+            .map_or_else(String::new, |persistance| format!(" persist {persistance}"));
+
+        let _ = writeln!(
+            out,
+            "{INDENT}var {} {:?}: {}{persistance} = {};",
+            variable.variable_id,
+            variable.name,
+            static_type_name(variable.static_type),
+            decompile_value(&variable.initial_value, scope)
+        );
+    }
+
+    let _ = writeln!(out, "{INDENT}activation_count {};", script.activation_count);
+    let _ = writeln!(out, "{INDENT}condition {};", decompile_value(&script.condition, scope));
+
+    for activator in &script.activation_list {
+        let _ = writeln!(
+            out,
+            "{INDENT}on {}({});",
+            activator_name(activator.activator_type).unwrap_or_else(|| activator.activator_type.to_string()),
+            list(&activator.parameters, |parameter| decompile_value(parameter, scope))
+        );
+    }
+
+    for action in &script.actions {
+        write_action(&mut out, action, scope, 1);
+    }
+
+    out.push_str("}\n");
+    out
+}
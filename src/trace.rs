@@ -0,0 +1,188 @@
+//! Tracing black-and-white images into terrain, so collision shapes can be sketched in an image editor.
+//!
+//! The solid pixels are outlined with marching squares ([`contour_outlines`]) and the outlines are simplified with
+//! Douglas–Peucker ([`simplify`]). Every connected region becomes one object, with its holes as extra rings.
+
+use glam::IVec2;
+use image::{Pixel, RgbaImage};
+
+use crate::error::Error;
+use crate::geometry::{contour_outlines, simplify};
+use crate::shapes::ShapeKind;
+use crate::types::{
+    level_data::LevelData, object::Object, object_id::ObjectId, object_property::ObjectProperty, vec2::Vec2,
+};
+
+/// How [`LevelData::trace_mask`] turns an image into objects.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TraceOptions {
+    /// The object the regions become, which has to be terrain-like, like `Terrain`, `Ice`, `Area` or
+    /// `FloatingZone`.
+    pub object_id: ObjectId,
+    /// The size of a pixel in world units.
+    pub pixel_size: f32,
+    /// Where the top left corner of the image ends up in the world.
+    pub origin: Vec2,
+    /// How far in pixels the simplified outlines may stray from the traced ones. Zero only merges straight runs.
+    pub tolerance: f32,
+    /// Pixels darker than this are solid, as long as they're at least half opaque.
+    pub threshold: u8,
+    /// Whether the pixels at least as light as the threshold are solid instead.
+    pub invert: bool,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            object_id: ObjectId::Terrain,
+            pixel_size: 0.25,
+            origin: Vec2::ZERO,
+            tolerance: 0.5,
+            threshold: 128,
+            invert: false,
+        }
+    }
+}
+
+impl LevelData {
+    /// Traces the solid pixels of the image and adds every connected region to the layer as an object with
+    /// `TerrainCorners`, returning their entity ids.
+    ///
+    /// Each object is positioned at the center of its region, and pixels that only touch at a corner end up in
+    /// separate regions. The layer is created if the level doesn't have it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotTerrainLike`] if the object id of the options isn't drawn from `TerrainCorners`.
+    pub fn trace_mask(&mut self, image: &RgbaImage, layer_id: i32, options: &TraceOptions) -> Result<Vec<i32>, Error> {
+        if !ShapeKind::of(options.object_id).is_terrain_like() {
+            return Err(Error::NotTerrainLike(options.object_id));
+        }
+
+        let cells = image.enumerate_pixels().filter_map(|(x, y, pixel)| {
+            let [luma, alpha] = pixel.to_luma_alpha().0;
+            let solid = alpha >= 128 && ((luma < options.threshold) != options.invert);
+
+            // Rows go down in the image, so they become negative cells to keep the world the right way up.
+            #[allow(clippy::cast_possible_wrap)]
+            solid.then(|| IVec2::new(x as i32, -(y as i32)))
+        });
+
+        // The cell of the top left pixel is centered on the origin of the grid.
+        let to_world = |point: Vec2| options.origin + (point + Vec2::new(0.5, -0.5)) * options.pixel_size;
+        let mut entity_ids = vec![];
+
+        for (polygon, entity_id) in contour_outlines(cells).into_iter().zip(self.next_entity_id()..) {
+            let rings: Vec<Vec<Vec2>> = polygon
+                .iter()
+                .map(|ring| simplify(ring, options.tolerance).into_iter().map(to_world).collect())
+                .collect();
+
+            let (min, max) = rings[0]
+                .iter()
+                .fold((rings[0][0], rings[0][0]), |(min, max), &point| (min.min(point), max.max(point)));
+            let position = min.midpoint(max);
+
+            let mut object = Object::get_default_with_id(options.object_id, layer_id);
+            object.entity_id = entity_id;
+            object.position = position;
+            object.properties = vec![ObjectProperty::TerrainCorners(
+                rings.into_iter().map(|ring| ring.into_iter().map(|point| point - position).collect()).collect(),
+            )];
+
+            self.objects.push(object);
+            entity_ids.push(entity_id);
+        }
+
+        self.layer_or_insert(layer_id, "Traced").children.extend(&entity_ids);
+
+        Ok(entity_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::signed_area;
+    use image::Rgba;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    /// An image with the pixels marked `#` black and the others white.
+    fn mask(rows: &[&str]) -> RgbaImage {
+        #[allow(clippy::cast_possible_truncation)]
+        let (width, height) = (rows[0].len() as u32, rows.len() as u32);
+
+        RgbaImage::from_fn(width, height, |x, y| {
+            if rows[y as usize].as_bytes()[x as usize] == b'#' { BLACK } else { Rgba([255; 4]) }
+        })
+    }
+
+    fn corners(object: &Object) -> &Vec<Vec<Vec2>> {
+        match &object.properties[..] {
+            [ObjectProperty::TerrainCorners(rings)] => rings,
+            properties => panic!("expected only TerrainCorners, got {properties:?}"),
+        }
+    }
+
+    #[test]
+    fn hole() {
+        let mut level = LevelData::default();
+        let options = TraceOptions { pixel_size: 1.0, tolerance: 0.0, ..TraceOptions::default() };
+
+        assert_eq!(level.trace_mask(&mask(&["###", "#.#", "###"]), 2, &options).unwrap(), [1]);
+
+        let object = &level.objects[0];
+        let rings = corners(object);
+        assert_eq!((object.object_id, object.in_layer), (ObjectId::Terrain, 2));
+        assert_eq!(object.position, Vec2::new(1.5, -1.5));
+        assert_eq!(level.layers[0].children, [1]);
+
+        // The outer ring is the 3 by 3 square with its corners cut off, and the hole a diamond around the middle.
+        assert_eq!(rings.len(), 2);
+        assert_eq!((rings[0].len(), signed_area(&rings[0])), (8, 8.5));
+        assert_eq!((rings[1].len(), signed_area(&rings[1])), (4, -0.5));
+        assert!(rings[1].contains(&Vec2::new(0.0, 0.5)) && rings[1].contains(&Vec2::new(-0.5, 0.0)));
+    }
+
+    #[test]
+    fn regions() {
+        let mut level = LevelData::default();
+        let image = mask(&["#..", ".#.", "..."]);
+
+        // Pixels touching at a corner are separate regions.
+        let entity_ids = level.trace_mask(&image, 1, &TraceOptions::default()).unwrap();
+        assert_eq!(entity_ids, [1, 2]);
+        assert!(level.objects.iter().all(|object| corners(object).len() == 1));
+
+        // Inverted, the white pixels are a single region with no holes.
+        let options = TraceOptions { invert: true, origin: Vec2::new(10.0, 0.0), ..TraceOptions::default() };
+        assert_eq!(level.trace_mask(&image, 1, &options).unwrap(), [3]);
+        assert_eq!(corners(&level.objects[2]).len(), 1);
+        assert_eq!(level.layers[0].children, [1, 2, 3]);
+    }
+
+    #[test]
+    fn threshold_and_alpha() {
+        let mut image = RgbaImage::from_pixel(2, 1, Rgba([100, 100, 100, 255]));
+        image.put_pixel(1, 0, Rgba([0, 0, 0, 127]));
+        let mut level = LevelData::default();
+
+        let threshold = |threshold: u8| TraceOptions { threshold, ..TraceOptions::default() };
+
+        // The grey pixel has to be darker than the threshold, and the black one is too transparent either way.
+        assert!(level.trace_mask(&image, 1, &threshold(100)).unwrap().is_empty());
+        assert_eq!(level.trace_mask(&image, 1, &threshold(101)).unwrap(), [1]);
+        assert_eq!(corners(&level.objects[0])[0].len(), 4);
+    }
+
+    #[test]
+    fn not_terrain_like() {
+        let options = TraceOptions { object_id: ObjectId::SpriteSquare, ..TraceOptions::default() };
+
+        assert!(matches!(
+            LevelData::default().trace_mask(&mask(&["#"]), 1, &options),
+            Err(Error::NotTerrainLike(ObjectId::SpriteSquare))
+        ));
+    }
+}
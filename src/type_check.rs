@@ -0,0 +1,458 @@
+//! A static type checker for nova scripts.
+//!
+//! Every `NovaValue` is checked against the [`StaticType`] its position requires: action fields use
+//! [`ActionType::value_type`], operands use [`DynamicType::operand_types`], variable writes, initial values
+//! and function arguments use the declared type of the variable or parameter. Function calls may pass at most one
+//! argument per parameter of the function. They may leave parameters out: every [`Parameter`] has a `default_value`,
+//! which is what the function reads instead (the interpreter does the same), so no parameter is required.
+//!
+//! [`Parameter`]: crate::types::nova_script::parameter::Parameter
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::types::{
+    level_data::LevelData,
+    nova_script::{
+        action::Action,
+        action_type::ActionType,
+        dynamic_type::DynamicType,
+        nova_value::NovaValue,
+        path::{PathSegment, ScriptPath},
+        static_type::StaticType,
+        NovaScript,
+    },
+};
+
+/// What is wrong with a value.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TypeErrorKind {
+    /// The value evaluates to `found` where a value of type `expected` is required.
+    Mismatch { expected: StaticType, found: StaticType },
+    /// The value has a different number of `sub_values` than its dynamic type takes operands.
+    OperandCount { dynamic_type: DynamicType, expected: usize, found: usize },
+    /// The value reads or the action writes a variable that doesn't exist.
+    UnknownVariable(i32),
+    /// The value reads a variable through a dynamic type of a different type than the variable is declared with.
+    VariableType { variable_id: i32, declared: StaticType, used: StaticType },
+    /// The value reads a parameter that the enclosing script doesn't declare.
+    UnknownParameter(i32),
+    /// The value reads a parameter through a dynamic type of a different type than the parameter is declared with.
+    ParameterType { parameter_id: i32, declared: StaticType, used: StaticType },
+    /// A `RunFunction` action calls a script that doesn't exist or isn't a function.
+    UnknownFunction(i32),
+    /// A `RunFunction` action passes an argument for a parameter that the function doesn't declare.
+    UnknownArgument { function_id: i32, parameter_id: i32 },
+    /// A `RunFunction` action passes several arguments for the same parameter.
+    DuplicateArgument { function_id: i32, parameter_id: i32 },
+}
+
+impl fmt::Display for TypeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch { expected, found } => write!(f, "expected a {expected:?} value, found {found:?}"),
+            Self::OperandCount { dynamic_type, expected, found } => {
+                write!(f, "{dynamic_type:?} takes {expected} operand(s), found {found}")
+            }
+            Self::UnknownVariable(id) => write!(f, "variable {id} doesn't exist"),
+            Self::VariableType { variable_id, declared, used } => {
+                write!(f, "variable {variable_id} is a {declared:?} but is read as {used:?}")
+            }
+            Self::UnknownParameter(id) => write!(f, "parameter {id} doesn't exist in this script"),
+            Self::ParameterType { parameter_id, declared, used } => {
+                write!(f, "parameter {parameter_id} is a {declared:?} but is read as {used:?}")
+            }
+            Self::UnknownFunction(id) => write!(f, "function {id} doesn't exist"),
+            Self::UnknownArgument { function_id, parameter_id } => {
+                write!(f, "function {function_id} has no parameter {parameter_id}")
+            }
+            Self::DuplicateArgument { function_id, parameter_id } => {
+                write!(f, "several arguments are passed for parameter {parameter_id} of function {function_id}")
+            }
+        }
+    }
+}
+
+/// A type error and where it was found.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct TypeError {
+    /// The location of the offending value or action.
+    pub path: ScriptPath,
+    /// What is wrong.
+    pub kind: TypeErrorKind,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+/// The variables, parameters and functions visible to the value being checked.
+struct Scope<'a> {
+    variables: BTreeMap<i32, StaticType>,
+    parameters: BTreeMap<i32, StaticType>,
+    functions: &'a BTreeMap<i32, &'a NovaScript>,
+}
+
+struct Checker {
+    errors: Vec<TypeError>,
+}
+
+impl LevelData {
+    /// Type checks every script, function call and variable initial value of the level.
+    #[must_use]
+    pub fn type_check(&self) -> Vec<TypeError> {
+        let functions: BTreeMap<i32, &NovaScript> = self
+            .nova_scripts
+            .iter()
+            .filter(|script| script.is_function)
+            .map(|script| (script.script_id, script))
+            .collect();
+
+        let globals: BTreeMap<i32, StaticType> = self
+            .global_variables
+            .iter()
+            .map(|variable| (variable.variable_id, variable.static_type))
+            .collect();
+
+        let mut checker = Checker { errors: Vec::new() };
+
+        let global_scope = Scope {
+            variables: globals.clone(),
+            parameters: BTreeMap::new(),
+            functions: &functions,
+        };
+
+        for variable in &self.global_variables {
+            checker.check_value(
+                &variable.initial_value,
+                Some(variable.static_type),
+                &global_scope,
+                &ScriptPath::globals().join(PathSegment::VariableInitialValue(variable.variable_id)),
+            );
+        }
+
+        for script in &self.nova_scripts {
+            let mut variables = globals.clone();
+            variables.extend(script.variables.iter().map(|variable| (variable.variable_id, variable.static_type)));
+
+            let scope = Scope {
+                variables,
+                parameters: script
+                    .parameters
+                    .iter()
+                    .map(|parameter| (parameter.parameter_id, parameter.static_type))
+                    .collect(),
+                functions: &functions,
+            };
+
+            checker.check_script(script, &scope);
+        }
+
+        checker.errors
+    }
+}
+
+impl Checker {
+    fn report(&mut self, path: &ScriptPath, kind: TypeErrorKind) {
+        self.errors.push(TypeError { path: path.clone(), kind });
+    }
+
+    fn check_script(&mut self, script: &NovaScript, scope: &Scope) {
+        let path = ScriptPath::script(script.script_id);
+
+        self.check_value(&script.condition, Some(StaticType::Bool), scope, &path.join(PathSegment::Condition));
+
+        for (index, activator) in script.activation_list.iter().enumerate() {
+            let activator_path = path.join(PathSegment::Activator(index));
+
+            for (index, parameter) in activator.parameters.iter().enumerate() {
+                self.check_value(parameter, None, scope, &activator_path.join(PathSegment::ActivatorParameter(index)));
+            }
+        }
+
+        for parameter in &script.parameters {
+            self.check_value(
+                &parameter.default_value,
+                Some(parameter.static_type),
+                scope,
+                &path.join(PathSegment::ParameterDefault(parameter.parameter_id)),
+            );
+        }
+
+        for variable in &script.variables {
+            self.check_value(
+                &variable.initial_value,
+                Some(variable.static_type),
+                scope,
+                &path.join(PathSegment::VariableInitialValue(variable.variable_id)),
+            );
+        }
+
+        self.check_actions(&script.actions, scope, &path.join(PathSegment::Actions("actions")));
+    }
+
+    fn check_actions(&mut self, actions: &[Action], scope: &Scope, path: &ScriptPath) {
+        for (index, action) in actions.iter().enumerate() {
+            self.check_action(&action.action_type, scope, &path.join(PathSegment::Action(index)));
+        }
+    }
+
+    fn check_action(&mut self, action: &ActionType, scope: &Scope, path: &ScriptPath) {
+        let target_type = action.target_variable().and_then(|variable| {
+            let declared = scope.variables.get(&variable).copied();
+
+            if declared.is_none() {
+                self.report(path, TypeErrorKind::UnknownVariable(variable));
+            }
+
+            declared
+        });
+
+        for (field, value) in action.values() {
+            let expected = if field == "value" && action.target_variable().is_some() {
+                target_type
+            } else {
+                action.value_type(field)
+            };
+
+            self.check_value(value, expected, scope, &path.join(PathSegment::Field(field)));
+        }
+
+        if let Some(call) = action.function_call() {
+            let function = scope.functions.get(&call.id);
+
+            if function.is_none() {
+                self.report(path, TypeErrorKind::UnknownFunction(call.id));
+            }
+
+            for (index, argument) in call.parameters.iter().enumerate() {
+                let argument_path = path.join(PathSegment::Argument(argument.parameter_id));
+
+                let declared = function.map(|function| {
+                    function
+                        .parameters
+                        .iter()
+                        .find(|parameter| parameter.parameter_id == argument.parameter_id)
+                        .map(|parameter| parameter.static_type)
+                });
+
+                if declared == Some(None) {
+                    self.report(
+                        &argument_path,
+                        TypeErrorKind::UnknownArgument {
+                            function_id: call.id,
+                            parameter_id: argument.parameter_id,
+                        },
+                    );
+                }
+
+                if call.parameters[..index].iter().any(|other| other.parameter_id == argument.parameter_id) {
+                    self.report(
+                        &argument_path,
+                        TypeErrorKind::DuplicateArgument {
+                            function_id: call.id,
+                            parameter_id: argument.parameter_id,
+                        },
+                    );
+                }
+
+                self.check_value(&argument.value, declared.flatten(), scope, &argument_path);
+            }
+        }
+
+        for (field, actions) in action.children() {
+            self.check_actions(actions, scope, &path.join(PathSegment::Actions(field)));
+        }
+    }
+
+    fn check_value(&mut self, value: &NovaValue, expected: Option<StaticType>, scope: &Scope, path: &ScriptPath) {
+        let dynamic_type = value.dynamic_type;
+        let found = dynamic_type.result_type();
+
+        if let Some(expected) = expected {
+            if expected != found {
+                self.report(path, TypeErrorKind::Mismatch { expected, found });
+            }
+        }
+
+        if let Some(used) = dynamic_type.variable_static_type() {
+            match scope.variables.get(&value.int_value) {
+                None => self.report(path, TypeErrorKind::UnknownVariable(value.int_value)),
+                Some(&declared) if declared != used => self.report(
+                    path,
+                    TypeErrorKind::VariableType {
+                        variable_id: value.int_value,
+                        declared,
+                        used,
+                    },
+                ),
+                Some(_) => {}
+            }
+        }
+
+        if let Some(used) = dynamic_type.parameter_static_type() {
+            match scope.parameters.get(&value.int_value) {
+                None => self.report(path, TypeErrorKind::UnknownParameter(value.int_value)),
+                Some(&declared) if declared != used => self.report(
+                    path,
+                    TypeErrorKind::ParameterType {
+                        parameter_id: value.int_value,
+                        declared,
+                        used,
+                    },
+                ),
+                Some(_) => {}
+            }
+        }
+
+        let operand_types = dynamic_type.operand_types();
+        let operands = value.sub_values.as_deref().unwrap_or_default();
+
+        // leaves may carry an empty or missing operand list, but nodes with operands have to match exactly
+        if !operand_types.is_empty() || !operands.is_empty() {
+            if operands.len() != operand_types.len() {
+                self.report(
+                    path,
+                    TypeErrorKind::OperandCount {
+                        dynamic_type,
+                        expected: operand_types.len(),
+                        found: operands.len(),
+                    },
+                );
+            }
+
+            for (index, operand) in operands.iter().enumerate() {
+                self.check_value(operand, operand_types.get(index).copied(), scope, &path.join(PathSegment::SubValue(index)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+    use crate::types::nova_script::variable::Variable;
+
+    fn global(variable_id: i32, name: &str, static_type: StaticType) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type,
+            persistance: None,
+            initial_value: NovaValue::default_constant(static_type),
+        }
+    }
+
+    fn level(scripts: &str) -> LevelData {
+        let globals = vec![global(1, "score", StaticType::Int), global(2, "speed", StaticType::Float)];
+
+        LevelData {
+            nova_scripts: compile_scripts(scripts, &globals).unwrap(),
+            global_variables: globals,
+            ..Default::default()
+        }
+    }
+
+    fn errors(level: &LevelData) -> Vec<String> {
+        level.type_check().iter().map(ToString::to_string).collect()
+    }
+
+    const FUNCTION: &str = r#"function 10 "f" { param 1 "count": int = 0; wait_frames(@count); }"#;
+
+    #[test]
+    fn well_typed_script() {
+        let level = level(&format!(
+            r#"
+            {FUNCTION}
+            script 1 "A" {{
+                var 3 "lives": int = 3;
+                condition $speed > 0.0;
+                on level_start();
+                $score = $score + $lives * 2;
+                if $speed >= 1.5 {{
+                    repeat 2 {{
+                        $speed = $speed / 2.0;
+                        call 10(1: $score % 3);
+                    }}
+                }}
+                call 10();
+            }}
+            "#
+        ));
+
+        assert_eq!(errors(&level), Vec::<String>::new());
+    }
+
+    #[test]
+    fn operand_mismatch() {
+        let level = level(r#"script 1 "A" { $score = int_add($score, true); $speed = 1.0 * float_from_int(2.5); }"#);
+
+        assert_eq!(
+            errors(&level),
+            vec![
+                "script 1.actions[0].value.sub_values[1]: expected a Int value, found Bool",
+                "script 1.actions[1].value.sub_values[1].sub_values[0]: expected a Int value, found Float",
+            ]
+        );
+    }
+
+    #[test]
+    fn field_and_variable_mismatch() {
+        let level = level(r#"script 1 "A" { wait(true); $score = 1.5; if 3 { } }"#);
+
+        assert_eq!(
+            errors(&level),
+            vec![
+                "script 1.actions[0].duration: expected a Float value, found Bool",
+                "script 1.actions[1].value: expected a Int value, found Float",
+                "script 1.actions[2].condition: expected a Bool value, found Int",
+            ]
+        );
+    }
+
+    #[test]
+    fn parameter_mismatch() {
+        let level = level(&format!(r#"{FUNCTION} script 1 "A" {{ call 10(1: 2.5); call 10(2: 1, 1: 1, 1: 2); }}"#));
+
+        assert_eq!(
+            level.type_check().into_iter().map(|error| error.kind).collect::<Vec<_>>(),
+            vec![
+                TypeErrorKind::Mismatch { expected: StaticType::Int, found: StaticType::Float },
+                TypeErrorKind::UnknownArgument { function_id: 10, parameter_id: 2 },
+                TypeErrorKind::DuplicateArgument { function_id: 10, parameter_id: 1 },
+            ]
+        );
+        assert_eq!(errors(&level)[0], "script 1.actions[0].argument(1): expected a Int value, found Float");
+    }
+
+    #[test]
+    fn declared_types() {
+        let scripts = format!(r#"{FUNCTION} script 1 "A" {{ wait($1:float); wait_frames(@1:int); call 11(); }}"#);
+        let mut level = level(&scripts);
+        level.global_variables[0].initial_value = NovaValue::default_constant(StaticType::Bool);
+
+        assert_eq!(
+            level.type_check().into_iter().map(|error| error.kind).collect::<Vec<_>>(),
+            vec![
+                TypeErrorKind::Mismatch { expected: StaticType::Int, found: StaticType::Bool },
+                TypeErrorKind::VariableType { variable_id: 1, declared: StaticType::Int, used: StaticType::Float },
+                TypeErrorKind::UnknownParameter(1),
+                TypeErrorKind::UnknownFunction(11),
+            ]
+        );
+    }
+
+    #[test]
+    fn operand_count() {
+        let mut level = level(r#"script 1 "A" { $score = $score + 1; }"#);
+        level.nova_scripts[0].actions[0].action_type.values_mut()[0].1.sub_values.as_mut().unwrap().pop();
+
+        assert_eq!(
+            errors(&level),
+            vec!["script 1.actions[0].value: IntAdd takes 2 operand(s), found 1"]
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use std::{fs::File, io::{BufWriter, Write as _}};
 use flate2::{write::GzEncoder, Compression};
 
-use crate::{Read, Write, Error, ReadVersioned};
+use crate::{Read, Write, Error, ReadVersioned, Uuid};
 #[cfg(feature = "image")]
 use super::image::Image;
+#[cfg(feature = "image")]
+use crate::render::{Camera, RenderOptions};
 use super::{layer::Layer, level::Level};
 use crate::types::{local_level::LocalLevel, level_data::LevelData, author_replay::AuthorReplay, theme::Theme, vec2::Vec2};
 use std::path::Path;
@@ -20,6 +22,15 @@ pub struct Exolvl {
     pub author_replay: AuthorReplay,
 }
 
+/// Which copy of the duplicated metadata [`Exolvl::sync_metadata`] keeps.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MetadataSource {
+    /// Keep the values from [`LocalLevel`].
+    Local,
+    /// Keep the values from [`LevelData`].
+    Data,
+}
+
 const EXPECTED_MAGIC: &[u8; 4] = b"NYA^";
 
 impl Read for Exolvl {
@@ -180,6 +191,27 @@ impl Exolvl {
         Ok(())
     }
 
+    /// Renders the level and stores the image as its thumbnail, leaving out invisible layers.
+    ///
+    /// Without a camera the thumbnail shows every visible object. The thumbnail keeps the size of the current one,
+    /// see [`LocalLevel::thumbnail_size`].
+    #[cfg(feature = "image")]
+    pub fn regenerate_thumbnail(&mut self, camera: Option<Camera>) {
+        let (width, height) = self.local_level.thumbnail_size();
+        let options = RenderOptions {
+            width,
+            height,
+            camera: camera.map(|camera| {
+                #[allow(clippy::cast_precision_loss)]
+                camera.with_aspect_ratio(width as f32 / height as f32)
+            }),
+            ..RenderOptions::default()
+        };
+
+        self.local_level.set_thumbnail_image(&Image(self.level_data.render(&options)));
+    }
+
+    /// Makes a level file out of the level, with `thumbnail` as the base64-encoded thumbnail.
     pub fn from_level(level: Level, name: &str, thumbnail: &str) -> Self {
         Self { 
             local_level: LocalLevel { 
@@ -202,4 +234,223 @@ impl Exolvl {
             author_replay: AuthorReplay(vec![]) 
         }
     }
-}
\ No newline at end of file
+}
+
+/// Generates a setter for each metadata field that is duplicated between [`LocalLevel`] and [`LevelData`],
+/// and [`Exolvl::sync_metadata`] which copies all of them from one side to the other.
+macro_rules! duplicated_metadata {
+    ($($setter:ident => $field:ident: $ty:ty),* $(,)?) => {
+        impl Exolvl {
+            $(
+                #[doc = concat!("Sets `", stringify!($field), "` in both the local level and the level data.")]
+                pub fn $setter(&mut self, $field: $ty) {
+                    self.local_level.$field.clone_from(&$field);
+                    self.level_data.$field = $field;
+                }
+            )*
+
+            /// Makes the metadata duplicated between the local level and the level data agree again,
+            /// overwriting the other copy with the values from `prefer`.
+            pub fn sync_metadata(&mut self, prefer: MetadataSource) {
+                match prefer {
+                    MetadataSource::Local => {
+                        $(self.level_data.$field.clone_from(&self.local_level.$field);)*
+                    }
+                    MetadataSource::Data => {
+                        $(self.local_level.$field.clone_from(&self.level_data.$field);)*
+                    }
+                }
+            }
+        }
+    };
+}
+
+duplicated_metadata!(
+    set_level_id => level_id: Uuid,
+    set_level_version => level_version: i32,
+    set_nova_level => nova_level: bool,
+    set_author_time => author_time: i64,
+    set_author_lap_times => author_lap_times: Vec<i64>,
+    set_silver_medal_time => silver_medal_time: i64,
+    set_gold_medal_time => gold_medal_time: i64,
+    set_laps => laps: i32,
+);
+
+impl Exolvl {
+    /// Increments `level_version` in both the local level and the level data and sets `update_date` to now.
+    pub fn bump_version(&mut self) {
+        self.set_level_version(self.local_level.level_version + 1);
+        self.local_level.update_date = chrono::Utc::now();
+    }
+
+    /// Returns a copy of this level with a fresh UUID, so that it's saved as a separate level instead of
+    /// overwriting this one.
+    ///
+    /// The copy starts at version 1 and its creation and update dates are set to now.
+    #[must_use]
+    pub fn save_as_copy(&self) -> Self {
+        let mut copy = self.clone();
+        let now = chrono::Utc::now();
+
+        copy.set_level_id(Uuid::new_v4());
+        copy.set_level_version(1);
+        copy.local_level.creation_date = now;
+        copy.local_level.update_date = now;
+
+        copy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The duplicated metadata of both sides of a level, in the order of the `duplicated_metadata!` fields.
+    type Metadata = (Uuid, i32, bool, i64, Vec<i64>, i64, i64, i32);
+
+    fn local_metadata(level: &Exolvl) -> Metadata {
+        let local = &level.local_level;
+
+        (
+            local.level_id,
+            local.level_version,
+            local.nova_level,
+            local.author_time,
+            local.author_lap_times.clone(),
+            local.silver_medal_time,
+            local.gold_medal_time,
+            local.laps,
+        )
+    }
+
+    fn data_metadata(level: &Exolvl) -> Metadata {
+        let data = &level.level_data;
+
+        (
+            data.level_id,
+            data.level_version,
+            data.nova_level,
+            data.author_time,
+            data.author_lap_times.clone(),
+            data.silver_medal_time,
+            data.gold_medal_time,
+            data.laps,
+        )
+    }
+
+    /// A level whose local level and level data disagree on every duplicated field.
+    fn out_of_sync() -> Exolvl {
+        Exolvl {
+            local_level: LocalLevel {
+                level_id: Uuid::from_u128(1),
+                level_version: 3,
+                nova_level: true,
+                author_time: 10_000,
+                author_lap_times: vec![10_000],
+                silver_medal_time: 15_000,
+                gold_medal_time: 12_000,
+                laps: 1,
+                ..Default::default()
+            },
+            level_data: LevelData {
+                level_id: Uuid::from_u128(2),
+                level_version: 4,
+                nova_level: false,
+                author_time: 20_000,
+                author_lap_times: vec![10_000, 10_000],
+                silver_medal_time: 30_000,
+                gold_medal_time: 25_000,
+                laps: 2,
+                ..Default::default()
+            },
+            author_replay: AuthorReplay::default(),
+        }
+    }
+
+    #[test]
+    fn setters_write_both_sides() {
+        let mut level = out_of_sync();
+
+        level.set_level_id(Uuid::from_u128(3));
+        level.set_level_version(5);
+        level.set_nova_level(true);
+        level.set_author_time(9_000);
+        level.set_author_lap_times(vec![4_000, 5_000]);
+        level.set_silver_medal_time(14_000);
+        level.set_gold_medal_time(11_000);
+        level.set_laps(2);
+
+        let expected = (Uuid::from_u128(3), 5, true, 9_000, vec![4_000, 5_000], 14_000, 11_000, 2);
+
+        assert_eq!(local_metadata(&level), expected);
+        assert_eq!(data_metadata(&level), expected);
+    }
+
+    #[test]
+    fn sync_metadata_from_local() {
+        let mut level = out_of_sync();
+        let local = local_metadata(&level);
+
+        level.sync_metadata(MetadataSource::Local);
+
+        assert_eq!(local_metadata(&level), local);
+        assert_eq!(data_metadata(&level), local);
+    }
+
+    #[test]
+    fn sync_metadata_from_data() {
+        let mut level = out_of_sync();
+        let data = data_metadata(&level);
+
+        level.sync_metadata(MetadataSource::Data);
+
+        assert_eq!(local_metadata(&level), data);
+        assert_eq!(data_metadata(&level), data);
+    }
+
+    #[test]
+    fn bump_version() {
+        let mut level = out_of_sync();
+        level.sync_metadata(MetadataSource::Local);
+        let before = chrono::Utc::now();
+
+        level.bump_version();
+
+        assert_eq!(level.local_level.level_version, 4);
+        assert_eq!(level.level_data.level_version, 4);
+        assert!(level.local_level.update_date >= before);
+    }
+
+    #[test]
+    fn save_as_copy() {
+        let mut level = out_of_sync();
+        level.sync_metadata(MetadataSource::Local);
+        let before = chrono::Utc::now();
+
+        let copy = level.save_as_copy();
+
+        assert_ne!(copy.local_level.level_id, level.local_level.level_id);
+        assert_eq!(copy.level_data.level_id, copy.local_level.level_id);
+        assert_eq!((copy.local_level.level_version, copy.level_data.level_version), (1, 1));
+        assert!(copy.local_level.creation_date >= before);
+        assert!(copy.local_level.update_date >= before);
+        assert_eq!(copy.level_data.objects, level.level_data.objects);
+        assert_eq!(level.local_level.level_id, Uuid::from_u128(1));
+        assert_eq!(level.local_level.level_version, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn regenerate_thumbnail() {
+        let mut level = Exolvl::default();
+
+        level.regenerate_thumbnail(None);
+        let thumbnail = level.local_level.thumbnail_image().unwrap().unwrap();
+        assert_eq!(thumbnail.0.dimensions(), (LocalLevel::THUMBNAIL_WIDTH, LocalLevel::THUMBNAIL_HEIGHT));
+
+        level.local_level.thumbnail = Image(image::RgbaImage::new(320, 200)).to_base64_string();
+        level.regenerate_thumbnail(None);
+        assert_eq!(level.local_level.thumbnail_image().unwrap().unwrap().0.dimensions(), (320, 200));
+    }
+
+}
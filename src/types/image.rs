@@ -101,6 +101,18 @@ impl<'de> serde::Deserialize<'de> for Image {
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Image(pub Vec<u8>);
 
+#[cfg(test)]
+impl Image {
+    /// A transparent square image for tests. Without the `image` feature it's `size` zero bytes instead.
+    pub(crate) fn blank(size: u32) -> Self {
+        #[cfg(feature = "image")]
+        return Self(RgbaImage::new(size, size));
+
+        #[cfg(not(feature = "image"))]
+        Self(vec![0; size as usize])
+    }
+}
+
 impl Read for Image {
     fn read(input: &mut impl std::io::Read) -> Result<Self, Error> {
         let data = Read::read(input)?;
@@ -2,8 +2,13 @@ use crate::{Read, Write, Error, Uuid, ReadVersioned};
 use crate::types::pattern::Pattern;
 use crate::types::prefab::Prefab;
 use crate::types::layer::Layer;
-use crate::types::{object::Object, brush::Brush, color::Color, nova_script::{NovaScript, variable::Variable, scripts_folder::ScriptsFolder, variables_folder::VariablesFolder}, theme::Theme, old_editor_types::simple_tile::SimpleTile, old_editor_types::object_tile::ObjectTile};
+use crate::types::{object::Object, brush::Brush, color::Color, nova_script::{NovaScript, variable::{Variable, VariableRef}, scripts_folder::ScriptsFolder, variables_folder::VariablesFolder}, theme::Theme, old_editor_types::simple_tile::SimpleTile, old_editor_types::object_tile::ObjectTile};
 use crate::types::vec2::Vec2;
+use crate::types::nova_script::{
+    action::retain_actions, dynamic_type::DynamicType, nova_value::NovaValue, visit::{ForEachValue, VisitMut},
+};
+use crate::types::object_property::ObjectProperty;
+use crate::references::{DeletePolicy, EntityRef, Reference, References};
 use ordered_float::OrderedFloat;
 
 /// The level data for an Exoracer level.
@@ -284,6 +289,22 @@ impl LevelData {
         self.objects.push(obj);
     }
 
+    /// Returns the layer with the given id, adding an empty one named `layer_name` after the others if there's
+    /// none.
+    pub(crate) fn layer_or_insert(&mut self, layer_id: i32, layer_name: &str) -> &mut Layer {
+        let index = self.layers.iter().position(|layer| layer.layer_id == layer_id).unwrap_or_else(|| {
+            self.layers.push(Layer { layer_id, layer_name: layer_name.to_string(), ..Layer::default() });
+            self.layers.len() - 1
+        });
+
+        &mut self.layers[index]
+    }
+
+    /// Returns one more than the largest entity id of the objects, which no object uses yet.
+    pub(crate) fn next_entity_id(&self) -> i32 {
+        self.objects.iter().map(|object| object.entity_id).max().unwrap_or(0) + 1
+    }
+
     /// returns the amount of objects in a new level or the amount of tiles in a legacy level
     pub fn get_object_count(&self) -> usize{
         if !self.nova_level {
@@ -296,4 +317,432 @@ impl LevelData {
         }
         self.objects.len()
     }
-}
\ No newline at end of file
+
+    /// Builds an index of every reference between the entities of this level.
+    #[must_use]
+    pub fn references(&self) -> References {
+        References::new(self)
+    }
+
+    /// Collects the references to `entity` and checks them against the delete policy.
+    ///
+    /// Returns every inbound reference, which the caller is going to clean up.
+    fn references_for_delete(&self, entity: EntityRef, policy: DeletePolicy) -> Result<Vec<Reference>, Error> {
+        let references = self.references();
+
+        if policy == DeletePolicy::Refuse {
+            let users: Vec<Reference> = references.users(entity).copied().collect();
+
+            if !users.is_empty() {
+                return Err(Error::DanglingReferences(users));
+            }
+        }
+
+        Ok(references.inbound(entity).copied().collect())
+    }
+
+    /// Calls `f` on every `NovaValue` in the global variables and the nova scripts of this level.
+    pub fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut NovaValue)) {
+        ForEachValue(f).visit_level_data_mut(self);
+    }
+
+    /// Deletes the object with the given entity id.
+    ///
+    /// With [`DeletePolicy::Cascade`] the object is also removed from layers, groups, `LinkedObjects`
+    /// and object constants in scripts. Members of a deleted group are ungrouped.
+    /// Returns the references that pointed at the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DanglingReferences`] if the policy is [`DeletePolicy::Refuse`] and the object is still used.
+    pub fn delete_object(&mut self, entity_id: i32, policy: DeletePolicy) -> Result<Vec<Reference>, Error> {
+        let removed = self.references_for_delete(EntityRef::Object(entity_id), policy)?;
+
+        self.objects.retain(|object| object.entity_id != entity_id);
+
+        for layer in &mut self.layers {
+            layer.children.retain(|&child| child != entity_id);
+        }
+
+        for object in &mut self.objects {
+            if object.in_group == entity_id {
+                object.in_group = 0;
+            }
+
+            object.group_members.retain(|&member| member != entity_id);
+
+            for property in &mut object.properties {
+                if let ObjectProperty::LinkedObjects(linked) = property {
+                    linked.retain(|&linked| linked != entity_id);
+                }
+            }
+        }
+
+        self.for_each_value_mut(&mut |value| match value.dynamic_type {
+            DynamicType::ObjectConstant if value.int_value == entity_id => value.int_value = 0,
+            DynamicType::ObjectSetConstant => {
+                if let Some(objects) = &mut value.int_list_values {
+                    objects.retain(|&object| object != entity_id);
+                }
+            }
+            _ => {}
+        });
+
+        Ok(removed)
+    }
+
+    /// Deletes the nova script or function with the given id.
+    ///
+    /// With [`DeletePolicy::Cascade`] the script is also removed from its folder, script constants are cleared
+    /// and `RunFunction` actions calling it are removed. Returns the references that pointed at the script.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DanglingReferences`] if the policy is [`DeletePolicy::Refuse`] and the script is still used.
+    pub fn delete_script(&mut self, script_id: i32, policy: DeletePolicy) -> Result<Vec<Reference>, Error> {
+        let removed = self.references_for_delete(EntityRef::Script(script_id), policy)?;
+
+        self.nova_scripts.retain(|script| script.script_id != script_id);
+
+        for folder in self.scripts_folders.iter_mut().flatten() {
+            folder.scripts.retain(|&script| script != script_id);
+        }
+
+        for script in &mut self.nova_scripts {
+            retain_actions(&mut script.actions, &mut |action| {
+                action.action_type.function_call().is_none_or(|function| function.id != script_id)
+            });
+        }
+
+        self.for_each_value_mut(&mut |value| {
+            if value.dynamic_type == DynamicType::ScriptConstant && value.int_value == script_id {
+                value.int_value = 0;
+            }
+        });
+
+        Ok(removed)
+    }
+
+    /// Deletes a global or script-local variable.
+    ///
+    /// With [`DeletePolicy::Cascade`] a global variable is also removed from its folder. Actions writing to the
+    /// variable are removed and values reading it are replaced by a default constant of the same type, but only
+    /// where its id refers to it: a local variable with the same id as a global one shadows it in its script.
+    /// Returns the references that pointed at the variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DanglingReferences`] if the policy is [`DeletePolicy::Refuse`] and the variable is still used.
+    pub fn delete_variable(&mut self, variable: VariableRef, policy: DeletePolicy) -> Result<Vec<Reference>, Error> {
+        let removed = self.references_for_delete(EntityRef::Variable(variable), policy)?;
+        let variable_id = variable.variable_id();
+
+        // Which scripts use the variable has to be known before its declaration is gone.
+        let in_scope: Vec<bool> = self
+            .nova_scripts
+            .iter()
+            .map(|script| VariableRef::resolve(variable_id, Some(script)) == variable)
+            .collect();
+
+        let mut clear_reads = |value: &mut NovaValue| {
+            if let Some(static_type) = value.dynamic_type.variable_static_type() {
+                if value.int_value == variable_id {
+                    *value = NovaValue::default_constant(static_type);
+                }
+            }
+        };
+
+        if let VariableRef::Global(_) = variable {
+            for global in &mut self.global_variables {
+                global.initial_value.for_each_mut(&mut clear_reads);
+            }
+        }
+
+        for (script, _) in self.nova_scripts.iter_mut().zip(in_scope).filter(|(_, in_scope)| *in_scope) {
+            retain_actions(&mut script.actions, &mut |action| {
+                action.action_type.target_variable() != Some(variable_id)
+            });
+            script.for_each_value_mut(&mut clear_reads);
+        }
+
+        match variable {
+            VariableRef::Global(_) => {
+                self.global_variables.retain(|global| global.variable_id != variable_id);
+
+                for folder in self.variables_folders.iter_mut().flatten() {
+                    folder.variables.retain(|&id| id != variable_id);
+                }
+            }
+            VariableRef::Local { script_id, .. } => {
+                for script in self.nova_scripts.iter_mut().filter(|script| script.script_id == script_id) {
+                    script.variables.retain(|local| local.variable_id != variable_id);
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Deletes the pattern with the given id.
+    ///
+    /// With [`DeletePolicy::Cascade`] the `Pattern` property is removed from objects and prefab items using it
+    /// and the custom terrain pattern is cleared. Returns the references that pointed at the pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DanglingReferences`] if the policy is [`DeletePolicy::Refuse`] and the pattern is still used.
+    pub fn delete_pattern(&mut self, pattern_id: i32, policy: DeletePolicy) -> Result<Vec<Reference>, Error> {
+        let removed = self.references_for_delete(EntityRef::Pattern(pattern_id), policy)?;
+
+        self.patterns.retain(|pattern| pattern.pattern_id != pattern_id);
+
+        let objects = self.objects.iter_mut().chain(self.prefabs.iter_mut().flat_map(|prefab| prefab.items.iter_mut()));
+
+        for object in objects {
+            object.properties.retain(|property| *property != ObjectProperty::Pattern(pattern_id));
+        }
+
+        if self.custom_terrain_pattern_id == pattern_id {
+            self.custom_terrain_pattern_id = 0;
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+    use crate::types::{image::Image, nova_script::static_type::StaticType};
+    use crate::references::ReferenceKind;
+
+    fn reference(from: EntityRef, to: EntityRef, kind: ReferenceKind) -> Reference {
+        Reference { from, to, kind }
+    }
+
+    fn global(variable_id: i32, name: &str) -> Variable {
+        Variable {
+            variable_id,
+            name: name.to_string(),
+            static_type: StaticType::Int,
+            persistance: None,
+            initial_value: NovaValue::default_constant(StaticType::Int),
+        }
+    }
+
+    fn users(result: Result<Vec<Reference>, Error>) -> Vec<Reference> {
+        match result {
+            Err(Error::DanglingReferences(users)) => users,
+            other => panic!("expected dangling references, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn delete_object() {
+        let mut level = LevelData {
+            layers: vec![Layer { layer_id: 1, children: vec![1, 2, 3], ..Default::default() }],
+            objects: vec![
+                Object { entity_id: 1, in_layer: 1, in_group: 3, ..Default::default() },
+                Object {
+                    entity_id: 2,
+                    in_layer: 1,
+                    properties: vec![ObjectProperty::LinkedObjects(vec![1])],
+                    ..Default::default()
+                },
+                Object { entity_id: 3, in_layer: 1, group_members: vec![1], ..Default::default() },
+            ],
+            nova_scripts: compile_scripts(r#"script 1 "A" { activate(objects[1, 2]); }"#, &[]).unwrap(),
+            ..Default::default()
+        };
+        let original = level.clone();
+        let object = EntityRef::Object(1);
+        let used_by = vec![
+            reference(EntityRef::Object(2), object, ReferenceKind::LinkedObject),
+            reference(EntityRef::Object(3), object, ReferenceKind::GroupMember),
+            reference(EntityRef::Script(1), object, ReferenceKind::Value),
+        ];
+
+        assert_eq!(users(level.delete_object(1, DeletePolicy::Refuse)), used_by);
+        assert_eq!(level, original);
+
+        let removed = level.delete_object(1, DeletePolicy::Cascade).unwrap();
+
+        assert_eq!(removed[0], reference(EntityRef::Layer(1), object, ReferenceKind::LayerChild));
+        assert_eq!(removed[1..], used_by);
+        assert_eq!(level.objects.iter().map(|object| object.entity_id).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(level.layers[0].children, vec![2, 3]);
+        assert_eq!(level.objects[0].properties, vec![ObjectProperty::LinkedObjects(Vec::new())]);
+        assert!(level.objects[1].group_members.is_empty());
+        assert_eq!(level.nova_scripts, compile_scripts(r#"script 1 "A" { activate(objects[2]); }"#, &[]).unwrap());
+        assert_eq!(level.references().dangling(&level).count(), 0);
+    }
+
+    #[test]
+    fn delete_script() {
+        let scripts = r#"
+            function 10 "f" { wait(1.0); }
+            script 1 "A" {
+                on level_start();
+                if true {
+                    call 10();
+                }
+                wait(2.0);
+            }
+        "#;
+        let mut level = LevelData {
+            nova_scripts: compile_scripts(scripts, &[]).unwrap(),
+            scripts_folders: Some(vec![ScriptsFolder {
+                folder_id: 1,
+                folder_name: "Folder".to_string(),
+                closed: false,
+                scripts: vec![1, 10],
+            }]),
+            ..Default::default()
+        };
+        let original = level.clone();
+        let call = reference(EntityRef::Script(1), EntityRef::Script(10), ReferenceKind::FunctionCall);
+
+        assert_eq!(users(level.delete_script(10, DeletePolicy::Refuse)), vec![call]);
+        assert_eq!(level, original);
+
+        let removed = level.delete_script(10, DeletePolicy::Cascade).unwrap();
+        let expected = r#"
+            script 1 "A" {
+                on level_start();
+                if true {
+                }
+                wait(2.0);
+            }
+        "#;
+
+        assert_eq!(
+            removed,
+            vec![reference(EntityRef::ScriptsFolder(1), EntityRef::Script(10), ReferenceKind::FolderScript), call]
+        );
+        assert_eq!(level.nova_scripts, compile_scripts(expected, &[]).unwrap());
+        assert_eq!(level.scripts_folders.unwrap()[0].scripts, vec![1]);
+    }
+
+    /// Script 1 declares a local with the id of the global `score`, which shadows it there.
+    const VARIABLE_SCRIPTS: &str = r#"
+        script 1 "A" {
+            var 2 "lives": int = 3;
+            $count = $count + 1;
+            $lives = $lives - 1;
+        }
+        script 2 "B" {
+            $score = $score + 1;
+            wait_frames($score);
+        }
+    "#;
+
+    #[test]
+    fn delete_global_variable() {
+        let globals = vec![global(1, "count"), global(2, "score")];
+        let mut level = LevelData {
+            nova_scripts: compile_scripts(VARIABLE_SCRIPTS, &globals).unwrap(),
+            global_variables: globals,
+            ..Default::default()
+        };
+        let original = level.clone();
+        let score = EntityRef::Variable(VariableRef::Global(2));
+
+        let lives = VariableRef::Local { script_id: 1, variable_id: 2 };
+
+        assert_eq!(VariableRef::resolve(2, Some(&level.nova_scripts[0])), lives);
+        assert_eq!(VariableRef::resolve(2, Some(&level.nova_scripts[1])), VariableRef::Global(2));
+        assert_eq!(
+            users(level.delete_variable(VariableRef::Global(2), DeletePolicy::Refuse)),
+            vec![
+                reference(EntityRef::Script(2), score, ReferenceKind::Value),
+                reference(EntityRef::Script(2), score, ReferenceKind::Value),
+                reference(EntityRef::Script(2), score, ReferenceKind::VariableWrite),
+            ]
+        );
+        assert_eq!(level, original);
+
+        level.delete_variable(VariableRef::Global(2), DeletePolicy::Cascade).unwrap();
+
+        let globals = vec![global(1, "count")];
+        let expected = r#"
+            script 1 "A" {
+                var 2 "lives": int = 3;
+                $count = $count + 1;
+                $lives = $lives - 1;
+            }
+            script 2 "B" {
+                wait_frames(0);
+            }
+        "#;
+
+        assert_eq!(level.nova_scripts, compile_scripts(expected, &globals).unwrap());
+        assert_eq!(level.global_variables, globals);
+    }
+
+    #[test]
+    fn delete_local_variable() {
+        let globals = vec![global(1, "count"), global(2, "score")];
+        let mut level = LevelData {
+            nova_scripts: compile_scripts(VARIABLE_SCRIPTS, &globals).unwrap(),
+            global_variables: globals.clone(),
+            ..Default::default()
+        };
+        let original = level.clone();
+        let lives = VariableRef::Local { script_id: 1, variable_id: 2 };
+
+        assert_eq!(
+            users(level.delete_variable(lives, DeletePolicy::Refuse)),
+            vec![
+                reference(EntityRef::Script(1), EntityRef::Variable(lives), ReferenceKind::Value),
+                reference(EntityRef::Script(1), EntityRef::Variable(lives), ReferenceKind::VariableWrite),
+            ]
+        );
+        assert_eq!(level, original);
+
+        level.delete_variable(lives, DeletePolicy::Cascade).unwrap();
+
+        let expected = r#"
+            script 1 "A" {
+                $count = $count + 1;
+            }
+            script 2 "B" {
+                $score = $score + 1;
+                wait_frames($score);
+            }
+        "#;
+
+        assert_eq!(level.nova_scripts, compile_scripts(expected, &globals).unwrap());
+        assert_eq!(level.global_variables, globals);
+    }
+
+    #[test]
+    fn delete_pattern() {
+        let mut level = LevelData {
+            patterns: vec![Pattern { pattern_id: 4, pattern_frames: Vec::new() }],
+            objects: vec![Object { entity_id: 1, properties: vec![ObjectProperty::Pattern(4)], ..Default::default() }],
+            prefabs: vec![Prefab {
+                prefab_id: 2,
+                prefab_image_data: Image::blank(1),
+                items: vec![Object { properties: vec![ObjectProperty::Pattern(4)], ..Default::default() }],
+            }],
+            custom_terrain_pattern_id: 4,
+            ..Default::default()
+        };
+        let original = level.clone();
+        let pattern = EntityRef::Pattern(4);
+        let used_by = vec![
+            reference(EntityRef::Object(1), pattern, ReferenceKind::Pattern),
+            reference(EntityRef::Prefab(2), pattern, ReferenceKind::Pattern),
+            reference(EntityRef::Level, pattern, ReferenceKind::CustomTerrainPattern),
+        ];
+
+        assert_eq!(users(level.delete_pattern(4, DeletePolicy::Refuse)), used_by);
+        assert_eq!(level, original);
+        assert_eq!(level.delete_pattern(4, DeletePolicy::Cascade).unwrap(), used_by);
+        assert!(level.patterns.is_empty());
+        assert!(level.objects[0].properties.is_empty());
+        assert!(level.prefabs[0].items[0].properties.is_empty());
+        assert_eq!(level.custom_terrain_pattern_id, 0);
+    }
+}
@@ -1,4 +1,8 @@
 use crate::{Read, Write, Error, Uuid};
+#[cfg(feature = "image")]
+use crate::types::image::Image;
+#[cfg(feature = "image")]
+use base64::{prelude::BASE64_STANDARD, Engine};
 
 /// The local level data for this level.
 ///
@@ -17,6 +21,9 @@ pub struct LocalLevel {
     /// The name of the level.
     pub level_name: String,
     /// The base64-encoded data for the thumbnail of the level.
+    ///
+    /// With the `image` feature, [`LocalLevel::thumbnail_image`] and [`LocalLevel::set_thumbnail_image`] handle the
+    /// encoding.
     pub thumbnail: String,
     /// When this level was created.
     pub creation_date: chrono::DateTime<chrono::Utc>,
@@ -39,6 +46,58 @@ pub struct LocalLevel {
     pub nova_level: bool,
 }
 
+#[cfg(feature = "image")]
+impl LocalLevel {
+    /// The width of the thumbnails [`LocalLevel::set_thumbnail_image`] stores for levels without one, in pixels.
+    pub const THUMBNAIL_WIDTH: u32 = 480;
+    /// The height of the thumbnails [`LocalLevel::set_thumbnail_image`] stores for levels without one, in pixels.
+    pub const THUMBNAIL_HEIGHT: u32 = 270;
+
+    /// The size of the current thumbnail, or [`Self::THUMBNAIL_WIDTH`] by [`Self::THUMBNAIL_HEIGHT`] if the level
+    /// doesn't have one or it can't be decoded.
+    #[must_use]
+    pub fn thumbnail_size(&self) -> (u32, u32) {
+        BASE64_STANDARD
+            .decode(&self.thumbnail)
+            .ok()
+            .and_then(|data| image::ImageReader::new(std::io::Cursor::new(data)).with_guessed_format().ok())
+            .and_then(|reader| reader.into_dimensions().ok())
+            .filter(|&(width, height)| width > 0 && height > 0)
+            .unwrap_or((Self::THUMBNAIL_WIDTH, Self::THUMBNAIL_HEIGHT))
+    }
+
+    /// Decodes the thumbnail, or returns `None` if the level doesn't have one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Base64`] if the thumbnail isn't valid base64, or [`Error::Image`] if it isn't an image.
+    pub fn thumbnail_image(&self) -> Result<Option<Image>, Error> {
+        if self.thumbnail.is_empty() {
+            return Ok(None);
+        }
+
+        let data = BASE64_STANDARD.decode(&self.thumbnail)?;
+
+        Ok(Some(image::load_from_memory(&data)?.into()))
+    }
+
+    /// Stores the image as the thumbnail, as a base64-encoded PNG.
+    ///
+    /// The thumbnail keeps its [size](Self::thumbnail_size): images of another size are scaled to cover it and
+    /// cropped around their center.
+    pub fn set_thumbnail_image(&mut self, thumbnail: &Image) {
+        let (width, height) = self.thumbnail_size();
+
+        self.thumbnail = if thumbnail.0.dimensions() == (width, height) {
+            thumbnail.to_base64_string()
+        } else {
+            let image = image::DynamicImage::ImageRgba8(thumbnail.0.clone());
+
+            Image::from(image.resize_to_fill(width, height, image::imageops::FilterType::Triangle)).to_base64_string()
+        };
+    }
+}
+
 impl Read for LocalLevel {
     fn read(input: &mut impl std::io::Read) -> Result<Self, Error> {
         Ok(Self {
@@ -77,4 +136,49 @@ impl Write for LocalLevel {
         self.private.write(output)?;
         self.nova_level.write(output)
     }
-}
\ No newline at end of file
+}
+#[cfg(all(test, feature = "image"))]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn image(width: u32, height: u32) -> Image {
+        Image(RgbaImage::from_fn(width, height, |x, y| Rgba([x as u8, y as u8, 7, 255])))
+    }
+
+    #[test]
+    fn thumbnail_round_trip() {
+        let mut level = LocalLevel::default();
+        assert!(level.thumbnail_image().unwrap().is_none());
+        assert_eq!(level.thumbnail_size(), (LocalLevel::THUMBNAIL_WIDTH, LocalLevel::THUMBNAIL_HEIGHT));
+
+        let thumbnail = image(LocalLevel::THUMBNAIL_WIDTH, LocalLevel::THUMBNAIL_HEIGHT);
+        level.set_thumbnail_image(&thumbnail);
+        assert_eq!(level.thumbnail_image().unwrap(), Some(thumbnail));
+    }
+
+    #[test]
+    fn thumbnail_keeps_its_size() {
+        let mut level = LocalLevel::default();
+
+        level.set_thumbnail_image(&image(100, 100));
+        assert_eq!(level.thumbnail_image().unwrap().unwrap().0.dimensions(), (480, 270));
+
+        // A thumbnail of another size, e.g. one saved by the game, keeps that size.
+        level.thumbnail = image(64, 36).to_base64_string();
+        level.set_thumbnail_image(&image(100, 100));
+        assert_eq!(level.thumbnail_size(), (64, 36));
+        assert_eq!(level.thumbnail_image().unwrap().unwrap().0.dimensions(), (64, 36));
+    }
+
+    #[test]
+    fn invalid_thumbnail() {
+        let mut level = LocalLevel { thumbnail: "not base64!".to_string(), ..Default::default() };
+        assert!(matches!(level.thumbnail_image(), Err(Error::Base64(_))));
+        assert_eq!(level.thumbnail_size(), (LocalLevel::THUMBNAIL_WIDTH, LocalLevel::THUMBNAIL_HEIGHT));
+
+        level.thumbnail = BASE64_STANDARD.encode("not an image");
+        assert!(matches!(level.thumbnail_image(), Err(Error::Image(_))));
+    }
+}
@@ -1,5 +1,10 @@
 use crate::{Error, Read, ReadContext, ReadVersioned, Write};
-use super::action_type::ActionType;
+use super::{
+    action_type::ActionType,
+    nova_value::NovaValue,
+    path::ScriptPath,
+    visit::{walk_actions_mut, ForEachAction, ForEachValue, RetainActions, Visit, VisitMut},
+};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -30,4 +35,27 @@ impl Write for Action {
         self.wait.write(output)?;
         self.action_type.write(output)
     }
-}
\ No newline at end of file
+}
+
+impl Action {
+    /// Calls `f` on every `NovaValue` in this action, including function call arguments and the values of nested actions.
+    pub fn for_each_value(&self, f: &mut impl FnMut(&NovaValue)) {
+        ForEachValue(f).visit_action(self, &ScriptPath::default());
+    }
+
+    /// Mutable version of [`Action::for_each_value`].
+    pub fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut NovaValue)) {
+        ForEachValue(f).visit_action_mut(self, &ScriptPath::default());
+    }
+
+    /// Calls `f` on this action and then on every action nested inside it, depth first.
+    pub fn for_each_action(&self, f: &mut impl FnMut(&Self)) {
+        ForEachAction(f).visit_action(self, &ScriptPath::default());
+    }
+}
+
+/// Removes every action for which `f` returns `false` from `actions` and from all action lists nested inside them.
+pub fn retain_actions(actions: &mut Vec<Action>, f: &mut impl FnMut(&Action) -> bool) {
+    actions.retain(|action| f(action));
+    walk_actions_mut(&mut RetainActions(f), actions, &ScriptPath::default());
+}
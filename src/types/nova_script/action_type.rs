@@ -1,5 +1,5 @@
 use crate::{Error, Read, ReadContext, ReadVersioned, Write};
-use super::{action::Action, nova_value::NovaValue, function_call::FunctionCall};
+use super::{action::Action, nova_value::NovaValue, function_call::FunctionCall, static_type::StaticType};
 
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -980,3 +980,282 @@ impl Write for ActionType {
         }
     }
 }
+
+/// Expands to a `Vec` of every `NovaValue` field of an action paired with its field name, in binary order.
+///
+/// Match ergonomics make the same arms work for both `&ActionType` and `&mut ActionType`.
+macro_rules! named_values {
+    ($action:expr) => {
+        match $action {
+            ActionType::Repeat { count, .. } => vec![("count", count)],
+            ActionType::RepeatWhile { condition, .. } => vec![("condition", condition)],
+            ActionType::ConditionBlock { condition, .. } => vec![("condition", condition)],
+            ActionType::Wait { duration } => vec![("duration", duration)],
+            ActionType::WaitFrames { frames } => vec![("frames", frames)],
+            ActionType::Move { target_objects, position, global, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("position", position),
+                ("global", global),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::Scale { target_objects, scale, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("scale", scale),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::Rotate { target_objects, rotation, shortest_path, global, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("rotation", rotation),
+                ("shortest_path", shortest_path),
+                ("global", global),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::RotateAround { target_objects, pivot, rotation, is_global, rotate_target, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("pivot", pivot),
+                ("rotation", rotation),
+                ("is_global", is_global),
+                ("rotate_target", rotate_target),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::VariableSet { value, .. } => value.into_iter().map(|value| ("value", value)).collect(),
+            ActionType::VariableReset { .. }
+            | ActionType::GameFinish
+            | ActionType::CameraFollowPlayer
+            | ActionType::RunFunction { .. } => vec![],
+            ActionType::ObjectReset { target_objects }
+            | ActionType::Activate { target_objects }
+            | ActionType::Deactivate { target_objects }
+            | ActionType::Kill { target_objects }
+            | ActionType::PlayParticleSystem { target_objects }
+            | ActionType::ObjectDelete { target_objects } => vec![("target_objects", target_objects)],
+            ActionType::SetColor { target_objects, color, channel, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("color", color),
+                ("channel", channel),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::SetTransparency { target_objects, transparency, channel, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("transparency", transparency),
+                ("channel", channel),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::SetSecondaryColor { target_objects, color, duration, easing }
+            | ActionType::SetBorderColor { target_objects, color, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("color", color),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::SetSecondaryTransparency { target_objects, transparency, duration, easing }
+            | ActionType::SetBorderTransparency { target_objects, transparency, duration, easing } => vec![
+                ("target_objects", target_objects),
+                ("transparency", transparency),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::SetSprite { target_objects, sprite } => vec![("target_objects", target_objects), ("sprite", sprite)],
+            ActionType::SetText { target_objects, text } => vec![("target_objects", target_objects), ("text", text)],
+            ActionType::SetEnabled { target_objects, enabled } => vec![("target_objects", target_objects), ("enabled", enabled)],
+            ActionType::Damage { target_objects, damage } => vec![("target_objects", target_objects), ("damage", damage)],
+            ActionType::CameraPan { position, duration, easing } => vec![
+                ("position", position),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::CameraZoom { viewport_size, duration, easing } => vec![
+                ("viewport_size", viewport_size),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::CameraZoomReset { duration, easing }
+            | ActionType::CameraOffsetReset { duration, easing } => vec![("duration", duration), ("easing", easing)],
+            ActionType::CameraOffset { offset, duration, easing } => vec![
+                ("offset", offset),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::CameraShake { strength, roughness, fade_in, fade_out, duration } => vec![
+                ("strength", strength),
+                ("roughness", roughness),
+                ("fade_in", fade_in),
+                ("fade_out", fade_out),
+                ("duration", duration),
+            ],
+            ActionType::PlaySoundDeprecated { sound, volume, pitch } => vec![
+                ("sound", sound),
+                ("volume", volume),
+                ("pitch", pitch),
+            ],
+            ActionType::PlaySound { sound, volume, pitch, do_loop, fade_in } => vec![
+                ("sound", sound),
+                ("volume", volume),
+                ("pitch", pitch),
+                ("do_loop", do_loop),
+                ("fade_in", fade_in),
+            ],
+            ActionType::PlayMusic { music, volume, pitch } => vec![
+                ("music", music),
+                ("volume", volume),
+                ("pitch", pitch),
+            ],
+            ActionType::SetDirection { target_objects, direction } => vec![("target_objects", target_objects), ("direction", direction)],
+            ActionType::SetGravity { target_objects, gravity } => vec![("target_objects", target_objects), ("gravity", gravity)],
+            ActionType::SetVelocity { target_objects, velocity } => vec![("target_objects", target_objects), ("velocity", velocity)],
+            ActionType::SetCinematic { enabled }
+            | ActionType::SetInputEnabled { enabled }
+            | ActionType::SetTimerEnabled { enabled } => vec![("enabled", enabled)],
+            ActionType::GameTextShow { text, duration } => vec![("text", text), ("duration", duration)],
+            ActionType::DialogueShow { text, position, reverse_direction } => vec![
+                ("text", text),
+                ("position", position),
+                ("reverse_direction", reverse_direction),
+            ],
+            ActionType::StopScript { script } => vec![("script", script)],
+            ActionType::TransitionIn { type_, color, duration, easing }
+            | ActionType::TransitionOut { type_, color, duration, easing } => vec![
+                ("type_", type_),
+                ("color", color),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::TimeScale { time_scale, duration, easing } => vec![
+                ("time_scale", time_scale),
+                ("duration", duration),
+                ("easing", easing),
+            ],
+            ActionType::VariableSetOverTime { value, duration, easing, .. } => value
+                .into_iter()
+                .map(|value| ("value", value))
+                .chain([("duration", duration), ("easing", easing)])
+                .collect(),
+            ActionType::RepeatForEachObject { target_objects, .. } => vec![("target_objects", target_objects)],
+            ActionType::StopSound { sound_instance, fade_out } => vec![("sound_instance", sound_instance), ("fade_out", fade_out)],
+            ActionType::StopParticleSystem { target_objects, clear } => vec![("target_objects", target_objects), ("clear", clear)],
+            ActionType::ObjectCreate { prefab, position, foreground } => vec![
+                ("prefab", prefab),
+                ("position", position),
+                ("foreground", foreground),
+            ],
+        }
+    };
+}
+
+/// Expands to a `Vec` of every nested action list of an action paired with its field name.
+macro_rules! named_children {
+    ($action:expr) => {
+        match $action {
+            ActionType::Repeat { actions, .. }
+            | ActionType::RepeatWhile { actions, .. }
+            | ActionType::RepeatForEachObject { actions, .. } => vec![("actions", actions)],
+            ActionType::ConditionBlock { if_actions, else_actions, .. } => vec![("if_actions", if_actions), ("else_actions", else_actions)],
+            _ => vec![],
+        }
+    };
+}
+
+impl ActionType {
+    /// Returns every `NovaValue` parameter of this action together with its field name.
+    ///
+    /// The arguments of a `RunFunction` call are not included, use [`ActionType::function_call`] for those.
+    #[must_use]
+    pub fn values(&self) -> Vec<(&'static str, &NovaValue)> {
+        named_values!(self)
+    }
+
+    /// Mutable version of [`ActionType::values`].
+    pub fn values_mut(&mut self) -> Vec<(&'static str, &mut NovaValue)> {
+        named_values!(self)
+    }
+
+    /// Returns the type that the `NovaValue` field with the given name (as listed by [`ActionType::values`]) must evaluate to.
+    ///
+    /// Returns `None` for unknown field names and for the `value` of `VariableSet` and `VariableSetOverTime`,
+    /// whose type is the type of the variable being set.
+    #[must_use]
+    pub fn value_type(&self, field: &str) -> Option<StaticType> {
+        Some(match (self, field) {
+            (_, "target_objects") => StaticType::ObjectSet,
+            (
+                _,
+                "condition" | "global" | "is_global" | "rotate_target" | "shortest_path" | "enabled" | "do_loop" | "clear"
+                | "reverse_direction" | "foreground",
+            ) => StaticType::Bool,
+            (Self::DialogueShow { .. }, "position") | (_, "count" | "frames" | "channel" | "direction" | "sound_instance") => {
+                StaticType::Int
+            }
+            (
+                _,
+                "duration" | "rotation" | "transparency" | "damage" | "viewport_size" | "strength" | "roughness" | "fade_in"
+                | "fade_out" | "volume" | "pitch" | "time_scale",
+            ) => StaticType::Float,
+            (_, "position" | "scale" | "pivot" | "offset" | "gravity" | "velocity") => StaticType::Vector,
+            (_, "color") => StaticType::Color,
+            (_, "easing") => StaticType::Easing,
+            (_, "sprite") => StaticType::Sprite,
+            (_, "text") => StaticType::String,
+            (_, "sound") => StaticType::Sound,
+            (_, "music") => StaticType::Music,
+            (_, "script") => StaticType::Script,
+            (_, "type_") => StaticType::Transition,
+            (_, "prefab") => StaticType::Prefab,
+            _ => return None,
+        })
+    }
+
+    /// Returns the nested action lists of this action (e.g. the body of a `Repeat`) together with their field names.
+    #[must_use]
+    pub fn children(&self) -> Vec<(&'static str, &Vec<Action>)> {
+        named_children!(self)
+    }
+
+    /// Mutable version of [`ActionType::children`].
+    pub fn children_mut(&mut self) -> Vec<(&'static str, &mut Vec<Action>)> {
+        named_children!(self)
+    }
+
+    /// Returns the function call if this is a `RunFunction` action.
+    #[must_use]
+    pub const fn function_call(&self) -> Option<&FunctionCall> {
+        match self {
+            Self::RunFunction { function } => Some(function),
+            _ => None,
+        }
+    }
+
+    /// Mutable version of [`ActionType::function_call`].
+    pub const fn function_call_mut(&mut self) -> Option<&mut FunctionCall> {
+        match self {
+            Self::RunFunction { function } => Some(function),
+            _ => None,
+        }
+    }
+
+    /// Returns the id of the variable this action writes to, if it is a `VariableSet`, `VariableReset` or `VariableSetOverTime` action.
+    #[must_use]
+    pub const fn target_variable(&self) -> Option<i32> {
+        match self {
+            Self::VariableSet { variable, .. }
+            | Self::VariableReset { variable }
+            | Self::VariableSetOverTime { variable, .. } => Some(*variable),
+            _ => None,
+        }
+    }
+
+    /// Mutable version of [`ActionType::target_variable`].
+    pub const fn target_variable_mut(&mut self) -> Option<&mut i32> {
+        match self {
+            Self::VariableSet { variable, .. }
+            | Self::VariableReset { variable }
+            | Self::VariableSetOverTime { variable, .. } => Some(variable),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{Read, Write, Error};
-use super::nova_value::NovaValue;
+use super::{nova_value::NovaValue, static_type::StaticType};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -22,4 +22,267 @@ impl Write for Activator {
         self.activator_type.write(output)?;
         self.parameters.write(output)
     }
-}
\ No newline at end of file
+}
+
+impl Activator {
+    /// Returns the typed form of this activator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activator type is unknown or the parameters don't match its schema.
+    pub fn kind(&self) -> Result<ActivatorKind, Error> {
+        ActivatorKind::try_from(self.clone())
+    }
+}
+
+/// What a parameter of an activator must be.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ActivatorParameterType {
+    /// The objects the activator reacts to: an `Object` or `ObjectSet` value.
+    Objects,
+    /// A value of the given type.
+    Value(StaticType),
+    /// A read of the variable to watch, like an `IntVariable`. Its type doesn't matter.
+    Variable,
+}
+
+impl ActivatorParameterType {
+    /// Whether `value` can be used for a parameter of this type.
+    #[must_use]
+    pub fn accepts(self, value: &NovaValue) -> bool {
+        match self {
+            Self::Objects => matches!(value.dynamic_type.result_type(), StaticType::Object | StaticType::ObjectSet),
+            Self::Value(static_type) => value.dynamic_type.result_type() == static_type,
+            Self::Variable => value.dynamic_type.variable_static_type().is_some(),
+        }
+    }
+}
+
+/// Generates [`ActivatorKind`] together with the id, name and parameter schema of every activator type, so that
+/// they can't disagree with each other.
+macro_rules! define_activator_kind {
+    ($($number:literal => $variant:ident $name:literal $({ $($field:ident: $parameter_type:expr),* })?),* $(,)?) => {
+        /// An [`Activator`] with named parameters.
+        ///
+        /// Converting an `Activator` checks the number and types of its parameters against
+        /// [`ActivatorKind::parameter_types`], converting back gives the same `Activator` again. Reading an
+        /// `ActivatorKind` does the same check.
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        pub enum ActivatorKind {
+            $($variant $({ $($field: NovaValue),* })?,)*
+        }
+
+        impl From<&ActivatorKind> for i32 {
+            fn from(value: &ActivatorKind) -> Self {
+                match value {
+                    $(ActivatorKind::$variant { .. } => $number,)*
+                }
+            }
+        }
+
+        impl ActivatorKind {
+            /// Returns a readable name for the activator type, or `None` for unknown types.
+            pub const fn name(activator_type: i32) -> Option<&'static str> {
+                Some(match activator_type {
+                    $($number => $name,)*
+                    _ => return None,
+                })
+            }
+
+            /// Returns the names and types of the parameters that an activator of the given type takes, in order,
+            /// or `None` for unknown types.
+            pub const fn parameter_types(
+                activator_type: i32,
+            ) -> Option<&'static [(&'static str, ActivatorParameterType)]> {
+                use ActivatorParameterType::{Objects, Value, Variable};
+
+                Some(match activator_type {
+                    $($number => &[$($((stringify!($field), $parameter_type)),*)?],)*
+                    _ => return None,
+                })
+            }
+
+            /// Returns every parameter of this activator together with its name.
+            pub fn parameters(&self) -> Vec<(&'static str, &NovaValue)> {
+                match self {
+                    $(Self::$variant $({ $($field),* })? => vec![$($((stringify!($field), $field)),*)?],)*
+                }
+            }
+
+            /// Builds the activator of the given type from its parameters, which have to match its schema.
+            fn from_parameters(activator_type: i32, parameters: Vec<NovaValue>) -> Option<Self> {
+                let mut parameters = parameters.into_iter();
+
+                Some(match activator_type {
+                    $($number => Self::$variant $({ $($field: parameters.next()?),* })?,)*
+                    _ => return None,
+                })
+            }
+        }
+    };
+}
+
+define_activator_kind!(
+    0 => LevelStart "level start",
+    1 => ObjectActivated "object activated" { target_objects: Objects },
+    2 => ObjectDeactivated "object deactivated" { target_objects: Objects },
+    3 => CollisionEnter "collision enter" { target_objects: Objects, other_objects: Objects },
+    4 => CollisionExit "collision exit" { target_objects: Objects, other_objects: Objects },
+    5 => InputDown "input down",
+    6 => InputReleased "input released",
+    7 => Timer "timer" { interval: Value(StaticType::Float) },
+    8 => VariableChanged "variable changed" { variable: Variable },
+    9 => ObjectDamaged "object damaged" { target_objects: Objects },
+    10 => ObjectKilled "object killed" { target_objects: Objects },
+    11 => PointerDown "pointer down",
+    12 => PointerReleased "pointer released",
+);
+
+impl ActivatorKind {
+    /// Returns the id of the variable a `VariableChanged` activator watches.
+    #[must_use]
+    pub const fn watched_variable(&self) -> Option<i32> {
+        match self {
+            Self::VariableChanged { variable } => Some(variable.int_value),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<Activator> for ActivatorKind {
+    type Error = Error;
+
+    fn try_from(value: Activator) -> Result<Self, Self::Error> {
+        let activator_type = value.activator_type;
+        let schema = Self::parameter_types(activator_type).ok_or(Error::InvalidActivatorType(activator_type))?;
+
+        if value.parameters.len() != schema.len() {
+            return Err(Error::InvalidActivatorParameterCount {
+                activator_type,
+                expected: schema.len(),
+                found: value.parameters.len(),
+            });
+        }
+
+        if let Some(index) = schema.iter().zip(&value.parameters).position(|((_, kind), value)| !kind.accepts(value)) {
+            return Err(Error::InvalidActivatorParameter { activator_type, index });
+        }
+
+        Self::from_parameters(activator_type, value.parameters).ok_or(Error::InvalidActivatorType(activator_type))
+    }
+}
+
+impl From<ActivatorKind> for Activator {
+    fn from(value: ActivatorKind) -> Self {
+        Self {
+            activator_type: i32::from(&value),
+            parameters: value.parameters().into_iter().map(|(_, parameter)| parameter.clone()).collect(),
+        }
+    }
+}
+
+impl Read for ActivatorKind {
+    fn read(input: &mut impl std::io::Read) -> Result<Self, Error> {
+        Activator::read(input)?.try_into()
+    }
+}
+
+impl Write for ActivatorKind {
+    fn write(&self, output: &mut impl std::io::Write) -> Result<(), Error> {
+        Activator::from(self.clone()).write(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::nova_script::dynamic_type::DynamicType;
+
+    fn bytes(value: &impl Write) -> Vec<u8> {
+        let mut output = Vec::new();
+        value.write(&mut output).unwrap();
+        output
+    }
+
+    fn object(entity_id: i32) -> NovaValue {
+        NovaValue { dynamic_type: DynamicType::ObjectConstant, int_value: entity_id, ..Default::default() }
+    }
+
+    fn raw(activator_type: i32, parameters: Vec<NovaValue>) -> Vec<u8> {
+        bytes(&Activator { activator_type, parameters })
+    }
+
+    #[test]
+    fn read_fixture() {
+        // A level start activator: the type id and an empty parameter list.
+        let fixture = [0, 0, 0, 0, 0, 0, 0, 0];
+
+        assert_eq!(ActivatorKind::read(&mut fixture.as_slice()).unwrap(), ActivatorKind::LevelStart);
+        assert_eq!(bytes(&ActivatorKind::LevelStart), fixture);
+    }
+
+    #[test]
+    fn round_trip() {
+        let kinds = [
+            ActivatorKind::LevelStart,
+            ActivatorKind::CollisionEnter { target_objects: object(12), other_objects: object(-1) },
+            ActivatorKind::Timer { interval: NovaValue::default_constant(StaticType::Float) },
+            ActivatorKind::VariableChanged {
+                variable: NovaValue { dynamic_type: DynamicType::IntVariable, int_value: 3, ..Default::default() },
+            },
+            ActivatorKind::PointerReleased,
+        ];
+
+        for kind in kinds {
+            let activator = Activator::from(kind.clone());
+            let written = bytes(&kind);
+
+            assert_eq!(written, bytes(&activator));
+            assert_eq!(ActivatorKind::read(&mut written.as_slice()).unwrap(), kind);
+            assert_eq!(activator.kind().unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn unknown_type() {
+        let fixture = [99, 0, 0, 0, 0, 0, 0, 0];
+
+        assert!(matches!(ActivatorKind::read(&mut fixture.as_slice()), Err(Error::InvalidActivatorType(99))));
+        assert_eq!(ActivatorKind::name(99), None);
+    }
+
+    #[test]
+    fn wrong_parameter_count() {
+        let input = raw(0, vec![object(1)]);
+
+        assert!(matches!(
+            ActivatorKind::read(&mut input.as_slice()),
+            Err(Error::InvalidActivatorParameterCount { activator_type: 0, expected: 0, found: 1 })
+        ));
+
+        let input = raw(3, vec![object(1)]);
+
+        assert!(matches!(
+            ActivatorKind::read(&mut input.as_slice()),
+            Err(Error::InvalidActivatorParameterCount { activator_type: 3, expected: 2, found: 1 })
+        ));
+    }
+
+    #[test]
+    fn wrong_parameter_type() {
+        let input = raw(7, vec![NovaValue::default_constant(StaticType::Bool)]);
+
+        assert!(matches!(
+            ActivatorKind::read(&mut input.as_slice()),
+            Err(Error::InvalidActivatorParameter { activator_type: 7, index: 0 })
+        ));
+
+        let input = raw(4, vec![object(1), NovaValue::default_constant(StaticType::Int)]);
+
+        assert!(matches!(
+            ActivatorKind::read(&mut input.as_slice()),
+            Err(Error::InvalidActivatorParameter { activator_type: 4, index: 1 })
+        ));
+    }
+}
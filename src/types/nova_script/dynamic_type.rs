@@ -1,4 +1,5 @@
 use crate::{Read, Write, Error};
+use super::static_type::StaticType;
 
 macro_rules! define_dynamic_type {
     ($($name:ident = $number:expr),*) => {
@@ -381,4 +382,360 @@ impl Write for DynamicType {
     fn write(&self, output: &mut impl std::io::Write) -> Result<(), Error> {
         i32::from(self).write(output)
     }
-}
\ No newline at end of file
+}
+
+macro_rules! define_signatures {
+    ($($name:ident => $result:ident($($operand:ident),*));*$(;)?) => {
+        impl DynamicType {
+            /// The type of the value this dynamic type evaluates to.
+            pub const fn result_type(self) -> StaticType {
+                match self {
+                    $(Self::$name => StaticType::$result,)*
+                }
+            }
+
+            /// The types of the operands this dynamic type expects in `NovaValue::sub_values`, in order.
+            ///
+            /// Constants, variables and parameters have no operands, their value is stored in the slots of the `NovaValue` itself.
+            pub const fn operand_types(self) -> &'static [StaticType] {
+                match self {
+                    $(Self::$name => &[$(StaticType::$operand),*],)*
+                }
+            }
+        }
+    };
+}
+
+define_signatures!(
+    BoolConstant => Bool();
+    BoolVariable => Bool();
+    BoolNot => Bool(Bool);
+    BoolAnd => Bool(Bool, Bool);
+    BoolOr => Bool(Bool, Bool);
+    BoolEqualBool => Bool(Bool, Bool);
+    BoolEqualNumber => Bool(Float, Float);
+    BoolEqualString => Bool(String, String);
+    BoolEqualColor => Bool(Color, Color);
+    BoolEqualVector => Bool(Vector, Vector);
+    BoolEqualObject => Bool(Object, Object);
+    BoolNotEqualBool => Bool(Bool, Bool);
+    BoolNotEqualNumber => Bool(Float, Float);
+    BoolNotEqualString => Bool(String, String);
+    BoolNotEqualColor => Bool(Color, Color);
+    BoolNotEqualVector => Bool(Vector, Vector);
+    BoolNotEqualObject => Bool(Object, Object);
+    BoolLess => Bool(Float, Float);
+    BoolLessOrEqual => Bool(Float, Float);
+    BoolGreater => Bool(Float, Float);
+    BoolGreaterOrEqual => Bool(Float, Float);
+    BoolObjectDead => Bool(Object);
+    BoolPlayerOnGround => Bool(Object);
+    BoolPlayerOnWalljump => Bool(Object);
+    BoolPlayerOnBooster => Bool(Object);
+    BoolPlayerOnSwing => Bool(Object);
+    BoolPlayerInFloatingZone => Bool(Object);
+    BoolPlayerUsingGlider => Bool(Object);
+    BoolObjectsColliding => Bool(Object, Object);
+    BoolInputDown => Bool();
+    BoolInputDownLeft => Bool();
+    BoolInputDownRight => Bool();
+    BoolInputHeld => Bool();
+    BoolInputHeldLeft => Bool();
+    BoolInputHeldRight => Bool();
+    BoolInputReleased => Bool();
+    BoolInputReleasedLeft => Bool();
+    BoolInputReleasedRight => Bool();
+    IntConstant => Int();
+    IntVariable => Int();
+    IntAdd => Int(Int, Int);
+    IntSubtract => Int(Int, Int);
+    IntMultiply => Int(Int, Int);
+    IntDivide => Int(Int, Int);
+    IntModulo => Int(Int, Int);
+    IntMin => Int(Int, Int);
+    IntMax => Int(Int, Int);
+    IntAbs => Int(Int);
+    IntSign => Int(Int);
+    IntRound => Int(Float);
+    IntCeil => Int(Float);
+    IntFloor => Int(Float);
+    IntRandom => Int(Int, Int);
+    IntRepeatCount => Int();
+    IntObjectDirection => Int(Object);
+    IntObjectSetCount => Int(ObjectSet);
+    FloatConstant => Float();
+    FloatVariable => Float();
+    FloatAdd => Float(Float, Float);
+    FloatSubtract => Float(Float, Float);
+    FloatMultiply => Float(Float, Float);
+    FloatDivide => Float(Float, Float);
+    FloatModulo => Float(Float, Float);
+    FloatMin => Float(Float, Float);
+    FloatMax => Float(Float, Float);
+    FloatAbs => Float(Float);
+    FloatSign => Float(Float);
+    FloatRound => Float(Float);
+    FloatCeil => Float(Float);
+    FloatFloor => Float(Float);
+    FloatCos => Float(Float);
+    FloatSin => Float(Float);
+    FloatTan => Float(Float);
+    FloatAcos => Float(Float);
+    FloatAsin => Float(Float);
+    FloatAtan => Float(Float);
+    FloatSqrt => Float(Float);
+    FloatPow => Float(Float, Float);
+    FloatRandom => Float(Float, Float);
+    FloatTime => Float();
+    FloatSemitones => Float(Float);
+    FloatVectorX => Float(Vector);
+    FloatVectorY => Float(Vector);
+    FloatVectorLength => Float(Vector);
+    FloatVectorLengthSqr => Float(Vector);
+    FloatVectorDistance => Float(Vector, Vector);
+    FloatVectorDistanceSqr => Float(Vector, Vector);
+    FloatVectorDot => Float(Vector, Vector);
+    FloatVectorAngle => Float(Vector);
+    FloatVectorAngleBetween => Float(Vector, Vector);
+    FloatObjectRotation => Float(Object);
+    FloatObjectGlobalRotation => Float(Object);
+    FloatCameraViewportSize => Float();
+    FloatDamageAmount => Float();
+    StringConstant => String();
+    StringVariable => String();
+    StringFromInt => String(Int);
+    StringFromFloat => String(Float);
+    StringConcat => String(String, String);
+    ColorConstant => Color();
+    ColorValues => Color(Float, Float, Float, Float);
+    ColorVariable => Color();
+    ColorObjectColor => Color(Object);
+    VectorConstant => Vector();
+    VectorValues => Vector(Float, Float);
+    VectorVariable => Vector();
+    VectorAdd => Vector(Vector, Vector);
+    VectorSubtract => Vector(Vector, Vector);
+    VectorMultiply => Vector(Vector, Float);
+    VectorDivide => Vector(Vector, Float);
+    VectorNormalize => Vector(Vector);
+    VectorPerpendicular => Vector(Vector);
+    VectorReflect => Vector(Vector, Vector);
+    VectorObjectPos => Vector(Object);
+    VectorObjectGlobalPos => Vector(Object);
+    VectorObjectScale => Vector(Object);
+    VectorObjectGlobalScale => Vector(Object);
+    VectorObjectVelocity => Vector(Object);
+    VectorCameraPos => Vector();
+    SoundConstant => Sound();
+    SoundVariable => Sound();
+    MusicConstant => Music();
+    MusicVariable => Music();
+    ObjectConstant => Object();
+    ObjectVariable => Object();
+    ObjectAnyObject => Object();
+    ObjectFirstFromSet => Object(ObjectSet);
+    ObjectRandomFromSet => Object(ObjectSet);
+    ObjectElementFromSet => Object(ObjectSet, Int);
+    ObjectSourceObject => Object();
+    ObjectCollidedObject => Object();
+    ObjectTargetObject => Object();
+    ObjectPlayer => Object();
+    ObjectParent => Object(Object);
+    ObjectSetConstant => ObjectSet();
+    ObjectSetVariable => ObjectSet();
+    ObjectSetConcat => ObjectSet(ObjectSet, ObjectSet);
+    ObjectSetPlayers => ObjectSet();
+    ObjectSetObjectsWithTagDeprecated => ObjectSet(String);
+    TransitionConstant => Transition();
+    TransitionVariable => Transition();
+    EasingConstant => Easing();
+    EasingVariable => Easing();
+    ObjectSetChildren => ObjectSet(Object);
+    BoolObjectActivated => Bool(Object);
+    FloatLevelTime => Float();
+    BoolPlayerJumpLocked => Bool(Object);
+    StringObjectTag => String(Object);
+    SpriteConstant => Sprite();
+    SpriteVariable => Sprite();
+    ScriptConstant => Script();
+    ScriptVariable => Script();
+    BoolParameter => Bool();
+    IntParameter => Int();
+    FloatParameter => Float();
+    StringParameter => String();
+    ColorParameter => Color();
+    VectorParameter => Vector();
+    SoundParameter => Sound();
+    MusicParameter => Music();
+    ObjectParameter => Object();
+    ObjectSetParameter => ObjectSet();
+    TransitionParameter => Transition();
+    EasingParameter => Easing();
+    SpriteParameter => Sprite();
+    ScriptParameter => Script();
+    BoolObjectsCollidingWithPoint => Bool(Object, Vector);
+    FloatRoundDecimals => Float(Float, Int);
+    VectorPointerPositionDeprecated => Vector();
+    VectorPointerWorldPositionDeprecated => Vector();
+    VectorCollisionPoint => Vector();
+    VectorCollisionNormal => Vector();
+    ObjectRepeatObject => Object();
+    ObjectClosestFromPoint => Object(ObjectSet, Vector);
+    ObjectSetAllObjectsDeprecated => ObjectSet();
+    ObjectSetObjectsInLayer => ObjectSet(Layer);
+    ObjectSetObjectsInCircle => ObjectSet(Vector, Float);
+    LayerConstant => Layer();
+    LayerVariable => Layer();
+    LayerParameter => Layer();
+    VectorRotate => Vector(Vector, Float);
+    IntLastSoundInstance => Int();
+    ObjectSetUnion => ObjectSet(ObjectSet, ObjectSet);
+    ObjectSetIntersection => ObjectSet(ObjectSet, ObjectSet);
+    ObjectSetDifference => ObjectSet(ObjectSet, ObjectSet);
+    ObjectSetRemoveAtIndex => ObjectSet(ObjectSet, Int);
+    VectorPointerPosition => Vector();
+    VectorPointerWorldPosition => Vector();
+    BoolPointerDown => Bool();
+    BoolPointerHeld => Bool();
+    BoolPointerReleased => Bool();
+    FloatColorR => Float(Color);
+    FloatColorG => Float(Color);
+    FloatColorB => Float(Color);
+    FloatColorA => Float(Color);
+    StringSubstring => String(String, Int, Int);
+    IntStringLength => Int(String);
+    IntFromString => Int(String);
+    FloatFromString => Float(String);
+    StringFromVector => String(Vector);
+    FloatAtan2 => Float(Float, Float);
+    PrefabConstant => Prefab();
+    PrefabVariable => Prefab();
+    PrefabParameter => Prefab();
+    ObjectLastCreatedObject => Object();
+    ObjectSetSetAtIndex => ObjectSet(ObjectSet, Int, Object);
+    ObjectSetSublist => ObjectSet(ObjectSet, Int, Int);
+    ObjectSetShuffle => ObjectSet(ObjectSet);
+    ObjectSetReverse => ObjectSet(ObjectSet);
+    BoolListConstant => BoolList();
+    BoolListVariable => BoolList();
+    BoolListParameter => BoolList();
+    BoolListAppend => BoolList(BoolList, Bool);
+    BoolListRemove => BoolList(BoolList, Bool);
+    BoolListSetAtIndex => BoolList(BoolList, Int, Bool);
+    BoolListRemoveAtIndex => BoolList(BoolList, Int);
+    BoolListSublist => BoolList(BoolList, Int, Int);
+    BoolListSort => BoolList(BoolList);
+    BoolListShuffle => BoolList(BoolList);
+    BoolListReverse => BoolList(BoolList);
+    IntListConstant => IntList();
+    IntListVariable => IntList();
+    IntListParameter => IntList();
+    IntListAppend => IntList(IntList, Int);
+    IntListRemove => IntList(IntList, Int);
+    IntListSetAtIndex => IntList(IntList, Int, Int);
+    IntListRemoveAtIndex => IntList(IntList, Int);
+    IntListSublist => IntList(IntList, Int, Int);
+    IntListSort => IntList(IntList);
+    IntListShuffle => IntList(IntList);
+    IntListReverse => IntList(IntList);
+    FloatListConstant => FloatList();
+    FloatListVariable => FloatList();
+    FloatListParameter => FloatList();
+    FloatListAppend => FloatList(FloatList, Float);
+    FloatListRemove => FloatList(FloatList, Float);
+    FloatListSetAtIndex => FloatList(FloatList, Int, Float);
+    FloatListRemoveAtIndex => FloatList(FloatList, Int);
+    FloatListSublist => FloatList(FloatList, Int, Int);
+    FloatListSort => FloatList(FloatList);
+    FloatListShuffle => FloatList(FloatList);
+    FloatListReverse => FloatList(FloatList);
+    StringListConstant => StringList();
+    StringListVariable => StringList();
+    StringListParameter => StringList();
+    StringListAppend => StringList(StringList, String);
+    StringListRemove => StringList(StringList, String);
+    StringListSetAtIndex => StringList(StringList, Int, String);
+    StringListRemoveAtIndex => StringList(StringList, Int);
+    StringListSublist => StringList(StringList, Int, Int);
+    StringListSort => StringList(StringList);
+    StringListShuffle => StringList(StringList);
+    StringListReverse => StringList(StringList);
+    ColorListConstant => ColorList();
+    ColorListVariable => ColorList();
+    ColorListParameter => ColorList();
+    ColorListAppend => ColorList(ColorList, Color);
+    ColorListRemove => ColorList(ColorList, Color);
+    ColorListSetAtIndex => ColorList(ColorList, Int, Color);
+    ColorListRemoveAtIndex => ColorList(ColorList, Int);
+    ColorListSublist => ColorList(ColorList, Int, Int);
+    ColorListShuffle => ColorList(ColorList);
+    ColorListReverse => ColorList(ColorList);
+    VectorListConstant => VectorList();
+    VectorListVariable => VectorList();
+    VectorListParameter => VectorList();
+    VectorListAppend => VectorList(VectorList, Vector);
+    VectorListRemove => VectorList(VectorList, Vector);
+    VectorListSetAtIndex => VectorList(VectorList, Int, Vector);
+    VectorListRemoveAtIndex => VectorList(VectorList, Int);
+    VectorListSublist => VectorList(VectorList, Int, Int);
+    VectorListShuffle => VectorList(VectorList);
+    VectorListReverse => VectorList(VectorList);
+    BoolFirstFromList => Bool(BoolList);
+    BoolRandomFromList => Bool(BoolList);
+    BoolElementFromList => Bool(BoolList, Int);
+    IntFirstFromList => Int(IntList);
+    IntRandomFromList => Int(IntList);
+    IntElementFromList => Int(IntList, Int);
+    FloatFirstFromList => Float(FloatList);
+    FloatRandomFromList => Float(FloatList);
+    FloatElementFromList => Float(FloatList, Int);
+    StringFirstFromList => String(StringList);
+    StringRandomFromList => String(StringList);
+    StringElementFromList => String(StringList, Int);
+    ColorFirstFromList => Color(ColorList);
+    ColorRandomFromList => Color(ColorList);
+    ColorElementFromList => Color(ColorList, Int);
+    VectorFirstFromList => Vector(VectorList);
+    VectorRandomFromList => Vector(VectorList);
+    VectorElementFromList => Vector(VectorList, Int);
+    BoolBoolListContains => Bool(BoolList, Bool);
+    BoolIntListContains => Bool(IntList, Int);
+    BoolFloatListContains => Bool(FloatList, Float);
+    BoolStringListContains => Bool(StringList, String);
+    BoolColorListContains => Bool(ColorList, Color);
+    BoolVectorListContains => Bool(VectorList, Vector);
+    BoolObjectSetContains => Bool(ObjectSet, Object);
+    IntBoolListCount => Int(BoolList);
+    IntBoolListIndexOf => Int(BoolList, Bool);
+    IntIntListCount => Int(IntList);
+    IntIntListIndexOf => Int(IntList, Int);
+    IntFloatListCount => Int(FloatList);
+    IntFloatListIndexOf => Int(FloatList, Float);
+    IntStringListCount => Int(StringList);
+    IntStringListIndexOf => Int(StringList, String);
+    IntColorListCount => Int(ColorList);
+    IntColorListIndexOf => Int(ColorList, Color);
+    IntVectorListCount => Int(VectorList);
+    IntVectorListIndexOf => Int(VectorList, Vector);
+    IntObjectSetIndexOf => Int(ObjectSet, Object);
+    ObjectSetAllObjects => ObjectSet();
+    ObjectSetAllRootObjects => ObjectSet();
+    ObjectSetObjectsWithTag => ObjectSet(String);
+    PrefabListConstant => PrefabList();
+    PrefabListVariable => PrefabList();
+    PrefabListParameter => PrefabList();
+    PrefabListAppend => PrefabList(PrefabList, Prefab);
+    PrefabListRemove => PrefabList(PrefabList, Prefab);
+    PrefabListSetAtIndex => PrefabList(PrefabList, Int, Prefab);
+    PrefabListRemoveAtIndex => PrefabList(PrefabList, Int);
+    PrefabListSublist => PrefabList(PrefabList, Int, Int);
+    PrefabListShuffle => PrefabList(PrefabList);
+    PrefabListReverse => PrefabList(PrefabList);
+    PrefabFirstFromList => Prefab(PrefabList);
+    PrefabRandomFromList => Prefab(PrefabList);
+    PrefabElementFromList => Prefab(PrefabList, Int);
+    BoolPrefabListContains => Bool(PrefabList, Prefab);
+    IntPrefabListCount => Int(PrefabList);
+    IntPrefabIndexOf => Int(PrefabList, Prefab);
+    FloatFromInt => Float(Int);
+);
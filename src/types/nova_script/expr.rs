@@ -0,0 +1,965 @@
+//! Typed builders for `NovaValue` trees.
+//!
+//! Every wrapper holds a `NovaValue` that evaluates to one [`StaticType`] and whose operands have the types their
+//! dynamic types expect, so expressions can be written with operators and methods instead of by hand:
+//!
+//! ```
+//! use exolvl::types::nova_script::{expr::{IntExpr, TypedExpr}, nova_value::NovaValue};
+//!
+//! let health = IntExpr::variable(1);
+//! let value: NovaValue = (health - 1).lt(10).into();
+//! ```
+//!
+//! Existing values can be lifted back with `TryFrom<NovaValue>`, which checks the whole tree.
+
+use std::fmt;
+use std::ops::{Add, BitAnd, BitOr, Div, Mul, Not, Rem, Sub};
+
+use ordered_float::OrderedFloat;
+
+use super::{dynamic_type::DynamicType, nova_value::NovaValue, static_type::StaticType};
+use crate::type_check::TypeErrorKind;
+use crate::types::{color::Color, vec2::Vec2};
+
+/// Why a `NovaValue` couldn't be lifted into a typed expression.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LiftError {
+    /// The `sub_values` indices leading from the lifted value to the offending one. Empty for the lifted value itself.
+    pub path: Vec<usize>,
+    /// What is wrong.
+    pub kind: TypeErrorKind,
+}
+
+impl fmt::Display for LiftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            return write!(f, "{}", self.kind);
+        }
+
+        for (i, index) in self.path.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+
+            write!(f, "sub_values[{index}]")?;
+        }
+
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl std::error::Error for LiftError {}
+
+/// Checks that `value` evaluates to `expected` and that all of its operands are well typed.
+fn check(value: &NovaValue, expected: StaticType, path: &mut Vec<usize>) -> Result<(), LiftError> {
+    let error = |path: &Vec<usize>, kind| LiftError { path: path.clone(), kind };
+
+    let dynamic_type = value.dynamic_type;
+    let found = dynamic_type.result_type();
+
+    if found != expected {
+        return Err(error(path, TypeErrorKind::Mismatch { expected, found }));
+    }
+
+    let operand_types = dynamic_type.operand_types();
+    let operands = value.sub_values.as_deref().unwrap_or_default();
+
+    if operands.len() != operand_types.len() {
+        return Err(error(
+            path,
+            TypeErrorKind::OperandCount {
+                dynamic_type,
+                expected: operand_types.len(),
+                found: operands.len(),
+            },
+        ));
+    }
+
+    for (index, (operand, &operand_type)) in operands.iter().zip(operand_types).enumerate() {
+        path.push(index);
+        check(operand, operand_type, path)?;
+        path.pop();
+    }
+
+    Ok(())
+}
+
+/// Builds a value of `dynamic_type` from its operands.
+fn node(dynamic_type: DynamicType, operands: Vec<NovaValue>) -> NovaValue {
+    debug_assert_eq!(dynamic_type.operand_types().len(), operands.len());
+
+    NovaValue {
+        dynamic_type,
+        sub_values: (!operands.is_empty()).then_some(operands),
+        ..Default::default()
+    }
+}
+
+/// A `NovaValue` that is known to evaluate to [`TypedExpr::STATIC_TYPE`].
+pub trait TypedExpr: Sized + Into<NovaValue> + TryFrom<NovaValue, Error = LiftError> {
+    /// The type this expression evaluates to.
+    const STATIC_TYPE: StaticType;
+
+    /// The underlying value.
+    fn as_value(&self) -> &NovaValue;
+
+    /// Reads the variable with this id.
+    fn variable(variable_id: i32) -> Self;
+
+    /// Reads the script parameter with this id.
+    fn parameter(parameter_id: i32) -> Self;
+}
+
+macro_rules! define_exprs {
+    ($($name:ident => $static_type:ident),*$(,)?) => {
+        $(
+            #[doc = concat!("A `NovaValue` that evaluates to a `", stringify!($static_type), "`.")]
+            #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+            pub struct $name(NovaValue);
+
+            impl TypedExpr for $name {
+                const STATIC_TYPE: StaticType = StaticType::$static_type;
+
+                fn as_value(&self) -> &NovaValue {
+                    &self.0
+                }
+
+                fn variable(variable_id: i32) -> Self {
+                    Self(NovaValue {
+                        dynamic_type: Self::STATIC_TYPE.variable_type(),
+                        int_value: variable_id,
+                        ..Default::default()
+                    })
+                }
+
+                fn parameter(parameter_id: i32) -> Self {
+                    Self(NovaValue {
+                        dynamic_type: Self::STATIC_TYPE.parameter_type(),
+                        int_value: parameter_id,
+                        ..Default::default()
+                    })
+                }
+            }
+
+            impl From<$name> for NovaValue {
+                fn from(expr: $name) -> Self {
+                    expr.0
+                }
+            }
+
+            impl TryFrom<NovaValue> for $name {
+                type Error = LiftError;
+
+                fn try_from(value: NovaValue) -> Result<Self, Self::Error> {
+                    check(&value, Self::STATIC_TYPE, &mut Vec::new())?;
+
+                    Ok(Self(value))
+                }
+            }
+        )*
+    };
+}
+
+define_exprs!(
+    BoolExpr => Bool,
+    IntExpr => Int,
+    FloatExpr => Float,
+    StringExpr => String,
+    ColorExpr => Color,
+    VectorExpr => Vector,
+    SoundExpr => Sound,
+    MusicExpr => Music,
+    ObjectExpr => Object,
+    ObjectSetExpr => ObjectSet,
+    TransitionExpr => Transition,
+    EasingExpr => Easing,
+    SpriteExpr => Sprite,
+    ScriptExpr => Script,
+    LayerExpr => Layer,
+    PrefabExpr => Prefab,
+    BoolListExpr => BoolList,
+    IntListExpr => IntList,
+    FloatListExpr => FloatList,
+    StringListExpr => StringList,
+    ColorListExpr => ColorList,
+    VectorListExpr => VectorList,
+    PrefabListExpr => PrefabList,
+);
+
+/// Constants that refer to an entity by the id in `int_value`.
+macro_rules! id_constants {
+    ($($name:ident),*$(,)?) => {
+        $(
+            impl $name {
+                /// A constant referring to the entity with this id.
+                pub fn constant(id: i32) -> Self {
+                    Self(NovaValue {
+                        int_value: id,
+                        ..NovaValue::default_constant(Self::STATIC_TYPE)
+                    })
+                }
+            }
+        )*
+    };
+}
+
+id_constants!(SoundExpr, MusicExpr, ObjectExpr, TransitionExpr, EasingExpr, SpriteExpr, ScriptExpr, LayerExpr, PrefabExpr);
+
+/// Constants stored in a single slot, with a `From` impl for the plain value.
+macro_rules! scalar_constants {
+    ($($name:ident($value:ident: $type:ty) => $slot:ident = $convert:expr),*$(,)?) => {
+        $(
+            impl $name {
+                /// A constant value.
+                pub fn constant($value: $type) -> Self {
+                    Self(NovaValue {
+                        $slot: $convert,
+                        ..NovaValue::default_constant(Self::STATIC_TYPE)
+                    })
+                }
+            }
+
+            impl From<$type> for $name {
+                fn from(value: $type) -> Self {
+                    Self::constant(value)
+                }
+            }
+        )*
+    };
+}
+
+scalar_constants!(
+    BoolExpr(value: bool) => bool_value = value,
+    IntExpr(value: i32) => int_value = value,
+    FloatExpr(value: f32) => float_value = OrderedFloat(value),
+    StringExpr(value: String) => string_value = Some(value),
+    ColorExpr(value: Color) => color_value = value,
+    VectorExpr(value: Vec2) => vector_value = value,
+);
+
+impl From<&str> for StringExpr {
+    fn from(value: &str) -> Self {
+        Self::constant(value.to_string())
+    }
+}
+
+/// Constants stored in one of the list slots.
+macro_rules! list_constants {
+    ($($name:ident($element:ty) => $slot:ident = $convert:expr),*$(,)?) => {
+        $(
+            impl $name {
+                /// A constant list.
+                pub fn constant(values: impl IntoIterator<Item = $element>) -> Self {
+                    Self(NovaValue {
+                        $slot: Some(values.into_iter().map($convert).collect()),
+                        ..NovaValue::default_constant(Self::STATIC_TYPE)
+                    })
+                }
+            }
+
+            impl FromIterator<$element> for $name {
+                fn from_iter<I: IntoIterator<Item = $element>>(values: I) -> Self {
+                    Self::constant(values)
+                }
+            }
+        )*
+    };
+}
+
+list_constants!(
+    ObjectSetExpr(i32) => int_list_values = std::convert::identity,
+    BoolListExpr(bool) => bool_list_values = std::convert::identity,
+    IntListExpr(i32) => int_list_values = std::convert::identity,
+    FloatListExpr(f32) => float_list_values = OrderedFloat,
+    StringListExpr(String) => string_list_values = std::convert::identity,
+    ColorListExpr(Color) => color_list_values = std::convert::identity,
+    VectorListExpr(Vec2) => vector_list_values = std::convert::identity,
+    PrefabListExpr(i32) => int_list_values = std::convert::identity,
+);
+
+/// Methods that take `self` as the first operand.
+macro_rules! methods {
+    ($name:ident { $($(#[$meta:meta])* fn $method:ident($($arg:ident: $arg_type:ident),*) -> $output:ident = $dynamic_type:ident;)* }) => {
+        impl $name {
+            $(
+                $(#[$meta])*
+                #[must_use]
+                pub fn $method(self $(, $arg: impl Into<$arg_type>)*) -> $output {
+                    $output(node(DynamicType::$dynamic_type, vec![self.0 $(, $arg.into().0)*]))
+                }
+            )*
+        }
+    };
+}
+
+/// Associated functions whose operands are all arguments.
+macro_rules! functions {
+    ($name:ident { $($(#[$meta:meta])* fn $function:ident($($arg:ident: $arg_type:ident),*) = $dynamic_type:ident;)* }) => {
+        impl $name {
+            $(
+                $(#[$meta])*
+                pub fn $function($($arg: impl Into<$arg_type>),*) -> Self {
+                    Self(node(DynamicType::$dynamic_type, vec![$($arg.into().0),*]))
+                }
+            )*
+        }
+    };
+}
+
+/// Binary operators whose right hand side can be anything that converts into `$rhs`.
+macro_rules! operators {
+    ($name:ident { $($trait:ident::$method:ident($rhs:ident) = $dynamic_type:ident;)* }) => {
+        $(
+            impl<R: Into<$rhs>> $trait<R> for $name {
+                type Output = Self;
+
+                fn $method(self, rhs: R) -> Self {
+                    Self(node(DynamicType::$dynamic_type, vec![self.0, rhs.into().0]))
+                }
+            }
+        )*
+    };
+}
+
+operators!(BoolExpr {
+    BitAnd::bitand(BoolExpr) = BoolAnd;
+    BitOr::bitor(BoolExpr) = BoolOr;
+});
+
+impl Not for BoolExpr {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(node(DynamicType::BoolNot, vec![self.0]))
+    }
+}
+
+methods!(BoolExpr {
+    /// `self == other`.
+    fn equals(other: BoolExpr) -> BoolExpr = BoolEqualBool;
+    /// `self != other`.
+    fn not_equals(other: BoolExpr) -> BoolExpr = BoolNotEqualBool;
+});
+
+functions!(BoolExpr {
+    /// Whether the player pressed down this frame.
+    fn input_down() = BoolInputDown;
+    /// Whether the player pressed down on the left this frame.
+    fn input_down_left() = BoolInputDownLeft;
+    /// Whether the player pressed down on the right this frame.
+    fn input_down_right() = BoolInputDownRight;
+    /// Whether the player is holding down.
+    fn input_held() = BoolInputHeld;
+    /// Whether the player is holding down on the left.
+    fn input_held_left() = BoolInputHeldLeft;
+    /// Whether the player is holding down on the right.
+    fn input_held_right() = BoolInputHeldRight;
+    /// Whether the player released this frame.
+    fn input_released() = BoolInputReleased;
+    /// Whether the player released on the left this frame.
+    fn input_released_left() = BoolInputReleasedLeft;
+    /// Whether the player released on the right this frame.
+    fn input_released_right() = BoolInputReleasedRight;
+    /// Whether the pointer was pressed this frame.
+    fn pointer_down() = BoolPointerDown;
+    /// Whether the pointer is held.
+    fn pointer_held() = BoolPointerHeld;
+    /// Whether the pointer was released this frame.
+    fn pointer_released() = BoolPointerReleased;
+});
+
+operators!(IntExpr {
+    Add::add(IntExpr) = IntAdd;
+    Sub::sub(IntExpr) = IntSubtract;
+    Mul::mul(IntExpr) = IntMultiply;
+    Div::div(IntExpr) = IntDivide;
+    Rem::rem(IntExpr) = IntModulo;
+});
+
+methods!(IntExpr {
+    /// The smaller of the two values.
+    fn min(other: IntExpr) -> IntExpr = IntMin;
+    /// The larger of the two values.
+    fn max(other: IntExpr) -> IntExpr = IntMax;
+    /// The absolute value.
+    fn abs() -> IntExpr = IntAbs;
+    /// `-1`, `0` or `1` depending on the sign.
+    fn sign() -> IntExpr = IntSign;
+    /// Converts to a float.
+    fn into_float() -> FloatExpr = FloatFromInt;
+    /// Formats as a string.
+    fn into_string() -> StringExpr = StringFromInt;
+});
+
+/// Comparisons between ints go through `FloatFromInt`, as the game only compares numbers as floats.
+impl IntExpr {
+    fn compare(self, dynamic_type: DynamicType, other: impl Into<Self>) -> BoolExpr {
+        BoolExpr(node(dynamic_type, vec![self.into_float().0, other.into().into_float().0]))
+    }
+
+    /// `self < other`.
+    pub fn lt(self, other: impl Into<Self>) -> BoolExpr {
+        self.compare(DynamicType::BoolLess, other)
+    }
+
+    /// `self <= other`.
+    pub fn le(self, other: impl Into<Self>) -> BoolExpr {
+        self.compare(DynamicType::BoolLessOrEqual, other)
+    }
+
+    /// `self > other`.
+    pub fn gt(self, other: impl Into<Self>) -> BoolExpr {
+        self.compare(DynamicType::BoolGreater, other)
+    }
+
+    /// `self >= other`.
+    pub fn ge(self, other: impl Into<Self>) -> BoolExpr {
+        self.compare(DynamicType::BoolGreaterOrEqual, other)
+    }
+
+    /// `self == other`.
+    pub fn equals(self, other: impl Into<Self>) -> BoolExpr {
+        self.compare(DynamicType::BoolEqualNumber, other)
+    }
+
+    /// `self != other`.
+    pub fn not_equals(self, other: impl Into<Self>) -> BoolExpr {
+        self.compare(DynamicType::BoolNotEqualNumber, other)
+    }
+}
+
+functions!(IntExpr {
+    /// A random int between `min` and `max`.
+    fn random(min: IntExpr, max: IntExpr) = IntRandom;
+    /// The iteration of the enclosing `Repeat` action.
+    fn repeat_count() = IntRepeatCount;
+    /// The instance of the last sound that was played.
+    fn last_sound_instance() = IntLastSoundInstance;
+});
+
+impl From<IntExpr> for FloatExpr {
+    fn from(value: IntExpr) -> Self {
+        value.into_float()
+    }
+}
+
+operators!(FloatExpr {
+    Add::add(FloatExpr) = FloatAdd;
+    Sub::sub(FloatExpr) = FloatSubtract;
+    Mul::mul(FloatExpr) = FloatMultiply;
+    Div::div(FloatExpr) = FloatDivide;
+    Rem::rem(FloatExpr) = FloatModulo;
+});
+
+methods!(FloatExpr {
+    /// The smaller of the two values.
+    fn min(other: FloatExpr) -> FloatExpr = FloatMin;
+    /// The larger of the two values.
+    fn max(other: FloatExpr) -> FloatExpr = FloatMax;
+    /// The absolute value.
+    fn abs() -> FloatExpr = FloatAbs;
+    /// `-1`, `0` or `1` depending on the sign.
+    fn sign() -> FloatExpr = FloatSign;
+    /// Rounds to the nearest whole number.
+    fn round() -> FloatExpr = FloatRound;
+    /// Rounds up.
+    fn ceil() -> FloatExpr = FloatCeil;
+    /// Rounds down.
+    fn floor() -> FloatExpr = FloatFloor;
+    /// Rounds to the given number of decimals.
+    fn round_decimals(decimals: IntExpr) -> FloatExpr = FloatRoundDecimals;
+    /// Rounds to the nearest int.
+    fn round_to_int() -> IntExpr = IntRound;
+    /// Rounds up to an int.
+    fn ceil_to_int() -> IntExpr = IntCeil;
+    /// Rounds down to an int.
+    fn floor_to_int() -> IntExpr = IntFloor;
+    /// The cosine, in degrees.
+    fn cos() -> FloatExpr = FloatCos;
+    /// The sine, in degrees.
+    fn sin() -> FloatExpr = FloatSin;
+    /// The tangent, in degrees.
+    fn tan() -> FloatExpr = FloatTan;
+    /// The arccosine, in degrees.
+    fn acos() -> FloatExpr = FloatAcos;
+    /// The arcsine, in degrees.
+    fn asin() -> FloatExpr = FloatAsin;
+    /// The arctangent, in degrees.
+    fn atan() -> FloatExpr = FloatAtan;
+    /// The angle of the point `(x, self)`, in degrees.
+    fn atan2(x: FloatExpr) -> FloatExpr = FloatAtan2;
+    /// The square root.
+    fn sqrt() -> FloatExpr = FloatSqrt;
+    /// `self` raised to `exponent`.
+    fn pow(exponent: FloatExpr) -> FloatExpr = FloatPow;
+    /// The pitch multiplier for shifting by this many semitones.
+    fn semitones() -> FloatExpr = FloatSemitones;
+    /// Formats as a string.
+    fn into_string() -> StringExpr = StringFromFloat;
+    /// `self < other`.
+    fn lt(other: FloatExpr) -> BoolExpr = BoolLess;
+    /// `self <= other`.
+    fn le(other: FloatExpr) -> BoolExpr = BoolLessOrEqual;
+    /// `self > other`.
+    fn gt(other: FloatExpr) -> BoolExpr = BoolGreater;
+    /// `self >= other`.
+    fn ge(other: FloatExpr) -> BoolExpr = BoolGreaterOrEqual;
+    /// `self == other`.
+    fn equals(other: FloatExpr) -> BoolExpr = BoolEqualNumber;
+    /// `self != other`.
+    fn not_equals(other: FloatExpr) -> BoolExpr = BoolNotEqualNumber;
+});
+
+functions!(FloatExpr {
+    /// A random float between `min` and `max`.
+    fn random(min: FloatExpr, max: FloatExpr) = FloatRandom;
+    /// The time since the script started, in seconds.
+    fn time() = FloatTime;
+    /// The time since the level started, in seconds.
+    fn level_time() = FloatLevelTime;
+    /// The size of the camera viewport.
+    fn camera_viewport_size() = FloatCameraViewportSize;
+    /// The amount of damage that activated the script.
+    fn damage_amount() = FloatDamageAmount;
+});
+
+operators!(StringExpr {
+    Add::add(StringExpr) = StringConcat;
+});
+
+methods!(StringExpr {
+    /// The part of the string of `length` characters starting at `start`.
+    fn substring(start: IntExpr, length: IntExpr) -> StringExpr = StringSubstring;
+    /// The number of characters.
+    fn length() -> IntExpr = IntStringLength;
+    /// Parses the string as an int.
+    fn parse_int() -> IntExpr = IntFromString;
+    /// Parses the string as a float.
+    fn parse_float() -> FloatExpr = FloatFromString;
+    /// `self == other`.
+    fn equals(other: StringExpr) -> BoolExpr = BoolEqualString;
+    /// `self != other`.
+    fn not_equals(other: StringExpr) -> BoolExpr = BoolNotEqualString;
+});
+
+methods!(ColorExpr {
+    /// The red component.
+    fn r() -> FloatExpr = FloatColorR;
+    /// The green component.
+    fn g() -> FloatExpr = FloatColorG;
+    /// The blue component.
+    fn b() -> FloatExpr = FloatColorB;
+    /// The alpha component.
+    fn a() -> FloatExpr = FloatColorA;
+    /// `self == other`.
+    fn equals(other: ColorExpr) -> BoolExpr = BoolEqualColor;
+    /// `self != other`.
+    fn not_equals(other: ColorExpr) -> BoolExpr = BoolNotEqualColor;
+});
+
+functions!(ColorExpr {
+    /// A color built from its components.
+    fn rgba(r: FloatExpr, g: FloatExpr, b: FloatExpr, a: FloatExpr) = ColorValues;
+});
+
+operators!(VectorExpr {
+    Add::add(VectorExpr) = VectorAdd;
+    Sub::sub(VectorExpr) = VectorSubtract;
+    Mul::mul(FloatExpr) = VectorMultiply;
+    Div::div(FloatExpr) = VectorDivide;
+});
+
+methods!(VectorExpr {
+    /// The x component.
+    fn x() -> FloatExpr = FloatVectorX;
+    /// The y component.
+    fn y() -> FloatExpr = FloatVectorY;
+    /// The length.
+    fn length() -> FloatExpr = FloatVectorLength;
+    /// The squared length.
+    fn length_squared() -> FloatExpr = FloatVectorLengthSqr;
+    /// The distance to `other`.
+    fn distance(other: VectorExpr) -> FloatExpr = FloatVectorDistance;
+    /// The squared distance to `other`.
+    fn distance_squared(other: VectorExpr) -> FloatExpr = FloatVectorDistanceSqr;
+    /// The dot product.
+    fn dot(other: VectorExpr) -> FloatExpr = FloatVectorDot;
+    /// The angle of the vector, in degrees.
+    fn angle() -> FloatExpr = FloatVectorAngle;
+    /// The angle between the two vectors, in degrees.
+    fn angle_between(other: VectorExpr) -> FloatExpr = FloatVectorAngleBetween;
+    /// The vector scaled to a length of one.
+    fn normalize() -> VectorExpr = VectorNormalize;
+    /// The vector rotated by 90 degrees.
+    fn perpendicular() -> VectorExpr = VectorPerpendicular;
+    /// The vector reflected off a surface with this normal.
+    fn reflect(normal: VectorExpr) -> VectorExpr = VectorReflect;
+    /// The vector rotated by `degrees`.
+    fn rotate(degrees: FloatExpr) -> VectorExpr = VectorRotate;
+    /// Formats as a string.
+    fn into_string() -> StringExpr = StringFromVector;
+    /// `self == other`.
+    fn equals(other: VectorExpr) -> BoolExpr = BoolEqualVector;
+    /// `self != other`.
+    fn not_equals(other: VectorExpr) -> BoolExpr = BoolNotEqualVector;
+});
+
+functions!(VectorExpr {
+    /// A vector built from its components.
+    fn new(x: FloatExpr, y: FloatExpr) = VectorValues;
+    /// The position of the camera.
+    fn camera_position() = VectorCameraPos;
+    /// The position of the pointer on the screen.
+    fn pointer_position() = VectorPointerPosition;
+    /// The position of the pointer in the world.
+    fn pointer_world_position() = VectorPointerWorldPosition;
+    /// The point of the collision that activated the script.
+    fn collision_point() = VectorCollisionPoint;
+    /// The normal of the collision that activated the script.
+    fn collision_normal() = VectorCollisionNormal;
+});
+
+methods!(ObjectExpr {
+    /// The local position.
+    fn position() -> VectorExpr = VectorObjectPos;
+    /// The position in the world.
+    fn global_position() -> VectorExpr = VectorObjectGlobalPos;
+    /// The local scale.
+    fn scale() -> VectorExpr = VectorObjectScale;
+    /// The scale in the world.
+    fn global_scale() -> VectorExpr = VectorObjectGlobalScale;
+    /// The velocity.
+    fn velocity() -> VectorExpr = VectorObjectVelocity;
+    /// The local rotation, in degrees.
+    fn rotation() -> FloatExpr = FloatObjectRotation;
+    /// The rotation in the world, in degrees.
+    fn global_rotation() -> FloatExpr = FloatObjectGlobalRotation;
+    /// The direction the object is facing.
+    fn direction() -> IntExpr = IntObjectDirection;
+    /// The color.
+    fn color() -> ColorExpr = ColorObjectColor;
+    /// The tag.
+    fn tag() -> StringExpr = StringObjectTag;
+    /// The parent object.
+    fn parent() -> ObjectExpr = ObjectParent;
+    /// The direct children.
+    fn children() -> ObjectSetExpr = ObjectSetChildren;
+    /// Whether the object is dead.
+    fn dead() -> BoolExpr = BoolObjectDead;
+    /// Whether the object is activated.
+    fn activated() -> BoolExpr = BoolObjectActivated;
+    /// Whether the two objects are colliding.
+    fn colliding_with(other: ObjectExpr) -> BoolExpr = BoolObjectsColliding;
+    /// Whether the object is colliding with a point.
+    fn colliding_with_point(point: VectorExpr) -> BoolExpr = BoolObjectsCollidingWithPoint;
+    /// Whether the player is on the ground.
+    fn on_ground() -> BoolExpr = BoolPlayerOnGround;
+    /// Whether the player is on a wall.
+    fn on_walljump() -> BoolExpr = BoolPlayerOnWalljump;
+    /// Whether the player is on a booster.
+    fn on_booster() -> BoolExpr = BoolPlayerOnBooster;
+    /// Whether the player is on a swing.
+    fn on_swing() -> BoolExpr = BoolPlayerOnSwing;
+    /// Whether the player is in a floating zone.
+    fn in_floating_zone() -> BoolExpr = BoolPlayerInFloatingZone;
+    /// Whether the player is using a glider.
+    fn using_glider() -> BoolExpr = BoolPlayerUsingGlider;
+    /// Whether the player can't jump.
+    fn jump_locked() -> BoolExpr = BoolPlayerJumpLocked;
+    /// `self == other`.
+    fn equals(other: ObjectExpr) -> BoolExpr = BoolEqualObject;
+    /// `self != other`.
+    fn not_equals(other: ObjectExpr) -> BoolExpr = BoolNotEqualObject;
+});
+
+functions!(ObjectExpr {
+    /// Any object.
+    fn any() = ObjectAnyObject;
+    /// The player.
+    fn player() = ObjectPlayer;
+    /// The object that activated the script.
+    fn source() = ObjectSourceObject;
+    /// The object that collided with the source object.
+    fn collided() = ObjectCollidedObject;
+    /// The object targeted by the activator.
+    fn target() = ObjectTargetObject;
+    /// The object of the current iteration of a `ForEachObject` action.
+    fn repeat_object() = ObjectRepeatObject;
+    /// The object created by the last `CreateObject` action.
+    fn last_created() = ObjectLastCreatedObject;
+});
+
+operators!(ObjectSetExpr {
+    Add::add(ObjectSetExpr) = ObjectSetConcat;
+    BitOr::bitor(ObjectSetExpr) = ObjectSetUnion;
+    BitAnd::bitand(ObjectSetExpr) = ObjectSetIntersection;
+    Sub::sub(ObjectSetExpr) = ObjectSetDifference;
+});
+
+methods!(ObjectSetExpr {
+    /// The number of objects.
+    fn count() -> IntExpr = IntObjectSetCount;
+    /// The first object.
+    fn first() -> ObjectExpr = ObjectFirstFromSet;
+    /// A random object.
+    fn random() -> ObjectExpr = ObjectRandomFromSet;
+    /// The object at `index`.
+    fn get(index: IntExpr) -> ObjectExpr = ObjectElementFromSet;
+    /// The object closest to `point`.
+    fn closest_to(point: VectorExpr) -> ObjectExpr = ObjectClosestFromPoint;
+    /// Whether the set contains `object`.
+    fn contains(object: ObjectExpr) -> BoolExpr = BoolObjectSetContains;
+    /// The index of `object`.
+    fn index_of(object: ObjectExpr) -> IntExpr = IntObjectSetIndexOf;
+    /// The set with `object` at `index` instead.
+    fn set_at(index: IntExpr, object: ObjectExpr) -> ObjectSetExpr = ObjectSetSetAtIndex;
+    /// The set without the object at `index`.
+    fn remove_at(index: IntExpr) -> ObjectSetExpr = ObjectSetRemoveAtIndex;
+    /// The `count` objects starting at `start`.
+    fn sublist(start: IntExpr, count: IntExpr) -> ObjectSetExpr = ObjectSetSublist;
+    /// The set in random order.
+    fn shuffle() -> ObjectSetExpr = ObjectSetShuffle;
+    /// The set in reverse order.
+    fn reverse() -> ObjectSetExpr = ObjectSetReverse;
+});
+
+functions!(ObjectSetExpr {
+    /// All players.
+    fn players() = ObjectSetPlayers;
+    /// All objects in the level.
+    fn all_objects() = ObjectSetAllObjects;
+    /// All objects without a parent.
+    fn all_root_objects() = ObjectSetAllRootObjects;
+    /// All objects with this tag.
+    fn with_tag(tag: StringExpr) = ObjectSetObjectsWithTag;
+    /// All objects in this layer.
+    fn in_layer(layer: LayerExpr) = ObjectSetObjectsInLayer;
+    /// All objects within `radius` of `center`.
+    fn in_circle(center: VectorExpr, radius: FloatExpr) = ObjectSetObjectsInCircle;
+});
+
+/// The operations every typed list supports, in the order of the arguments.
+macro_rules! list_methods {
+    ($($list:ident of $element:ident:
+        $append:ident, $remove:ident, $set_at:ident, $remove_at:ident, $sublist:ident, $shuffle:ident, $reverse:ident,
+        $first:ident, $random:ident, $get:ident, $contains:ident, $count:ident, $index_of:ident;)*) => {
+        $(
+            methods!($list {
+                /// The list with `element` added at the end.
+                fn append(element: $element) -> $list = $append;
+                /// The list without the first occurrence of `element`.
+                fn remove(element: $element) -> $list = $remove;
+                /// The list with `element` at `index` instead.
+                fn set_at(index: IntExpr, element: $element) -> $list = $set_at;
+                /// The list without the element at `index`.
+                fn remove_at(index: IntExpr) -> $list = $remove_at;
+                /// The `count` elements starting at `start`.
+                fn sublist(start: IntExpr, count: IntExpr) -> $list = $sublist;
+                /// The list in random order.
+                fn shuffle() -> $list = $shuffle;
+                /// The list in reverse order.
+                fn reverse() -> $list = $reverse;
+                /// The first element.
+                fn first() -> $element = $first;
+                /// A random element.
+                fn random() -> $element = $random;
+                /// The element at `index`.
+                fn get(index: IntExpr) -> $element = $get;
+                /// Whether the list contains `element`.
+                fn contains(element: $element) -> BoolExpr = $contains;
+                /// The number of elements.
+                fn count() -> IntExpr = $count;
+                /// The index of `element`.
+                fn index_of(element: $element) -> IntExpr = $index_of;
+            });
+        )*
+    };
+}
+
+list_methods!(
+    BoolListExpr of BoolExpr:
+        BoolListAppend, BoolListRemove, BoolListSetAtIndex, BoolListRemoveAtIndex, BoolListSublist, BoolListShuffle,
+        BoolListReverse, BoolFirstFromList, BoolRandomFromList, BoolElementFromList, BoolBoolListContains,
+        IntBoolListCount, IntBoolListIndexOf;
+    IntListExpr of IntExpr:
+        IntListAppend, IntListRemove, IntListSetAtIndex, IntListRemoveAtIndex, IntListSublist, IntListShuffle,
+        IntListReverse, IntFirstFromList, IntRandomFromList, IntElementFromList, BoolIntListContains,
+        IntIntListCount, IntIntListIndexOf;
+    FloatListExpr of FloatExpr:
+        FloatListAppend, FloatListRemove, FloatListSetAtIndex, FloatListRemoveAtIndex, FloatListSublist,
+        FloatListShuffle, FloatListReverse, FloatFirstFromList, FloatRandomFromList, FloatElementFromList,
+        BoolFloatListContains, IntFloatListCount, IntFloatListIndexOf;
+    StringListExpr of StringExpr:
+        StringListAppend, StringListRemove, StringListSetAtIndex, StringListRemoveAtIndex, StringListSublist,
+        StringListShuffle, StringListReverse, StringFirstFromList, StringRandomFromList, StringElementFromList,
+        BoolStringListContains, IntStringListCount, IntStringListIndexOf;
+    ColorListExpr of ColorExpr:
+        ColorListAppend, ColorListRemove, ColorListSetAtIndex, ColorListRemoveAtIndex, ColorListSublist,
+        ColorListShuffle, ColorListReverse, ColorFirstFromList, ColorRandomFromList, ColorElementFromList,
+        BoolColorListContains, IntColorListCount, IntColorListIndexOf;
+    VectorListExpr of VectorExpr:
+        VectorListAppend, VectorListRemove, VectorListSetAtIndex, VectorListRemoveAtIndex, VectorListSublist,
+        VectorListShuffle, VectorListReverse, VectorFirstFromList, VectorRandomFromList, VectorElementFromList,
+        BoolVectorListContains, IntVectorListCount, IntVectorListIndexOf;
+    PrefabListExpr of PrefabExpr:
+        PrefabListAppend, PrefabListRemove, PrefabListSetAtIndex, PrefabListRemoveAtIndex, PrefabListSublist,
+        PrefabListShuffle, PrefabListReverse, PrefabFirstFromList, PrefabRandomFromList, PrefabElementFromList,
+        BoolPrefabListContains, IntPrefabListCount, IntPrefabIndexOf;
+);
+
+/// Only lists of comparable elements can be sorted.
+macro_rules! sortable_lists {
+    ($($list:ident => $sort:ident),*$(,)?) => {
+        $(
+            methods!($list {
+                /// The list sorted in ascending order.
+                fn sort() -> $list = $sort;
+            });
+        )*
+    };
+}
+
+sortable_lists!(
+    BoolListExpr => BoolListSort,
+    IntListExpr => IntListSort,
+    FloatListExpr => FloatListSort,
+    StringListExpr => StringListSort,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{compile_value, Scope};
+    use crate::types::{level_data::LevelData, nova_script::variable::Variable};
+
+    fn variable(variable_id: i32, static_type: StaticType, initial_value: NovaValue) -> Variable {
+        Variable { variable_id, name: format!("v{variable_id}"), static_type, persistance: None, initial_value }
+    }
+
+    /// Type checks every expression as the initial value of a global variable of its type, next to the globals
+    /// 1 (int), 2 (float), 3 (vector) and 4 (object set) that the expressions read.
+    fn type_check(expressions: Vec<(StaticType, NovaValue)>) -> LevelData {
+        let read = [StaticType::Int, StaticType::Float, StaticType::Vector, StaticType::ObjectSet];
+        let level = LevelData {
+            global_variables: (1..)
+                .zip(read)
+                .map(|(id, static_type)| variable(id, static_type, NovaValue::default_constant(static_type)))
+                .chain((100..).zip(expressions).map(|(id, (static_type, value))| variable(id, static_type, value)))
+                .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(level.type_check(), Vec::new());
+
+        level
+    }
+
+    fn typed<T: TypedExpr>(expr: T) -> (StaticType, NovaValue) {
+        (T::STATIC_TYPE, expr.into())
+    }
+
+    #[test]
+    fn int_comparisons_convert_to_float() {
+        let health = || IntExpr::variable(1);
+        let comparisons = [
+            health().lt(10),
+            health().le(10),
+            health().gt(10),
+            health().ge(10),
+            health().equals(10),
+            health().not_equals(10),
+        ];
+
+        let less: NovaValue = comparisons[0].clone().into();
+        let operands = less.sub_values.as_deref().unwrap();
+        assert_eq!(less.dynamic_type, DynamicType::BoolLess);
+        assert_eq!(operands[0], FloatExpr::from(health()).into());
+        assert_eq!(operands[1].dynamic_type, DynamicType::FloatFromInt);
+
+        let scope = Scope::new(&[variable(1, StaticType::Int, NovaValue::default_constant(StaticType::Int))]);
+        let texts = ["$v1 < 10", "$v1 <= 10", "$v1 > 10", "$v1 >= 10", "$v1 == 10", "$v1 != 10"];
+
+        for (comparison, text) in comparisons.iter().zip(texts) {
+            assert_eq!(compile_value(text, &scope).as_ref(), Ok(comparison.as_value()), "{text}");
+        }
+
+        type_check(comparisons.into_iter().map(typed).collect());
+    }
+
+    #[test]
+    fn operators_build_well_typed_trees() {
+        let int = (IntExpr::variable(1) + 2) * 3 % 4 / 5 - IntExpr::random(0, 10);
+        let float = FloatExpr::variable(2) * int.clone() + 0.5;
+        let vector = (VectorExpr::variable(3) - VectorExpr::new(1.0, float.clone())) * 2.0 / float.clone().abs();
+        let objects = (ObjectSetExpr::variable(4) | ObjectSetExpr::constant([1, 2])) - ObjectSetExpr::constant([2]);
+        let bool = !(vector.clone().x().lt(1.0) & int.clone().ge(0)) | objects.clone().count().equals(1);
+
+        assert_eq!(
+            NovaValue::from(IntExpr::variable(1) + 2).sub_values,
+            Some(vec![IntExpr::variable(1).into(), IntExpr::constant(2).into()])
+        );
+        assert_eq!(NovaValue::from(bool.clone()).dynamic_type, DynamicType::BoolOr);
+
+        let level = type_check(vec![typed(int), typed(float), typed(vector), typed(objects), typed(bool)]);
+
+        for variable in &level.global_variables {
+            let lifted = match variable.static_type {
+                StaticType::Int => IntExpr::try_from(variable.initial_value.clone()).map(NovaValue::from),
+                StaticType::Float => FloatExpr::try_from(variable.initial_value.clone()).map(NovaValue::from),
+                StaticType::Vector => VectorExpr::try_from(variable.initial_value.clone()).map(NovaValue::from),
+                StaticType::ObjectSet => ObjectSetExpr::try_from(variable.initial_value.clone()).map(NovaValue::from),
+                _ => BoolExpr::try_from(variable.initial_value.clone()).map(NovaValue::from),
+            };
+
+            assert_eq!(lifted.as_ref(), Ok(&variable.initial_value));
+        }
+    }
+
+    #[test]
+    fn constants_and_lists() {
+        let prefabs = PrefabListExpr::constant([1, 2]).append(PrefabExpr::constant(3));
+        let names: StringListExpr = ["a".to_string(), "b".to_string()].into_iter().collect();
+
+        assert_eq!(NovaValue::from(ObjectExpr::constant(7)).int_value, 7);
+        assert_eq!(NovaValue::from(StringExpr::from("text")).string_value.as_deref(), Some("text"));
+        assert_eq!(NovaValue::from(names.clone()).string_list_values.map(|names| names.len()), Some(2));
+
+        type_check(vec![
+            typed(prefabs.clone().count()),
+            typed(prefabs.contains(PrefabExpr::constant(1))),
+            typed(names.sort().index_of("b")),
+            typed(FloatListExpr::constant([1.0, 2.0]).random().round()),
+            typed(ColorExpr::rgba(1.0, 0.5, IntExpr::constant(0), 1.0)),
+        ]);
+    }
+
+    #[test]
+    fn lifting_reports_where_the_tree_is_wrong() {
+        let float = NovaValue::default_constant(StaticType::Float);
+        let int = NovaValue::default_constant(StaticType::Int);
+        let unconverted = NovaValue {
+            dynamic_type: DynamicType::BoolLess,
+            sub_values: Some(vec![float.clone(), int.clone()]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            IntExpr::try_from(float),
+            Err(LiftError {
+                path: Vec::new(),
+                kind: TypeErrorKind::Mismatch { expected: StaticType::Int, found: StaticType::Float }
+            })
+        );
+
+        let nested = NovaValue { sub_values: Some(vec![unconverted]), ..NovaValue::from(!BoolExpr::constant(true)) };
+        let error = BoolExpr::try_from(nested).unwrap_err();
+        assert_eq!(error.path, vec![0, 1]);
+        assert_eq!(error.to_string(), "sub_values[0].sub_values[1]: expected a Float value, found Int");
+
+        let missing = NovaValue { sub_values: Some(vec![int]), ..NovaValue::from(IntExpr::constant(1) + 1) };
+        assert_eq!(
+            IntExpr::try_from(missing).unwrap_err().kind,
+            TypeErrorKind::OperandCount { dynamic_type: DynamicType::IntAdd, expected: 2, found: 1 }
+        );
+    }
+}
@@ -3,7 +3,7 @@ use crate::{error::Error, Read, Write};
 use super::nova_value::NovaValue;
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct FunctionCall {
     pub id: i32,
     pub parameters: Vec<CallParameter>,
@@ -7,11 +7,15 @@ pub mod variable;
 pub mod function_call;
 pub mod static_type;
 pub mod dynamic_type;
+pub mod expr;
 pub mod scripts_folder;
 pub mod variables_folder;
+pub mod path;
+pub mod visit;
 
 use crate::{Error, Read, ReadVersioned, Write};
 use {nova_value::NovaValue, activator::Activator, parameter::Parameter, variable::Variable, action::Action};
+use {path::ScriptPath, visit::{ForEachAction, ForEachValue, Visit, VisitMut}};
 
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -65,4 +69,22 @@ impl Write for NovaScript {
         self.variables.write(output)?;
         self.actions.write(output)
     }
-}
\ No newline at end of file
+}
+impl NovaScript {
+    /// Calls `f` on every `NovaValue` in this script: the condition, the activator parameters,
+    /// the parameter defaults, the initial values of local variables and every value in its actions.
+    pub fn for_each_value(&self, f: &mut impl FnMut(&NovaValue)) {
+        ForEachValue(f).visit_script(self, &ScriptPath::script(self.script_id));
+    }
+
+    /// Mutable version of [`NovaScript::for_each_value`].
+    pub fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut NovaValue)) {
+        let path = ScriptPath::script(self.script_id);
+        ForEachValue(f).visit_script_mut(self, &path);
+    }
+
+    /// Calls `f` on every action in this script, including nested ones.
+    pub fn for_each_action(&self, f: &mut impl FnMut(&Action)) {
+        ForEachAction(f).visit_script(self, &ScriptPath::script(self.script_id));
+    }
+}
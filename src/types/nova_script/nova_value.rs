@@ -3,7 +3,12 @@ use std::default;
 use crate::{Error, Read, Write};
 use crate::types::vec2::Vec2;
 use crate::types::color::Color;
-use super::dynamic_type::DynamicType;
+use super::{
+    dynamic_type::DynamicType,
+    path::ScriptPath,
+    static_type::StaticType,
+    visit::{ForEachValue, Visit, VisitMut},
+};
 use ordered_float::OrderedFloat;
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -67,3 +72,37 @@ impl Write for NovaValue {
     }
 }
 
+impl NovaValue {
+    /// Returns a constant value of the given type with all slots left at their defaults.
+    ///
+    /// List types get an empty list instead of `None`.
+    #[must_use]
+    pub fn default_constant(static_type: StaticType) -> Self {
+        let mut value = Self {
+            dynamic_type: static_type.constant_type(),
+            ..Default::default()
+        };
+
+        match static_type {
+            StaticType::BoolList => value.bool_list_values = Some(Vec::new()),
+            StaticType::IntList | StaticType::PrefabList | StaticType::ObjectSet => value.int_list_values = Some(Vec::new()),
+            StaticType::FloatList => value.float_list_values = Some(Vec::new()),
+            StaticType::StringList => value.string_list_values = Some(Vec::new()),
+            StaticType::ColorList => value.color_list_values = Some(Vec::new()),
+            StaticType::VectorList => value.vector_list_values = Some(Vec::new()),
+            _ => {}
+        }
+
+        value
+    }
+
+    /// Calls `f` on this value and then on every value nested in its `sub_values`, depth first.
+    pub fn for_each(&self, f: &mut impl FnMut(&Self)) {
+        ForEachValue(f).visit_value(self, &ScriptPath::default());
+    }
+
+    /// Mutable version of [`NovaValue::for_each`].
+    pub fn for_each_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        ForEachValue(f).visit_value_mut(self, &ScriptPath::default());
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+
+/// One step from a `NovaScript` (or a global variable) down to a nested action or value.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PathSegment {
+    /// `NovaScript::condition`.
+    Condition,
+    /// An activator in `NovaScript::activation_list`, by index.
+    Activator(usize),
+    /// A parameter of an activator, by index.
+    ActivatorParameter(usize),
+    /// The `default_value` of the script parameter with this id.
+    ParameterDefault(i32),
+    /// The `initial_value` of the variable with this id.
+    VariableInitialValue(i32),
+    /// An action list, by field name: `actions` for the top level list of a script, or e.g. `if_actions` of a `ConditionBlock`.
+    Actions(&'static str),
+    /// An action in the preceding action list, by index.
+    Action(usize),
+    /// A `NovaValue` field of the preceding action, by field name.
+    Field(&'static str),
+    /// The argument passed for the function parameter with this id in a `RunFunction` action.
+    Argument(i32),
+    /// An operand in `NovaValue::sub_values`, by index.
+    SubValue(usize),
+}
+
+/// The location of an action or value inside a level's scripts.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScriptPath {
+    /// The script the location is in, or `None` for the initial values of global variables.
+    pub script_id: Option<i32>,
+    /// The steps from the script down to the location.
+    pub segments: Vec<PathSegment>,
+}
+
+impl ScriptPath {
+    /// The path of a script itself.
+    #[must_use]
+    pub const fn script(script_id: i32) -> Self {
+        Self {
+            script_id: Some(script_id),
+            segments: Vec::new(),
+        }
+    }
+
+    /// The path of the level's global variables.
+    #[must_use]
+    pub const fn globals() -> Self {
+        Self {
+            script_id: None,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Returns this path extended by one segment.
+    #[must_use]
+    pub fn join(&self, segment: PathSegment) -> Self {
+        let mut path = self.clone();
+        path.segments.push(segment);
+        path
+    }
+}
+
+impl fmt::Display for ScriptPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.script_id {
+            Some(script_id) => write!(f, "script {script_id}")?,
+            None => write!(f, "globals")?,
+        }
+
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Condition => write!(f, ".condition")?,
+                PathSegment::Activator(index) => write!(f, ".activation_list[{index}]")?,
+                PathSegment::ActivatorParameter(index) => write!(f, ".parameters[{index}]")?,
+                PathSegment::ParameterDefault(id) => write!(f, ".parameter({id}).default_value")?,
+                PathSegment::VariableInitialValue(id) => write!(f, ".variable({id}).initial_value")?,
+                PathSegment::Actions(field) | PathSegment::Field(field) => write!(f, ".{field}")?,
+                PathSegment::Action(index) => write!(f, "[{index}]")?,
+                PathSegment::Argument(id) => write!(f, ".argument({id})")?,
+                PathSegment::SubValue(index) => write!(f, ".sub_values[{index}]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_segments() {
+        let path = ScriptPath::script(1)
+            .join(PathSegment::Actions("actions"))
+            .join(PathSegment::Action(0))
+            .join(PathSegment::Actions("else_actions"))
+            .join(PathSegment::Action(0))
+            .join(PathSegment::Argument(1));
+
+        assert_eq!(path.to_string(), "script 1.actions[0].else_actions[0].argument(1)");
+        assert_eq!(path.segments.len(), 5);
+        assert!(ScriptPath::script(1) < path);
+        assert_eq!(ScriptPath::globals().to_string(), "globals");
+    }
+}
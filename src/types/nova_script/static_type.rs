@@ -1,4 +1,5 @@
 use crate::{Read, Write, Error};
+use super::dynamic_type::DynamicType;
 
 
 macro_rules! define_static_type {
@@ -68,4 +69,87 @@ impl Write for StaticType {
     fn write(&self, output: &mut impl std::io::Write) -> Result<(), Error> {
         i32::from(self).write(output)
     }
-}
\ No newline at end of file
+}
+
+macro_rules! define_type_families {
+    ($($static_type:ident => $constant:ident, $variable:ident, $parameter:ident);*$(;)?) => {
+        impl StaticType {
+            /// The `DynamicType` of a constant value of this type.
+            pub const fn constant_type(self) -> DynamicType {
+                match self {
+                    $(Self::$static_type => DynamicType::$constant,)*
+                }
+            }
+
+            /// The `DynamicType` that reads a variable of this type.
+            pub const fn variable_type(self) -> DynamicType {
+                match self {
+                    $(Self::$static_type => DynamicType::$variable,)*
+                }
+            }
+
+            /// The `DynamicType` that reads a function parameter of this type.
+            pub const fn parameter_type(self) -> DynamicType {
+                match self {
+                    $(Self::$static_type => DynamicType::$parameter,)*
+                }
+            }
+        }
+
+        impl DynamicType {
+            /// If this is a `*Constant` dynamic type, returns the type of the constant.
+            pub const fn constant_static_type(self) -> Option<StaticType> {
+                match self {
+                    $(Self::$constant => Some(StaticType::$static_type),)*
+                    _ => None,
+                }
+            }
+
+            /// If this is a `*Variable` dynamic type, returns the type of the variable it reads.
+            ///
+            /// The id of the variable is stored in the `int_value` of the `NovaValue`.
+            pub const fn variable_static_type(self) -> Option<StaticType> {
+                match self {
+                    $(Self::$variable => Some(StaticType::$static_type),)*
+                    _ => None,
+                }
+            }
+
+            /// If this is a `*Parameter` dynamic type, returns the type of the parameter it reads.
+            ///
+            /// The id of the parameter is stored in the `int_value` of the `NovaValue`.
+            pub const fn parameter_static_type(self) -> Option<StaticType> {
+                match self {
+                    $(Self::$parameter => Some(StaticType::$static_type),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+define_type_families!(
+    Bool => BoolConstant, BoolVariable, BoolParameter;
+    Int => IntConstant, IntVariable, IntParameter;
+    Float => FloatConstant, FloatVariable, FloatParameter;
+    String => StringConstant, StringVariable, StringParameter;
+    Color => ColorConstant, ColorVariable, ColorParameter;
+    Vector => VectorConstant, VectorVariable, VectorParameter;
+    Sound => SoundConstant, SoundVariable, SoundParameter;
+    Music => MusicConstant, MusicVariable, MusicParameter;
+    Object => ObjectConstant, ObjectVariable, ObjectParameter;
+    ObjectSet => ObjectSetConstant, ObjectSetVariable, ObjectSetParameter;
+    Transition => TransitionConstant, TransitionVariable, TransitionParameter;
+    Easing => EasingConstant, EasingVariable, EasingParameter;
+    Sprite => SpriteConstant, SpriteVariable, SpriteParameter;
+    Script => ScriptConstant, ScriptVariable, ScriptParameter;
+    Layer => LayerConstant, LayerVariable, LayerParameter;
+    Prefab => PrefabConstant, PrefabVariable, PrefabParameter;
+    BoolList => BoolListConstant, BoolListVariable, BoolListParameter;
+    IntList => IntListConstant, IntListVariable, IntListParameter;
+    FloatList => FloatListConstant, FloatListVariable, FloatListParameter;
+    StringList => StringListConstant, StringListVariable, StringListParameter;
+    ColorList => ColorListConstant, ColorListVariable, ColorListParameter;
+    VectorList => VectorListConstant, VectorListVariable, VectorListParameter;
+    PrefabList => PrefabListConstant, PrefabListVariable, PrefabListParameter;
+);
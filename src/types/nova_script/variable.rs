@@ -1,5 +1,5 @@
 use crate::{Error, Read, ReadVersioned, Write};
-use super::{static_type::StaticType, nova_value::NovaValue};
+use super::{static_type::StaticType, nova_value::NovaValue, NovaScript};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -11,6 +11,39 @@ pub struct Variable {
     pub initial_value: NovaValue,
 }
 
+/// A global or script-local variable.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VariableRef {
+    /// A variable of `LevelData::global_variables`.
+    Global(i32),
+    /// A variable of `NovaScript::variables`.
+    Local { script_id: i32, variable_id: i32 },
+}
+
+impl VariableRef {
+    /// The id of the variable.
+    #[must_use]
+    pub const fn variable_id(self) -> i32 {
+        match self {
+            Self::Global(variable_id) | Self::Local { variable_id, .. } => variable_id,
+        }
+    }
+
+    /// The variable that `variable_id` refers to inside of `script`, or outside of any script if it's `None`.
+    ///
+    /// Local variables shadow global ones with the same id. Ids that aren't declared anywhere resolve to a global
+    /// variable.
+    #[must_use]
+    pub fn resolve(variable_id: i32, script: Option<&NovaScript>) -> Self {
+        match script {
+            Some(script) if script.variables.iter().any(|variable| variable.variable_id == variable_id) => {
+                Self::Local { script_id: script.script_id, variable_id }
+            }
+            _ => Self::Global(variable_id),
+        }
+    }
+}
+
 impl ReadVersioned for Variable {
     fn read(input: &mut impl std::io::Read, version: i32) -> Result<Self, Error> {
         Ok(Self {
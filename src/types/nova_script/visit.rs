@@ -0,0 +1,469 @@
+//! Traversal of the action and value tree of nova scripts.
+//!
+//! [`Visit`] and [`VisitMut`] have one method per node type. Each method is called with the node and its
+//! [`ScriptPath`], and by default walks into the children of the node through the matching `walk_*` function.
+//! Overriding a method intercepts that node type. Calling the `walk_*` function from the override keeps
+//! walking into the children, not calling it skips them.
+//!
+//! Finding every place an object is referenced takes a few lines:
+//!
+//! ```no_run
+//! # let level = exolvl::types::level_data::LevelData::default();
+//! use exolvl::types::nova_script::{
+//!     dynamic_type::DynamicType, nova_value::NovaValue, path::ScriptPath, visit::{walk_value, Visit},
+//! };
+//!
+//! struct FindObject(i32, Vec<ScriptPath>);
+//!
+//! impl Visit for FindObject {
+//!     fn visit_value(&mut self, value: &NovaValue, path: &ScriptPath) {
+//!         if value.dynamic_type == DynamicType::ObjectConstant && value.int_value == self.0 {
+//!             self.1.push(path.clone());
+//!         }
+//!
+//!         walk_value(self, value, path);
+//!     }
+//! }
+//!
+//! let mut finder = FindObject(42, Vec::new());
+//! finder.visit_level_data(&level);
+//! ```
+
+use crate::types::level_data::LevelData;
+
+use super::{
+    action::Action,
+    action_type::ActionType,
+    activator::Activator,
+    function_call::{CallParameter, FunctionCall},
+    nova_value::NovaValue,
+    path::{PathSegment, ScriptPath},
+    NovaScript,
+};
+
+/// Walks the scripts of a level immutably. See the [module documentation](self).
+pub trait Visit {
+    /// Visits the initial values of the global variables and every nova script of the level.
+    fn visit_level_data(&mut self, level_data: &LevelData) {
+        walk_level_data(self, level_data);
+    }
+
+    fn visit_script(&mut self, script: &NovaScript, path: &ScriptPath) {
+        walk_script(self, script, path);
+    }
+
+    fn visit_activator(&mut self, activator: &Activator, path: &ScriptPath) {
+        walk_activator(self, activator, path);
+    }
+
+    fn visit_action(&mut self, action: &Action, path: &ScriptPath) {
+        walk_action(self, action, path);
+    }
+
+    /// Visits the fields of an action. The path is the path of the action.
+    fn visit_action_type(&mut self, action_type: &ActionType, path: &ScriptPath) {
+        walk_action_type(self, action_type, path);
+    }
+
+    /// Visits the function call of a `RunFunction` action. The path is the path of the action.
+    fn visit_function_call(&mut self, function_call: &FunctionCall, path: &ScriptPath) {
+        walk_function_call(self, function_call, path);
+    }
+
+    fn visit_call_parameter(&mut self, call_parameter: &CallParameter, path: &ScriptPath) {
+        walk_call_parameter(self, call_parameter, path);
+    }
+
+    fn visit_value(&mut self, value: &NovaValue, path: &ScriptPath) {
+        walk_value(self, value, path);
+    }
+}
+
+pub fn walk_level_data<V: Visit + ?Sized>(visitor: &mut V, level_data: &LevelData) {
+    let globals = ScriptPath::globals();
+
+    for variable in &level_data.global_variables {
+        visitor.visit_value(
+            &variable.initial_value,
+            &globals.join(PathSegment::VariableInitialValue(variable.variable_id)),
+        );
+    }
+
+    for script in &level_data.nova_scripts {
+        visitor.visit_script(script, &ScriptPath::script(script.script_id));
+    }
+}
+
+pub fn walk_script<V: Visit + ?Sized>(visitor: &mut V, script: &NovaScript, path: &ScriptPath) {
+    visitor.visit_value(&script.condition, &path.join(PathSegment::Condition));
+
+    for (index, activator) in script.activation_list.iter().enumerate() {
+        visitor.visit_activator(activator, &path.join(PathSegment::Activator(index)));
+    }
+
+    for parameter in &script.parameters {
+        visitor.visit_value(&parameter.default_value, &path.join(PathSegment::ParameterDefault(parameter.parameter_id)));
+    }
+
+    for variable in &script.variables {
+        visitor.visit_value(
+            &variable.initial_value,
+            &path.join(PathSegment::VariableInitialValue(variable.variable_id)),
+        );
+    }
+
+    walk_actions(visitor, &script.actions, &path.join(PathSegment::Actions("actions")));
+}
+
+pub fn walk_activator<V: Visit + ?Sized>(visitor: &mut V, activator: &Activator, path: &ScriptPath) {
+    for (index, parameter) in activator.parameters.iter().enumerate() {
+        visitor.visit_value(parameter, &path.join(PathSegment::ActivatorParameter(index)));
+    }
+}
+
+/// Visits every action of an action list. `path` is the path of the list.
+pub fn walk_actions<V: Visit + ?Sized>(visitor: &mut V, actions: &[Action], path: &ScriptPath) {
+    for (index, action) in actions.iter().enumerate() {
+        visitor.visit_action(action, &path.join(PathSegment::Action(index)));
+    }
+}
+
+pub fn walk_action<V: Visit + ?Sized>(visitor: &mut V, action: &Action, path: &ScriptPath) {
+    visitor.visit_action_type(&action.action_type, path);
+}
+
+/// Visits the values of an action, then its function call and then its nested action lists.
+pub fn walk_action_type<V: Visit + ?Sized>(visitor: &mut V, action_type: &ActionType, path: &ScriptPath) {
+    for (field, value) in action_type.values() {
+        visitor.visit_value(value, &path.join(PathSegment::Field(field)));
+    }
+
+    if let Some(function_call) = action_type.function_call() {
+        visitor.visit_function_call(function_call, path);
+    }
+
+    for (field, actions) in action_type.children() {
+        walk_actions(visitor, actions, &path.join(PathSegment::Actions(field)));
+    }
+}
+
+pub fn walk_function_call<V: Visit + ?Sized>(visitor: &mut V, function_call: &FunctionCall, path: &ScriptPath) {
+    for parameter in &function_call.parameters {
+        visitor.visit_call_parameter(parameter, &path.join(PathSegment::Argument(parameter.parameter_id)));
+    }
+}
+
+pub fn walk_call_parameter<V: Visit + ?Sized>(visitor: &mut V, call_parameter: &CallParameter, path: &ScriptPath) {
+    visitor.visit_value(&call_parameter.value, path);
+}
+
+pub fn walk_value<V: Visit + ?Sized>(visitor: &mut V, value: &NovaValue, path: &ScriptPath) {
+    for (index, sub_value) in value.sub_values.iter().flatten().enumerate() {
+        visitor.visit_value(sub_value, &path.join(PathSegment::SubValue(index)));
+    }
+}
+
+/// Mutable version of [`Visit`].
+pub trait VisitMut {
+    fn visit_level_data_mut(&mut self, level_data: &mut LevelData) {
+        walk_level_data_mut(self, level_data);
+    }
+
+    fn visit_script_mut(&mut self, script: &mut NovaScript, path: &ScriptPath) {
+        walk_script_mut(self, script, path);
+    }
+
+    fn visit_activator_mut(&mut self, activator: &mut Activator, path: &ScriptPath) {
+        walk_activator_mut(self, activator, path);
+    }
+
+    fn visit_action_mut(&mut self, action: &mut Action, path: &ScriptPath) {
+        walk_action_mut(self, action, path);
+    }
+
+    fn visit_action_type_mut(&mut self, action_type: &mut ActionType, path: &ScriptPath) {
+        walk_action_type_mut(self, action_type, path);
+    }
+
+    fn visit_function_call_mut(&mut self, function_call: &mut FunctionCall, path: &ScriptPath) {
+        walk_function_call_mut(self, function_call, path);
+    }
+
+    fn visit_call_parameter_mut(&mut self, call_parameter: &mut CallParameter, path: &ScriptPath) {
+        walk_call_parameter_mut(self, call_parameter, path);
+    }
+
+    fn visit_value_mut(&mut self, value: &mut NovaValue, path: &ScriptPath) {
+        walk_value_mut(self, value, path);
+    }
+}
+
+pub fn walk_level_data_mut<V: VisitMut + ?Sized>(visitor: &mut V, level_data: &mut LevelData) {
+    let globals = ScriptPath::globals();
+
+    for variable in &mut level_data.global_variables {
+        visitor.visit_value_mut(
+            &mut variable.initial_value,
+            &globals.join(PathSegment::VariableInitialValue(variable.variable_id)),
+        );
+    }
+
+    for script in &mut level_data.nova_scripts {
+        let path = ScriptPath::script(script.script_id);
+        visitor.visit_script_mut(script, &path);
+    }
+}
+
+pub fn walk_script_mut<V: VisitMut + ?Sized>(visitor: &mut V, script: &mut NovaScript, path: &ScriptPath) {
+    visitor.visit_value_mut(&mut script.condition, &path.join(PathSegment::Condition));
+
+    for (index, activator) in script.activation_list.iter_mut().enumerate() {
+        visitor.visit_activator_mut(activator, &path.join(PathSegment::Activator(index)));
+    }
+
+    for parameter in &mut script.parameters {
+        let path = path.join(PathSegment::ParameterDefault(parameter.parameter_id));
+        visitor.visit_value_mut(&mut parameter.default_value, &path);
+    }
+
+    for variable in &mut script.variables {
+        let path = path.join(PathSegment::VariableInitialValue(variable.variable_id));
+        visitor.visit_value_mut(&mut variable.initial_value, &path);
+    }
+
+    walk_actions_mut(visitor, &mut script.actions, &path.join(PathSegment::Actions("actions")));
+}
+
+pub fn walk_activator_mut<V: VisitMut + ?Sized>(visitor: &mut V, activator: &mut Activator, path: &ScriptPath) {
+    for (index, parameter) in activator.parameters.iter_mut().enumerate() {
+        visitor.visit_value_mut(parameter, &path.join(PathSegment::ActivatorParameter(index)));
+    }
+}
+
+pub fn walk_actions_mut<V: VisitMut + ?Sized>(visitor: &mut V, actions: &mut [Action], path: &ScriptPath) {
+    for (index, action) in actions.iter_mut().enumerate() {
+        visitor.visit_action_mut(action, &path.join(PathSegment::Action(index)));
+    }
+}
+
+pub fn walk_action_mut<V: VisitMut + ?Sized>(visitor: &mut V, action: &mut Action, path: &ScriptPath) {
+    visitor.visit_action_type_mut(&mut action.action_type, path);
+}
+
+pub fn walk_action_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, action_type: &mut ActionType, path: &ScriptPath) {
+    for (field, value) in action_type.values_mut() {
+        visitor.visit_value_mut(value, &path.join(PathSegment::Field(field)));
+    }
+
+    if let Some(function_call) = action_type.function_call_mut() {
+        visitor.visit_function_call_mut(function_call, path);
+    }
+
+    for (field, actions) in action_type.children_mut() {
+        walk_actions_mut(visitor, actions, &path.join(PathSegment::Actions(field)));
+    }
+}
+
+pub fn walk_function_call_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    function_call: &mut FunctionCall,
+    path: &ScriptPath,
+) {
+    for parameter in &mut function_call.parameters {
+        let path = path.join(PathSegment::Argument(parameter.parameter_id));
+        visitor.visit_call_parameter_mut(parameter, &path);
+    }
+}
+
+pub fn walk_call_parameter_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    call_parameter: &mut CallParameter,
+    path: &ScriptPath,
+) {
+    visitor.visit_value_mut(&mut call_parameter.value, path);
+}
+
+pub fn walk_value_mut<V: VisitMut + ?Sized>(visitor: &mut V, value: &mut NovaValue, path: &ScriptPath) {
+    for (index, sub_value) in value.sub_values.iter_mut().flatten().enumerate() {
+        visitor.visit_value_mut(sub_value, &path.join(PathSegment::SubValue(index)));
+    }
+}
+
+/// Calls the closure on every value, before walking into its operands. Backs the `for_each_value` helpers.
+pub(crate) struct ForEachValue<F>(pub F);
+
+impl<F: FnMut(&NovaValue)> Visit for ForEachValue<F> {
+    fn visit_value(&mut self, value: &NovaValue, path: &ScriptPath) {
+        (self.0)(value);
+        walk_value(self, value, path);
+    }
+}
+
+impl<F: FnMut(&mut NovaValue)> VisitMut for ForEachValue<F> {
+    fn visit_value_mut(&mut self, value: &mut NovaValue, path: &ScriptPath) {
+        (self.0)(value);
+        walk_value_mut(self, value, path);
+    }
+}
+
+/// Calls the closure on every action, before walking into its nested actions. Backs the `for_each_action` helpers.
+pub(crate) struct ForEachAction<F>(pub F);
+
+impl<F: FnMut(&Action)> Visit for ForEachAction<F> {
+    fn visit_action(&mut self, action: &Action, path: &ScriptPath) {
+        (self.0)(action);
+        walk_action(self, action, path);
+    }
+
+    fn visit_value(&mut self, _: &NovaValue, _: &ScriptPath) {}
+}
+
+/// Removes the nested actions for which the closure returns `false`. Backs
+/// [`retain_actions`](super::action::retain_actions).
+pub(crate) struct RetainActions<F>(pub F);
+
+impl<F: FnMut(&Action) -> bool> VisitMut for RetainActions<F> {
+    fn visit_action_type_mut(&mut self, action_type: &mut ActionType, path: &ScriptPath) {
+        for (_, actions) in action_type.children_mut() {
+            actions.retain(|action| (self.0)(action));
+        }
+
+        walk_action_type_mut(self, action_type, path);
+    }
+
+    fn visit_value_mut(&mut self, _: &mut NovaValue, _: &ScriptPath) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+    use crate::types::nova_script::{
+        action::retain_actions, dynamic_type::DynamicType, static_type::StaticType, variable::Variable,
+    };
+
+    fn level() -> LevelData {
+        let globals = vec![Variable {
+            variable_id: 7,
+            name: "score".to_string(),
+            static_type: StaticType::Int,
+            persistance: None,
+            initial_value: NovaValue::default_constant(StaticType::Int),
+        }];
+        let scripts = r#"
+            function 10 "f" { param 1 "count": int = 0; }
+            script 1 "A" {
+                var 2 "lives": int = 3;
+                condition $lives > 0;
+                on collision_enter(object(5), object(6));
+                if $lives > 1 {
+                    repeat 2 { $lives = $lives - 1; }
+                } else {
+                    call 10(1: 3);
+                }
+            }
+        "#;
+
+        LevelData {
+            nova_scripts: compile_scripts(scripts, &globals).unwrap(),
+            global_variables: globals,
+            ..Default::default()
+        }
+    }
+
+    /// The path of every action and value, in the order they're visited.
+    #[derive(Default)]
+    struct Paths {
+        actions: Vec<String>,
+        values: Vec<(String, DynamicType)>,
+    }
+
+    impl Visit for Paths {
+        fn visit_action(&mut self, action: &Action, path: &ScriptPath) {
+            self.actions.push(path.to_string());
+            walk_action(self, action, path);
+        }
+
+        fn visit_value(&mut self, value: &NovaValue, path: &ScriptPath) {
+            self.values.push((path.to_string(), value.dynamic_type));
+            walk_value(self, value, path);
+        }
+    }
+
+    impl VisitMut for Paths {
+        fn visit_action_mut(&mut self, action: &mut Action, path: &ScriptPath) {
+            self.actions.push(path.to_string());
+            walk_action_mut(self, action, path);
+        }
+
+        fn visit_value_mut(&mut self, value: &mut NovaValue, path: &ScriptPath) {
+            self.values.push((path.to_string(), value.dynamic_type));
+            walk_value_mut(self, value, path);
+        }
+    }
+
+    #[test]
+    fn nested_action_paths() {
+        let mut paths = Paths::default();
+        paths.visit_level_data(&level());
+
+        assert_eq!(
+            paths.actions,
+            [
+                "script 1.actions[0]",
+                "script 1.actions[0].if_actions[0]",
+                "script 1.actions[0].if_actions[0].actions[0]",
+                "script 1.actions[0].else_actions[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_value_paths() {
+        let mut paths = Paths::default();
+        paths.visit_level_data(&level());
+
+        for expected in [
+            ("globals.variable(7).initial_value", DynamicType::IntConstant),
+            ("script 10.parameter(1).default_value", DynamicType::IntConstant),
+            ("script 1.condition.sub_values[0].sub_values[0]", DynamicType::IntVariable),
+            ("script 1.activation_list[0].parameters[1]", DynamicType::ObjectConstant),
+            ("script 1.variable(2).initial_value", DynamicType::IntConstant),
+            ("script 1.actions[0].condition.sub_values[1]", DynamicType::FloatFromInt),
+            ("script 1.actions[0].if_actions[0].count", DynamicType::IntConstant),
+            ("script 1.actions[0].if_actions[0].actions[0].value", DynamicType::IntSubtract),
+            ("script 1.actions[0].if_actions[0].actions[0].value.sub_values[0]", DynamicType::IntVariable),
+            ("script 1.actions[0].else_actions[0].argument(1)", DynamicType::IntConstant),
+        ] {
+            assert!(paths.values.contains(&(expected.0.to_string(), expected.1)), "{} not visited", expected.0);
+        }
+
+        let mut mutable = Paths::default();
+        mutable.visit_level_data_mut(&mut level());
+        assert_eq!(mutable.actions, paths.actions);
+        assert_eq!(mutable.values, paths.values);
+    }
+
+    #[test]
+    fn helpers_match_the_visitor() {
+        let mut level = level();
+        let mut paths = Paths::default();
+        paths.visit_level_data(&level);
+
+        let mut values = Vec::new();
+        level.for_each_value_mut(&mut |value| values.push(value.dynamic_type));
+        assert_eq!(values, paths.values.iter().map(|(_, dynamic_type)| *dynamic_type).collect::<Vec<_>>());
+
+        let script = &level.nova_scripts[1];
+        let mut actions = 0;
+        script.for_each_action(&mut |_| actions += 1);
+        assert_eq!(actions, paths.actions.len());
+
+        retain_actions(&mut level.nova_scripts[1].actions, &mut |action| {
+            !matches!(action.action_type, ActionType::Repeat { .. })
+        });
+        let mut paths = Paths::default();
+        paths.visit_level_data(&level);
+        assert_eq!(paths.actions, ["script 1.actions[0]", "script 1.actions[0].else_actions[0]"]);
+    }
+}
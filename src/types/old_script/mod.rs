@@ -1,4 +1,5 @@
 pub mod old_action;
+pub mod old_action_kind;
 pub mod old_action_property;
 pub mod old_action_type;
 
@@ -0,0 +1,446 @@
+use ordered_float::OrderedFloat;
+use uuid::Uuid;
+
+use crate::{Read, Write, Error};
+use crate::types::{color::Color, vec2::Vec2};
+use super::{old_action::OldAction, old_action_property::OldActionProperty, old_action_type::OldActionType};
+
+/// A value stored in the string of an [`OldActionProperty`].
+///
+/// Parsing and formatting round trip: formatting a parsed value and parsing it again gives the same value.
+pub trait OldActionValue: Sized {
+    /// Parses the value, or returns `None` if the string isn't a valid value of this type.
+    fn parse_value(value: &str) -> Option<Self>;
+
+    fn format_value(&self) -> String;
+}
+
+macro_rules! from_str_values {
+    ($($ty:ty),*$(,)?) => {
+        $(
+            impl OldActionValue for $ty {
+                fn parse_value(value: &str) -> Option<Self> {
+                    value.parse().ok()
+                }
+
+                fn format_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+from_str_values!(i32, bool, String, Uuid, OrderedFloat<f32>);
+
+/// Comma separated entity ids, like `3,7`.
+impl OldActionValue for Vec<i32> {
+    fn parse_value(value: &str) -> Option<Self> {
+        value.split(',').map(str::trim).filter(|id| !id.is_empty()).map(|id| id.parse().ok()).collect()
+    }
+
+    fn format_value(&self) -> String {
+        self.iter().map(ToString::to_string).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// A vector written as `x,y`.
+impl OldActionValue for Vec2 {
+    fn parse_value(value: &str) -> Option<Self> {
+        let (x, y) = value.split_once(',')?;
+
+        Some(Self::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+    }
+
+    fn format_value(&self) -> String {
+        format!("{},{}", self.x, self.y)
+    }
+}
+
+/// A color written as `#RRGGBBAA`.
+impl OldActionValue for Color {
+    fn parse_value(value: &str) -> Option<Self> {
+        // `Color::from_str` panics on malformed strings, so check the shape first.
+        let well_formed = value.len() == 9
+            && value.starts_with('#')
+            && value[1..].chars().all(|character| character.is_ascii_hexdigit());
+
+        well_formed.then(|| value.parse().ok()).flatten()
+    }
+
+    fn format_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+macro_rules! define_old_action_kind {
+    ($($name:ident { $($field:ident: $ty:ty = $key:literal),*$(,)? }),*$(,)?) => {
+        /// The properties of an [`OldAction`], parsed according to its [`OldActionType`].
+        ///
+        /// Every field is `None` when the action doesn't have the property or its value can't be parsed. Objects
+        /// are given by entity id and scripts by uuid.
+        ///
+        /// The property names aren't documented by the game and haven't been checked against levels it saved, they
+        /// are the crate's best guess from the names of the legacy editor's action fields.
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        pub enum OldActionKind {
+            $($name { $($field: Option<$ty>),* }),*
+        }
+
+        impl OldActionKind {
+            pub const fn action_type(&self) -> OldActionType {
+                match self {
+                    $(Self::$name { .. } => OldActionType::$name,)*
+                }
+            }
+
+            /// Returns the property names that actions of the given type know, in the order
+            /// [`OldActionKind::to_properties`] writes them.
+            pub const fn property_names(action_type: OldActionType) -> &'static [&'static str] {
+                match action_type {
+                    $(OldActionType::$name => &[$($key),*],)*
+                }
+            }
+
+            /// Parses the properties of an action of the given type. Returns the properties whose names the type
+            /// doesn't know, that appear more than once or whose values can't be parsed, in their original order.
+            pub fn from_properties(
+                action_type: OldActionType,
+                properties: Vec<OldActionProperty>,
+            ) -> (Self, Vec<OldActionProperty>) {
+                let (kind, parsed) = Self::parse(action_type, &properties);
+                let unknown_properties = properties
+                    .into_iter()
+                    .zip(parsed)
+                    .filter_map(|(property, parsed)| (!parsed).then_some(property))
+                    .collect();
+
+                (kind, unknown_properties)
+            }
+
+            /// Parses the properties of an action of the given type, and returns which of them were parsed.
+            fn parse(action_type: OldActionType, properties: &[OldActionProperty]) -> (Self, Vec<bool>) {
+                #[allow(unused_mut)]
+                let mut parsed = vec![false; properties.len()];
+
+                let kind = match action_type {
+                    $(OldActionType::$name => Self::$name {
+                        $($field: take_property(properties, &mut parsed, $key),)*
+                    },)*
+                };
+
+                (kind, parsed)
+            }
+
+            /// Formats the field of the property with this name, or returns `None` if the field isn't set or the
+            /// action type has no such property. Returns `original` unchanged if it parses to the field's value.
+            fn format_property(&self, name: &str, original: Option<&str>) -> Option<String> {
+                match self {
+                    $(Self::$name { $($field),* } => {
+                        $(if name == $key {
+                            return format_property($field.as_ref(), original);
+                        })*
+
+                        None
+                    })*
+                }
+            }
+
+            /// Returns the properties that are set, in the order of [`OldActionKind::property_names`].
+            pub fn to_properties(&self) -> Vec<OldActionProperty> {
+                #[allow(unused_mut)]
+                let mut properties = Vec::new();
+
+                match self {
+                    $(Self::$name { $($field),* } => {
+                        $(push_property(&mut properties, $key, $field.as_ref());)*
+                    })*
+                }
+
+                properties
+            }
+
+            /// Returns the entity ids the action targets, if it has a `target` property.
+            pub const fn target(&self) -> Option<&Vec<i32>> {
+                match self {
+                    Self::Move { target, .. }
+                    | Self::Jump { target }
+                    | Self::Slam { target }
+                    | Self::Charge { target }
+                    | Self::Scale { target, .. }
+                    | Self::Rotate { target, .. }
+                    | Self::RotateAround { target, .. }
+                    | Self::SetDirection { target, .. }
+                    | Self::Activate { target }
+                    | Self::Deactivate { target }
+                    | Self::PanCameraToObject { target, .. }
+                    | Self::SetVulnerable { target, .. }
+                    | Self::Color { target, .. }
+                    | Self::Damage { target, .. }
+                    | Self::Kill { target }
+                    | Self::SetGravity { target, .. }
+                    | Self::SetVelocity { target, .. } => target.as_ref(),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+define_old_action_kind!(
+    RunScript { script: Uuid = "script" },
+    StopScripts {},
+    Wait { duration: OrderedFloat<f32> = "duration" },
+    WaitFrames { frames: i32 = "frames" },
+    Move {
+        target: Vec<i32> = "target",
+        position: Vec2 = "position",
+        duration: OrderedFloat<f32> = "duration",
+        easing: i32 = "easing",
+    },
+    Jump { target: Vec<i32> = "target" },
+    Slam { target: Vec<i32> = "target" },
+    Charge { target: Vec<i32> = "target" },
+    Scale {
+        target: Vec<i32> = "target",
+        scale: Vec2 = "scale",
+        duration: OrderedFloat<f32> = "duration",
+        easing: i32 = "easing",
+    },
+    Rotate {
+        target: Vec<i32> = "target",
+        rotation: OrderedFloat<f32> = "rotation",
+        duration: OrderedFloat<f32> = "duration",
+        easing: i32 = "easing",
+    },
+    RotateAround {
+        target: Vec<i32> = "target",
+        pivot: Vec2 = "pivot",
+        rotation: OrderedFloat<f32> = "rotation",
+        duration: OrderedFloat<f32> = "duration",
+        easing: i32 = "easing",
+    },
+    SetDirection { target: Vec<i32> = "target", direction: i32 = "direction" },
+    Activate { target: Vec<i32> = "target" },
+    Deactivate { target: Vec<i32> = "target" },
+    PlaySound { sound: i32 = "sound", volume: OrderedFloat<f32> = "volume", pitch: OrderedFloat<f32> = "pitch" },
+    PlayMusic { music: i32 = "music", volume: OrderedFloat<f32> = "volume", pitch: OrderedFloat<f32> = "pitch" },
+    SetCinematic { enabled: bool = "enabled" },
+    SetInputEnabled { enabled: bool = "enabled" },
+    PanCameraToObject { target: Vec<i32> = "target", duration: OrderedFloat<f32> = "duration", easing: i32 = "easing" },
+    CameraFollowPlayer {},
+    ShowGameText { text: String = "text", duration: OrderedFloat<f32> = "duration" },
+    SetVulnerable { target: Vec<i32> = "target", vulnerable: bool = "vulnerable" },
+    Color {
+        target: Vec<i32> = "target",
+        color: Color = "color",
+        duration: OrderedFloat<f32> = "duration",
+        easing: i32 = "easing",
+    },
+    Damage { target: Vec<i32> = "target", damage: OrderedFloat<f32> = "damage" },
+    Kill { target: Vec<i32> = "target" },
+    Finish {},
+    SetGravity { target: Vec<i32> = "target", gravity: Vec2 = "gravity" },
+    SetVelocity { target: Vec<i32> = "target", velocity: Vec2 = "velocity" },
+);
+
+/// Parses the value of the first property with this name, and marks it as parsed if it's valid.
+fn take_property<T: OldActionValue>(properties: &[OldActionProperty], parsed: &mut [bool], name: &str) -> Option<T> {
+    let index = properties.iter().position(|property| property.name == name)?;
+    let value = T::parse_value(&properties[index].value)?;
+    parsed[index] = true;
+
+    Some(value)
+}
+
+fn format_property<T: OldActionValue + PartialEq>(value: Option<&T>, original: Option<&str>) -> Option<String> {
+    let value = value?;
+
+    match original {
+        Some(original) if T::parse_value(original).as_ref() == Some(value) => Some(original.to_string()),
+        _ => Some(value.format_value()),
+    }
+}
+
+fn push_property<T: OldActionValue>(properties: &mut Vec<OldActionProperty>, name: &str, value: Option<&T>) {
+    if let Some(value) = value {
+        properties.push(OldActionProperty { name: name.to_string(), value: value.format_value() });
+    }
+}
+
+/// An [`OldAction`] with its properties parsed into an [`OldActionKind`].
+///
+/// Converting back gives exactly the same action again. Every property keeps its position, and known properties keep
+/// their original string unless their field was changed. Fields that were set afterwards are added after the original
+/// properties, in the order of [`OldActionKind::property_names`], and so are unknown properties that were added.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TypedOldAction {
+    pub kind: OldActionKind,
+    pub wait: bool,
+    /// The properties that the action type doesn't know, that appear more than once or whose values can't be
+    /// parsed, in their original order.
+    pub unknown_properties: Vec<OldActionProperty>,
+    /// The properties of the original action: the parsed ones as they were read, and `None` in place of each unknown
+    /// property.
+    layout: Vec<Option<OldActionProperty>>,
+}
+
+impl TypedOldAction {
+    /// Returns an action without unknown properties.
+    #[must_use]
+    pub const fn new(kind: OldActionKind, wait: bool) -> Self {
+        Self { kind, wait, unknown_properties: Vec::new(), layout: Vec::new() }
+    }
+
+    /// Returns the unknown properties that the action type knows by name but whose values couldn't be parsed.
+    pub fn invalid_properties(&self) -> impl Iterator<Item = &OldActionProperty> {
+        let names = OldActionKind::property_names(self.kind.action_type());
+
+        self.unknown_properties.iter().filter(move |property| {
+            names.contains(&property.name.as_str()) && self.kind.format_property(&property.name, None).is_none()
+        })
+    }
+}
+
+impl OldAction {
+    /// Returns the typed form of this action.
+    #[must_use]
+    pub fn typed(&self) -> TypedOldAction {
+        TypedOldAction::from(self.clone())
+    }
+}
+
+impl From<OldAction> for TypedOldAction {
+    fn from(value: OldAction) -> Self {
+        let (kind, parsed) = OldActionKind::parse(value.action_type, &value.properties);
+        let mut unknown_properties = Vec::new();
+        let mut layout = Vec::new();
+
+        for (property, parsed) in value.properties.into_iter().zip(parsed) {
+            if parsed {
+                layout.push(Some(property));
+            } else {
+                unknown_properties.push(property);
+                layout.push(None);
+            }
+        }
+
+        Self { kind, wait: value.wait, unknown_properties, layout }
+    }
+}
+
+impl From<TypedOldAction> for OldAction {
+    fn from(value: TypedOldAction) -> Self {
+        let action_type = value.kind.action_type();
+        let mut unknown_properties = value.unknown_properties.into_iter();
+        let mut written = Vec::new();
+        let mut properties = Vec::new();
+
+        for slot in value.layout {
+            let Some(original) = slot else {
+                properties.extend(unknown_properties.next());
+                continue;
+            };
+
+            if let Some(formatted) = value.kind.format_property(&original.name, Some(&original.value)) {
+                properties.push(OldActionProperty { name: original.name.clone(), value: formatted });
+            }
+
+            written.push(original.name);
+        }
+
+        for &name in OldActionKind::property_names(action_type) {
+            if !written.iter().any(|written| written == name) {
+                if let Some(value) = value.kind.format_property(name, None) {
+                    properties.push(OldActionProperty { name: name.to_string(), value });
+                }
+            }
+        }
+
+        properties.extend(unknown_properties);
+
+        Self { action_type, wait: value.wait, properties }
+    }
+}
+
+impl Read for TypedOldAction {
+    fn read(input: &mut impl std::io::Read) -> Result<Self, Error> {
+        Ok(OldAction::read(input)?.into())
+    }
+}
+
+impl Write for TypedOldAction {
+    fn write(&self, output: &mut impl std::io::Write) -> Result<(), Error> {
+        OldAction::from(self.clone()).write(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property(name: &str, value: &str) -> OldActionProperty {
+        OldActionProperty { name: name.to_string(), value: value.to_string() }
+    }
+
+    fn action(action_type: OldActionType, properties: &[(&str, &str)]) -> OldAction {
+        OldAction {
+            action_type,
+            wait: true,
+            properties: properties.iter().map(|&(name, value)| property(name, value)).collect(),
+        }
+    }
+
+    #[test]
+    fn round_trip_keeps_strings_and_order() {
+        let original = action(
+            OldActionType::Move,
+            &[("foo", "bar"), ("duration", "1.50"), ("position", "1.0,2.0"), ("target", "1, 2"), ("duration", "3")],
+        );
+        let typed = original.typed();
+
+        assert_eq!(
+            typed.kind,
+            OldActionKind::Move {
+                target: Some(vec![1, 2]),
+                position: Some(Vec2::new(1.0, 2.0)),
+                duration: Some(OrderedFloat(1.5)),
+                easing: None,
+            }
+        );
+        assert_eq!(typed.unknown_properties, vec![property("foo", "bar"), property("duration", "3")]);
+        assert_eq!(typed.invalid_properties().count(), 0);
+        assert_eq!(OldAction::from(typed), original);
+    }
+
+    #[test]
+    fn round_trip_keeps_invalid_values() {
+        let original = action(OldActionType::Wait, &[("duration", "abc")]);
+        let typed = original.typed();
+
+        assert_eq!(typed.kind, OldActionKind::Wait { duration: None });
+        assert_eq!(typed.invalid_properties().collect::<Vec<_>>(), vec![&property("duration", "abc")]);
+        assert_eq!(OldAction::from(typed), original);
+    }
+
+    #[test]
+    fn edited_fields_are_formatted_in_place() {
+        let original = action(OldActionType::Move, &[("foo", "bar"), ("duration", "1.50"), ("target", "1, 2")]);
+        let mut typed = original.typed();
+
+        if let OldActionKind::Move { duration, target, easing, .. } = &mut typed.kind {
+            *duration = Some(OrderedFloat(2.5));
+            *target = None;
+            *easing = Some(3);
+        }
+
+        typed.unknown_properties.push(property("baz", "qux"));
+
+        assert_eq!(
+            OldAction::from(typed),
+            action(OldActionType::Move, &[("foo", "bar"), ("duration", "2.5"), ("easing", "3"), ("baz", "qux")])
+        );
+    }
+}
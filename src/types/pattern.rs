@@ -1,6 +1,15 @@
+#[cfg(feature = "image")]
+use std::{fs::File, io::BufReader, path::Path};
+
 use crate::traits::{Read, Write};
 use crate::error::Error;
 use super::image::Image;
+#[cfg(feature = "image")]
+use image::{
+    codecs::{gif::{GifDecoder, GifEncoder, Repeat}, png::PngDecoder, webp::WebPDecoder},
+    imageops::FilterType,
+    AnimationDecoder, Delay, DynamicImage, Frame, ImageError, RgbaImage,
+};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "image", derive(Clone, Debug, Hash, PartialEq, Eq))]
@@ -36,4 +45,224 @@ impl From<Vec<Image>> for Pattern {
             pattern_frames: value,
         }
     }
-}
\ No newline at end of file
+}
+
+/// How [`Pattern::from_animation`] turns an animation into pattern frames.
+///
+/// There's no `Default`: the limits depend on what the level should allow, so the caller has to pick them.
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct AnimationOptions {
+    /// Frames larger than this in either direction are scaled down to fit, keeping their aspect ratio.
+    pub max_size: Option<u32>,
+    /// Animations with more frames than this are rejected with [`Error::TooManyFrames`].
+    pub max_frames: Option<usize>,
+    /// Every color channel is rounded to this many evenly spaced levels, e.g. `4` for four values per channel.
+    pub color_levels: Option<u8>,
+}
+
+#[cfg(feature = "image")]
+impl Pattern {
+    /// How long every frame is shown in exported animations, in milliseconds. Patterns don't store timing.
+    pub const EXPORT_FRAME_DELAY_MS: u32 = 100;
+
+    /// Imports an animated GIF, APNG or WebP file as a pattern with a new random id, or every image in a folder as
+    /// a frame in the order of their file names.
+    ///
+    /// Other image files and still PNG and WebP files become a single frame. The timing of the frames is lost.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file or folder can't be read or the folder contains no images,
+    /// [`Error::Image`] if a file can't be decoded, or [`Error::TooManyFrames`] if there are more frames than
+    /// `options.max_frames`.
+    pub fn from_animation(path: &Path, options: &AnimationOptions) -> Result<Self, Error> {
+        let frames = if path.is_dir() { read_frame_folder(path)? } else { read_animation(path)? };
+
+        if let Some(limit) = options.max_frames.filter(|&limit| frames.len() > limit) {
+            return Err(Error::TooManyFrames { limit, found: frames.len() });
+        }
+
+        let frames = frames.into_iter().map(|frame| Image(limit_frame(frame, options))).collect::<Vec<_>>();
+
+        Ok(frames.into())
+    }
+
+    /// Writes the frames as an endlessly looping GIF if `path` ends in `.gif`, or as numbered PNG files in the
+    /// folder `path` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file or folder can't be created, or [`Error::Image`] if a frame can't be encoded
+    /// or written.
+    pub fn export_animation(&self, path: &Path) -> Result<(), Error> {
+        let is_gif = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+
+        if !is_gif {
+            std::fs::create_dir_all(path)?;
+
+            for (index, frame) in self.pattern_frames.iter().enumerate() {
+                frame.0.save(path.join(format!("frame_{index:03}.png")))?;
+            }
+
+            return Ok(());
+        }
+
+        let mut encoder = GifEncoder::new(std::io::BufWriter::new(File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let delay = Delay::from_numer_denom_ms(Self::EXPORT_FRAME_DELAY_MS, 1);
+        encoder.encode_frames(self.pattern_frames.iter().map(|frame| Frame::from_parts(frame.0.clone(), 0, 0, delay)))?;
+
+        Ok(())
+    }
+}
+
+/// Reads every frame of an animation file, composited to the full size of the animation.
+#[cfg(feature = "image")]
+fn read_animation(path: &Path) -> Result<Vec<RgbaImage>, Error> {
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_lowercase();
+    let reader = || -> Result<_, Error> { Ok(BufReader::new(File::open(path)?)) };
+
+    let frames = match extension.as_str() {
+        "gif" => GifDecoder::new(reader()?)?.into_frames().collect_frames()?,
+        "png" | "apng" => {
+            let decoder = PngDecoder::new(reader()?)?;
+
+            if !decoder.is_apng()? {
+                return Ok(vec![image::open(path)?.to_rgba8()]);
+            }
+
+            decoder.apng()?.into_frames().collect_frames()?
+        }
+        "webp" => {
+            let decoder = WebPDecoder::new(reader()?)?;
+
+            if !decoder.has_animation() {
+                return Ok(vec![image::open(path)?.to_rgba8()]);
+            }
+
+            decoder.into_frames().collect_frames()?
+        }
+        _ => return Ok(vec![image::open(path)?.to_rgba8()]),
+    };
+
+    Ok(frames.into_iter().map(Frame::into_buffer).collect())
+}
+
+/// Reads every image file in the folder, sorted by file name. Files that aren't images are skipped.
+#[cfg(feature = "image")]
+fn read_frame_folder(path: &Path) -> Result<Vec<RgbaImage>, Error> {
+    let mut paths = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    let mut frames = vec![];
+
+    for path in paths.iter().filter(|path| path.is_file()) {
+        match image::open(path) {
+            Ok(frame) => frames.push(frame.to_rgba8()),
+            Err(ImageError::Unsupported(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    if frames.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "the folder doesn't contain any images").into());
+    }
+
+    Ok(frames)
+}
+
+/// Scales the frame down and reduces its colors as the options ask for.
+#[cfg(feature = "image")]
+fn limit_frame(frame: RgbaImage, options: &AnimationOptions) -> RgbaImage {
+    let mut frame = match options.max_size {
+        Some(max_size) if frame.width() > max_size || frame.height() > max_size => {
+            let max_size = max_size.max(1);
+
+            DynamicImage::ImageRgba8(frame).resize(max_size, max_size, FilterType::Triangle).to_rgba8()
+        }
+        _ => frame,
+    };
+
+    if let Some(levels) = options.color_levels.filter(|&levels| levels >= 2) {
+        let step = 255.0 / f32::from(levels - 1);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let quantize = |channel: u8| ((f32::from(channel) / step).round() * step).round() as u8;
+
+        for pixel in frame.pixels_mut() {
+            pixel.0 = pixel.0.map(quantize);
+        }
+    }
+
+    frame
+}
+
+#[cfg(all(test, feature = "image"))]
+mod tests {
+    use super::*;
+    use image::Rgba;
+    use std::path::PathBuf;
+
+    const NO_LIMITS: AnimationOptions = AnimationOptions { max_size: None, max_frames: None, color_levels: None };
+
+    fn pattern() -> Pattern {
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+
+        Pattern {
+            pattern_id: 1,
+            pattern_frames: colors.map(|color| Image(RgbaImage::from_pixel(8, 4, Rgba(color)))).to_vec(),
+        }
+    }
+
+    /// A path in the temporary directory that is unique to the test.
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("exolvl-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn gif_round_trip() {
+        let path = temp_path("round-trip.gif");
+        let original = pattern();
+
+        original.export_animation(&path).unwrap();
+        let imported = Pattern::from_animation(&path, &NO_LIMITS).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported.pattern_frames, original.pattern_frames);
+    }
+
+    #[test]
+    fn folder_round_trip() {
+        let path = temp_path("frames");
+        let original = pattern();
+
+        original.export_animation(&path).unwrap();
+        let imported = Pattern::from_animation(&path, &NO_LIMITS).unwrap();
+        std::fs::remove_dir_all(&path).unwrap();
+
+        assert_eq!(imported.pattern_frames, original.pattern_frames);
+    }
+
+    #[test]
+    fn limits() {
+        let path = temp_path("limits.gif");
+        pattern().export_animation(&path).unwrap();
+
+        let too_many = Pattern::from_animation(&path, &AnimationOptions { max_frames: Some(2), ..NO_LIMITS });
+        assert!(matches!(too_many, Err(Error::TooManyFrames { limit: 2, found: 3 })));
+
+        let options = AnimationOptions { max_size: Some(4), max_frames: Some(3), color_levels: Some(2) };
+        let limited = Pattern::from_animation(&path, &options).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(limited.pattern_frames.len(), 3);
+        assert!(limited.pattern_frames.iter().all(|frame| frame.0.dimensions() == (4, 2)));
+        assert!(limited.pattern_frames[0].0.pixels().all(|pixel| pixel.0 == [255, 0, 0, 255]));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::traits::{Read, Write};
 use crate::error::Error;
 use crate::types::{image::Image, object::Object};
+#[cfg(feature = "image")]
+use crate::{render::RenderOptions, types::level_data::LevelData};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -10,6 +12,31 @@ pub struct Prefab {
     pub items: Vec<Object>,
 }
 
+#[cfg(feature = "image")]
+impl Prefab {
+    /// The width and height of the images [`Prefab::regenerate_image`] renders for prefabs without an image, in
+    /// pixels.
+    pub const IMAGE_SIZE: u32 = 128;
+
+    /// The size of `prefab_image_data`, or [`Self::IMAGE_SIZE`] squared if it's empty.
+    #[must_use]
+    pub fn image_size(&self) -> (u32, u32) {
+        match self.prefab_image_data.0.dimensions() {
+            (0, _) | (_, 0) => (Self::IMAGE_SIZE, Self::IMAGE_SIZE),
+            size => size,
+        }
+    }
+
+    /// Renders the items and stores the image as `prefab_image_data`, using the theme and patterns of `level`. The
+    /// image keeps its [size](Self::image_size).
+    pub fn regenerate_image(&mut self, level: &LevelData) {
+        let (width, height) = self.image_size();
+        let options = RenderOptions { width, height, ..RenderOptions::default() };
+
+        self.prefab_image_data = Image(level.render_prefab(self, &options));
+    }
+}
+
 impl Read for Prefab {
     fn read(input: &mut impl std::io::Read) -> Result<Self, Error> {
         Ok(Self {
@@ -26,4 +53,44 @@ impl Write for Prefab {
         self.prefab_image_data.write(output)?;
         self.items.write(output)
     }
-}
\ No newline at end of file
+}
+#[cfg(all(test, feature = "image"))]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+
+    fn prefab(width: u32, height: u32) -> Prefab {
+        Prefab {
+            prefab_id: 1,
+            prefab_image_data: Image(RgbaImage::new(width, height)),
+            items: vec![Object::default()],
+        }
+    }
+
+    #[test]
+    fn regenerate_image() {
+        let level = LevelData::default();
+
+        let mut empty = prefab(0, 0);
+        empty.regenerate_image(&level);
+        assert_eq!(empty.prefab_image_data.0.dimensions(), (Prefab::IMAGE_SIZE, Prefab::IMAGE_SIZE));
+
+        let mut existing = prefab(64, 32);
+        existing.regenerate_image(&level);
+        assert_eq!(existing.image_size(), (64, 32));
+        assert_ne!(existing.prefab_image_data, prefab(64, 32).prefab_image_data);
+    }
+
+    #[test]
+    fn image_round_trip() {
+        let mut original = prefab(0, 0);
+        original.regenerate_image(&LevelData::default());
+
+        let mut bytes = Vec::new();
+        original.write(&mut bytes).unwrap();
+        let read = Prefab::read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(read, original);
+        assert_eq!(read.image_size(), (Prefab::IMAGE_SIZE, Prefab::IMAGE_SIZE));
+    }
+}
@@ -0,0 +1,475 @@
+//! Flowcharts of nova scripts and graphs of how the scripts of a level fit together, as Graphviz DOT or Mermaid.
+//!
+//! [`script_flowchart`] draws the actions of one script: `if` blocks branch into their `true` and `false` actions,
+//! loops draw an edge back from the end of their body. [`level_graph`] links the activators of every script to the
+//! script, the script to the functions it runs, and both to the objects they refer to.
+//!
+//! ```no_run
+//! # let level = exolvl::types::level_data::LevelData::default();
+//! use exolvl::viz::{script_flowchart, VizOptions};
+//!
+//! let options = VizOptions { expressions: true, ..VizOptions::default() };
+//! let dot = script_flowchart(&level.nova_scripts[0], &level.global_variables, &options).to_dot();
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use crate::references::{EntityRef, References};
+use crate::text::{action_name, decompile_action, decompile_value, Scope};
+use crate::types::{
+    level_data::LevelData,
+    nova_script::{
+        action::Action,
+        action_type::ActionType,
+        activator::ActivatorKind,
+        path::{PathSegment, ScriptPath},
+        variable::Variable,
+        NovaScript,
+    },
+};
+
+/// Controls how the graphs are drawn.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct VizOptions {
+    /// Label nodes with the decompiled action or expression instead of just the action name.
+    pub expressions: bool,
+    /// Draw blocks nested deeper than this as a single node. Top level actions are at depth 0.
+    pub max_depth: Option<usize>,
+    /// Draw the blocks of the actions at these paths as a single node.
+    pub collapsed: BTreeSet<ScriptPath>,
+}
+
+/// How a node is drawn.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeShape {
+    /// Actions and scripts.
+    Box,
+    /// Branches.
+    Diamond,
+    /// Loops.
+    Hexagon,
+    /// Start and end of a flowchart, and activators.
+    Rounded,
+    /// Functions.
+    Subroutine,
+    /// Objects.
+    Ellipse,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Node {
+    /// An identifier that is valid in both DOT and Mermaid.
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+}
+
+/// A directed graph that can be printed as DOT or Mermaid.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Graph {
+    pub name: String,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    fn node(&mut self, label: impl Into<String>, shape: NodeShape) -> String {
+        let id = format!("n{}", self.nodes.len());
+        self.nodes.push(Node { id: id.clone(), label: label.into(), shape });
+        id
+    }
+
+    fn edge(&mut self, from: &str, to: &str, label: Option<&str>) {
+        self.edges.push(Edge { from: from.to_string(), to: to.to_string(), label: label.map(str::to_string) });
+    }
+
+    /// Prints the graph in the Graphviz DOT language.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph {} {{\n", dot_string(&self.name));
+
+        for node in &self.nodes {
+            let shape = match node.shape {
+                NodeShape::Box => "box",
+                NodeShape::Diamond => "diamond",
+                NodeShape::Hexagon => "hexagon",
+                NodeShape::Rounded => "box, style=rounded",
+                NodeShape::Subroutine => "box, peripheries=2",
+                NodeShape::Ellipse => "ellipse",
+            };
+            let _ = writeln!(out, "    {} [label={}, shape={shape}];", node.id, dot_string(&node.label));
+        }
+
+        for edge in &self.edges {
+            let _ = match &edge.label {
+                Some(label) => writeln!(out, "    {} -> {} [label={}];", edge.from, edge.to, dot_string(label)),
+                None => writeln!(out, "    {} -> {};", edge.from, edge.to),
+            };
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Prints the graph as a Mermaid flowchart.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        let mut out = format!("---\ntitle: {}\n---\nflowchart TD\n", mermaid_string(&self.name));
+
+        for node in &self.nodes {
+            let label = mermaid_string(&node.label);
+            let _ = match node.shape {
+                NodeShape::Box => writeln!(out, "    {}[\"{label}\"]", node.id),
+                NodeShape::Diamond => writeln!(out, "    {}{{\"{label}\"}}", node.id),
+                NodeShape::Hexagon => writeln!(out, "    {}{{{{\"{label}\"}}}}", node.id),
+                NodeShape::Rounded => writeln!(out, "    {}(\"{label}\")", node.id),
+                NodeShape::Subroutine => writeln!(out, "    {}[[\"{label}\"]]", node.id),
+                NodeShape::Ellipse => writeln!(out, "    {}([\"{label}\"])", node.id),
+            };
+        }
+
+        for edge in &self.edges {
+            let _ = match &edge.label {
+                Some(label) => writeln!(out, "    {} -->|\"{}\"| {}", edge.from, mermaid_string(label), edge.to),
+                None => writeln!(out, "    {} --> {}", edge.from, edge.to),
+            };
+        }
+
+        out
+    }
+}
+
+/// Quotes a string for DOT.
+fn dot_string(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// Escapes a string for a quoted Mermaid label.
+fn mermaid_string(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br>")
+}
+
+/// Draws the actions of a script as a flowchart, from a start node with the script's name to an end node.
+#[must_use]
+pub fn script_flowchart(script: &NovaScript, globals: &[Variable], options: &VizOptions) -> Graph {
+    let mut flowchart = Flowchart {
+        graph: Graph { name: script.script_name.clone(), ..Graph::default() },
+        scope: Scope::new(globals).with_script(script),
+        options,
+    };
+
+    let start = flowchart.graph.node(script.script_name.clone(), NodeShape::Rounded);
+    let path = ScriptPath::script(script.script_id).join(PathSegment::Actions("actions"));
+    let exits = flowchart.block(&script.actions, &path, 0, vec![(start, None)]);
+    let end = flowchart.graph.node("end", NodeShape::Rounded);
+    flowchart.connect(exits, &end);
+
+    flowchart.graph
+}
+
+/// A node that the next action continues from, and the label of that edge.
+type Exit = (String, Option<&'static str>);
+
+struct Flowchart<'a> {
+    graph: Graph,
+    scope: Scope,
+    options: &'a VizOptions,
+}
+
+impl Flowchart<'_> {
+    fn connect(&mut self, exits: Vec<Exit>, to: &str) {
+        for (from, label) in exits {
+            self.graph.edge(&from, to, label);
+        }
+    }
+
+    /// Draws a list of actions after `exits` and returns the exits of its last action.
+    fn block(&mut self, actions: &[Action], path: &ScriptPath, depth: usize, mut exits: Vec<Exit>) -> Vec<Exit> {
+        for (index, action) in actions.iter().enumerate() {
+            exits = self.action(action, &path.join(PathSegment::Action(index)), depth, exits);
+        }
+
+        exits
+    }
+
+    fn action(&mut self, action: &Action, path: &ScriptPath, depth: usize, exits: Vec<Exit>) -> Vec<Exit> {
+        let children = action.action_type.children();
+        let collapsed = self.options.collapsed.contains(path) || self.options.max_depth.is_some_and(|max| depth >= max);
+
+        if children.is_empty() || collapsed {
+            let mut label = self.label(action);
+
+            if !children.is_empty() {
+                let count: usize = children.iter().map(|(_, actions)| count_actions(actions)).sum();
+                let _ = write!(label, " … ({count} action{})", if count == 1 { "" } else { "s" });
+            }
+
+            let node = self.graph.node(label, NodeShape::Box);
+            self.connect(exits, &node);
+
+            return vec![(node, None)];
+        }
+
+        if let ActionType::ConditionBlock { if_actions, else_actions, .. } = &action.action_type {
+            let node = self.graph.node(self.label(action), NodeShape::Diamond);
+            self.connect(exits, &node);
+
+            let if_path = path.join(PathSegment::Actions("if_actions"));
+            let else_path = path.join(PathSegment::Actions("else_actions"));
+            let mut branches = self.block(if_actions, &if_path, depth + 1, vec![(node.clone(), Some("true"))]);
+            branches.extend(self.block(else_actions, &else_path, depth + 1, vec![(node, Some("false"))]));
+
+            return branches;
+        }
+
+        // Every other action with children is a loop over its only block.
+        let node = self.graph.node(self.label(action), NodeShape::Hexagon);
+        self.connect(exits, &node);
+
+        for (field, actions) in children {
+            let body_path = path.join(PathSegment::Actions(field));
+            let body = self.block(actions, &body_path, depth + 1, vec![(node.clone(), Some("loop"))]);
+            self.connect(body, &node);
+        }
+
+        vec![(node, Some("done"))]
+    }
+
+    fn label(&self, action: &Action) -> String {
+        let wait = if action.wait { "await " } else { "" };
+
+        if !self.options.expressions {
+            return format!("{wait}{}", action_name(&action.action_type));
+        }
+
+        match &action.action_type {
+            ActionType::ConditionBlock { condition, .. } => format!("if {}", decompile_value(condition, &self.scope)),
+            ActionType::RepeatWhile { condition, .. } => format!("while {}", decompile_value(condition, &self.scope)),
+            ActionType::Repeat { count, .. } => format!("{wait}repeat {}", decompile_value(count, &self.scope)),
+            ActionType::RepeatForEachObject { target_objects, .. } => {
+                format!("{wait}for_each {}", decompile_value(target_objects, &self.scope))
+            }
+            _ => decompile_action(action, &self.scope).trim_end().trim_end_matches(';').to_string(),
+        }
+    }
+}
+
+/// The number of actions in the list, including nested ones.
+fn count_actions(actions: &[Action]) -> usize {
+    actions
+        .iter()
+        .map(|action| {
+            1 + action.action_type.children().iter().map(|(_, actions)| count_actions(actions)).sum::<usize>()
+        })
+        .sum()
+}
+
+/// Draws every script of the level with the activators that start it, the functions it runs and the objects it
+/// refers to.
+pub fn level_graph(level: &LevelData, options: &VizOptions) -> Graph {
+    let mut graph = Graph { name: "level".to_string(), ..Graph::default() };
+    let script_nodes: Vec<(i32, String)> = level
+        .nova_scripts
+        .iter()
+        .map(|script| {
+            let (kind, shape) = if script.is_function {
+                ("function", NodeShape::Subroutine)
+            } else {
+                ("script", NodeShape::Box)
+            };
+
+            (script.script_id, graph.node(format!("{kind} {} {:?}", script.script_id, script.script_name), shape))
+        })
+        .collect();
+    let node_of = |script_id: i32| script_nodes.iter().find(|(id, _)| *id == script_id).map(|(_, node)| node.clone());
+
+    for (script, (_, script_node)) in level.nova_scripts.iter().zip(&script_nodes) {
+        let scope = Scope::new(&level.global_variables).with_script(script);
+
+        for activator in &script.activation_list {
+            let name = ActivatorKind::name(activator.activator_type)
+                .map_or_else(|| format!("activator {}", activator.activator_type), str::to_string);
+            let label = if options.expressions && !activator.parameters.is_empty() {
+                let parameters: Vec<String> =
+                    activator.parameters.iter().map(|parameter| decompile_value(parameter, &scope)).collect();
+                format!("{name}({})", parameters.join(", "))
+            } else {
+                name
+            };
+
+            let node = graph.node(label, NodeShape::Rounded);
+            graph.edge(&node, script_node, None);
+        }
+    }
+
+    let call_graph = level.call_graph();
+
+    for (script_id, script_node) in &script_nodes {
+        for function in call_graph.callees(*script_id) {
+            if let Some(function_node) = node_of(function) {
+                graph.edge(script_node, &function_node, Some("runs"));
+            }
+        }
+    }
+
+    let references = References::new(level);
+    let mut object_nodes: Vec<(i32, String)> = Vec::new();
+
+    for (script_id, script_node) in &script_nodes {
+        let objects: BTreeSet<i32> = references
+            .outbound(EntityRef::Script(*script_id))
+            .filter_map(|reference| match reference.to {
+                EntityRef::Object(entity_id) => Some(entity_id),
+                _ => None,
+            })
+            .collect();
+
+        for entity_id in objects {
+            let object_node = if let Some((_, node)) = object_nodes.iter().find(|(id, _)| *id == entity_id) {
+                node.clone()
+            } else {
+                let label = level.objects.iter().find(|object| object.entity_id == entity_id).map_or_else(
+                    || format!("object {entity_id}"),
+                    |object| format!("{} {entity_id}", object.object_id),
+                );
+                let node = graph.node(label, NodeShape::Ellipse);
+                object_nodes.push((entity_id, node.clone()));
+                node
+            };
+
+            graph.edge(script_node, &object_node, None);
+        }
+    }
+
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::compile_scripts;
+
+    fn door() -> NovaScript {
+        let source = r#"
+            script 1 "Door" { on level_start(); wait(1.0); if true { wait(2.0); } else { repeat 2 { wait(0.5); } } }
+        "#;
+
+        compile_scripts(source, &[]).unwrap().remove(0)
+    }
+
+    #[test]
+    fn flowchart_dot() {
+        let dot = script_flowchart(&door(), &[], &VizOptions::default()).to_dot();
+
+        assert_eq!(
+            dot,
+            r#"digraph "Door" {
+    n0 [label="Door", shape=box, style=rounded];
+    n1 [label="wait", shape=box];
+    n2 [label="if", shape=diamond];
+    n3 [label="wait", shape=box];
+    n4 [label="repeat", shape=hexagon];
+    n5 [label="wait", shape=box];
+    n6 [label="end", shape=box, style=rounded];
+    n0 -> n1;
+    n1 -> n2;
+    n2 -> n3 [label="true"];
+    n2 -> n4 [label="false"];
+    n4 -> n5 [label="loop"];
+    n5 -> n4;
+    n3 -> n6;
+    n4 -> n6 [label="done"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn flowchart_mermaid() {
+        let mermaid = script_flowchart(&door(), &[], &VizOptions::default()).to_mermaid();
+
+        assert_eq!(
+            mermaid,
+            r#"---
+title: Door
+---
+flowchart TD
+    n0("Door")
+    n1["wait"]
+    n2{"if"}
+    n3["wait"]
+    n4{{"repeat"}}
+    n5["wait"]
+    n6("end")
+    n0 --> n1
+    n1 --> n2
+    n2 -->|"true"| n3
+    n2 -->|"false"| n4
+    n4 -->|"loop"| n5
+    n5 --> n4
+    n3 --> n6
+    n4 -->|"done"| n6
+"#
+        );
+    }
+
+    #[test]
+    fn collapsed_blocks() {
+        let path = ScriptPath::script(1).join(PathSegment::Actions("actions")).join(PathSegment::Action(1));
+        let options = VizOptions { collapsed: BTreeSet::from([path]), ..VizOptions::default() };
+        let graph = script_flowchart(&door(), &[], &options);
+        let labels: Vec<&str> = graph.nodes.iter().map(|node| node.label.as_str()).collect();
+
+        assert_eq!(labels, ["Door", "wait", "if … (3 actions)", "end"]);
+        assert_eq!(graph.edges.len(), 3);
+
+        let options = VizOptions { max_depth: Some(1), ..VizOptions::default() };
+        let graph = script_flowchart(&door(), &[], &options);
+        let labels: Vec<&str> = graph.nodes.iter().map(|node| node.label.as_str()).collect();
+
+        assert_eq!(labels, ["Door", "wait", "if", "wait", "repeat … (1 action)", "end"]);
+    }
+
+    #[test]
+    fn expressions_and_escaping() {
+        let source = r#"script 1 "Say \"hi\"" { on level_start(); if true { game_text_show("a\"b", 1.0); } }"#;
+        let script = compile_scripts(source, &[]).unwrap().remove(0);
+        let graph = script_flowchart(&script, &[], &VizOptions { expressions: true, ..VizOptions::default() });
+
+        assert_eq!(graph.nodes[1].label, "if true");
+        assert_eq!(graph.nodes[2].label, r#"game_text_show(text: "a\"b", duration: 1.0)"#);
+        assert!(graph.to_dot().starts_with("digraph \"Say \\\"hi\\\"\" {\n"));
+        assert!(graph.to_mermaid().starts_with("---\ntitle: Say #quot;hi#quot;\n---\n"));
+    }
+
+    #[test]
+    fn level_graph_links_activators_functions_and_objects() {
+        let source = r#"
+            function 10 "f" { activation_count 0; }
+            script 1 "A" { on collision_enter(object(5), object_player()); call 10(); activate(objects[5]); }
+        "#;
+        let level = LevelData { nova_scripts: compile_scripts(source, &[]).unwrap(), ..Default::default() };
+        let graph = level_graph(&level, &VizOptions::default());
+        let label = |id: &str| graph.nodes.iter().find(|node| node.id == id).unwrap().label.as_str();
+        let edges: Vec<(&str, &str, Option<&str>)> =
+            graph.edges.iter().map(|edge| (label(&edge.from), label(&edge.to), edge.label.as_deref())).collect();
+
+        assert_eq!(
+            edges,
+            [
+                ("collision enter", "script 1 \"A\"", None),
+                ("script 1 \"A\"", "function 10 \"f\"", Some("runs")),
+                ("script 1 \"A\"", "object 5", None),
+            ]
+        );
+    }
+}